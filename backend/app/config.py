@@ -1,19 +1,120 @@
 from __future__ import annotations
 
 import os
+import tomllib
+from contextlib import contextmanager
+from contextvars import ContextVar
+from functools import lru_cache
 from pathlib import Path
+from typing import Any, Iterator, Optional
 
 
 DEFAULT_DATA_DIR_NAME = ".oikonomos"
+CONFIG_FILE_NAME = "config.toml"
+LOG_LEVELS = {"DEBUG", "INFO", "WARNING", "ERROR", "CRITICAL"}
+
+_data_dir_override: ContextVar[Optional[Path]] = ContextVar("data_dir_override", default=None)
+
+
+def get_config_path() -> Path:
+    override = os.environ.get("OIKONOMOS_CONFIG")
+    if override:
+        return Path(override).expanduser()
+    return Path.home() / DEFAULT_DATA_DIR_NAME / CONFIG_FILE_NAME
+
+
+@lru_cache(maxsize=8)
+def _load_config_file(path: str, mtime_ns: int) -> dict[str, Any]:
+    try:
+        with open(path, "rb") as handle:
+            return tomllib.load(handle)
+    except tomllib.TOMLDecodeError as exc:
+        raise ValueError(f"invalid config file {path}: {exc}") from exc
+
+
+def get_file_config() -> dict[str, Any]:
+    """Optional deployment config; environment variables still take precedence over it."""
+    path = get_config_path()
+    try:
+        mtime_ns = path.stat().st_mtime_ns
+    except FileNotFoundError:
+        return {}
+    return _load_config_file(str(path), mtime_ns)
+
+
+@contextmanager
+def use_data_dir(path: Path) -> Iterator[None]:
+    """Point database access in the current context at another data dir (used by replay)."""
+    token = _data_dir_override.set(path)
+    try:
+        yield
+    finally:
+        _data_dir_override.reset(token)
 
 
 def get_data_dir() -> Path:
+    scoped = _data_dir_override.get()
+    if scoped is not None:
+        return scoped
     override = os.environ.get("OIKONOMOS_DATA_DIR")
     if override:
         return Path(override).expanduser()
+    configured = get_file_config().get("data_dir")
+    if configured:
+        return Path(str(configured)).expanduser()
     return Path.home() / DEFAULT_DATA_DIR_NAME
 
 
+def is_read_only() -> bool:
+    value = os.environ.get("OIKONOMOS_READ_ONLY")
+    if value is not None:
+        return value.strip().lower() in {"1", "true", "yes", "on"}
+    return bool(get_file_config().get("read_only", False))
+
+
+def get_log_level() -> str:
+    level = str(os.environ.get("OIKONOMOS_LOG_LEVEL") or get_file_config().get("log_level", "INFO")).upper()
+    if level not in LOG_LEVELS:
+        raise ValueError(f"invalid log level: {level}")
+    return level
+
+
+def get_scheduler_interval_seconds() -> int:
+    """Seconds between background job runs; 0 turns the scheduler off."""
+    value = os.environ.get("OIKONOMOS_SCHEDULER_INTERVAL_SECONDS") or get_file_config().get(
+        "scheduler_interval_seconds", 3600
+    )
+    seconds = int(value)
+    if seconds < 0:
+        raise ValueError(f"invalid scheduler interval: {seconds}")
+    return seconds
+
+
+def get_command_log_path() -> Optional[Path]:
+    value = os.environ.get("OIKONOMOS_COMMAND_LOG") or get_file_config().get("command_log")
+    return Path(str(value)).expanduser() if value else None
+
+
+def get_price_provider_url() -> Optional[str]:
+    value = os.environ.get("OIKONOMOS_PRICE_PROVIDER_URL") or get_file_config().get("price_provider_url")
+    return str(value) if value else None
+
+
+def get_receipt_reader_url() -> Optional[str]:
+    value = os.environ.get("OIKONOMOS_RECEIPT_READER_URL") or get_file_config().get("receipt_reader_url")
+    return str(value) if value else None
+
+
+def get_receipt_reader_command() -> Optional[str]:
+    value = os.environ.get("OIKONOMOS_RECEIPT_READER_COMMAND") or get_file_config().get("receipt_reader_command")
+    return str(value) if value else None
+
+
+def get_configured_setting(key: str) -> Optional[str]:
+    value = get_file_config().get(key)
+    return None if value is None else str(value)
+
+
 def get_db_path() -> Path:
     return get_data_dir() / "data.db"
 
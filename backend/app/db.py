@@ -3,14 +3,16 @@ from __future__ import annotations
 import sqlite3
 import uuid
 from contextlib import contextmanager
-from datetime import date, datetime, timezone
+from contextvars import ContextVar
+from datetime import date, datetime, timedelta, timezone
 from pathlib import Path
-from typing import Iterator, Optional
+from typing import Iterator, Optional, Sequence
+from zoneinfo import ZoneInfo
 
 import bcrypt
 
 from app.config import get_data_dir, get_db_path, get_default_admin_email, get_default_admin_password
-from app.models import ApiError
+from app.models import ApiError, add_months
 
 
 SCHEMA_SQL = """
@@ -39,24 +41,44 @@ CREATE TABLE IF NOT EXISTS accounts (
     type TEXT NOT NULL CHECK(type IN ('Asset', 'Liability')),
     purpose TEXT NOT NULL CHECK(purpose IN ('Investment', 'Productivity', 'LifeSupport', 'Spiritual')),
     balance_cents INTEGER NOT NULL,
+    -- The balance the account was opened with; balance_cents less every row that moved it.
+    opening_balance_cents INTEGER NULL,
+    exclude_from_adjustment_kpi INTEGER NOT NULL DEFAULT 0,
+    is_archived INTEGER NOT NULL DEFAULT 0,
+    currency TEXT NULL,
+    liquidity TEXT NOT NULL DEFAULT 'Liquid' CHECK(liquidity IN ('Liquid', 'Semi', 'Illiquid')),
+    interest_rate_percent REAL NULL CHECK(interest_rate_percent IS NULL OR interest_rate_percent >= 0),
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
+    device_id TEXT NULL,
     CHECK(type != 'Liability' OR balance_cents <= 0)
 );
 CREATE TABLE IF NOT EXISTS categories (
     id TEXT PRIMARY KEY,
     name TEXT NOT NULL UNIQUE,
     parent_id TEXT NULL REFERENCES categories(id) ON DELETE SET NULL,
-    is_active INTEGER NOT NULL DEFAULT 1
+    -- How pickers and reports show it: a "#rrggbb" color, an icon name, and its place in lists.
+    color TEXT NULL,
+    icon TEXT NULL,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    device_id TEXT NULL
 );
 CREATE TABLE IF NOT EXISTS tags (
     id TEXT PRIMARY KEY,
-    name TEXT NOT NULL UNIQUE
+    name TEXT NOT NULL UNIQUE,
+    -- "#rrggbb" for the tag's chip.
+    color TEXT NULL,
+    description TEXT NULL,
+    is_archived INTEGER NOT NULL DEFAULT 0,
+    device_id TEXT NULL
 );
 CREATE TABLE IF NOT EXISTS payees (
     id TEXT PRIMARY KEY,
     name TEXT NOT NULL UNIQUE,
-    default_category_id TEXT NULL REFERENCES categories(id) ON DELETE SET NULL
+    default_category_id TEXT NULL REFERENCES categories(id) ON DELETE SET NULL,
+    is_archived INTEGER NOT NULL DEFAULT 0,
+    device_id TEXT NULL
 );
 CREATE TABLE IF NOT EXISTS transactions (
     id TEXT PRIMARY KEY,
@@ -65,11 +87,34 @@ CREATE TABLE IF NOT EXISTS transactions (
     to_account_id TEXT NULL REFERENCES accounts(id) ON DELETE SET NULL,
     payee_id TEXT NULL REFERENCES payees(id) ON DELETE SET NULL,
     category_id TEXT NULL REFERENCES categories(id) ON DELETE SET NULL,
-    accrual_type TEXT NOT NULL CHECK(accrual_type IN ('Flow', 'Depreciation', 'Adjustment')),
+    accrual_type TEXT NOT NULL CHECK(accrual_type IN ('Flow', 'Depreciation', 'Adjustment', 'OpeningBalance')),
     is_asset_purchase INTEGER NOT NULL DEFAULT 0,
     note TEXT NULL,
     occurred_at TEXT NOT NULL,
-    created_at TEXT NOT NULL
+    created_at TEXT NOT NULL,
+    recurring_rule_id TEXT NULL REFERENCES recurring_rules(id) ON DELETE SET NULL,
+    -- On the offsetting entry that voided another transaction: the one it cancels.
+    reversal_of_id TEXT NULL REFERENCES transactions(id) ON DELETE SET NULL,
+    -- Whether the bank has shown the row yet; Reconciled once a statement reconciliation covered it.
+    status TEXT NOT NULL DEFAULT 'Cleared' CHECK(status IN ('Pending', 'Cleared', 'Reconciled')),
+    -- Set while the row sits in the trash; every balance, ledger and report skips it.
+    deleted_at TEXT NULL,
+    -- For imported rows: the bank's own reference if it gave one, and a hash of the line.
+    external_id TEXT NULL,
+    import_hash TEXT NULL,
+    device_id TEXT NULL,
+    period_ym TEXT GENERATED ALWAYS AS (substr(occurred_at, 1, 7)) VIRTUAL
+);
+-- The double-entry legs of each transaction: signed amounts that sum to zero, two or more.
+-- They are what a transaction did to each balance; from/to on the row only mirror a
+-- two-leg entry. A NULL account is the world outside the books, the other side of income
+-- and spending. An account with legs cannot be deleted out from under them.
+CREATE TABLE IF NOT EXISTS transaction_postings (
+    transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    leg INTEGER NOT NULL,
+    account_id TEXT NULL REFERENCES accounts(id) ON DELETE RESTRICT,
+    amount_cents INTEGER NOT NULL CHECK(amount_cents != 0),
+    PRIMARY KEY (transaction_id, leg)
 );
 CREATE TABLE IF NOT EXISTS transaction_tags (
     transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
@@ -79,13 +124,34 @@ CREATE TABLE IF NOT EXISTS transaction_tags (
 CREATE TABLE IF NOT EXISTS amortization_schedules (
     id TEXT PRIMARY KEY,
     asset_account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
-    strategy TEXT NOT NULL CHECK(strategy IN ('Linear', 'Accelerated')),
+    strategy TEXT NOT NULL CHECK(strategy IN ('Linear', 'Accelerated', 'DecliningBalance', 'Usage')),
     total_periods INTEGER NOT NULL CHECK(total_periods > 0),
     residual_cents INTEGER NOT NULL CHECK(residual_cents >= 0),
     start_date TEXT NOT NULL,
     source_transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
     status TEXT NOT NULL DEFAULT 'Active' CHECK(status IN ('Active', 'Completed', 'Cancelled')),
-    created_at TEXT NOT NULL
+    -- Set once part of the asset was disposed of: the share of the purchase this schedule
+    -- still depreciates, and the depreciation moved to or from the disposed part's schedule.
+    basis_cents INTEGER NULL CHECK(basis_cents IS NULL OR basis_cents > 0),
+    carried_depreciation_cents INTEGER NOT NULL DEFAULT 0,
+    -- When the part this schedule covers left the asset account.
+    disposed_at TEXT NULL,
+    -- Appraisal deltas booked against the asset. From rebased_period_ym on, the periods
+    -- left depreciate the last appraised carrying amount instead of the purchase.
+    revaluation_cents INTEGER NOT NULL DEFAULT 0,
+    rebased_period_ym TEXT NULL,
+    rebased_cents INTEGER NULL CHECK(rebased_cents IS NULL OR rebased_cents >= 0),
+    declining_factor_percent REAL NULL CHECK(declining_factor_percent IS NULL OR declining_factor_percent > 0),
+    -- Lifetime usage a Usage schedule spreads its depreciation over, in whatever unit is logged.
+    expected_units REAL NULL CHECK(expected_units IS NULL OR expected_units > 0),
+    -- Depreciate the first month only for the days in service; the rest falls in an extra month.
+    prorate_daily INTEGER NOT NULL DEFAULT 0,
+    -- Asset register details; the warranty expiry is a YYYY-MM-DD date.
+    serial_number TEXT NULL,
+    receipt_reference TEXT NULL,
+    warranty_expires_on TEXT NULL,
+    created_at TEXT NOT NULL,
+    device_id TEXT NULL
 );
 CREATE TABLE IF NOT EXISTS amortization_postings (
     id TEXT PRIMARY KEY,
@@ -94,8 +160,68 @@ CREATE TABLE IF NOT EXISTS amortization_postings (
     amount_cents INTEGER NOT NULL CHECK(amount_cents > 0),
     transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
     generated_at TEXT NOT NULL,
+    device_id TEXT NULL,
+    UNIQUE(schedule_id, period_ym)
+);
+CREATE TABLE IF NOT EXISTS usage_logs (
+    id TEXT PRIMARY KEY,
+    schedule_id TEXT NOT NULL REFERENCES amortization_schedules(id) ON DELETE CASCADE,
+    period_ym TEXT NOT NULL,
+    units REAL NOT NULL CHECK(units >= 0),
+    note TEXT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    device_id TEXT NULL,
     UNIQUE(schedule_id, period_ym)
 );
+CREATE TABLE IF NOT EXISTS asset_revaluations (
+    id TEXT PRIMARY KEY,
+    schedule_id TEXT NOT NULL REFERENCES amortization_schedules(id) ON DELETE CASCADE,
+    appraised_cents INTEGER NOT NULL CHECK(appraised_cents >= 0),
+    carrying_cents INTEGER NOT NULL,
+    -- Null when the appraisal matched the carrying amount and nothing was booked.
+    transaction_id TEXT NULL REFERENCES transactions(id) ON DELETE SET NULL,
+    occurred_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    device_id TEXT NULL
+);
+CREATE TABLE IF NOT EXISTS loans (
+    id TEXT PRIMARY KEY,
+    liability_account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    payment_account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    principal_cents INTEGER NOT NULL CHECK(principal_cents > 0),
+    apr_percent REAL NOT NULL CHECK(apr_percent >= 0),
+    term_months INTEGER NOT NULL CHECK(term_months > 0),
+    payment_day INTEGER NOT NULL CHECK(payment_day BETWEEN 1 AND 28),
+    start_period_ym TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'Active' CHECK(status IN ('Active', 'Completed', 'Cancelled')),
+    created_at TEXT NOT NULL,
+    device_id TEXT NULL
+);
+CREATE TABLE IF NOT EXISTS loan_payments (
+    id TEXT PRIMARY KEY,
+    loan_id TEXT NOT NULL REFERENCES loans(id) ON DELETE CASCADE,
+    period_ym TEXT NOT NULL,
+    principal_cents INTEGER NOT NULL CHECK(principal_cents >= 0),
+    interest_cents INTEGER NOT NULL CHECK(interest_cents >= 0),
+    principal_transaction_id TEXT NULL REFERENCES transactions(id) ON DELETE SET NULL,
+    interest_transaction_id TEXT NULL REFERENCES transactions(id) ON DELETE SET NULL,
+    generated_at TEXT NOT NULL,
+    device_id TEXT NULL,
+    UNIQUE(loan_id, period_ym)
+);
+CREATE TABLE IF NOT EXISTS interest_accruals (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    period_ym TEXT NOT NULL,
+    owed_cents INTEGER NOT NULL CHECK(owed_cents > 0),
+    rate_percent REAL NOT NULL,
+    amount_cents INTEGER NOT NULL CHECK(amount_cents > 0),
+    transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    generated_at TEXT NOT NULL,
+    device_id TEXT NULL,
+    UNIQUE(account_id, period_ym)
+);
 CREATE TABLE IF NOT EXISTS balance_snapshots (
     id TEXT PRIMARY KEY,
     account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
@@ -103,12 +229,430 @@ CREATE TABLE IF NOT EXISTS balance_snapshots (
     system_balance_cents INTEGER NOT NULL,
     delta_cents INTEGER NOT NULL,
     captured_at TEXT NOT NULL,
-    adjustment_tx_id TEXT NULL REFERENCES transactions(id) ON DELETE SET NULL
+    adjustment_tx_id TEXT NULL REFERENCES transactions(id) ON DELETE SET NULL,
+    device_id TEXT NULL
+);
+CREATE TABLE IF NOT EXISTS budgets (
+    id TEXT PRIMARY KEY,
+    category_id TEXT NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    period_ym TEXT NOT NULL,
+    amount_cents INTEGER NOT NULL CHECK(amount_cents >= 0),
+    rollover INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    device_id TEXT NULL,
+    UNIQUE(category_id, period_ym)
+);
+CREATE TABLE IF NOT EXISTS recurring_rules (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    amount_cents INTEGER NOT NULL CHECK(amount_cents > 0),
+    from_account_id TEXT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    to_account_id TEXT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    payee_id TEXT NULL REFERENCES payees(id) ON DELETE SET NULL,
+    category_id TEXT NULL REFERENCES categories(id) ON DELETE SET NULL,
+    note TEXT NULL,
+    frequency TEXT NOT NULL CHECK(frequency IN ('Daily', 'Weekly', 'Monthly', 'Yearly')),
+    interval_count INTEGER NOT NULL DEFAULT 1 CHECK(interval_count > 0),
+    start_date TEXT NOT NULL,
+    end_date TEXT NULL,
+    generated_count INTEGER NOT NULL DEFAULT 0,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    device_id TEXT NULL
+);
+CREATE TABLE IF NOT EXISTS bills (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    payee_id TEXT NULL REFERENCES payees(id) ON DELETE SET NULL,
+    liability_account_id TEXT NULL REFERENCES accounts(id) ON DELETE SET NULL,
+    expected_amount_cents INTEGER NOT NULL CHECK(expected_amount_cents > 0),
+    due_day INTEGER NOT NULL CHECK(due_day BETWEEN 1 AND 31),
+    is_active INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    device_id TEXT NULL
+);
+CREATE TABLE IF NOT EXISTS account_valuations (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    value_cents INTEGER NOT NULL CHECK(value_cents >= 0),
+    note TEXT NULL,
+    valued_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    device_id TEXT NULL
+);
+CREATE TABLE IF NOT EXISTS maintenance_events (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    occurred_on TEXT NOT NULL,
+    cost_cents INTEGER NOT NULL CHECK(cost_cents >= 0),
+    note TEXT NULL,
+    -- The payment for the work, when it was booked.
+    transaction_id TEXT NULL REFERENCES transactions(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL,
+    device_id TEXT NULL
+);
+CREATE TABLE IF NOT EXISTS account_photos (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    valuation_id TEXT NULL REFERENCES account_valuations(id) ON DELETE SET NULL,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    stored_name TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    device_id TEXT NULL
+);
+-- Receipts and other proof for a transaction. Files are named by the hash of their content,
+-- so the same scan attached twice is stored once.
+CREATE TABLE IF NOT EXISTS transaction_attachments (
+    id TEXT PRIMARY KEY,
+    transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    content_hash TEXT NOT NULL,
+    stored_name TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    device_id TEXT NULL
+);
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    id TEXT PRIMARY KEY,
+    from_currency TEXT NOT NULL,
+    to_currency TEXT NOT NULL,
+    rate REAL NOT NULL CHECK(rate > 0),
+    rate_date TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    device_id TEXT NULL,
+    UNIQUE(from_currency, to_currency, rate_date)
+);
+-- Coins held in an asset account, counted in hundred-millionths (satoshis for BTC); the
+-- account balance follows quantity times the last entered unit price.
+CREATE TABLE IF NOT EXISTS crypto_holdings (
+    account_id TEXT PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+    symbol TEXT NOT NULL,
+    quantity_units INTEGER NOT NULL CHECK(quantity_units >= 0),
+    unit_price_cents INTEGER NULL CHECK(unit_price_cents >= 0),
+    priced_at TEXT NULL,
+    updated_at TEXT NOT NULL
+);
+-- Securities held in a brokerage account. The quantity is whatever the open lots still hold;
+-- each buy opens a lot and sells consume the oldest lots first.
+CREATE TABLE IF NOT EXISTS holdings (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    ticker TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    device_id TEXT NULL,
+    UNIQUE(account_id, ticker)
+);
+CREATE TABLE IF NOT EXISTS lots (
+    id TEXT PRIMARY KEY,
+    holding_id TEXT NOT NULL REFERENCES holdings(id) ON DELETE CASCADE,
+    quantity_units INTEGER NOT NULL CHECK(quantity_units > 0),
+    remaining_units INTEGER NOT NULL CHECK(remaining_units >= 0),
+    cost_cents INTEGER NOT NULL CHECK(cost_cents >= 0),
+    remaining_cost_cents INTEGER NOT NULL CHECK(remaining_cost_cents >= 0),
+    acquired_at TEXT NOT NULL,
+    transaction_id TEXT NULL REFERENCES transactions(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL,
+    device_id TEXT NULL
+);
+CREATE TABLE IF NOT EXISTS lot_disposals (
+    id TEXT PRIMARY KEY,
+    lot_id TEXT NOT NULL REFERENCES lots(id) ON DELETE CASCADE,
+    transaction_id TEXT NULL REFERENCES transactions(id) ON DELETE SET NULL,
+    quantity_units INTEGER NOT NULL CHECK(quantity_units > 0),
+    cost_cents INTEGER NOT NULL CHECK(cost_cents >= 0),
+    disposed_at TEXT NOT NULL,
+    device_id TEXT NULL
 );
+-- Price of one unit of an instrument (a ticker or coin symbol) at the close of a day.
+CREATE TABLE IF NOT EXISTS prices (
+    id TEXT PRIMARY KEY,
+    instrument TEXT NOT NULL,
+    price_date TEXT NOT NULL,
+    price_cents INTEGER NOT NULL CHECK(price_cents >= 0),
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    device_id TEXT NULL,
+    UNIQUE(instrument, price_date)
+);
+-- Month-end unrealized gain or loss on a foreign-currency account, in the base currency.
+-- The posting itself has no accounts: the native balance did not move, only its worth.
+CREATE TABLE IF NOT EXISTS fx_revaluations (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    period_ym TEXT NOT NULL,
+    base_currency TEXT NOT NULL,
+    balance_cents INTEGER NOT NULL,
+    value_cents INTEGER NOT NULL,
+    gain_cents INTEGER NOT NULL CHECK(gain_cents != 0),
+    transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    generated_at TEXT NOT NULL,
+    device_id TEXT NULL,
+    UNIQUE(account_id, period_ym)
+);
+CREATE TABLE IF NOT EXISTS holding_revaluations (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    period_ym TEXT NOT NULL,
+    market_value_cents INTEGER NOT NULL,
+    cost_basis_cents INTEGER NOT NULL,
+    adjustment_cents INTEGER NOT NULL CHECK(adjustment_cents != 0),
+    transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    generated_at TEXT NOT NULL,
+    device_id TEXT NULL,
+    UNIQUE(account_id, period_ym)
+);
+CREATE TABLE IF NOT EXISTS allocation_targets (
+    purpose TEXT PRIMARY KEY CHECK(purpose IN ('Investment', 'Productivity', 'LifeSupport', 'Spiritual')),
+    target_percent REAL NOT NULL CHECK(target_percent >= 0 AND target_percent <= 100),
+    updated_at TEXT NOT NULL
+);
+-- How one bank's CSV export maps onto statement lines.
+CREATE TABLE IF NOT EXISTS import_profiles (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    account_id TEXT NULL REFERENCES accounts(id) ON DELETE SET NULL,
+    delimiter TEXT NOT NULL,
+    skip_rows INTEGER NOT NULL DEFAULT 0 CHECK(skip_rows >= 0),
+    date_column TEXT NOT NULL,
+    date_format TEXT NOT NULL,
+    amount_column TEXT NULL,
+    debit_column TEXT NULL,
+    credit_column TEXT NULL,
+    description_column TEXT NULL,
+    external_id_column TEXT NULL,
+    sign_convention TEXT NOT NULL CHECK(sign_convention IN ('Signed', 'Inverted', 'DebitCredit')),
+    decimal_separator TEXT NOT NULL CHECK(decimal_separator IN ('.', ',')),
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    device_id TEXT NULL
+);
+-- Statement lines waiting for the user to confirm them; nothing here touches the ledger.
+CREATE TABLE IF NOT EXISTS import_batches (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS import_rows (
+    batch_id TEXT NOT NULL REFERENCES import_batches(id) ON DELETE CASCADE,
+    line_index INTEGER NOT NULL,
+    occurred_on TEXT NOT NULL,
+    amount_cents INTEGER NOT NULL CHECK(amount_cents != 0),
+    description TEXT NULL,
+    external_id TEXT NULL,
+    import_hash TEXT NOT NULL,
+    payee_id TEXT NULL REFERENCES payees(id) ON DELETE SET NULL,
+    PRIMARY KEY (batch_id, line_index)
+);
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    id TEXT PRIMARY KEY,
+    status TEXT NOT NULL CHECK(status IN ('Running', 'Completed')),
+    processed_count INTEGER NOT NULL DEFAULT 0,
+    total_count INTEGER NOT NULL DEFAULT 0,
+    started_at TEXT NOT NULL,
+    completed_at TEXT NULL
+);
+CREATE TABLE IF NOT EXISTS monthly_category_totals (
+    period_ym TEXT NOT NULL,
+    category_id TEXT NOT NULL,
+    direction TEXT NOT NULL CHECK(direction IN ('Inflow', 'Outflow', 'Transfer')),
+    accrual_type TEXT NOT NULL,
+    is_asset_purchase INTEGER NOT NULL,
+    amount_cents INTEGER NOT NULL,
+    transaction_count INTEGER NOT NULL,
+    PRIMARY KEY (period_ym, category_id, direction, accrual_type, is_asset_purchase)
+) WITHOUT ROWID;
 CREATE INDEX IF NOT EXISTS idx_transactions_occurred_at ON transactions(occurred_at);
 CREATE INDEX IF NOT EXISTS idx_transactions_accrual_type_occurred_at ON transactions(accrual_type, occurred_at);
+CREATE INDEX IF NOT EXISTS idx_transaction_postings_account ON transaction_postings(account_id, transaction_id);
 CREATE INDEX IF NOT EXISTS idx_amortization_postings_schedule_period ON amortization_postings(schedule_id, period_ym);
 CREATE INDEX IF NOT EXISTS idx_balance_snapshots_account_captured ON balance_snapshots(account_id, captured_at DESC);
+CREATE INDEX IF NOT EXISTS idx_account_valuations_account_valued ON account_valuations(account_id, valued_at);
+CREATE INDEX IF NOT EXISTS idx_maintenance_events_account ON maintenance_events(account_id, occurred_on);
+CREATE INDEX IF NOT EXISTS idx_transaction_attachments_transaction ON transaction_attachments(transaction_id);
+CREATE INDEX IF NOT EXISTS idx_lots_holding_acquired ON lots(holding_id, acquired_at);
+CREATE INDEX IF NOT EXISTS idx_lot_disposals_lot ON lot_disposals(lot_id);
+"""
+
+# Tables whose rows remember which installation created or last modified them.
+DEVICE_STAMPED_TABLES: tuple[str, ...] = (
+    "accounts",
+    "categories",
+    "tags",
+    "payees",
+    "transactions",
+    "amortization_schedules",
+    "amortization_postings",
+    "asset_revaluations",
+    "usage_logs",
+    "loans",
+    "loan_payments",
+    "interest_accruals",
+    "balance_snapshots",
+    "budgets",
+    "recurring_rules",
+    "bills",
+    "account_valuations",
+    "maintenance_events",
+    "account_photos",
+    "transaction_attachments",
+    "exchange_rates",
+    "fx_revaluations",
+    "holdings",
+    "lots",
+    "lot_disposals",
+    "prices",
+    "holding_revaluations",
+    "import_profiles",
+)
+DEVICE_ID_KEY = "device_id"
+
+
+def _transaction_direction_sql(row: str) -> str:
+    return f"""CASE
+        WHEN {row}.from_account_id IS NOT NULL AND {row}.to_account_id IS NOT NULL THEN 'Transfer'
+        WHEN {row}.from_account_id IS NOT NULL THEN 'Outflow'
+        -- A multi-leg flow keeps its accounts in the postings and moves money between them.
+        WHEN {row}.to_account_id IS NULL AND {row}.accrual_type = 'Flow' THEN 'Transfer'
+        ELSE 'Inflow'
+    END"""
+
+
+def _monthly_totals_delta_sql(row: str, sign: str) -> str:
+    delta = f"""
+    INSERT INTO monthly_category_totals (
+        period_ym, category_id, direction, accrual_type, is_asset_purchase, amount_cents, transaction_count
+    )
+    SELECT
+        {row}.period_ym, IFNULL({row}.category_id, ''), {_transaction_direction_sql(row)},
+        {row}.accrual_type, {row}.is_asset_purchase, {sign}{row}.amount_cents, {sign}1
+    WHERE {row}.deleted_at IS NULL
+    ON CONFLICT(period_ym, category_id, direction, accrual_type, is_asset_purchase) DO UPDATE SET
+        amount_cents = amount_cents + excluded.amount_cents,
+        transaction_count = transaction_count + excluded.transaction_count;"""
+    if sign == "-":
+        delta += f"""
+    DELETE FROM monthly_category_totals
+    WHERE period_ym = {row}.period_ym AND transaction_count = 0;"""
+    return delta
+
+
+# Per calendar month sums of every transaction, kept in step by triggers so reports can read
+# a handful of rows instead of scanning years of history. Like the device triggers these are
+# part of the database file: every writer, including a plain sqlite3 shell, must keep them true.
+# Rows in the trash count towards nothing. The triggers are dropped and created again on every
+# start so databases made by an older version pick up changes to them.
+MONTHLY_TOTALS_SQL = f"""
+DROP TRIGGER IF EXISTS monthly_totals_insert;
+DROP TRIGGER IF EXISTS monthly_totals_delete;
+DROP TRIGGER IF EXISTS monthly_totals_update;
+CREATE TRIGGER IF NOT EXISTS monthly_totals_insert AFTER INSERT ON transactions
+BEGIN {_monthly_totals_delta_sql("NEW", "+")}
+END;
+CREATE TRIGGER IF NOT EXISTS monthly_totals_delete AFTER DELETE ON transactions
+BEGIN {_monthly_totals_delta_sql("OLD", "-")}
+END;
+CREATE TRIGGER IF NOT EXISTS monthly_totals_update AFTER UPDATE OF
+    amount_cents, from_account_id, to_account_id, category_id, accrual_type, is_asset_purchase, occurred_at, deleted_at
+ON transactions
+BEGIN {_monthly_totals_delta_sql("OLD", "-")} {_monthly_totals_delta_sql("NEW", "+")}
+END;
+"""
+
+
+def _device_trigger_sql(table: str) -> str:
+    device_id = f"(SELECT value FROM settings WHERE key = '{DEVICE_ID_KEY}')"
+    return f"""
+DROP TRIGGER IF EXISTS stamp_{table}_insert;
+DROP TRIGGER IF EXISTS stamp_{table}_update;
+CREATE TRIGGER stamp_{table}_insert AFTER INSERT ON {table}
+WHEN NEW.device_id IS NULL
+BEGIN UPDATE {table} SET device_id = {device_id} WHERE id = NEW.id; END;
+CREATE TRIGGER stamp_{table}_update AFTER UPDATE ON {table}
+WHEN NEW.device_id IS OLD.device_id AND NEW.device_id IS NOT {device_id}
+BEGIN UPDATE {table} SET device_id = {device_id} WHERE id = NEW.id; END;
+"""
+
+
+# Every row names the installation that created or last modified it, read from `settings`
+# when the row is written so no service has to pass the id along. A write that sets
+# device_id itself keeps it, as a row merged from another installation would. Only the
+# latest writer is kept: there is no history of earlier changes. Recreated on every start
+# like the monthly totals, which also restores them on tables a constraint migration rebuilt.
+DEVICE_TRIGGERS_SQL = "".join(_device_trigger_sql(table) for table in DEVICE_STAMPED_TABLES)
+
+# Postings used to be derived from from/to by triggers; commands now write them with the
+# transaction (`insert_postings`), so older databases lose the triggers on start.
+POSTINGS_SQL = """
+DROP TRIGGER IF EXISTS transaction_postings_insert;
+DROP TRIGGER IF EXISTS transaction_postings_update;
+"""
+
+# Columns added after the initial schema. Fresh databases get them from SCHEMA_SQL,
+# existing databases are upgraded in place on startup.
+COLUMN_MIGRATIONS: tuple[tuple[str, str, str], ...] = (
+    ("accounts", "exclude_from_adjustment_kpi", "INTEGER NOT NULL DEFAULT 0"),
+    ("accounts", "is_archived", "INTEGER NOT NULL DEFAULT 0"),
+    ("accounts", "currency", "TEXT NULL"),
+    ("accounts", "liquidity", "TEXT NOT NULL DEFAULT 'Liquid' CHECK(liquidity IN ('Liquid', 'Semi', 'Illiquid'))"),
+    ("accounts", "interest_rate_percent", "REAL NULL CHECK(interest_rate_percent IS NULL OR interest_rate_percent >= 0)"),
+    ("accounts", "opening_balance_cents", "INTEGER NULL"),
+    ("amortization_schedules", "basis_cents", "INTEGER NULL CHECK(basis_cents IS NULL OR basis_cents > 0)"),
+    ("amortization_schedules", "carried_depreciation_cents", "INTEGER NOT NULL DEFAULT 0"),
+    ("amortization_schedules", "disposed_at", "TEXT NULL"),
+    ("amortization_schedules", "revaluation_cents", "INTEGER NOT NULL DEFAULT 0"),
+    ("amortization_schedules", "rebased_period_ym", "TEXT NULL"),
+    ("amortization_schedules", "rebased_cents", "INTEGER NULL CHECK(rebased_cents IS NULL OR rebased_cents >= 0)"),
+    (
+        "amortization_schedules",
+        "declining_factor_percent",
+        "REAL NULL CHECK(declining_factor_percent IS NULL OR declining_factor_percent > 0)",
+    ),
+    ("amortization_schedules", "expected_units", "REAL NULL CHECK(expected_units IS NULL OR expected_units > 0)"),
+    ("amortization_schedules", "prorate_daily", "INTEGER NOT NULL DEFAULT 0"),
+    ("amortization_schedules", "serial_number", "TEXT NULL"),
+    ("amortization_schedules", "receipt_reference", "TEXT NULL"),
+    ("amortization_schedules", "warranty_expires_on", "TEXT NULL"),
+    ("payees", "is_archived", "INTEGER NOT NULL DEFAULT 0"),
+    ("tags", "is_archived", "INTEGER NOT NULL DEFAULT 0"),
+    ("tags", "color", "TEXT NULL"),
+    ("tags", "description", "TEXT NULL"),
+    ("categories", "color", "TEXT NULL"),
+    ("categories", "icon", "TEXT NULL"),
+    ("categories", "sort_order", "INTEGER NOT NULL DEFAULT 0"),
+    ("transactions", "recurring_rule_id", "TEXT NULL REFERENCES recurring_rules(id) ON DELETE SET NULL"),
+    ("transactions", "reversal_of_id", "TEXT NULL REFERENCES transactions(id) ON DELETE SET NULL"),
+    (
+        "transactions",
+        "status",
+        "TEXT NOT NULL DEFAULT 'Cleared' CHECK(status IN ('Pending', 'Cleared', 'Reconciled'))",
+    ),
+    ("transactions", "deleted_at", "TEXT NULL"),
+    ("transactions", "external_id", "TEXT NULL"),
+    ("transactions", "import_hash", "TEXT NULL"),
+    *((table, "device_id", "TEXT NULL") for table in DEVICE_STAMPED_TABLES),
+    # Calendar month of occurred_at; SQLite computes it on write and the index below stores it.
+    ("transactions", "period_ym", "TEXT GENERATED ALWAYS AS (substr(occurred_at, 1, 7)) VIRTUAL"),
+)
+
+# Indexes over migrated columns, created once older databases have been given the column.
+MIGRATED_INDEX_SQL = """
+CREATE INDEX IF NOT EXISTS idx_transactions_period_ym_category ON transactions(period_ym, category_id);
+CREATE INDEX IF NOT EXISTS idx_transactions_external_id ON transactions(external_id);
+CREATE INDEX IF NOT EXISTS idx_transactions_import_hash ON transactions(import_hash);
+CREATE INDEX IF NOT EXISTS idx_transactions_reversal_of ON transactions(reversal_of_id);
 """
 
 
@@ -139,30 +683,113 @@ def normalize_timestamp(value: Optional[str]) -> str:
     return parsed.astimezone(timezone.utc).replace(microsecond=0).isoformat().replace("+00:00", "Z")
 
 
-def parse_period(period_ym: str) -> tuple[date, str, str]:
+def _format_utc(moment: datetime) -> str:
+    return moment.astimezone(timezone.utc).isoformat().replace("+00:00", "Z")
+
+
+def day_start_utc(day: date, tz: Optional[ZoneInfo] = None) -> str:
+    """The UTC timestamp at which `day` begins in `tz`; without one, days are UTC days."""
+    if tz is None:
+        return f"{day.isoformat()}T00:00:00Z"
+    return _format_utc(datetime(day.year, day.month, day.day, tzinfo=tz))
+
+
+def day_end_utc(day: date, tz: Optional[ZoneInfo] = None) -> str:
+    """The last whole second of `day` in `tz`, where entries closing a day or month are booked."""
+    next_day = day + timedelta(days=1)
+    if tz is None:
+        return f"{day.isoformat()}T23:59:59Z"
+    return _format_utc(datetime(next_day.year, next_day.month, next_day.day, tzinfo=tz) - timedelta(seconds=1))
+
+
+def local_date(value: str, tz: Optional[ZoneInfo] = None) -> date:
+    """The day an RFC3339 timestamp falls on in `tz`."""
+    moment = parse_rfc3339_utc(value)
+    return (moment if tz is None else moment.astimezone(tz)).date()
+
+
+def local_today(tz: Optional[ZoneInfo] = None) -> date:
+    return datetime.now(tz or timezone.utc).date()
+
+
+def _sql_local_time(value: Optional[str], key: str) -> Optional[str]:
+    if value is None:
+        return None
+    moment = datetime.fromisoformat(value.replace("Z", "+00:00")).astimezone(ZoneInfo(key))
+    return moment.strftime("%Y-%m-%d %H:%M:%S")
+
+
+def local_time_sql(column: str, tz: Optional[ZoneInfo] = None) -> str:
+    """SQL reading a UTC timestamp column as wall-clock time in `tz`."""
+    if tz is None:
+        return column
+    key = tz.key.replace("'", "''")
+    return f"local_time({column}, '{key}')"
+
+
+def local_day_sql(column: str, tz: Optional[ZoneInfo] = None) -> str:
+    """SQL computing the YYYY-MM-DD day a timestamp column falls on in `tz`."""
+    return f"substr({local_time_sql(column, tz)}, 1, 10)"
+
+
+def parse_period(
+    period_ym: str, allow_spans: bool = False, start_day: int = 1, tz: Optional[ZoneInfo] = None
+) -> tuple[date, str, str]:
+    """Parses YYYY-MM into its first day and [start, end) timestamps.
+
+    With `allow_spans`, reports may also pass a quarter (YYYY-Qn) or a whole year (YYYY);
+    everything keyed by a single month (budgets, depreciation) keeps the strict form.
+    A `start_day` after the 1st shifts every boundary, so with 25 the period 2026-03
+    runs from March 25th up to and including April 24th. With `tz`, the boundaries fall
+    on midnight in that timezone rather than in UTC.
+    """
     parts = period_ym.split("-")
-    if len(parts) != 2:
+    if len(parts) not in ((1, 2) if allow_spans else (2,)):
         raise ApiError("invalid_input", f"invalid periodYm: {period_ym}")
 
     try:
         year = int(parts[0])
-        month = int(parts[1])
-        start = date(year, month, 1)
+        if len(parts) == 1:
+            start, months = date(year, 1, 1), 12
+        elif allow_spans and parts[1][:1] in {"Q", "q"}:
+            quarter = int(parts[1][1:])
+            if quarter < 1 or quarter > 4:
+                raise ValueError(quarter)
+            start, months = date(year, quarter * 3 - 2, 1), 3
+        else:
+            start, months = date(year, int(parts[1]), 1), 1
     except ValueError as exc:
         raise ApiError("invalid_input", f"invalid periodYm: {period_ym}") from exc
 
-    if month == 12:
-        next_month = date(year + 1, 1, 1)
-    else:
-        next_month = date(year, month + 1, 1)
-
     return (
-        start,
-        f"{start.isoformat()}T00:00:00Z",
-        f"{next_month.isoformat()}T00:00:00Z",
+        start.replace(day=start_day),
+        day_start_utc(start.replace(day=start_day), tz),
+        day_start_utc(add_months(start, months).replace(day=start_day), tz),
     )
 
 
+def period_of(day: date, start_day: int = 1) -> str:
+    """The YYYY-MM label of the period containing `day` when periods begin on `start_day`."""
+    shifted = day - timedelta(days=start_day - 1)
+    return format_period(date(shifted.year, shifted.month, 1))
+
+
+def transaction_period_sql(start_day: int = 1, alias: str = "", tz: Optional[ZoneInfo] = None) -> str:
+    """SQL computing `period_of` for a transaction's occurred_at.
+
+    Calendar months in UTC read the indexed `period_ym` column; a shifted start day or a
+    timezone has to be computed per row.
+    """
+    prefix = f"{alias}." if alias else ""
+    if start_day == 1 and tz is None:
+        return f"{prefix}period_ym"
+    return f"strftime('%Y-%m', {local_time_sql(f'{prefix}occurred_at', tz)}, '-{start_day - 1} days')"
+
+
+def format_period(month_start: date) -> str:
+    return f"{month_start.year:04d}-{month_start.month:02d}"
+
+
 def parse_date_ymd(value: str, field_name: str) -> date:
     try:
         return date.fromisoformat(value)
@@ -176,11 +803,55 @@ def ensure_data_dir() -> Path:
     return data_dir
 
 
+_device_ids: dict[str, str] = {}
+_query_only: ContextVar[bool] = ContextVar("query_only", default=False)
+_memory_database: ContextVar[Optional[str]] = ContextVar("memory_database", default=None)
+
+
+def refuse_writes() -> None:
+    """Open every later connection in the current context with SQLite's query_only set."""
+    _query_only.set(True)
+
+
+@contextmanager
+def use_memory_database() -> Iterator[None]:
+    """Open every connection in the current context on one fresh in-memory database (used by replay)."""
+    uri = f"file:oikonomos-{uuid.uuid4()}?mode=memory&cache=shared"
+    # A shared in-memory database lives only while some connection to it is open.
+    keeper = sqlite3.connect(uri, uri=True)
+    token = _memory_database.set(uri)
+    try:
+        yield
+    finally:
+        _memory_database.reset(token)
+        keeper.close()
+
+
+def get_device_id(conn: sqlite3.Connection) -> Optional[str]:
+    db_path = _memory_database.get() or str(get_db_path())
+    if db_path not in _device_ids:
+        try:
+            row = conn.execute("SELECT value FROM settings WHERE key = ?", (DEVICE_ID_KEY,)).fetchone()
+        except sqlite3.OperationalError:
+            return None
+        if row is None:
+            return None
+        _device_ids[db_path] = row["value"]
+    return _device_ids[db_path]
+
+
 def get_connection() -> sqlite3.Connection:
-    ensure_data_dir()
-    conn = sqlite3.connect(get_db_path())
+    memory_database = _memory_database.get()
+    if memory_database is not None:
+        conn = sqlite3.connect(memory_database, uri=True)
+    else:
+        ensure_data_dir()
+        conn = sqlite3.connect(get_db_path())
     conn.row_factory = sqlite3.Row
     conn.execute("PRAGMA foreign_keys = ON")
+    conn.create_function("local_time", 2, _sql_local_time, deterministic=True)
+    if _query_only.get():
+        conn.execute("PRAGMA query_only = ON")
     return conn
 
 
@@ -203,10 +874,192 @@ def ensure_default_admin_user(conn: sqlite3.Connection) -> None:
     )
 
 
+def ensure_device_id(conn: sqlite3.Connection) -> None:
+    if conn.execute("SELECT 1 FROM settings WHERE key = ?", (DEVICE_ID_KEY,)).fetchone() is not None:
+        return
+    conn.execute(
+        "INSERT INTO settings (key, value, updated_at) VALUES (?, ?, ?)",
+        (DEVICE_ID_KEY, str(uuid.uuid4()), now_utc_rfc3339()),
+    )
+
+
+# (table, old text, new text) for CHECK constraints widened after release. SQLite cannot
+# alter a constraint, so the table is copied into one created from the edited definition.
+CONSTRAINT_MIGRATIONS: tuple[tuple[str, str, str], ...] = (
+    (
+        "amortization_schedules",
+        "CHECK(strategy IN ('Linear', 'Accelerated'))",
+        "CHECK(strategy IN ('Linear', 'Accelerated', 'DecliningBalance'))",
+    ),
+    (
+        "amortization_schedules",
+        "CHECK(strategy IN ('Linear', 'Accelerated', 'DecliningBalance'))",
+        "CHECK(strategy IN ('Linear', 'Accelerated', 'DecliningBalance', 'Usage'))",
+    ),
+    (
+        "transactions",
+        "CHECK(accrual_type IN ('Flow', 'Depreciation', 'Adjustment'))",
+        "CHECK(accrual_type IN ('Flow', 'Depreciation', 'Adjustment', 'OpeningBalance'))",
+    ),
+    (
+        "transaction_postings",
+        "account_id TEXT NULL REFERENCES accounts(id) ON DELETE SET NULL",
+        "account_id TEXT NULL REFERENCES accounts(id) ON DELETE RESTRICT",
+    ),
+)
+
+
+def _rebuild_with_constraint(conn: sqlite3.Connection, table: str, old: str, new: str) -> None:
+    row = conn.execute("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?", (table,)).fetchone()
+    if row is None or old not in row["sql"]:
+        return
+    create_sql = row["sql"].replace(old, new).replace(table, f"{table}_rebuilt", 1)
+    # Dropping the table takes its indexes and triggers with it, so they are put back after.
+    dependents = [
+        dependent["sql"]
+        for dependent in conn.execute(
+            "SELECT sql FROM sqlite_master WHERE type IN ('index', 'trigger') AND tbl_name = ? AND sql IS NOT NULL",
+            (table,),
+        ).fetchall()
+    ]
+    # Generated columns are computed again by the new table and cannot be copied into it.
+    columns = ", ".join(
+        column["name"] for column in conn.execute(f"PRAGMA table_xinfo({table})").fetchall() if column["hidden"] == 0
+    )
+    # Other tables point at this one; foreign keys stay off while it is briefly missing.
+    conn.execute("PRAGMA foreign_keys = OFF")
+    try:
+        with transaction(conn):
+            conn.execute(create_sql)
+            conn.execute(f"INSERT INTO {table}_rebuilt ({columns}) SELECT {columns} FROM {table}")
+            conn.execute(f"DROP TABLE {table}")
+            conn.execute(f"ALTER TABLE {table}_rebuilt RENAME TO {table}")
+            for sql in dependents:
+                conn.execute(sql)
+    finally:
+        conn.execute("PRAGMA foreign_keys = ON")
+
+
+def migrate_schema(conn: sqlite3.Connection) -> None:
+    for table, column, definition in COLUMN_MIGRATIONS:
+        # table_xinfo, unlike table_info, also lists generated columns.
+        existing = {row["name"] for row in conn.execute(f"PRAGMA table_xinfo({table})").fetchall()}
+        if column not in existing:
+            conn.execute(f"ALTER TABLE {table} ADD COLUMN {column} {definition}")
+    for table, old, new in CONSTRAINT_MIGRATIONS:
+        _rebuild_with_constraint(conn, table, old, new)
+
+
+def rebuild_monthly_category_totals(conn: sqlite3.Connection) -> None:
+    conn.execute("DELETE FROM monthly_category_totals")
+    conn.execute(
+        f"""
+        INSERT INTO monthly_category_totals (
+            period_ym, category_id, direction, accrual_type, is_asset_purchase, amount_cents, transaction_count
+        )
+        SELECT
+          period_ym, IFNULL(category_id, ''), {_transaction_direction_sql("transactions")},
+          accrual_type, is_asset_purchase, SUM(amount_cents), COUNT(*)
+        FROM transactions
+        WHERE deleted_at IS NULL
+        GROUP BY 1, 2, 3, 4, 5
+        """
+    )
+
+
+def ensure_monthly_category_totals(conn: sqlite3.Connection) -> None:
+    # Every live transaction counts towards some row, so an empty table next to existing
+    # transactions means the database predates the triggers.
+    if conn.execute("SELECT 1 FROM monthly_category_totals LIMIT 1").fetchone() is not None:
+        return
+    if conn.execute("SELECT 1 FROM transactions WHERE deleted_at IS NULL LIMIT 1").fetchone() is None:
+        return
+    rebuild_monthly_category_totals(conn)
+
+
+def two_leg_postings(
+    from_account_id: Optional[str], to_account_id: Optional[str], amount_cents: int
+) -> list[tuple[Optional[str], int]]:
+    """The legs of a from/to entry: what leaves the source side arrives on the destination side."""
+    return [(from_account_id, -amount_cents), (to_account_id, amount_cents)]
+
+
+def validate_postings(legs: Sequence[tuple[Optional[str], int]]) -> None:
+    if len(legs) < 2:
+        raise ApiError("invalid_input", "a transaction needs at least two postings", details={"field": "postings"})
+    if any(amount == 0 for _, amount in legs):
+        raise ApiError("invalid_input", "postings cannot be zero", details={"field": "postings"})
+    if sum(amount for _, amount in legs) != 0:
+        raise ApiError("invalid_input", "postings must sum to zero", details={"field": "postings"})
+
+
+def insert_postings(conn: sqlite3.Connection, tx_id: str, legs: Sequence[tuple[Optional[str], int]]) -> None:
+    validate_postings(legs)
+    conn.executemany(
+        "INSERT INTO transaction_postings (transaction_id, leg, account_id, amount_cents) VALUES (?, ?, ?, ?)",
+        [(tx_id, leg, account_id, amount) for leg, (account_id, amount) in enumerate(legs)],
+    )
+
+
+def rebuild_transaction_postings(conn: sqlite3.Connection) -> None:
+    # Databases from before the ledger only have two-leg rows, so from/to say it all.
+    conn.execute("DELETE FROM transaction_postings")
+    conn.execute(
+        """
+        INSERT INTO transaction_postings (transaction_id, leg, account_id, amount_cents)
+        SELECT id, 0, from_account_id, -amount_cents FROM transactions WHERE accrual_type != 'Depreciation'
+        UNION ALL
+        SELECT id, 1, to_account_id, amount_cents FROM transactions WHERE accrual_type != 'Depreciation'
+        """
+    )
+
+
+def ensure_transaction_postings(conn: sqlite3.Connection) -> None:
+    # Only databases from before the ledger have balance-moving rows without legs.
+    if conn.execute("SELECT 1 FROM transaction_postings LIMIT 1").fetchone() is not None:
+        return
+    if conn.execute("SELECT 1 FROM transactions WHERE accrual_type != 'Depreciation' LIMIT 1").fetchone() is None:
+        return
+    rebuild_transaction_postings(conn)
+
+
+def ensure_opening_balances(conn: sqlite3.Connection) -> None:
+    # Accounts from before opening balances were kept get the one their history implies,
+    # taking today's balance as right.
+    conn.execute(
+        """
+        UPDATE accounts
+        SET opening_balance_cents = balance_cents - COALESCE((
+            SELECT SUM(p.amount_cents)
+            FROM transaction_postings p JOIN transactions t ON t.id = p.transaction_id
+            WHERE p.account_id = accounts.id AND t.deleted_at IS NULL
+        ), 0)
+        WHERE opening_balance_cents IS NULL
+        """
+    )
+
+
+def covers_whole_months(first_day: date, last_day: date, tz: Optional[ZoneInfo] = None) -> bool:
+    """Whether [first_day, last_day] can be answered from `monthly_category_totals`.
+
+    Those totals are kept per UTC month, so with a timezone set every range is summed row by row.
+    """
+    return tz is None and first_day.day == 1 and (last_day + timedelta(days=1)).day == 1
+
+
 def initialize_database() -> None:
     ensure_data_dir()
     with get_connection() as conn:
         conn.executescript(SCHEMA_SQL)
+        migrate_schema(conn)
+        conn.executescript(MIGRATED_INDEX_SQL)
+        ensure_device_id(conn)
+        conn.executescript(DEVICE_TRIGGERS_SQL)
+        conn.executescript(MONTHLY_TOTALS_SQL)
+        ensure_monthly_category_totals(conn)
+        conn.executescript(POSTINGS_SQL)
+        ensure_transaction_postings(conn)
+        ensure_opening_balances(conn)
         ensure_default_admin_user(conn)
 
 
@@ -219,3 +1072,30 @@ def transaction(conn: sqlite3.Connection) -> Iterator[None]:
     except Exception:
         conn.rollback()
         raise
+
+
+@contextmanager
+def scratch_copy(conn: sqlite3.Connection) -> Iterator[sqlite3.Connection]:
+    """An in-memory copy of the database for projections that run the writers and discard the result."""
+    scratch = sqlite3.connect(":memory:")
+    conn.backup(scratch)
+    scratch.row_factory = sqlite3.Row
+    scratch.execute("PRAGMA foreign_keys = ON")
+    scratch.create_function("local_time", 2, _sql_local_time, deterministic=True)
+    try:
+        yield scratch
+    finally:
+        scratch.close()
+
+
+@contextmanager
+def savepoint(conn: sqlite3.Connection, name: str) -> Iterator[None]:
+    """Nested inside `transaction`: a failure undoes only this block's writes and re-raises."""
+    conn.execute(f"SAVEPOINT {name}")
+    try:
+        yield
+    except Exception:
+        conn.execute(f"ROLLBACK TO {name}")
+        conn.execute(f"RELEASE {name}")
+        raise
+    conn.execute(f"RELEASE {name}")
@@ -0,0 +1,34 @@
+from __future__ import annotations
+
+import threading
+from collections import deque
+from typing import Any
+
+from app.db import now_utc_rfc3339
+from app.models import EventDto
+
+
+MAX_BUFFERED_EVENTS = 500
+
+_lock = threading.Lock()
+_events: deque[EventDto] = deque(maxlen=MAX_BUFFERED_EVENTS)
+_last_seq = 0
+
+
+def emit(event_type: str, payload: dict[str, Any]) -> EventDto:
+    global _last_seq
+    with _lock:
+        _last_seq += 1
+        event = EventDto(seq=_last_seq, type=event_type, payload=payload, emittedAt=now_utc_rfc3339())
+        _events.append(event)
+        return event
+
+
+def events_since(after_seq: int = 0) -> list[EventDto]:
+    with _lock:
+        return [event for event in _events if event.seq > after_seq]
+
+
+def last_seq() -> int:
+    with _lock:
+        return _last_seq
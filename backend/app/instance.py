@@ -0,0 +1,227 @@
+"""Single-writer coordination between backend instances sharing one data dir.
+
+The owner holds an exclusive `flock` on `instance.lock` and refreshes the heartbeat written
+in it; later instances run read-only. The OS lock makes claiming atomic, so two instances
+starting together cannot both win, and the kernel drops it when its holder exits. A follower
+asks for ownership by writing `instance.takeover`; the owner names it in the lock file and
+lets go on its next heartbeat, and both sides emit events so their windows can switch modes.
+A follower takes a free lock on its own heartbeat once the file names it or has gone stale.
+"""
+
+from __future__ import annotations
+
+import fcntl
+import json
+import logging
+import os
+import threading
+import uuid
+from datetime import datetime, timedelta, timezone
+from pathlib import Path
+from typing import Any, Optional
+
+from app import events
+from app.config import get_data_dir
+from app.db import now_utc_rfc3339, parse_rfc3339_utc
+from app.models import ApiError, InstanceStatusDto
+
+
+logger = logging.getLogger(__name__)
+
+LOCK_FILE_NAME = "instance.lock"
+TAKEOVER_FILE_NAME = "instance.takeover"
+HEARTBEAT_SECONDS = 5
+STALE_AFTER_SECONDS = 20
+
+_instance_id = str(uuid.uuid4())
+_state_lock = threading.Lock()
+_is_owner = False
+_data_dir: Optional[Path] = None
+_lock_fd: Optional[int] = None
+_stop = threading.Event()
+_thread: Optional[threading.Thread] = None
+
+
+def _path(name: str) -> Path:
+    return (_data_dir or get_data_dir()) / name
+
+
+def _read(name: str) -> Optional[dict[str, Any]]:
+    try:
+        return json.loads(_path(name).read_text(encoding="utf-8"))
+    except (FileNotFoundError, ValueError):
+        return None
+
+
+def _write(name: str, payload: dict[str, Any]) -> None:
+    # Write-then-rename so another instance never reads a half-written file.
+    target = _path(name)
+    scratch = target.with_name(f"{target.name}.{_instance_id}.tmp")
+    scratch.write_text(json.dumps(payload), encoding="utf-8")
+    os.replace(scratch, target)
+
+
+def _remove(name: str) -> None:
+    _path(name).unlink(missing_ok=True)
+
+
+def _write_lock(owner_id: str, owner_pid: int) -> None:
+    # In place through the locked descriptor: the OS lock belongs to this inode, so the
+    # file is never renamed over or removed.
+    payload = json.dumps({"instanceId": owner_id, "pid": owner_pid, "heartbeatAt": now_utc_rfc3339()})
+    os.ftruncate(_lock_fd, 0)
+    os.pwrite(_lock_fd, payload.encode("utf-8"), 0)
+
+
+def _try_lock() -> bool:
+    global _lock_fd
+    if _lock_fd is not None:
+        return True
+    fd = os.open(_path(LOCK_FILE_NAME), os.O_RDWR | os.O_CREAT, 0o644)
+    try:
+        fcntl.flock(fd, fcntl.LOCK_EX | fcntl.LOCK_NB)
+    except BlockingIOError:
+        os.close(fd)
+        return False
+    _lock_fd = fd
+    return True
+
+
+def _unlock() -> None:
+    global _lock_fd
+    if _lock_fd is not None:
+        fcntl.flock(_lock_fd, fcntl.LOCK_UN)
+        os.close(_lock_fd)
+        _lock_fd = None
+
+
+def _is_stale(lock: Optional[dict[str, Any]]) -> bool:
+    if lock is None:
+        return True
+    try:
+        heartbeat = parse_rfc3339_utc(str(lock.get("heartbeatAt")))
+    except ApiError:
+        return True
+    return datetime.now(timezone.utc) - heartbeat > timedelta(seconds=STALE_AFTER_SECONDS)
+
+
+def _status() -> InstanceStatusDto:
+    lock = _read(LOCK_FILE_NAME)
+    takeover = _read(TAKEOVER_FILE_NAME)
+    return InstanceStatusDto(
+        instanceId=_instance_id,
+        isOwner=_is_owner,
+        ownerInstanceId=None if lock is None else lock.get("instanceId"),
+        ownerHeartbeatAt=None if lock is None else lock.get("heartbeatAt"),
+        takeoverPending=takeover is not None and takeover.get("instanceId") == _instance_id,
+    )
+
+
+def _acquire() -> bool:
+    """Take the OS lock unless another process holds it or it was handed to another instance."""
+    if not _try_lock():
+        return False
+    lock = _read(LOCK_FILE_NAME)
+    if lock is None or lock.get("instanceId") == _instance_id or _is_stale(lock):
+        return True
+    # Released for a takeover the requester has not picked up yet.
+    _unlock()
+    return False
+
+
+def _become_owner(event_type: str) -> None:
+    global _is_owner
+    _write_lock(_instance_id, os.getpid())
+    _is_owner = True
+    events.emit(event_type, _status().model_dump(mode="json"))
+
+
+def is_owner() -> bool:
+    with _state_lock:
+        return _is_owner
+
+
+def get_status() -> InstanceStatusDto:
+    with _state_lock:
+        return _status()
+
+
+def heartbeat() -> None:
+    """One coordination step; the background thread runs this every HEARTBEAT_SECONDS."""
+    global _is_owner
+    with _state_lock:
+        if not _is_owner:
+            if _acquire():
+                _remove(TAKEOVER_FILE_NAME)
+                _become_owner("instance:ownership_acquired")
+            return
+
+        lock = _read(LOCK_FILE_NAME)
+        owner_id = None if lock is None else lock.get("instanceId")
+        if owner_id not in (None, _instance_id):
+            # The lock file was handed to another instance behind our back.
+            _unlock()
+            _is_owner = False
+            events.emit("instance:ownership_lost", _status().model_dump(mode="json"))
+            return
+
+        request = _read(TAKEOVER_FILE_NAME)
+        if request is not None and request.get("instanceId") not in (None, _instance_id):
+            _write_lock(str(request["instanceId"]), int(request.get("pid", 0)))
+            _unlock()
+            _is_owner = False
+            events.emit("instance:ownership_released", _status().model_dump(mode="json"))
+            return
+
+        _write_lock(_instance_id, os.getpid())
+
+
+def request_takeover() -> InstanceStatusDto:
+    with _state_lock:
+        if not _is_owner:
+            if _acquire():
+                _remove(TAKEOVER_FILE_NAME)
+                _become_owner("instance:ownership_acquired")
+            else:
+                _write(
+                    TAKEOVER_FILE_NAME,
+                    {"instanceId": _instance_id, "pid": os.getpid(), "requestedAt": now_utc_rfc3339()},
+                )
+        return _status()
+
+
+def _run() -> None:
+    while not _stop.wait(HEARTBEAT_SECONDS):
+        try:
+            heartbeat()
+        except OSError as exc:
+            logger.warning("instance heartbeat failed: %s", exc)
+
+
+def start() -> None:
+    global _data_dir, _thread
+    with _state_lock:
+        _data_dir = get_data_dir()
+        _data_dir.mkdir(parents=True, exist_ok=True)
+        if _acquire():
+            _become_owner("instance:ownership_acquired")
+        else:
+            logger.warning("another instance owns %s; starting read-only", _path(LOCK_FILE_NAME))
+
+    _stop.clear()
+    _thread = threading.Thread(target=_run, name="instance-heartbeat", daemon=True)
+    _thread.start()
+
+
+def stop() -> None:
+    global _is_owner, _thread
+    _stop.set()
+    if _thread is not None:
+        _thread.join(timeout=HEARTBEAT_SECONDS)
+        _thread = None
+    with _state_lock:
+        if _is_owner and _lock_fd is not None:
+            # Cleared rather than removed, so followers need not wait for it to go stale.
+            os.ftruncate(_lock_fd, 0)
+        _unlock()
+        _is_owner = False
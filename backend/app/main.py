@@ -1,32 +1,98 @@
 from __future__ import annotations
 
+import logging
 import sqlite3
 from contextlib import asynccontextmanager
 
-from fastapi import APIRouter, Depends, FastAPI
+from fastapi import APIRouter, Depends, FastAPI, Request
 from fastapi.middleware.cors import CORSMiddleware
 from fastapi.responses import JSONResponse
 
-from app.config import API_PREFIX
-from app.db import initialize_database
+from app import instance as instance_lock
+from app import scheduler
+from app.config import API_PREFIX, get_log_level, is_read_only
+from app.db import initialize_database, refuse_writes
+from app.migrations import backfills_running, start_backfills, stop_backfills
 from app.models import ApiError, ErrorResponse
 from app.routers import (
     accounts,
+    allocation,
+    amortization,
     asset_purchases,
     auth,
+    bills,
+    budgets,
+    categories,
+    dashboard,
+    events,
+    exchange_rates,
+    exports,
+    holdings,
+    imports,
+    instance,
     kpis,
+    loans,
+    maintenance,
+    onboarding,
+    payees,
+    prices,
     reconciliations,
+    recurring,
     reports,
+    settings,
     system,
+    tags,
     transactions,
+    trash,
+    usage,
 )
 from app.services.auth import get_current_user
 
 
+logger = logging.getLogger(__name__)
+
+
 @asynccontextmanager
 async def lifespan(_: FastAPI):
-    initialize_database()
+    logging.basicConfig(level=get_log_level())
+    if is_read_only():
+        # Serve the data dir as it is: no schema upgrades, backfills, lock file or jobs.
+        logger.info("starting read-only; skipping migrations and background jobs")
+        yield
+        return
+    # Claim the lock first: only the owner may migrate, and a follower serves the data as is.
+    instance_lock.start()
+    if instance_lock.is_owner():
+        initialize_database()
+        start_backfills()
+    scheduler.start()
     yield
+    scheduler.stop()
+    stop_backfills()
+    instance_lock.stop()
+
+
+async def require_writable(request: Request) -> None:
+    # Async so the flag lands in the request's context, which the sync handler runs under.
+    if request.method in {"GET", "HEAD", "OPTIONS"}:
+        refuse_writes()
+        return
+    if is_read_only():
+        raise ApiError("read_only", "backend is running in read-only mode", status_code=403)
+    if not instance_lock.is_owner():
+        raise ApiError(
+            "read_only",
+            "another instance owns the database; request a takeover to write",
+            status_code=403,
+            details={"reason": "not_owner"},
+        )
+    if backfills_running():
+        raise ApiError(
+            "read_only",
+            "data migrations are still running; writes resume when they finish",
+            status_code=403,
+            details={"reason": "migrating"},
+        )
 
 
 app = FastAPI(title="Oikonomos API", version="1.0.0", lifespan=lifespan)
@@ -58,6 +124,13 @@ def handle_integrity_error(_, exc: sqlite3.IntegrityError) -> JSONResponse:
 
 @app.exception_handler(sqlite3.Error)
 def handle_db_error(_, exc: sqlite3.Error) -> JSONResponse:
+    if "integer overflow" in str(exc):
+        payload = ErrorResponse(
+            code="amount_out_of_range",
+            message="aggregated amount exceeds the supported range",
+            details={"reason": str(exc)},
+        )
+        return JSONResponse(status_code=400, content=payload.model_dump())
     payload = ErrorResponse(code="db_error", message="database error", details={"reason": str(exc)})
     return JSONResponse(status_code=500, content=payload.model_dump())
 
@@ -68,14 +141,40 @@ def healthz() -> dict[str, str]:
 
 
 app.include_router(auth.router, prefix=API_PREFIX)
+# Outside protected_api so a read-only follower can still ask for write ownership.
+app.include_router(instance.router, prefix=API_PREFIX, dependencies=[Depends(get_current_user)])
 
-protected_api = APIRouter(prefix=API_PREFIX, dependencies=[Depends(get_current_user)])
+protected_api = APIRouter(
+    prefix=API_PREFIX,
+    dependencies=[Depends(get_current_user), Depends(require_writable)],
+)
 protected_api.include_router(system.router)
+protected_api.include_router(events.router)
+protected_api.include_router(onboarding.router)
+protected_api.include_router(settings.router)
+protected_api.include_router(dashboard.router)
 protected_api.include_router(accounts.router)
+protected_api.include_router(allocation.router)
+protected_api.include_router(categories.router)
+protected_api.include_router(payees.router)
+protected_api.include_router(tags.router)
+protected_api.include_router(budgets.router)
 protected_api.include_router(transactions.router)
+protected_api.include_router(trash.router)
+protected_api.include_router(usage.router)
+protected_api.include_router(recurring.router)
+protected_api.include_router(bills.router)
 protected_api.include_router(asset_purchases.router)
+protected_api.include_router(amortization.router)
 protected_api.include_router(reconciliations.router)
+protected_api.include_router(imports.router)
 protected_api.include_router(reports.router)
 protected_api.include_router(kpis.router)
+protected_api.include_router(exports.router)
+protected_api.include_router(exchange_rates.router)
+protected_api.include_router(holdings.router)
+protected_api.include_router(prices.router)
+protected_api.include_router(loans.router)
+protected_api.include_router(maintenance.router)
 
 app.include_router(protected_api)
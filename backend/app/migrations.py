@@ -0,0 +1,189 @@
+"""Resumable data backfills that run in the background once the schema is in place.
+
+Column additions live in `db.COLUMN_MIGRATIONS` and are cheap. Backfills can touch every
+row of a large database, so each one works in small batches: a batch and its progress
+counter commit together, which means killing the process mid-way leaves a consistent
+database and the next start picks up where the last committed batch stopped. They run on
+a thread started after startup so the app serves reads (and `migration:progress` events)
+meanwhile; writes are refused until `backfills_running()` turns false.
+"""
+
+from __future__ import annotations
+
+import logging
+import sqlite3
+import threading
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Callable, Optional
+
+from app import events
+from app.config import get_data_dir, use_data_dir
+from app.db import get_connection, now_utc_rfc3339, transaction
+from app.models import MigrationStateDto, MigrationStatus
+from app.services.settings import BASE_CURRENCY_KEY, get_effective_setting
+
+
+logger = logging.getLogger(__name__)
+
+BACKFILL_BATCH_SIZE = 500
+
+_done = threading.Event()
+_done.set()
+_stop = threading.Event()
+_thread: Optional[threading.Thread] = None
+
+
+@dataclass(frozen=True)
+class Backfill:
+    id: str
+    # Number of rows still waiting for this backfill.
+    count_pending: Callable[[sqlite3.Connection], int]
+    # Processes up to `limit` pending rows and returns how many it handled; must be idempotent.
+    run_batch: Callable[[sqlite3.Connection, int], int]
+
+
+def _count_accounts_without_currency(conn: sqlite3.Connection) -> int:
+    if get_effective_setting(conn, BASE_CURRENCY_KEY) is None:
+        return 0
+    return conn.execute("SELECT COUNT(*) FROM accounts WHERE currency IS NULL").fetchone()[0]
+
+
+def _assign_base_currency(conn: sqlite3.Connection, limit: int) -> int:
+    # Without a base currency there is nothing to assign yet; onboarding fills these in later.
+    base_currency = get_effective_setting(conn, BASE_CURRENCY_KEY)
+    if base_currency is None:
+        return 0
+    return conn.execute(
+        """
+        UPDATE accounts SET currency = ?
+        WHERE id IN (SELECT id FROM accounts WHERE currency IS NULL LIMIT ?)
+        """,
+        (base_currency, limit),
+    ).rowcount
+
+
+# Applied in order; an id must never be reused once it has shipped.
+BACKFILLS: list[Backfill] = [
+    Backfill("account-currency", _count_accounts_without_currency, _assign_base_currency),
+]
+
+
+def _state_from_row(row: sqlite3.Row) -> MigrationStateDto:
+    return MigrationStateDto(
+        id=row["id"],
+        status=MigrationStatus(row["status"]),
+        processedCount=row["processed_count"],
+        totalCount=row["total_count"],
+        startedAt=row["started_at"],
+        completedAt=row["completed_at"],
+    )
+
+
+def _load_state(conn: sqlite3.Connection, migration_id: str) -> Optional[MigrationStateDto]:
+    row = conn.execute(
+        """
+        SELECT id, status, processed_count, total_count, started_at, completed_at
+        FROM schema_migrations
+        WHERE id = ?
+        """,
+        (migration_id,),
+    ).fetchone()
+    return _state_from_row(row) if row is not None else None
+
+
+def _publish(state: MigrationStateDto) -> None:
+    logger.info(
+        "migration %s: %s/%s rows (%s)", state.id, state.processedCount, state.totalCount, state.status.value
+    )
+    events.emit("migration:progress", state.model_dump(mode="json"))
+
+
+def _run_backfill(conn: sqlite3.Connection, backfill: Backfill) -> None:
+    state = _load_state(conn, backfill.id)
+    if state is not None and state.status == MigrationStatus.COMPLETED:
+        return
+
+    processed = state.processedCount if state is not None else 0
+    total = processed + backfill.count_pending(conn)
+    with transaction(conn):
+        conn.execute(
+            """
+            INSERT INTO schema_migrations (id, status, processed_count, total_count, started_at)
+            VALUES (?, 'Running', ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET total_count = excluded.total_count
+            """,
+            (backfill.id, processed, total, now_utc_rfc3339()),
+        )
+    _publish(_load_state(conn, backfill.id))
+
+    while True:
+        with transaction(conn):
+            handled = backfill.run_batch(conn, BACKFILL_BATCH_SIZE)
+            conn.execute(
+                "UPDATE schema_migrations SET processed_count = processed_count + ? WHERE id = ?",
+                (handled, backfill.id),
+            )
+            if handled < BACKFILL_BATCH_SIZE:
+                conn.execute(
+                    "UPDATE schema_migrations SET status = 'Completed', completed_at = ? WHERE id = ?",
+                    (now_utc_rfc3339(), backfill.id),
+                )
+        _publish(_load_state(conn, backfill.id))
+        if handled < BACKFILL_BATCH_SIZE or _stop.is_set():
+            return
+
+
+def run_backfills() -> None:
+    with get_connection() as conn:
+        for backfill in BACKFILLS:
+            if _stop.is_set():
+                return
+            _run_backfill(conn, backfill)
+
+
+def _run(data_dir: Path) -> None:
+    with use_data_dir(data_dir):
+        try:
+            run_backfills()
+        except Exception:
+            logger.exception("backfills failed; they resume on the next start")
+        finally:
+            _done.set()
+
+
+def start_backfills() -> None:
+    global _thread
+    _stop.clear()
+    _done.clear()
+    _thread = threading.Thread(target=_run, args=(get_data_dir(),), name="backfills", daemon=True)
+    _thread.start()
+
+
+def stop_backfills() -> None:
+    """Stop after the batch in flight; the next start resumes from there."""
+    global _thread
+    _stop.set()
+    if _thread is not None:
+        _thread.join()
+        _thread = None
+
+
+def backfills_running() -> bool:
+    return not _done.is_set()
+
+
+def wait_for_backfills(timeout: Optional[float] = None) -> bool:
+    return _done.wait(timeout)
+
+
+def list_migration_states() -> list[MigrationStateDto]:
+    with get_connection() as conn:
+        rows = conn.execute(
+            """
+            SELECT id, status, processed_count, total_count, started_at, completed_at
+            FROM schema_migrations
+            ORDER BY started_at ASC, id ASC
+            """
+        ).fetchall()
+    return [_state_from_row(row) for row in rows]
@@ -2,7 +2,7 @@ from __future__ import annotations
 
 from datetime import date
 from enum import Enum
-from typing import Optional
+from typing import Any, Optional
 
 from pydantic import BaseModel
 
@@ -19,20 +19,91 @@ class AssetPurpose(str, Enum):
     SPIRITUAL = "Spiritual"
 
 
+class AccountLiquidity(str, Enum):
+    LIQUID = "Liquid"
+    SEMI = "Semi"
+    ILLIQUID = "Illiquid"
+
+
 class AccrualType(str, Enum):
     FLOW = "Flow"
     DEPRECIATION = "Depreciation"
     ADJUSTMENT = "Adjustment"
+    # What an account held when it was opened; moves the balance but is neither income nor expense.
+    OPENING_BALANCE = "OpeningBalance"
+
+
+class TransactionStatus(str, Enum):
+    PENDING = "Pending"
+    CLEARED = "Cleared"
+    RECONCILED = "Reconciled"
 
 
 class AmortizationStrategy(str, Enum):
     LINEAR = "Linear"
+    # Sum of the years' digits.
     ACCELERATED = "Accelerated"
+    # A fixed share of the book value each period, switching to straight line once that is more.
+    DECLINING_BALANCE = "DecliningBalance"
+    # In proportion to the usage logged each month against the usage expected over its life.
+    USAGE = "Usage"
+
+
+class AmortizationStatus(str, Enum):
+    ACTIVE = "Active"
+    COMPLETED = "Completed"
+    CANCELLED = "Cancelled"
+
+
+class RecurrenceFrequency(str, Enum):
+    DAILY = "Daily"
+    WEEKLY = "Weekly"
+    MONTHLY = "Monthly"
+    YEARLY = "Yearly"
+
+
+class BalanceGranularity(str, Enum):
+    DAY = "Day"
+    MONTH = "Month"
+
+
+class BillStatus(str, Enum):
+    PAID = "Paid"
+    UPCOMING = "Upcoming"
+    OVERDUE = "Overdue"
+
+
+class ReportKind(str, Enum):
+    CASH = "Cash"
+    UTILITY = "Utility"
+    PURPOSE = "Purpose"
+    BUDGET = "Budget"
+
+
+class AnomalyReason(str, Enum):
+    UNUSUAL_AMOUNT = "UnusualAmount"
+    POSSIBLE_DUPLICATE = "PossibleDuplicate"
+
+
+class ImportSignConvention(str, Enum):
+    # One amount column, negative for money out.
+    SIGNED = "Signed"
+    # One amount column, positive for money out, as on most credit card exports.
+    INVERTED = "Inverted"
+    # Money out and money in in separate columns.
+    DEBIT_CREDIT = "DebitCredit"
 
 
 class InitStateDto(BaseModel):
     dataDir: str
     databasePath: str
+    onboardingCompleted: bool
+    readOnly: bool
+    locale: Optional[str] = None
+    deviceId: Optional[str] = None
+    currencySymbol: Optional[str] = None
+    decimalPlaces: int = 2
+    numberLocale: Optional[str] = None
 
 
 class AccountDto(BaseModel):
@@ -41,8 +112,124 @@ class AccountDto(BaseModel):
     accountType: AccountType
     purpose: AssetPurpose
     balanceCents: int
+    excludeFromAdjustmentKpi: bool
+    isArchived: bool
+    currency: Optional[str] = None
+    # The balance in the base currency at today's nearest rate; only filled by the account list.
+    convertedBalanceCents: Optional[int] = None
+    liquidity: AccountLiquidity
+    # Annual rate charged monthly on what a liability owes; None when it carries no interest.
+    interestRatePercent: Optional[float] = None
+    # The balance less the depreciation posted for assets still held in the account.
+    bookValueCents: int
     createdAt: str
     updatedAt: str
+    deviceId: Optional[str] = None
+
+
+class BalancePointDto(BaseModel):
+    date: str
+    balanceCents: int
+
+
+class BalanceHistoryDto(BaseModel):
+    accountId: str
+    granularity: BalanceGranularity
+    points: list[BalancePointDto]
+
+
+class AccountValuationDto(BaseModel):
+    id: str
+    accountId: str
+    valueCents: int
+    note: Optional[str] = None
+    valuedAt: str
+    photoIds: list[str]
+
+
+class CreateAccountValuationInput(BaseModel):
+    valueCents: int
+    valuedAt: Optional[str] = None
+    note: Optional[str] = None
+
+
+class MaintenanceEventDto(BaseModel):
+    id: str
+    accountId: str
+    occurredOn: str
+    costCents: int
+    note: Optional[str] = None
+    transactionId: Optional[str] = None
+    createdAt: str
+
+
+class CreateMaintenanceEventInput(BaseModel):
+    occurredOn: str
+    costCents: int
+    note: Optional[str] = None
+    transactionId: Optional[str] = None
+
+
+class CryptoHoldingDto(BaseModel):
+    accountId: str
+    symbol: str
+    # Decimal strings such as "0.0153": a float cannot hold satoshi precision for large holdings.
+    quantity: str
+    quantityUnits: int
+    unitPriceCents: Optional[int] = None
+    pricedAt: Optional[str] = None
+    valueCents: Optional[int] = None
+
+
+class SetCryptoHoldingInput(BaseModel):
+    symbol: str
+    quantity: str
+
+
+class SetCryptoPriceInput(BaseModel):
+    unitPriceCents: int
+    pricedAt: Optional[str] = None
+
+
+class AccountPhotoDto(BaseModel):
+    id: str
+    accountId: str
+    valuationId: Optional[str] = None
+    fileName: str
+    contentType: str
+    sizeBytes: int
+    createdAt: str
+
+
+class UploadAccountPhotoInput(BaseModel):
+    fileName: str
+    contentBase64: str
+    valuationId: Optional[str] = None
+
+
+class TransactionAttachmentDto(BaseModel):
+    id: str
+    transactionId: str
+    fileName: str
+    contentType: str
+    sizeBytes: int
+    contentHash: str
+    createdAt: str
+
+
+class UploadAttachmentInput(BaseModel):
+    fileName: str
+    contentBase64: str
+
+
+class ReceiptSuggestionDto(BaseModel):
+    # Whatever the receipt reader made out; each is None when it could not.
+    amountCents: Optional[int] = None
+    occurredOn: Optional[str] = None
+    payeeName: Optional[str] = None
+    # The known payee the name matches, and its default category.
+    payeeId: Optional[str] = None
+    categoryId: Optional[str] = None
 
 
 class CreateAccountInput(BaseModel):
@@ -50,6 +237,29 @@ class CreateAccountInput(BaseModel):
     accountType: AccountType
     purpose: AssetPurpose
     initialBalanceCents: int
+    excludeFromAdjustmentKpi: Optional[bool] = None
+    currency: Optional[str] = None
+    liquidity: Optional[AccountLiquidity] = None
+    interestRatePercent: Optional[float] = None
+    # Book initialBalanceCents as an OpeningBalance transaction dated openedAt (default now)
+    # instead of leaving it out of the ledger.
+    recordOpeningBalance: bool = False
+    openedAt: Optional[str] = None
+
+
+class RecordOpeningBalanceInput(BaseModel):
+    # Defaults to before the account's first transaction.
+    occurredAt: Optional[str] = None
+
+
+class UpdateAccountInput(BaseModel):
+    name: Optional[str] = None
+    purpose: Optional[AssetPurpose] = None
+    excludeFromAdjustmentKpi: Optional[bool] = None
+    isArchived: Optional[bool] = None
+    liquidity: Optional[AccountLiquidity] = None
+    # 0 stops the interest accrual.
+    interestRatePercent: Optional[float] = None
 
 
 class TransactionDto(BaseModel):
@@ -64,6 +274,22 @@ class TransactionDto(BaseModel):
     note: Optional[str] = None
     occurredAt: str
     createdAt: str
+    status: TransactionStatus = TransactionStatus.CLEARED
+    deviceId: Optional[str] = None
+
+
+class TransactionPostingDto(BaseModel):
+    leg: int
+    # None is the outside world: the income or spending side of a flow.
+    accountId: Optional[str] = None
+    amountCents: int
+
+
+class AccountValuationTimelineDto(BaseModel):
+    accountId: str
+    valuations: list[AccountValuationDto]
+    photos: list[AccountPhotoDto]
+    adjustments: list[TransactionDto]
 
 
 class CreateTransactionInput(BaseModel):
@@ -76,6 +302,76 @@ class CreateTransactionInput(BaseModel):
     isAssetPurchase: Optional[bool] = None
     note: Optional[str] = None
     occurredAt: Optional[str] = None
+    # Pending until the bank shows it; defaults to Cleared.
+    status: Optional[TransactionStatus] = None
+
+
+class PostingInput(BaseModel):
+    accountId: str
+    # Signed: positive arrives on the account, negative leaves it.
+    amountCents: int
+
+
+class CreateMultiLegTransactionInput(BaseModel):
+    # Two or more legs on distinct accounts in one currency, summing to zero.
+    postings: list[PostingInput]
+    payeeId: Optional[str] = None
+    categoryId: Optional[str] = None
+    note: Optional[str] = None
+    occurredAt: Optional[str] = None
+    status: Optional[TransactionStatus] = None
+
+
+class CreateTransactionsBatchInput(BaseModel):
+    transactions: list[CreateTransactionInput]
+
+
+class DuplicateTransactionInput(BaseModel):
+    # Defaults to now, like a new entry.
+    occurredAt: Optional[str] = None
+
+
+class RecategorizeAdjustmentInput(BaseModel):
+    categoryId: str
+    payeeId: Optional[str] = None
+    # None keeps the note the reconciliation wrote.
+    note: Optional[str] = None
+
+
+class MarkClearedInput(BaseModel):
+    transactionIds: list[str]
+    # False moves cleared rows back to Pending.
+    cleared: bool = True
+
+
+class MarkClearedResultDto(BaseModel):
+    transactions: list[TransactionDto]
+
+
+class TrashedTransactionDto(BaseModel):
+    transaction: TransactionDto
+    # None once the transaction has been restored.
+    deletedAt: Optional[str] = None
+
+
+class VoidTransactionInput(BaseModel):
+    # Defaults to the original's date, so the two cancel out within the same period.
+    occurredAt: Optional[str] = None
+    note: Optional[str] = None
+
+
+class VoidTransactionResultDto(BaseModel):
+    original: TransactionDto
+    reversal: TransactionDto
+
+
+class PurgeTrashInput(BaseModel):
+    # Only purge rows deleted at least this many days ago; None empties the whole trash.
+    olderThanDays: Optional[int] = None
+
+
+class PurgeTrashResultDto(BaseModel):
+    purgedCount: int
 
 
 class TransactionFilter(BaseModel):
@@ -88,6 +384,82 @@ class PagedTransactionsDto(BaseModel):
     total: int
 
 
+class RecurringRuleDto(BaseModel):
+    id: str
+    name: str
+    amountCents: int
+    fromAccountId: Optional[str] = None
+    toAccountId: Optional[str] = None
+    payeeId: Optional[str] = None
+    categoryId: Optional[str] = None
+    note: Optional[str] = None
+    frequency: RecurrenceFrequency
+    interval: int
+    startDate: str
+    endDate: Optional[str] = None
+    nextRunDate: Optional[str] = None
+    isActive: bool
+
+
+class CreateRecurringRuleInput(BaseModel):
+    name: str
+    amountCents: int
+    fromAccountId: Optional[str] = None
+    toAccountId: Optional[str] = None
+    payeeId: Optional[str] = None
+    categoryId: Optional[str] = None
+    note: Optional[str] = None
+    frequency: RecurrenceFrequency
+    interval: int = 1
+    startDate: str
+    endDate: Optional[str] = None
+
+
+class RunRecurringInput(BaseModel):
+    throughDate: Optional[str] = None
+
+
+class RecurringRuleFailureDto(BaseModel):
+    ruleId: str
+    name: str
+    code: str
+    message: str
+
+
+class RunRecurringResultDto(BaseModel):
+    createdTransactions: list[TransactionDto]
+    # Rules that could not be booked; their writes were rolled back and the others went ahead.
+    failedRules: list[RecurringRuleFailureDto]
+    # Some rule had more occurrences due than one run books; running again continues.
+    hasMore: bool
+
+
+class BillDto(BaseModel):
+    id: str
+    name: str
+    payeeId: Optional[str] = None
+    liabilityAccountId: Optional[str] = None
+    expectedAmountCents: int
+    dueDay: int
+    isActive: bool
+
+
+class CreateBillInput(BaseModel):
+    name: str
+    payeeId: Optional[str] = None
+    liabilityAccountId: Optional[str] = None
+    expectedAmountCents: int
+    dueDay: int
+
+
+class UpcomingBillDto(BaseModel):
+    bill: BillDto
+    dueDate: str
+    status: BillStatus
+    paidTransactionId: Optional[str] = None
+    paidAmountCents: Optional[int] = None
+
+
 class AmortizationScheduleDto(BaseModel):
     id: str
     assetAccountId: str
@@ -97,107 +469,1272 @@ class AmortizationScheduleDto(BaseModel):
     startDate: str
     sourceTransactionId: str
     status: str
+    # Multiple of the straight-line rate, e.g. 200 for double declining balance.
+    decliningFactorPercent: Optional[float] = None
+    expectedUnits: Optional[float] = None
+    prorateDaily: bool = False
+    serialNumber: Optional[str] = None
+    receiptReference: Optional[str] = None
+    warrantyExpiresOn: Optional[str] = None
 
 
-class CreateAssetPurchaseInput(BaseModel):
-    fromAccountId: str
-    assetAccountId: str
+class AmortizationScheduleProgressDto(AmortizationScheduleDto):
+    purchaseAmountCents: int
+    periodsPosted: int
+    depreciatedCents: int
+    # Appraisal deltas booked so far, up for a write-up and down for an impairment.
+    revaluationCents: int = 0
+    # Purchase amount plus revaluations, less the depreciation posted so far.
+    remainingBookValueCents: int
+    disposedAt: Optional[str] = None
+
+
+class AmortizationPostingDto(BaseModel):
+    id: str
+    scheduleId: str
+    periodYm: str
     amountCents: int
-    categoryId: Optional[str] = None
-    payeeId: Optional[str] = None
-    note: Optional[str] = None
-    occurredAt: Optional[str] = None
-    strategy: AmortizationStrategy
-    totalPeriods: int
-    residualCents: int
-    startDate: str
+    transactionId: str
+    generatedAt: str
 
 
-class AssetPurchaseResultDto(BaseModel):
-    transaction: TransactionDto
-    schedule: AmortizationScheduleDto
+class LogUsageInput(BaseModel):
+    periodYm: str
+    units: float
+    note: Optional[str] = None
 
 
-class ReconcileInput(BaseModel):
-    accountId: str
-    actualBalanceCents: int
-    occurredAt: Optional[str] = None
+class UsageLogDto(BaseModel):
+    id: str
+    scheduleId: str
+    periodYm: str
+    units: float
     note: Optional[str] = None
+    createdAt: str
+    updatedAt: str
 
 
-class ReconcileResultDto(BaseModel):
-    account: AccountDto
-    deltaCents: int
-    adjustmentTransaction: Optional[TransactionDto] = None
+class CostPerUseItemDto(BaseModel):
+    scheduleId: str
+    assetAccountId: str
+    label: str
+    purpose: AssetPurpose
+    # Purchase less residual: what the asset costs over its life.
+    costCents: int
+    unitsToDate: float
+    # None until some usage has been logged.
+    costPerUseCents: Optional[int] = None
 
 
-class ReportPeriodInput(BaseModel):
+class CostPerUseReportDto(BaseModel):
+    items: list[CostPerUseItemDto]
+
+
+class DepreciationForecastMonthDto(BaseModel):
     periodYm: str
+    amountCents: int
 
 
-class ReportItemDto(BaseModel):
+class DepreciationForecastDto(BaseModel):
+    months: list[DepreciationForecastMonthDto]
+    totalCents: int
+
+
+class TotalCostOfOwnershipItemDto(BaseModel):
+    accountId: str
     label: str
-    amountCents: int
+    purpose: AssetPurpose
+    purchaseCents: int
+    depreciatedCents: int
+    maintenanceCents: int
+    # Purchase plus maintenance: what owning the asset has cost in all.
+    totalCostCents: int
+    # Depreciation plus maintenance: the part of that already used up.
+    consumedCents: int
 
 
-class ReportDto(BaseModel):
-    periodYm: str
-    totalExpenseCents: int
-    items: list[ReportItemDto]
+class TotalCostOfOwnershipReportDto(BaseModel):
+    items: list[TotalCostOfOwnershipItemDto]
 
 
-class KpiPeriodInput(BaseModel):
-    fromPeriodYm: Optional[str] = None
-    toPeriodYm: Optional[str] = None
+class CatchUpDepreciationInput(BaseModel):
+    periodYm: str
 
 
-class AdjustmentKpiDto(BaseModel):
-    adjustmentTotalCents: int
-    expenseTotalCents: int
-    ratio: float
+class DepreciationCatchUpResultDto(BaseModel):
+    periodYm: str
+    postings: list[AmortizationPostingDto]
 
 
-class LoginInput(BaseModel):
-    email: str
-    password: str
+class CancelAmortizationScheduleInput(BaseModel):
+    # Also take back the depreciation already generated, as for an asset that was returned.
+    reversePostings: bool = False
 
 
-class TokenRefreshInput(BaseModel):
-    refreshToken: str
+class CancelAmortizationScheduleResultDto(BaseModel):
+    schedule: AmortizationScheduleProgressDto
+    reversedTransactionIds: list[str]
 
 
-class AuthTokensDto(BaseModel):
-    accessToken: str
-    refreshToken: str
-    tokenType: str = "Bearer"
-    expiresIn: int
+class RevalueAssetInput(BaseModel):
+    appraisedCents: int
+    note: Optional[str] = None
+    occurredAt: Optional[str] = None
 
 
-class CurrentUserDto(BaseModel):
+class AssetRevaluationDto(BaseModel):
     id: str
-    email: str
+    scheduleId: str
+    appraisedCents: int
+    # Book value just before the appraisal; the difference is what was booked.
+    carryingCents: int
+    transactionId: Optional[str] = None
+    occurredAt: str
 
 
-class ErrorResponse(BaseModel):
-    code: str
-    message: str
-    details: Optional[dict] = None
+class AssetRevaluationResultDto(BaseModel):
+    revaluation: AssetRevaluationDto
+    schedule: AmortizationScheduleProgressDto
+    transaction: Optional[TransactionDto] = None
 
 
-class ApiError(Exception):
-    def __init__(
-        self,
-        code: str,
-        message: str,
-        status_code: int = 400,
-        details: Optional[dict] = None,
-    ):
-        self.code = code
-        self.message = message
-        self.status_code = status_code
-        self.details = details
-        super().__init__(message)
+class DisposeAssetInput(BaseModel):
+    # Share of the asset that leaves, e.g. 50 for one of two monitors bought together.
+    disposedPercent: float
+    proceedsCents: int = 0
+    # Account the sale proceeds go to; required when there are proceeds.
+    toAccountId: Optional[str] = None
+    occurredAt: Optional[str] = None
 
 
-def months_between(start_month: date, target_month: date) -> int:
-    return (target_month.year - start_month.year) * 12 + (target_month.month - start_month.month)
+class DisposeAssetResultDto(BaseModel):
+    schedule: AmortizationScheduleProgressDto
+    disposedSchedule: AmortizationScheduleProgressDto
+    transactions: list[TransactionDto]
+
+
+class CreateAssetPurchaseInput(BaseModel):
+    fromAccountId: str
+    assetAccountId: str
+    amountCents: int
+    categoryId: Optional[str] = None
+    payeeId: Optional[str] = None
+    note: Optional[str] = None
+    occurredAt: Optional[str] = None
+    strategy: AmortizationStrategy
+    totalPeriods: int
+    residualCents: int
+    startDate: str
+    # Required for DecliningBalance and Usage respectively, and refused by the other strategies.
+    decliningFactorPercent: Optional[float] = None
+    expectedUnits: Optional[float] = None
+    # Charge the first month only for the days from startDate; not available for Usage.
+    prorateDaily: bool = False
+    serialNumber: Optional[str] = None
+    receiptReference: Optional[str] = None
+    warrantyExpiresOn: Optional[str] = None
+
+
+class UpdateAssetDetailsInput(BaseModel):
+    # None keeps the current value and an empty string clears it.
+    serialNumber: Optional[str] = None
+    receiptReference: Optional[str] = None
+    warrantyExpiresOn: Optional[str] = None
+
+
+class ExpiringWarrantyDto(BaseModel):
+    scheduleId: str
+    assetAccountId: str
+    label: str
+    serialNumber: Optional[str] = None
+    warrantyExpiresOn: str
+    daysLeft: int
+
+
+class AssetPurchaseResultDto(BaseModel):
+    transaction: TransactionDto
+    schedule: AmortizationScheduleDto
+
+
+class LoanDto(BaseModel):
+    id: str
+    liabilityAccountId: str
+    paymentAccountId: str
+    principalCents: int
+    aprPercent: float
+    termMonths: int
+    paymentDay: int
+    startPeriodYm: str
+    # The level monthly installment; the last one settles whatever principal is left.
+    paymentCents: int
+    status: str
+
+
+class CreateLoanInput(BaseModel):
+    liabilityAccountId: str
+    paymentAccountId: str
+    principalCents: int
+    aprPercent: float
+    termMonths: int
+    paymentDay: int
+    startPeriodYm: str
+    # When set, the principal is booked as paid out of the liability into this account.
+    disbursedToAccountId: Optional[str] = None
+    occurredAt: Optional[str] = None
+
+
+class LoanScheduleEntryDto(BaseModel):
+    periodYm: str
+    paymentDate: str
+    paymentCents: int
+    principalCents: int
+    interestCents: int
+    remainingPrincipalCents: int
+    posted: bool
+
+
+class LoanScheduleDto(BaseModel):
+    loan: LoanDto
+    entries: list[LoanScheduleEntryDto]
+
+
+class LoanCreatedDto(BaseModel):
+    loan: LoanDto
+    disbursement: Optional[TransactionDto] = None
+
+
+class PrepaymentSimulationDto(BaseModel):
+    """The rest of a loan's schedule, first as planned and then with the extra payments."""
+
+    loanId: str
+    # First installment not yet posted; the simulation starts from the principal owed before it.
+    fromPeriodYm: str
+    remainingPrincipalCents: int
+    lumpSumCents: int
+    lumpSumPeriodYm: str
+    monthlyExtraCents: int
+    baselineMonths: int
+    baselineInterestCents: int
+    baselinePayoffPeriodYm: str
+    simulatedMonths: int
+    simulatedInterestCents: int
+    simulatedPayoffPeriodYm: str
+    monthsSaved: int
+    interestSavedCents: int
+
+
+class AccrueInterestInput(BaseModel):
+    periodYm: str
+
+
+class InterestAccrualDto(BaseModel):
+    id: str
+    accountId: str
+    periodYm: str
+    owedCents: int
+    ratePercent: float
+    amountCents: int
+    transactionId: str
+
+
+class InterestAccrualResultDto(BaseModel):
+    periodYm: str
+    accruals: list[InterestAccrualDto]
+
+
+class PostLoanPaymentsInput(BaseModel):
+    periodYm: str
+
+
+class LoanPaymentsResultDto(BaseModel):
+    periodYm: str
+    transactions: list[TransactionDto]
+
+
+class ReconcileInput(BaseModel):
+    accountId: str
+    actualBalanceCents: int
+    occurredAt: Optional[str] = None
+    note: Optional[str] = None
+    # Compare a statement against the cleared balance and mark the cleared rows Reconciled.
+    clearedOnly: Optional[bool] = None
+
+
+class SetAccountBalanceInput(BaseModel):
+    newBalanceCents: int
+    note: Optional[str] = None
+    occurredAt: Optional[str] = None
+
+
+class ReconcileResultDto(BaseModel):
+    account: AccountDto
+    deltaCents: int
+    adjustmentTransaction: Optional[TransactionDto] = None
+    # Only for a statement reconciliation: the balance less pending rows, and the rows it covered.
+    clearedBalanceCents: Optional[int] = None
+    reconciledCount: int = 0
+
+
+class BalanceDriftPointDto(BaseModel):
+    capturedAt: str
+    deltaCents: int
+
+
+class BalanceDriftSeriesDto(BaseModel):
+    accountId: str
+    label: str
+    points: list[BalanceDriftPointDto]
+    # Sum of the deltas' sizes, so drift in both directions counts against the books.
+    totalAbsoluteDeltaCents: int
+
+
+class BalanceDriftReportDto(BaseModel):
+    series: list[BalanceDriftSeriesDto]
+
+
+class StatementLineInput(BaseModel):
+    date: str
+    # Signed like the statement: positive money in, negative money out.
+    amountCents: int
+    description: Optional[str] = None
+    # The bank's own id for the line, when its export has one.
+    externalId: Optional[str] = None
+
+
+class MatchStatementInput(BaseModel):
+    accountId: str
+    lines: list[StatementLineInput]
+    dateWindowDays: int = 3
+
+
+class StatementLineDto(StatementLineInput):
+    # Position of the line in the submitted statement.
+    index: int
+
+
+class StatementMatchDto(BaseModel):
+    line: StatementLineDto
+    transaction: TransactionDto
+
+
+class StatementMatchResultDto(BaseModel):
+    matches: list[StatementMatchDto]
+    # On the statement but not in the books, and the other way round.
+    unmatchedLines: list[StatementLineDto]
+    unmatchedTransactions: list[TransactionDto]
+
+
+class ImportStatementInput(BaseModel):
+    accountId: str
+    lines: list[StatementLineInput]
+    # A line whose best payee match scores at least this is linked to it without asking.
+    autoLinkThreshold: float = 0.8
+
+
+class PayeeCandidateDto(BaseModel):
+    payeeId: str
+    name: str
+    # Similarity between the line's descriptor and the payee name, from 0 to 1.
+    score: float
+
+
+class ImportedLineDto(BaseModel):
+    index: int
+    transaction: TransactionDto
+    # Closest payees for the user to confirm; empty once one was linked automatically.
+    candidates: list[PayeeCandidateDto]
+
+
+class StagedLineDto(StatementLineDto):
+    payeeId: Optional[str]
+    # The linked payee's default category, which the booked transaction will get.
+    categoryId: Optional[str]
+    candidates: list[PayeeCandidateDto]
+    # Already in the books, so committing skips it.
+    isDuplicate: bool
+
+
+class ImportPreviewDto(BaseModel):
+    id: str
+    accountId: str
+    createdAt: str
+    lines: list[StagedLineDto]
+
+
+class ImportStatementResultDto(BaseModel):
+    lines: list[ImportedLineDto]
+    # Already imported before, so not booked again.
+    skippedLines: list[StatementLineDto]
+
+
+class ImportProfileDto(BaseModel):
+    id: str
+    name: str
+    accountId: Optional[str] = None
+    delimiter: str
+    # Lines above the header row, such as a bank's account summary.
+    skipRows: int
+    # Columns are named by their header.
+    dateColumn: str
+    # strptime format, e.g. "%d.%m.%Y".
+    dateFormat: str
+    amountColumn: Optional[str] = None
+    debitColumn: Optional[str] = None
+    creditColumn: Optional[str] = None
+    descriptionColumn: Optional[str] = None
+    externalIdColumn: Optional[str] = None
+    signConvention: ImportSignConvention
+    decimalSeparator: str
+    createdAt: str
+    updatedAt: str
+
+
+class CreateImportProfileInput(BaseModel):
+    name: str
+    accountId: Optional[str] = None
+    delimiter: str = ","
+    skipRows: int = 0
+    dateColumn: str
+    dateFormat: str = "%Y-%m-%d"
+    amountColumn: Optional[str] = None
+    debitColumn: Optional[str] = None
+    creditColumn: Optional[str] = None
+    descriptionColumn: Optional[str] = None
+    externalIdColumn: Optional[str] = None
+    signConvention: ImportSignConvention = ImportSignConvention.SIGNED
+    decimalSeparator: str = "."
+
+
+class UpdateImportProfileInput(BaseModel):
+    name: Optional[str] = None
+    accountId: Optional[str] = None
+    delimiter: Optional[str] = None
+    skipRows: Optional[int] = None
+    dateColumn: Optional[str] = None
+    dateFormat: Optional[str] = None
+    amountColumn: Optional[str] = None
+    debitColumn: Optional[str] = None
+    creditColumn: Optional[str] = None
+    descriptionColumn: Optional[str] = None
+    externalIdColumn: Optional[str] = None
+    signConvention: Optional[ImportSignConvention] = None
+    decimalSeparator: Optional[str] = None
+
+
+class PreviewCsvImportInput(BaseModel):
+    profileId: str
+    # The exported file's text.
+    content: str
+    # Defaults to the profile's account.
+    accountId: Optional[str] = None
+    autoLinkThreshold: float = 0.8
+
+
+class AssignPayeeInput(BaseModel):
+    payeeId: str
+
+
+class BalanceSnapshotDto(BaseModel):
+    id: str
+    accountId: str
+    actualBalanceCents: int
+    systemBalanceCents: int
+    deltaCents: int
+    capturedAt: str
+    # None when the balances matched, or once the adjustment was deleted.
+    adjustmentTransaction: Optional[TransactionDto] = None
+
+
+class LotDto(BaseModel):
+    id: str
+    quantity: str
+    quantityUnits: int
+    originalQuantityUnits: int
+    costBasisCents: int
+    acquiredAt: str
+
+
+class HoldingDto(BaseModel):
+    id: str
+    accountId: str
+    ticker: str
+    quantity: str
+    quantityUnits: int
+    costBasisCents: int
+    lots: list[LotDto]
+    # From the latest recorded price; None until the instrument has one.
+    priceCents: Optional[int] = None
+    priceDate: Optional[str] = None
+    marketValueCents: Optional[int] = None
+
+
+class PriceDto(BaseModel):
+    id: str
+    instrument: str
+    priceDate: str
+    priceCents: int
+
+
+class SetPriceInput(BaseModel):
+    instrument: str
+    priceDate: str
+    priceCents: int
+
+
+class RefreshPricesInput(BaseModel):
+    priceDate: Optional[str] = None
+
+
+class RefreshPricesResultDto(BaseModel):
+    prices: list[PriceDto]
+    missingInstruments: list[str]
+
+
+class PortfolioValuePointDto(BaseModel):
+    date: str
+    valueCents: int
+    costBasisCents: int
+
+
+class PortfolioValueHistoryDto(BaseModel):
+    accountId: Optional[str] = None
+    granularity: BalanceGranularity
+    points: list[PortfolioValuePointDto]
+    # Held at some point in the range without any price on or before that day; left out of valueCents.
+    unpricedTickers: list[str]
+
+
+class PerformanceItemDto(BaseModel):
+    label: str
+    holdingId: Optional[str] = None
+    accountId: Optional[str] = None
+    startValueCents: int
+    endValueCents: int
+    # Money put in minus money taken out over the range.
+    netContributionsCents: int
+    # Annualized money-weighted return; None when it cannot be solved for.
+    xirr: Optional[float] = None
+    # Time-weighted return over the whole range, not annualized.
+    twr: Optional[float] = None
+
+
+class PerformanceReportDto(BaseModel):
+    fromDate: str
+    toDate: str
+    holdings: list[PerformanceItemDto]
+    investmentGroup: PerformanceItemDto
+
+
+class SecurityTradeInput(BaseModel):
+    """A buy pays amountCents (fees included) from the cash account; a sell pays it into it."""
+
+    accountId: str
+    cashAccountId: str
+    ticker: str
+    quantity: str
+    amountCents: int
+    occurredAt: Optional[str] = None
+
+
+class SecurityTradeResultDto(BaseModel):
+    holding: HoldingDto
+    transaction: TransactionDto
+    # Proceeds minus the cost basis of the lots sold; None for a buy.
+    realizedGainCents: Optional[int] = None
+
+
+class RealizedGainDto(BaseModel):
+    transactionId: str
+    accountId: str
+    ticker: str
+    soldAt: str
+    quantity: str
+    quantityUnits: int
+    proceedsCents: int
+    costBasisCents: int
+    gainCents: int
+
+
+class RealizedGainsReportDto(BaseModel):
+    year: int
+    items: list[RealizedGainDto]
+    totalProceedsCents: int
+    totalCostBasisCents: int
+    totalGainCents: int
+
+
+class RevalueHoldingsInput(BaseModel):
+    periodYm: str
+
+
+class HoldingRevaluationDto(BaseModel):
+    id: str
+    accountId: str
+    periodYm: str
+    # Holdings without a price yet count at cost, so they add nothing unrealized.
+    marketValueCents: int
+    costBasisCents: int
+    # Change in the unrealized gain carried on the account since its last revaluation.
+    adjustmentCents: int
+    transactionId: str
+
+
+class HoldingRevaluationResultDto(BaseModel):
+    periodYm: str
+    revaluations: list[HoldingRevaluationDto]
+
+
+class CryptoRevaluationResultDto(BaseModel):
+    holding: CryptoHoldingDto
+    account: AccountDto
+    adjustmentTransaction: Optional[TransactionDto] = None
+
+
+class ReportPeriodInput(BaseModel):
+    periodYm: str
+
+
+class ReportRangeInput(BaseModel):
+    """Inclusive YYYY-MM-DD bounds for reports over a custom range instead of a calendar month."""
+
+    fromDate: str
+    toDate: str
+
+
+class ReportItemDto(BaseModel):
+    label: str
+    amountCents: int
+    topTransactionIds: Optional[list[str]] = None
+    # With groupByParent, a top-level category's subcategories; its amount includes theirs.
+    children: Optional[list[ReportItemDto]] = None
+
+
+class ReportDto(BaseModel):
+    periodYm: Optional[str] = None
+    fromDate: str
+    toDate: str
+    totalExpenseCents: int
+    items: list[ReportItemDto]
+
+
+class CashFlowSectionDto(BaseModel):
+    # Money coming in is positive, money going out negative.
+    items: list[ReportItemDto]
+    netCents: int
+
+
+class CashFlowReportDto(ReportDto):
+    """Expense side keeps the ReportDto fields; income and transfers are reported separately."""
+
+    totalIncomeCents: int
+    incomeItems: list[ReportItemDto]
+    # Moves between two accounts of the same type; paying into or drawing from a liability is
+    # debt and counted on its own.
+    totalTransferCents: int
+    totalDebtRepaymentCents: int
+    totalBorrowingCents: int
+    netCents: int
+    # The same movements as a cash flow statement: income and spending are operating,
+    # buying and selling assets investing, drawing and repaying loans financing.
+    operating: CashFlowSectionDto
+    investing: CashFlowSectionDto
+    financing: CashFlowSectionDto
+    # Set when a base currency is configured; None if any amount has no rate to convert with.
+    baseCurrency: Optional[str] = None
+    convertedTotalIncomeCents: Optional[int] = None
+    convertedTotalExpenseCents: Optional[int] = None
+    convertedNetCents: Optional[int] = None
+
+
+class IncomeStatementDto(BaseModel):
+    periodYm: Optional[str] = None
+    fromDate: str
+    toDate: str
+    incomeItems: list[ReportItemDto]
+    totalIncomeCents: int
+    expenseItems: list[ReportItemDto]
+    totalExpenseCents: int
+    # Reported on its own line rather than under the asset's category.
+    depreciationCents: int
+    netResultCents: int
+
+
+class TrendPointDto(BaseModel):
+    periodYm: str
+    amountCents: int
+    topTransactionIds: Optional[list[str]] = None
+
+
+class CategoryTrendSeriesDto(BaseModel):
+    categoryId: str
+    label: str
+    points: list[TrendPointDto]
+
+
+class CategoryTrendDto(BaseModel):
+    fromPeriodYm: str
+    toPeriodYm: str
+    series: list[CategoryTrendSeriesDto]
+
+
+class RollingAverageItemDto(BaseModel):
+    categoryId: Optional[str] = None
+    label: str
+    threeMonthAverageCents: int
+    sixMonthAverageCents: int
+
+
+class RollingAverageReportDto(BaseModel):
+    fromPeriodYm: str
+    toPeriodYm: str
+    threeMonthAverageCents: int
+    sixMonthAverageCents: int
+    items: list[RollingAverageItemDto]
+
+
+class CategoryDto(BaseModel):
+    id: str
+    name: str
+    parentId: Optional[str] = None
+    color: Optional[str] = None
+    icon: Optional[str] = None
+    # Lists put lower numbers first, then go by name.
+    sortOrder: int = 0
+    isActive: bool
+
+
+class CreateCategoryInput(BaseModel):
+    name: str
+    parentId: Optional[str] = None
+    color: Optional[str] = None
+    icon: Optional[str] = None
+    sortOrder: int = 0
+
+
+class UpdateCategoryInput(BaseModel):
+    name: Optional[str] = None
+    # An empty string clears the color or icon.
+    color: Optional[str] = None
+    icon: Optional[str] = None
+    sortOrder: Optional[int] = None
+
+
+class CategorySuggestionDto(BaseModel):
+    category: CategoryDto
+    # Live transactions booked under it; the most used come first.
+    useCount: int
+
+
+class MergeCategoriesInput(BaseModel):
+    sourceId: str
+    targetId: str
+
+
+class MergeCategoriesResultDto(BaseModel):
+    category: CategoryDto
+    movedTransactionCount: int
+    movedBudgetCount: int
+
+
+class PayeeDto(BaseModel):
+    id: str
+    name: str
+    defaultCategoryId: Optional[str] = None
+    isArchived: bool
+
+
+class CreatePayeeInput(BaseModel):
+    name: str
+    defaultCategoryId: Optional[str] = None
+
+
+class PayeeSuggestionDto(BaseModel):
+    payeeId: str
+    # The category most used with this payee, else its default.
+    categoryId: Optional[str] = None
+    # Distinct amounts of its latest transactions, newest first.
+    recentAmountsCents: list[int]
+
+
+class MergePayeesInput(BaseModel):
+    sourceIds: list[str]
+    targetId: str
+
+
+class MergePayeesResultDto(BaseModel):
+    payee: PayeeDto
+    movedTransactionCount: int
+
+
+class TagDto(BaseModel):
+    id: str
+    name: str
+    color: Optional[str] = None
+    description: Optional[str] = None
+    # Hidden from pickers; the transactions it is on keep it.
+    isArchived: bool
+
+
+class CreateTagInput(BaseModel):
+    name: str
+    color: Optional[str] = None
+    description: Optional[str] = None
+
+
+class UpdateTagInput(BaseModel):
+    name: Optional[str] = None
+    # An empty string clears the color or description.
+    color: Optional[str] = None
+    description: Optional[str] = None
+    isArchived: Optional[bool] = None
+
+
+class BudgetDto(BaseModel):
+    id: str
+    categoryId: str
+    periodYm: str
+    amountCents: int
+    rollover: bool
+
+
+class SetBudgetInput(BaseModel):
+    categoryId: str
+    periodYm: str
+    amountCents: int
+    rollover: Optional[bool] = None
+
+
+class ExchangeRateDto(BaseModel):
+    id: str
+    fromCurrency: str
+    toCurrency: str
+    rate: float
+    rateDate: str
+
+
+class SetExchangeRateInput(BaseModel):
+    """One unit of fromCurrency buys `rate` units of toCurrency on rateDate."""
+
+    fromCurrency: str
+    toCurrency: str
+    rate: float
+    rateDate: str
+
+
+class RevalueForeignAccountsInput(BaseModel):
+    periodYm: str
+
+
+class FxRevaluationDto(BaseModel):
+    id: str
+    accountId: str
+    periodYm: str
+    baseCurrency: str
+    balanceCents: int
+    valueCents: int
+    gainCents: int
+    transactionId: str
+
+
+class FxRevaluationResultDto(BaseModel):
+    periodYm: str
+    revaluations: list[FxRevaluationDto]
+    skippedAccountIds: list[str]
+
+
+class BudgetReportItemDto(BaseModel):
+    categoryId: str
+    label: str
+    budgetedCents: int
+    carriedOverCents: int
+    effectiveBudgetCents: int
+    spentCents: int
+    remainingCents: int
+    rollover: bool
+    # With groupByParent, the budgeted subcategories rolled up into this item.
+    children: Optional[list[BudgetReportItemDto]] = None
+
+
+class BudgetReportDto(BaseModel):
+    periodYm: str
+    totalEffectiveBudgetCents: int
+    totalSpentCents: int
+    items: list[BudgetReportItemDto]
+
+
+class UtilityMatrixExportInput(BaseModel):
+    year: int
+    path: str
+
+
+class ReportCsvExportInput(BaseModel):
+    report: ReportKind
+    path: str
+    periodYm: Optional[str] = None
+    fromDate: Optional[str] = None
+    toDate: Optional[str] = None
+
+
+class AnonymizedExportInput(BaseModel):
+    path: str
+    perturbAmounts: bool = False
+
+
+class ExportResultDto(BaseModel):
+    path: str
+    rowCount: int
+
+
+class OnboardingInput(BaseModel):
+    baseCurrency: str
+    periodStartDay: int = 1
+    accounts: list[CreateAccountInput]
+    categories: list[CreateCategoryInput] = []
+
+
+class OnboardingResultDto(BaseModel):
+    baseCurrency: str
+    periodStartDay: int
+    accounts: list[AccountDto]
+    categories: list[CategoryDto]
+
+
+class SettingsDto(BaseModel):
+    baseCurrency: Optional[str] = None
+    locale: Optional[str] = None
+    periodStartDay: int
+    spendingPaceAlertPercent: int
+    backupRetention: int
+    currencySymbol: Optional[str] = None
+    decimalPlaces: int
+    numberLocale: Optional[str] = None
+    timezone: str = "UTC"
+
+
+class UpdateSettingsInput(BaseModel):
+    baseCurrency: Optional[str] = None
+    locale: Optional[str] = None
+    periodStartDay: Optional[int] = None
+    spendingPaceAlertPercent: Optional[int] = None
+    backupRetention: Optional[int] = None
+    currencySymbol: Optional[str] = None
+    decimalPlaces: Optional[int] = None
+    numberLocale: Optional[str] = None
+    timezone: Optional[str] = None
+
+
+class KpiPeriodInput(BaseModel):
+    fromPeriodYm: Optional[str] = None
+    toPeriodYm: Optional[str] = None
+
+
+class AdjustmentKpiDto(BaseModel):
+    adjustmentTotalCents: int
+    expenseTotalCents: int
+    ratio: float
+
+
+class DebtToAssetPointDto(BaseModel):
+    date: str
+    assetsCents: int
+    liabilitiesCents: int
+    ratio: Optional[float] = None
+
+
+class DebtToAssetKpiDto(BaseModel):
+    assetsCents: int
+    liabilitiesCents: int
+    ratio: Optional[float] = None
+    series: list[DebtToAssetPointDto]
+
+
+class UsageScoreDto(BaseModel):
+    id: str
+    score: float
+    useCount: int
+    lastUsedAt: str
+
+
+class UsageStatsDto(BaseModel):
+    halfLifeDays: int
+    accounts: list[UsageScoreDto]
+    categories: list[UsageScoreDto]
+
+
+class DashboardDto(BaseModel):
+    periodYm: str
+    netWorthCents: int
+    totalAssetsCents: int
+    totalLiabilitiesCents: int
+    monthToDateExpenseCents: int
+    monthToDateIncomeCents: int
+    pendingReconciliationCount: int
+    activeAmortizationCount: int
+    baseCurrency: Optional[str] = None
+    convertedNetWorthCents: Optional[int] = None
+    convertedTotalAssetsCents: Optional[int] = None
+    convertedTotalLiabilitiesCents: Optional[int] = None
+
+
+class AllocationTargetDto(BaseModel):
+    purpose: AssetPurpose
+    targetPercent: float
+
+
+class SetAllocationTargetsInput(BaseModel):
+    targets: list[AllocationTargetDto]
+
+
+class AllocationDriftItemDto(BaseModel):
+    purpose: AssetPurpose
+    actualCents: int
+    actualPercent: float
+    targetPercent: Optional[float] = None
+    targetCents: Optional[int] = None
+    rebalanceCents: Optional[int] = None
+
+
+class AllocationDriftReportDto(BaseModel):
+    totalAssetsCents: int
+    items: list[AllocationDriftItemDto]
+
+
+class LiquidityReportItemDto(BaseModel):
+    liquidity: AccountLiquidity
+    assetsCents: int
+    liabilitiesCents: int
+    netCents: int
+
+
+class LiquidityReportDto(BaseModel):
+    netWorthCents: int
+    items: list[LiquidityReportItemDto]
+
+
+class SpendingPaceDto(BaseModel):
+    periodYm: str
+    asOfDate: str
+    dayOfMonth: int
+    monthToDateCents: int
+    baselineCents: Optional[int] = None
+    historyMonthCount: int
+    thresholdPercent: int
+    overPacePercent: Optional[int] = None
+    isOverPace: bool
+
+
+class SpendingForecastItemDto(BaseModel):
+    categoryId: Optional[str] = None
+    label: str
+    monthToDateCents: int
+    scheduledCents: int
+    projectedCents: int
+    budgetCents: Optional[int] = None
+    isOverBudget: bool
+
+
+class SpendingForecastDto(BaseModel):
+    periodYm: str
+    asOfDate: str
+    daysElapsed: int
+    daysInPeriod: int
+    monthToDateCents: int
+    projectedCents: int
+    items: list[SpendingForecastItemDto]
+
+
+class CleanupItemDto(BaseModel):
+    id: str
+    name: str
+    lastUsedAt: Optional[str] = None
+
+
+class CleanupSuggestionsDto(BaseModel):
+    cutoffDate: str
+    payees: list[CleanupItemDto]
+    tags: list[CleanupItemDto]
+    categories: list[CleanupItemDto]
+    accounts: list[CleanupItemDto]
+
+
+class ArchiveInput(BaseModel):
+    payeeIds: list[str] = []
+    tagIds: list[str] = []
+    categoryIds: list[str] = []
+    accountIds: list[str] = []
+
+
+class ArchiveResultDto(BaseModel):
+    archivedCount: int
+
+
+class RebuildBalancesInput(BaseModel):
+    # Only report the discrepancies, leaving the stored balances alone.
+    dryRun: bool = False
+
+
+class BalanceDiscrepancyDto(BaseModel):
+    accountId: str
+    name: str
+    storedCents: int
+    rebuiltCents: int
+    differenceCents: int
+
+
+class RebuildBalancesResultDto(BaseModel):
+    checkedCount: int
+    discrepancies: list[BalanceDiscrepancyDto]
+    applied: bool
+
+
+class AuditFindingDto(BaseModel):
+    checkId: str
+    description: str
+    rowIds: list[str]
+
+
+class DatabaseMaintenanceResultDto(BaseModel):
+    integrityOk: bool
+    integrityMessages: list[str]
+    # VACUUM and ANALYZE are skipped when the integrity check fails.
+    compacted: bool
+    sizeBeforeBytes: int
+    sizeAfterBytes: int
+    reclaimedBytes: int
+
+
+class TransactionAnomalyDto(BaseModel):
+    transaction: TransactionDto
+    reason: AnomalyReason
+    message: str
+    typicalAmountCents: Optional[int] = None
+    relatedTransactionId: Optional[str] = None
+
+
+class UpgradeFindingDto(BaseModel):
+    checkId: str
+    description: str
+    convertible: bool
+    rowIds: list[str]
+
+
+class UpgradeConversionInput(BaseModel):
+    checkIds: list[str]
+
+
+class UpgradeConversionResultDto(BaseModel):
+    checkId: str
+    convertedCount: int
+
+
+class EventDto(BaseModel):
+    seq: int
+    type: str
+    payload: dict[str, Any]
+    emittedAt: str
+
+
+class InstanceStatusDto(BaseModel):
+    instanceId: str
+    isOwner: bool
+    ownerInstanceId: Optional[str] = None
+    ownerHeartbeatAt: Optional[str] = None
+    takeoverPending: bool
+
+
+class MigrationStatus(str, Enum):
+    RUNNING = "Running"
+    COMPLETED = "Completed"
+
+
+class MigrationStateDto(BaseModel):
+    id: str
+    status: MigrationStatus
+    processedCount: int
+    totalCount: int
+    startedAt: str
+    completedAt: Optional[str] = None
+
+
+class ReplayFailureDto(BaseModel):
+    index: int
+    command: str
+    code: str
+    message: str
+
+
+class ReplayResultDto(BaseModel):
+    replayedCount: int
+    failures: list[ReplayFailureDto]
+
+
+class LoginInput(BaseModel):
+    email: str
+    password: str
+
+
+class TokenRefreshInput(BaseModel):
+    refreshToken: str
+
+
+class AuthTokensDto(BaseModel):
+    accessToken: str
+    refreshToken: str
+    tokenType: str = "Bearer"
+    expiresIn: int
+
+
+class CurrentUserDto(BaseModel):
+    id: str
+    email: str
+
+
+class ErrorResponse(BaseModel):
+    code: str
+    message: str
+    details: Optional[dict] = None
+
+
+class ApiError(Exception):
+    def __init__(
+        self,
+        code: str,
+        message: str,
+        status_code: int = 400,
+        details: Optional[dict] = None,
+    ):
+        self.code = code
+        self.message = message
+        self.status_code = status_code
+        self.details = details
+        super().__init__(message)
+
+
+# Upper bound (exclusive) for any single amount or balance, in cents. Well below the
+# 64-bit SQLite integer range so sums over many rows cannot wrap either.
+AMOUNT_LIMIT_CENTS = 10**15
+
+
+def ensure_amount_in_range(value: int, field_name: str) -> int:
+    if abs(value) >= AMOUNT_LIMIT_CENTS:
+        raise ApiError(
+            "amount_out_of_range",
+            f"{field_name} is out of range: |{field_name}| must be below {AMOUNT_LIMIT_CENTS} cents",
+            details={"field": field_name, "value": value},
+        )
+    return value
+
+
+def months_between(start_month: date, target_month: date) -> int:
+    return (target_month.year - start_month.year) * 12 + (target_month.month - start_month.month)
+
+
+def format_cents(cents: int) -> str:
+    sign = "-" if cents < 0 else ""
+    whole, fraction = divmod(abs(cents), 100)
+    return f"{sign}{whole}.{fraction:02d}"
+
+
+def add_months(month_start: date, months: int) -> date:
+    index = month_start.year * 12 + (month_start.month - 1) + months
+    return date(index // 12, index % 12 + 1, 1)
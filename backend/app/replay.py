@@ -0,0 +1,216 @@
+"""Opt-in command recorder and a replayer for reproducing user-reported state.
+
+When a command log is configured, every mutating command appends one JSON line with
+its name, its inputs with free text replaced by stable placeholders, and the ids it
+returned. `python -m app.replay <log>` runs the same sequence against a fresh in-memory
+database, remapping recorded ids to the ones the replay produced.
+"""
+
+from __future__ import annotations
+
+import functools
+import hashlib
+import json
+import logging
+import re
+import sys
+import tempfile
+import threading
+import typing
+from enum import Enum
+from pathlib import Path
+from typing import Any, Callable, Optional
+
+from pydantic import BaseModel
+
+from app import models
+from app.config import get_command_log_path, use_data_dir
+from app.models import ApiError, ReplayFailureDto, ReplayResultDto
+
+
+logger = logging.getLogger(__name__)
+
+# Strings are kept only when they are ids, dates, enum values or one of these codes, which
+# are checked against fixed lists and carry nothing about the user; all else is hashed.
+CODE_FIELDS = {
+    "currency", "baseCurrency", "fromCurrency", "toCurrency", "currencySymbol",
+    "locale", "numberLocale", "timezone", "color",
+}
+DATE_PATTERN = re.compile(r"\d{4}-\d{2}(-\d{2}([T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:\d{2})?)?)?")
+ENUM_VALUES = {
+    member.value
+    for value in vars(models).values()
+    if isinstance(value, type) and issubclass(value, Enum)
+    for member in value
+}
+
+_commands: dict[str, Callable[..., Any]] = {}
+_write_lock = threading.Lock()
+
+
+def _placeholder(field: str, value: str) -> str:
+    return f"{field}-{hashlib.sha256(value.encode('utf-8')).hexdigest()[:10]}"
+
+
+def _is_kept(field: Optional[str], value: str) -> bool:
+    if field is not None and (field == "id" or field.endswith(("Id", "Ids")) or field in CODE_FIELDS):
+        return True
+    return DATE_PATTERN.fullmatch(value) is not None or value in ENUM_VALUES
+
+
+def sanitize(value: Any, field: Optional[str] = None) -> Any:
+    if isinstance(value, dict):
+        return {key: sanitize(item, key) for key, item in value.items()}
+    if isinstance(value, list):
+        return [sanitize(item, field) for item in value]
+    if isinstance(value, str) and not _is_kept(field, value):
+        return _placeholder(field or "value", value)
+    return value
+
+
+def _to_json(value: Any) -> Any:
+    if isinstance(value, BaseModel):
+        return value.model_dump(mode="json")
+    if isinstance(value, list):
+        return [_to_json(item) for item in value]
+    return value
+
+
+def _collect_ids(value: Any, found: list[str]) -> list[str]:
+    if isinstance(value, dict):
+        for key, item in value.items():
+            if key == "id" and isinstance(item, str):
+                found.append(item)
+            else:
+                _collect_ids(item, found)
+    elif isinstance(value, list):
+        for item in value:
+            _collect_ids(item, found)
+    return found
+
+
+def _error_code(exc: Exception) -> str:
+    return exc.code if isinstance(exc, ApiError) else type(exc).__name__
+
+
+def _append(entry: dict[str, Any]) -> None:
+    path = get_command_log_path()
+    if path is None:
+        return
+    try:
+        with _write_lock, open(path, "a", encoding="utf-8") as handle:
+            handle.write(json.dumps(entry) + "\n")
+    except OSError as exc:
+        logger.warning("could not write command log %s: %s", path, exc)
+
+
+def recorded_command(func: Callable[..., Any]) -> Callable[..., Any]:
+    """Register a command handler for replay and record its invocations when enabled."""
+    name = func.__name__
+    _commands[name] = func
+
+    @functools.wraps(func)
+    def wrapper(*args: Any, **kwargs: Any) -> Any:
+        bound = {key: sanitize(_to_json(value), key) for key, value in kwargs.items()}
+        try:
+            result = func(*args, **kwargs)
+        except Exception as exc:
+            _append({"command": name, "args": bound, "error": _error_code(exc)})
+            raise
+        _append({"command": name, "args": bound, "ids": _collect_ids(_to_json(result), [])})
+        return result
+
+    return wrapper
+
+
+def _remap(value: Any, id_map: dict[str, str]) -> Any:
+    if isinstance(value, dict):
+        return {key: _remap(item, id_map) for key, item in value.items()}
+    if isinstance(value, list):
+        return [_remap(item, id_map) for item in value]
+    if isinstance(value, str):
+        return id_map.get(value, value)
+    return value
+
+
+def _model_for(annotation: Any) -> Optional[type[BaseModel]]:
+    for candidate in (annotation, *typing.get_args(annotation)):
+        if isinstance(candidate, type) and issubclass(candidate, BaseModel):
+            return candidate
+    return None
+
+
+def _bind_args(func: Callable[..., Any], raw_args: dict[str, Any], scratch: Path) -> dict[str, Any]:
+    hints = typing.get_type_hints(func)
+    bound: dict[str, Any] = {}
+    for key, value in raw_args.items():
+        if isinstance(value, dict) and "path" in value:
+            # Export targets were anonymized, so point them into the scratch directory.
+            value = {**value, "path": str(scratch / Path(value["path"]).name)}
+        model = _model_for(hints.get(key))
+        bound[key] = model.model_validate(value) if model is not None and value is not None else value
+    return bound
+
+
+def replay_commands(path: str) -> ReplayResultDto:
+    from app.db import initialize_database, use_memory_database
+
+    log_path = Path(path).expanduser()
+    if not log_path.is_file():
+        raise ApiError("invalid_input", f"command log not found: {log_path}")
+    entries = [json.loads(line) for line in log_path.read_text(encoding="utf-8").splitlines() if line.strip()]
+
+    failures: list[ReplayFailureDto] = []
+    id_map: dict[str, str] = {}
+    # The registry holds the undecorated handlers, so replaying never appends to the log.
+    # Files that commands write, such as exports and attachments, go to a scratch directory.
+    with tempfile.TemporaryDirectory(prefix="oikonomos-replay-") as scratch_dir:
+        scratch = Path(scratch_dir)
+        with use_data_dir(scratch / "data"), use_memory_database():
+            initialize_database()
+            for index, entry in enumerate(entries):
+                name = entry["command"]
+                func = _commands.get(name)
+                if func is None:
+                    failures.append(ReplayFailureDto(index=index, command=name, code="unknown_command", message=name))
+                    continue
+                try:
+                    result = func(**_bind_args(func, _remap(entry.get("args", {}), id_map), scratch))
+                except Exception as exc:
+                    code = _error_code(exc)
+                    if code != entry.get("error"):
+                        failures.append(ReplayFailureDto(index=index, command=name, code=code, message=str(exc)))
+                    continue
+                if "error" in entry:
+                    failures.append(
+                        ReplayFailureDto(
+                            index=index,
+                            command=name,
+                            code="unexpected_success",
+                            message=f"recorded failure {entry['error']} did not reproduce",
+                        )
+                    )
+                id_map.update(zip(entry.get("ids", []), _collect_ids(_to_json(result), [])))
+
+    return ReplayResultDto(replayedCount=len(entries), failures=failures)
+
+
+def main(argv: list[str]) -> int:
+    if len(argv) != 1:
+        print("usage: python -m app.replay <command-log.jsonl>", file=sys.stderr)
+        return 2
+
+    # Importing the app registers every recorded command on app.replay; when run with -m
+    # this file is __main__, so go through the registered module rather than this copy.
+    import app.main  # noqa: F401
+    from app.replay import replay_commands as run_replay
+
+    result = run_replay(argv[0])
+    for failure in result.failures:
+        print(f"#{failure.index} {failure.command}: {failure.code} {failure.message}")
+    print(f"replayed {result.replayedCount} commands, {len(result.failures)} diverged")
+    return 1 if result.failures else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main(sys.argv[1:]))
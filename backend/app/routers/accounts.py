@@ -1,16 +1,144 @@
-﻿from fastapi import APIRouter
+﻿from fastapi import APIRouter, Query
+from fastapi.responses import FileResponse
 
-from app.models import AccountDto, CreateAccountInput
-from app.services.finance import create_account, list_accounts
+from app.models import (
+    AccountDto,
+    AccountPhotoDto,
+    AccountValuationDto,
+    AccountValuationTimelineDto,
+    AccrueInterestInput,
+    BalanceGranularity,
+    BalanceHistoryDto,
+    CreateAccountInput,
+    CreateAccountValuationInput,
+    CreateMaintenanceEventInput,
+    CryptoHoldingDto,
+    CryptoRevaluationResultDto,
+    InterestAccrualResultDto,
+    MaintenanceEventDto,
+    ReconcileResultDto,
+    RecordOpeningBalanceInput,
+    SetAccountBalanceInput,
+    SetCryptoHoldingInput,
+    SetCryptoPriceInput,
+    TransactionDto,
+    UpdateAccountInput,
+    UploadAccountPhotoInput,
+)
+from app.replay import recorded_command
+from app.services.crypto import get_crypto_holding, set_crypto_holding, set_crypto_price
+from app.services.finance import (
+    create_account,
+    get_account_balance_history,
+    list_accounts,
+    record_opening_balance,
+    set_account_balance,
+    update_account,
+)
+from app.services.interest import accrue_interest
+from app.services.valuations import (
+    create_account_valuation,
+    create_maintenance_event,
+    get_account_photo_file,
+    list_account_valuations,
+    list_maintenance_events,
+    upload_account_photo,
+)
 
 router = APIRouter(prefix="/accounts", tags=["accounts"])
 
 
 @router.get("", response_model=list[AccountDto])
-def get_accounts() -> list[AccountDto]:
-    return list_accounts()
+def get_accounts(includeArchived: bool = Query(default=False)) -> list[AccountDto]:
+    return list_accounts(includeArchived)
 
 
 @router.post("", response_model=AccountDto)
+@recorded_command
 def post_account(input_data: CreateAccountInput) -> AccountDto:
     return create_account(input_data)
+
+
+@router.post("/interest-accruals", response_model=InterestAccrualResultDto)
+@recorded_command
+def post_interest_accruals(input_data: AccrueInterestInput) -> InterestAccrualResultDto:
+    return accrue_interest(input_data)
+
+
+@router.patch("/{account_id}", response_model=AccountDto)
+@recorded_command
+def patch_account(account_id: str, input_data: UpdateAccountInput) -> AccountDto:
+    return update_account(account_id, input_data)
+
+
+@router.post("/{account_id}/balance", response_model=ReconcileResultDto)
+@recorded_command
+def post_account_balance(account_id: str, input_data: SetAccountBalanceInput) -> ReconcileResultDto:
+    return set_account_balance(account_id, input_data)
+
+
+@router.post("/{account_id}/opening-balance", response_model=TransactionDto)
+@recorded_command
+def post_opening_balance(account_id: str, input_data: RecordOpeningBalanceInput) -> TransactionDto:
+    return record_opening_balance(account_id, input_data)
+
+
+@router.get("/{account_id}/balance-history", response_model=BalanceHistoryDto)
+def get_balance_history(
+    account_id: str,
+    fromDate: str = Query(...),
+    toDate: str = Query(...),
+    granularity: BalanceGranularity = Query(default=BalanceGranularity.DAY),
+) -> BalanceHistoryDto:
+    return get_account_balance_history(account_id, fromDate, toDate, granularity)
+
+
+@router.get("/{account_id}/valuations", response_model=AccountValuationTimelineDto)
+def get_account_valuations(account_id: str) -> AccountValuationTimelineDto:
+    return list_account_valuations(account_id)
+
+
+@router.post("/{account_id}/valuations", response_model=AccountValuationDto)
+@recorded_command
+def post_account_valuation(account_id: str, input_data: CreateAccountValuationInput) -> AccountValuationDto:
+    return create_account_valuation(account_id, input_data)
+
+
+@router.get("/{account_id}/maintenance", response_model=list[MaintenanceEventDto])
+def get_maintenance_events(account_id: str) -> list[MaintenanceEventDto]:
+    return list_maintenance_events(account_id)
+
+
+@router.post("/{account_id}/maintenance", response_model=MaintenanceEventDto)
+@recorded_command
+def post_maintenance_event(account_id: str, input_data: CreateMaintenanceEventInput) -> MaintenanceEventDto:
+    return create_maintenance_event(account_id, input_data)
+
+
+@router.get("/{account_id}/crypto", response_model=CryptoHoldingDto)
+def get_account_crypto(account_id: str) -> CryptoHoldingDto:
+    return get_crypto_holding(account_id)
+
+
+@router.post("/{account_id}/crypto", response_model=CryptoRevaluationResultDto)
+@recorded_command
+def post_account_crypto(account_id: str, input_data: SetCryptoHoldingInput) -> CryptoRevaluationResultDto:
+    return set_crypto_holding(account_id, input_data)
+
+
+@router.post("/{account_id}/crypto/price", response_model=CryptoRevaluationResultDto)
+@recorded_command
+def post_account_crypto_price(account_id: str, input_data: SetCryptoPriceInput) -> CryptoRevaluationResultDto:
+    return set_crypto_price(account_id, input_data)
+
+
+# Photos are binary attachments rather than ledger state, so they stay out of the command log.
+@router.post("/{account_id}/photos", response_model=AccountPhotoDto)
+def post_account_photo(account_id: str, input_data: UploadAccountPhotoInput) -> AccountPhotoDto:
+    return upload_account_photo(account_id, input_data)
+
+
+@router.get("/{account_id}/photos/{photo_id}")
+def get_account_photo(account_id: str, photo_id: str) -> FileResponse:
+    path, content_type = get_account_photo_file(account_id, photo_id)
+    return FileResponse(path, media_type=content_type)
@@ -0,0 +1,18 @@
+from fastapi import APIRouter
+
+from app.models import AllocationTargetDto, SetAllocationTargetsInput
+from app.replay import recorded_command
+from app.services.allocation import list_allocation_targets, set_allocation_targets
+
+router = APIRouter(prefix="/allocation-targets", tags=["allocation"])
+
+
+@router.get("", response_model=list[AllocationTargetDto])
+def get_allocation_targets() -> list[AllocationTargetDto]:
+    return list_allocation_targets()
+
+
+@router.post("", response_model=list[AllocationTargetDto])
+@recorded_command
+def post_allocation_targets(input_data: SetAllocationTargetsInput) -> list[AllocationTargetDto]:
+    return set_allocation_targets(input_data)
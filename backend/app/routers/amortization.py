@@ -0,0 +1,98 @@
+from fastapi import APIRouter, Query
+
+from app.models import (
+    AmortizationPostingDto,
+    AmortizationScheduleProgressDto,
+    AmortizationStatus,
+    AssetRevaluationResultDto,
+    CancelAmortizationScheduleInput,
+    CancelAmortizationScheduleResultDto,
+    CatchUpDepreciationInput,
+    DepreciationCatchUpResultDto,
+    DisposeAssetInput,
+    DisposeAssetResultDto,
+    ExpiringWarrantyDto,
+    LogUsageInput,
+    RevalueAssetInput,
+    UpdateAssetDetailsInput,
+    UsageLogDto,
+)
+from app.replay import recorded_command
+from app.services.amortization import (
+    cancel_amortization_schedule,
+    catch_up_depreciation,
+    dispose_asset,
+    list_amortization_postings,
+    list_amortization_schedules,
+    list_expiring_warranties,
+    list_usage_logs,
+    log_usage,
+    revalue_asset,
+    update_asset_details,
+)
+
+router = APIRouter(prefix="/amortization-schedules", tags=["amortization-schedules"])
+
+
+@router.get("", response_model=list[AmortizationScheduleProgressDto])
+def get_amortization_schedules(
+    status: AmortizationStatus | None = Query(default=None),
+    assetAccountId: str | None = Query(default=None),
+) -> list[AmortizationScheduleProgressDto]:
+    return list_amortization_schedules(status, assetAccountId)
+
+
+@router.post("/catch-up", response_model=DepreciationCatchUpResultDto)
+@recorded_command
+def post_catch_up_depreciation(input_data: CatchUpDepreciationInput) -> DepreciationCatchUpResultDto:
+    return catch_up_depreciation(input_data)
+
+
+@router.get("/expiring-warranties", response_model=list[ExpiringWarrantyDto])
+def get_expiring_warranties(
+    withinDays: int = Query(default=30),
+    asOf: str | None = Query(default=None),
+) -> list[ExpiringWarrantyDto]:
+    return list_expiring_warranties(withinDays, asOf)
+
+
+@router.patch("/{schedule_id}", response_model=AmortizationScheduleProgressDto)
+@recorded_command
+def patch_asset_details(schedule_id: str, input_data: UpdateAssetDetailsInput) -> AmortizationScheduleProgressDto:
+    return update_asset_details(schedule_id, input_data)
+
+
+@router.get("/{schedule_id}/postings", response_model=list[AmortizationPostingDto])
+def get_amortization_postings(schedule_id: str) -> list[AmortizationPostingDto]:
+    return list_amortization_postings(schedule_id)
+
+
+@router.post("/{schedule_id}/cancel", response_model=CancelAmortizationScheduleResultDto)
+@recorded_command
+def post_cancel_amortization_schedule(
+    schedule_id: str, input_data: CancelAmortizationScheduleInput
+) -> CancelAmortizationScheduleResultDto:
+    return cancel_amortization_schedule(schedule_id, input_data)
+
+
+@router.post("/{schedule_id}/dispose", response_model=DisposeAssetResultDto)
+@recorded_command
+def post_dispose_asset(schedule_id: str, input_data: DisposeAssetInput) -> DisposeAssetResultDto:
+    return dispose_asset(schedule_id, input_data)
+
+
+@router.post("/{schedule_id}/revaluations", response_model=AssetRevaluationResultDto)
+@recorded_command
+def post_revalue_asset(schedule_id: str, input_data: RevalueAssetInput) -> AssetRevaluationResultDto:
+    return revalue_asset(schedule_id, input_data)
+
+
+@router.get("/{schedule_id}/usage", response_model=list[UsageLogDto])
+def get_usage_logs(schedule_id: str) -> list[UsageLogDto]:
+    return list_usage_logs(schedule_id)
+
+
+@router.post("/{schedule_id}/usage", response_model=UsageLogDto)
+@recorded_command
+def post_usage_log(schedule_id: str, input_data: LogUsageInput) -> UsageLogDto:
+    return log_usage(schedule_id, input_data)
@@ -0,0 +1,26 @@
+from fastapi import APIRouter, Query
+
+from app.models import BillDto, CreateBillInput, UpcomingBillDto
+from app.replay import recorded_command
+from app.services.bills import create_bill, list_bills, list_upcoming_bills
+
+router = APIRouter(prefix="/bills", tags=["bills"])
+
+
+@router.get("", response_model=list[BillDto])
+def get_bills() -> list[BillDto]:
+    return list_bills()
+
+
+@router.post("", response_model=BillDto)
+@recorded_command
+def post_bill(input_data: CreateBillInput) -> BillDto:
+    return create_bill(input_data)
+
+
+@router.get("/upcoming", response_model=list[UpcomingBillDto])
+def get_upcoming_bills(
+    periodYm: str | None = Query(default=None),
+    asOf: str | None = Query(default=None),
+) -> list[UpcomingBillDto]:
+    return list_upcoming_bills(periodYm, asOf)
@@ -0,0 +1,18 @@
+from fastapi import APIRouter, Query
+
+from app.models import BudgetDto, SetBudgetInput
+from app.replay import recorded_command
+from app.services.budgets import list_budgets, set_budget
+
+router = APIRouter(prefix="/budgets", tags=["budgets"])
+
+
+@router.get("", response_model=list[BudgetDto])
+def get_budgets(periodYm: str = Query(...)) -> list[BudgetDto]:
+    return list_budgets(periodYm)
+
+
+@router.post("", response_model=BudgetDto)
+@recorded_command
+def post_budget(input_data: SetBudgetInput) -> BudgetDto:
+    return set_budget(input_data)
@@ -0,0 +1,48 @@
+from fastapi import APIRouter, Query
+
+from app.models import (
+    CategoryDto,
+    CategorySuggestionDto,
+    CreateCategoryInput,
+    MergeCategoriesInput,
+    MergeCategoriesResultDto,
+    UpdateCategoryInput,
+)
+from app.replay import recorded_command
+from app.services.dimensions import (
+    create_category,
+    list_categories,
+    merge_categories,
+    suggest_categories,
+    update_category,
+)
+
+router = APIRouter(prefix="/categories", tags=["categories"])
+
+
+@router.get("", response_model=list[CategoryDto])
+def get_categories(includeInactive: bool = Query(default=False)) -> list[CategoryDto]:
+    return list_categories(includeInactive)
+
+
+@router.get("/suggestions", response_model=list[CategorySuggestionDto])
+def get_category_suggestions(prefix: str = Query(default="")) -> list[CategorySuggestionDto]:
+    return suggest_categories(prefix)
+
+
+@router.post("", response_model=CategoryDto)
+@recorded_command
+def post_category(input_data: CreateCategoryInput) -> CategoryDto:
+    return create_category(input_data)
+
+
+@router.patch("/{category_id}", response_model=CategoryDto)
+@recorded_command
+def patch_category(category_id: str, input_data: UpdateCategoryInput) -> CategoryDto:
+    return update_category(category_id, input_data)
+
+
+@router.post("/merge", response_model=MergeCategoriesResultDto)
+@recorded_command
+def post_merge_categories(input_data: MergeCategoriesInput) -> MergeCategoriesResultDto:
+    return merge_categories(input_data)
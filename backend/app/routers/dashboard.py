@@ -0,0 +1,26 @@
+from fastapi import APIRouter, Query
+
+from app.models import DashboardDto, SpendingForecastDto, SpendingPaceDto
+from app.services.dashboard import get_dashboard
+from app.services.forecast import forecast_spending
+from app.services.pace import get_spending_pace
+
+router = APIRouter(prefix="/dashboard", tags=["dashboard"])
+
+
+@router.get("", response_model=DashboardDto)
+def get_dashboard_summary() -> DashboardDto:
+    return get_dashboard()
+
+
+@router.get("/spending-pace", response_model=SpendingPaceDto)
+def get_dashboard_spending_pace(
+    periodYm: str | None = Query(default=None),
+    asOf: str | None = Query(default=None),
+) -> SpendingPaceDto:
+    return get_spending_pace(periodYm, asOf)
+
+
+@router.get("/spending-forecast", response_model=SpendingForecastDto)
+def get_dashboard_spending_forecast(asOf: str | None = Query(default=None)) -> SpendingForecastDto:
+    return forecast_spending(asOf)
@@ -0,0 +1,31 @@
+import asyncio
+import json
+from typing import AsyncIterator
+
+from fastapi import APIRouter, Query
+from fastapi.responses import StreamingResponse
+
+from app.events import events_since
+from app.models import EventDto
+
+router = APIRouter(prefix="/events", tags=["events"])
+
+STREAM_POLL_SECONDS = 0.5
+
+
+@router.get("", response_model=list[EventDto])
+def get_events(afterSeq: int = Query(default=0)) -> list[EventDto]:
+    return events_since(afterSeq)
+
+
+@router.get("/stream")
+async def get_event_stream(afterSeq: int = Query(default=0)) -> StreamingResponse:
+    async def stream() -> AsyncIterator[str]:
+        cursor = afterSeq
+        while True:
+            for event in events_since(cursor):
+                cursor = event.seq
+                yield f"id: {event.seq}\nevent: {event.type}\ndata: {json.dumps(event.model_dump())}\n\n"
+            await asyncio.sleep(STREAM_POLL_SECONDS)
+
+    return StreamingResponse(stream(), media_type="text/event-stream")
@@ -0,0 +1,28 @@
+from fastapi import APIRouter, Query
+
+from app.models import ExchangeRateDto, FxRevaluationResultDto, RevalueForeignAccountsInput, SetExchangeRateInput
+from app.replay import recorded_command
+from app.services.currencies import list_exchange_rates, set_exchange_rate
+from app.services.revaluation import revalue_foreign_accounts
+
+router = APIRouter(prefix="/exchange-rates", tags=["exchange-rates"])
+
+
+@router.get("", response_model=list[ExchangeRateDto])
+def get_exchange_rates(
+    fromCurrency: str | None = Query(default=None),
+    toCurrency: str | None = Query(default=None),
+) -> list[ExchangeRateDto]:
+    return list_exchange_rates(fromCurrency, toCurrency)
+
+
+@router.post("", response_model=ExchangeRateDto)
+@recorded_command
+def post_exchange_rate(input_data: SetExchangeRateInput) -> ExchangeRateDto:
+    return set_exchange_rate(input_data)
+
+
+@router.post("/revaluations", response_model=FxRevaluationResultDto)
+@recorded_command
+def post_revaluation(input_data: RevalueForeignAccountsInput) -> FxRevaluationResultDto:
+    return revalue_foreign_accounts(input_data)
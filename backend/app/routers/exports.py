@@ -0,0 +1,25 @@
+from fastapi import APIRouter
+
+from app.models import AnonymizedExportInput, ExportResultDto, ReportCsvExportInput, UtilityMatrixExportInput
+from app.replay import recorded_command
+from app.services.exports import export_anonymized, export_report_csv, export_utility_matrix
+
+router = APIRouter(prefix="/exports", tags=["exports"])
+
+
+@router.post("/utility-matrix", response_model=ExportResultDto)
+@recorded_command
+def post_utility_matrix(input_data: UtilityMatrixExportInput) -> ExportResultDto:
+    return export_utility_matrix(input_data)
+
+
+@router.post("/report-csv", response_model=ExportResultDto)
+@recorded_command
+def post_report_csv(input_data: ReportCsvExportInput) -> ExportResultDto:
+    return export_report_csv(input_data)
+
+
+@router.post("/anonymized", response_model=ExportResultDto)
+@recorded_command
+def post_anonymized(input_data: AnonymizedExportInput) -> ExportResultDto:
+    return export_anonymized(input_data)
@@ -0,0 +1,54 @@
+from fastapi import APIRouter, Query
+
+from app.models import (
+    BalanceGranularity,
+    HoldingDto,
+    HoldingRevaluationResultDto,
+    PortfolioValueHistoryDto,
+    RevalueHoldingsInput,
+    SecurityTradeInput,
+    SecurityTradeResultDto,
+)
+from app.replay import recorded_command
+from app.services.holdings import (
+    buy_security,
+    get_portfolio_value_history,
+    list_holdings,
+    revalue_holdings,
+    sell_security,
+)
+
+router = APIRouter(prefix="/holdings", tags=["holdings"])
+
+
+@router.get("", response_model=list[HoldingDto])
+def get_holdings(accountId: str | None = Query(default=None)) -> list[HoldingDto]:
+    return list_holdings(accountId)
+
+
+@router.get("/value-history", response_model=PortfolioValueHistoryDto)
+def get_value_history(
+    fromDate: str = Query(...),
+    toDate: str = Query(...),
+    granularity: BalanceGranularity = Query(default=BalanceGranularity.DAY),
+    accountId: str | None = Query(default=None),
+) -> PortfolioValueHistoryDto:
+    return get_portfolio_value_history(fromDate, toDate, granularity, accountId)
+
+
+@router.post("/buy", response_model=SecurityTradeResultDto)
+@recorded_command
+def post_buy(input_data: SecurityTradeInput) -> SecurityTradeResultDto:
+    return buy_security(input_data)
+
+
+@router.post("/sell", response_model=SecurityTradeResultDto)
+@recorded_command
+def post_sell(input_data: SecurityTradeInput) -> SecurityTradeResultDto:
+    return sell_security(input_data)
+
+
+@router.post("/revaluations", response_model=HoldingRevaluationResultDto)
+@recorded_command
+def post_revaluations(input_data: RevalueHoldingsInput) -> HoldingRevaluationResultDto:
+    return revalue_holdings(input_data)
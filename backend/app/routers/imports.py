@@ -0,0 +1,90 @@
+from fastapi import APIRouter
+
+from app.models import (
+    CreateImportProfileInput,
+    ImportPreviewDto,
+    ImportProfileDto,
+    ImportStatementInput,
+    ImportStatementResultDto,
+    PreviewCsvImportInput,
+    UpdateImportProfileInput,
+)
+from app.replay import recorded_command
+from app.services.imports import (
+    commit_import,
+    create_import_profile,
+    delete_import_profile,
+    discard_import,
+    get_import,
+    import_statement,
+    list_import_profiles,
+    list_imports,
+    preview_csv_import,
+    preview_import,
+    update_import_profile,
+)
+
+router = APIRouter(prefix="/imports", tags=["imports"])
+
+
+@router.get("", response_model=list[ImportPreviewDto])
+def get_imports() -> list[ImportPreviewDto]:
+    return list_imports()
+
+
+@router.post("/statement", response_model=ImportStatementResultDto)
+@recorded_command
+def post_import_statement(input_data: ImportStatementInput) -> ImportStatementResultDto:
+    return import_statement(input_data)
+
+
+@router.post("/preview", response_model=ImportPreviewDto)
+@recorded_command
+def post_preview_import(input_data: ImportStatementInput) -> ImportPreviewDto:
+    return preview_import(input_data)
+
+
+@router.post("/preview-csv", response_model=ImportPreviewDto)
+@recorded_command
+def post_preview_csv_import(input_data: PreviewCsvImportInput) -> ImportPreviewDto:
+    return preview_csv_import(input_data)
+
+
+@router.get("/profiles", response_model=list[ImportProfileDto])
+def get_import_profiles() -> list[ImportProfileDto]:
+    return list_import_profiles()
+
+
+@router.post("/profiles", response_model=ImportProfileDto)
+@recorded_command
+def post_import_profile(input_data: CreateImportProfileInput) -> ImportProfileDto:
+    return create_import_profile(input_data)
+
+
+@router.patch("/profiles/{profile_id}", response_model=ImportProfileDto)
+@recorded_command
+def patch_import_profile(profile_id: str, input_data: UpdateImportProfileInput) -> ImportProfileDto:
+    return update_import_profile(profile_id, input_data)
+
+
+@router.post("/profiles/{profile_id}/delete", response_model=ImportProfileDto)
+@recorded_command
+def post_delete_import_profile(profile_id: str) -> ImportProfileDto:
+    return delete_import_profile(profile_id)
+
+
+@router.get("/{batch_id}", response_model=ImportPreviewDto)
+def get_import_by_id(batch_id: str) -> ImportPreviewDto:
+    return get_import(batch_id)
+
+
+@router.post("/{batch_id}/commit", response_model=ImportStatementResultDto)
+@recorded_command
+def post_commit_import(batch_id: str) -> ImportStatementResultDto:
+    return commit_import(batch_id)
+
+
+@router.post("/{batch_id}/discard", response_model=ImportPreviewDto)
+@recorded_command
+def post_discard_import(batch_id: str) -> ImportPreviewDto:
+    return discard_import(batch_id)
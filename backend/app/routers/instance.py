@@ -0,0 +1,19 @@
+from fastapi import APIRouter
+
+from app import instance
+from app.config import is_read_only
+from app.models import ApiError, InstanceStatusDto
+
+router = APIRouter(prefix="/instance", tags=["instance"])
+
+
+@router.get("", response_model=InstanceStatusDto)
+def get_instance_status() -> InstanceStatusDto:
+    return instance.get_status()
+
+
+@router.post("/takeover", response_model=InstanceStatusDto)
+def post_takeover() -> InstanceStatusDto:
+    if is_read_only():
+        raise ApiError("read_only", "backend is configured read-only", status_code=403)
+    return instance.request_takeover()
@@ -1,7 +1,7 @@
 ﻿from fastapi import APIRouter, Query
 
-from app.models import AdjustmentKpiDto, KpiPeriodInput
-from app.services.finance import list_adjustment_kpi
+from app.models import AdjustmentKpiDto, DebtToAssetKpiDto, KpiPeriodInput
+from app.services.finance import get_debt_to_asset_kpi, list_adjustment_kpi
 
 router = APIRouter(prefix="/kpis", tags=["kpis"])
 
@@ -15,3 +15,8 @@ def get_adjustment_kpi(
     if fromPeriodYm is not None or toPeriodYm is not None:
         payload = KpiPeriodInput(fromPeriodYm=fromPeriodYm, toPeriodYm=toPeriodYm)
     return list_adjustment_kpi(payload)
+
+
+@router.get("/debt-to-asset", response_model=DebtToAssetKpiDto)
+def get_debt_to_asset(months: int = Query(default=12)) -> DebtToAssetKpiDto:
+    return get_debt_to_asset_kpi(months)
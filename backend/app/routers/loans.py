@@ -0,0 +1,53 @@
+from fastapi import APIRouter, Query
+
+from app.models import (
+    CreateLoanInput,
+    LoanCreatedDto,
+    LoanDto,
+    LoanPaymentsResultDto,
+    LoanScheduleDto,
+    PostLoanPaymentsInput,
+    PrepaymentSimulationDto,
+)
+from app.replay import recorded_command
+from app.services.loans import (
+    create_loan,
+    get_loan_schedule,
+    list_loans,
+    post_loan_payments,
+    simulate_prepayment,
+)
+
+router = APIRouter(prefix="/loans", tags=["loans"])
+
+
+@router.get("", response_model=list[LoanDto])
+def get_loans() -> list[LoanDto]:
+    return list_loans()
+
+
+@router.post("", response_model=LoanCreatedDto)
+@recorded_command
+def post_loan(input_data: CreateLoanInput) -> LoanCreatedDto:
+    return create_loan(input_data)
+
+
+@router.post("/payments", response_model=LoanPaymentsResultDto)
+@recorded_command
+def post_payments(input_data: PostLoanPaymentsInput) -> LoanPaymentsResultDto:
+    return post_loan_payments(input_data)
+
+
+@router.get("/{loan_id}/schedule", response_model=LoanScheduleDto)
+def get_schedule(loan_id: str) -> LoanScheduleDto:
+    return get_loan_schedule(loan_id)
+
+
+@router.get("/{loan_id}/prepayment-simulation", response_model=PrepaymentSimulationDto)
+def get_prepayment_simulation(
+    loan_id: str,
+    lumpSumCents: int = Query(default=0),
+    lumpSumPeriodYm: str | None = Query(default=None),
+    monthlyExtraCents: int = Query(default=0),
+) -> PrepaymentSimulationDto:
+    return simulate_prepayment(loan_id, lumpSumCents, lumpSumPeriodYm, monthlyExtraCents)
@@ -0,0 +1,64 @@
+from fastapi import APIRouter, Query
+
+from app.models import (
+    ArchiveInput,
+    ArchiveResultDto,
+    AuditFindingDto,
+    CleanupSuggestionsDto,
+    DatabaseMaintenanceResultDto,
+    RebuildBalancesInput,
+    RebuildBalancesResultDto,
+    UpgradeConversionInput,
+    UpgradeConversionResultDto,
+    UpgradeFindingDto,
+)
+from app.replay import recorded_command
+from app.services.maintenance import (
+    archive_items,
+    audit_data,
+    maintain_database,
+    rebuild_balances,
+    suggest_cleanup,
+)
+from app.services.upgrade import convert_legacy_data, scan_legacy_data
+
+router = APIRouter(prefix="/maintenance", tags=["maintenance"])
+
+
+@router.get("/cleanup-suggestions", response_model=CleanupSuggestionsDto)
+def get_cleanup_suggestions(months: int = Query(default=6)) -> CleanupSuggestionsDto:
+    return suggest_cleanup(months)
+
+
+@router.post("/archive", response_model=ArchiveResultDto)
+@recorded_command
+def post_archive(input_data: ArchiveInput) -> ArchiveResultDto:
+    return archive_items(input_data)
+
+
+@router.post("/rebuild-balances", response_model=RebuildBalancesResultDto)
+@recorded_command
+def post_rebuild_balances(input_data: RebuildBalancesInput) -> RebuildBalancesResultDto:
+    return rebuild_balances(input_data)
+
+
+@router.get("/audit", response_model=list[AuditFindingDto])
+def get_audit() -> list[AuditFindingDto]:
+    return audit_data()
+
+
+@router.post("/database", response_model=DatabaseMaintenanceResultDto)
+@recorded_command
+def post_database_maintenance() -> DatabaseMaintenanceResultDto:
+    return maintain_database()
+
+
+@router.get("/upgrade-findings", response_model=list[UpgradeFindingDto])
+def get_upgrade_findings() -> list[UpgradeFindingDto]:
+    return scan_legacy_data()
+
+
+@router.post("/upgrade-conversions", response_model=list[UpgradeConversionResultDto])
+@recorded_command
+def post_upgrade_conversions(input_data: UpgradeConversionInput) -> list[UpgradeConversionResultDto]:
+    return convert_legacy_data(input_data)
@@ -0,0 +1,13 @@
+from fastapi import APIRouter
+
+from app.models import OnboardingInput, OnboardingResultDto
+from app.replay import recorded_command
+from app.services.onboarding import complete_onboarding
+
+router = APIRouter(prefix="/onboarding", tags=["onboarding"])
+
+
+@router.post("", response_model=OnboardingResultDto)
+@recorded_command
+def post_onboarding(input_data: OnboardingInput) -> OnboardingResultDto:
+    return complete_onboarding(input_data)
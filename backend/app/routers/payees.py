@@ -0,0 +1,43 @@
+from fastapi import APIRouter, Query
+
+from app.models import (
+    CreatePayeeInput,
+    MergePayeesInput,
+    MergePayeesResultDto,
+    PayeeDto,
+    PayeeSuggestionDto,
+    TransactionDto,
+)
+from app.replay import recorded_command
+from app.services.dimensions import create_payee, list_payees, merge_payees, suggest_for_payee
+from app.services.finance import apply_payee_default_category
+
+router = APIRouter(prefix="/payees", tags=["payees"])
+
+
+@router.get("", response_model=list[PayeeDto])
+def get_payees(includeArchived: bool = Query(default=False)) -> list[PayeeDto]:
+    return list_payees(includeArchived)
+
+
+@router.get("/{payee_id}/suggestion", response_model=PayeeSuggestionDto)
+def get_payee_suggestion(payee_id: str) -> PayeeSuggestionDto:
+    return suggest_for_payee(payee_id)
+
+
+@router.post("", response_model=PayeeDto)
+@recorded_command
+def post_payee(input_data: CreatePayeeInput) -> PayeeDto:
+    return create_payee(input_data)
+
+
+@router.post("/merge", response_model=MergePayeesResultDto)
+@recorded_command
+def post_merge_payees(input_data: MergePayeesInput) -> MergePayeesResultDto:
+    return merge_payees(input_data)
+
+
+@router.post("/{payee_id}/apply-default-category", response_model=list[TransactionDto])
+@recorded_command
+def post_apply_default_category(payee_id: str) -> list[TransactionDto]:
+    return apply_payee_default_category(payee_id)
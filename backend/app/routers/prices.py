@@ -0,0 +1,29 @@
+from fastapi import APIRouter, Query
+
+from app.models import PriceDto, RefreshPricesInput, RefreshPricesResultDto, SetPriceInput
+from app.replay import recorded_command
+from app.services.prices import list_prices, refresh_prices, set_price
+
+router = APIRouter(prefix="/prices", tags=["prices"])
+
+
+@router.get("", response_model=list[PriceDto])
+def get_prices(
+    instrument: str | None = Query(default=None),
+    fromDate: str | None = Query(default=None),
+    toDate: str | None = Query(default=None),
+) -> list[PriceDto]:
+    return list_prices(instrument, fromDate, toDate)
+
+
+@router.post("", response_model=PriceDto)
+@recorded_command
+def post_price(input_data: SetPriceInput) -> PriceDto:
+    return set_price(input_data)
+
+
+# Fetched prices depend on what the provider answers at the time, so replaying the command
+# could not reproduce them; they stay out of the command log.
+@router.post("/refresh", response_model=RefreshPricesResultDto)
+def post_refresh_prices(input_data: RefreshPricesInput) -> RefreshPricesResultDto:
+    return refresh_prices(input_data)
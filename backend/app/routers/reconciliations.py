@@ -1,11 +1,31 @@
-﻿from fastapi import APIRouter
+﻿from fastapi import APIRouter, Query
 
-from app.models import ReconcileInput, ReconcileResultDto
-from app.services.finance import reconcile_account
+from app.models import (
+    BalanceSnapshotDto,
+    MatchStatementInput,
+    ReconcileInput,
+    ReconcileResultDto,
+    StatementMatchResultDto,
+)
+from app.replay import recorded_command
+from app.services.finance import list_balance_snapshots, reconcile_account
+from app.services.statements import match_statement
 
 router = APIRouter(prefix="/reconciliations", tags=["reconciliations"])
 
 
+@router.get("", response_model=list[BalanceSnapshotDto])
+def get_reconciliations(accountId: str = Query(...)) -> list[BalanceSnapshotDto]:
+    return list_balance_snapshots(accountId)
+
+
 @router.post("", response_model=ReconcileResultDto)
+@recorded_command
 def post_reconciliation(input_data: ReconcileInput) -> ReconcileResultDto:
     return reconcile_account(input_data)
+
+
+@router.post("/match", response_model=StatementMatchResultDto)
+@recorded_command
+def post_statement_match(input_data: MatchStatementInput) -> StatementMatchResultDto:
+    return match_statement(input_data)
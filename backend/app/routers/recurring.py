@@ -0,0 +1,37 @@
+from typing import Optional
+
+from fastapi import APIRouter
+
+from app.models import CreateRecurringRuleInput, RecurringRuleDto, RunRecurringInput, RunRecurringResultDto
+from app.replay import recorded_command
+from app.services.recurring import (
+    create_recurring_rule,
+    deactivate_recurring_rule,
+    list_recurring_rules,
+    run_recurring,
+)
+
+router = APIRouter(prefix="/recurring-rules", tags=["recurring-rules"])
+
+
+@router.get("", response_model=list[RecurringRuleDto])
+def get_recurring_rules() -> list[RecurringRuleDto]:
+    return list_recurring_rules()
+
+
+@router.post("", response_model=RecurringRuleDto)
+@recorded_command
+def post_recurring_rule(input_data: CreateRecurringRuleInput) -> RecurringRuleDto:
+    return create_recurring_rule(input_data)
+
+
+@router.post("/{rule_id}/deactivate", response_model=RecurringRuleDto)
+@recorded_command
+def post_deactivate_recurring_rule(rule_id: str) -> RecurringRuleDto:
+    return deactivate_recurring_rule(rule_id)
+
+
+@router.post("/run", response_model=RunRecurringResultDto)
+@recorded_command
+def post_run_recurring(input_data: Optional[RunRecurringInput] = None) -> RunRecurringResultDto:
+    return run_recurring(input_data)
@@ -1,16 +1,153 @@
 ﻿from fastapi import APIRouter, Query
 
-from app.models import ReportDto
-from app.services.finance import get_cash_flow_report, get_utility_report
+from app.models import (
+    AllocationDriftReportDto,
+    AssetPurpose,
+    BalanceDriftReportDto,
+    BudgetReportDto,
+    CashFlowReportDto,
+    CategoryTrendDto,
+    CostPerUseReportDto,
+    DepreciationForecastDto,
+    IncomeStatementDto,
+    LiquidityReportDto,
+    PerformanceReportDto,
+    RealizedGainsReportDto,
+    ReportDto,
+    ReportRangeInput,
+    RollingAverageReportDto,
+    TotalCostOfOwnershipReportDto,
+)
+from app.replay import recorded_command
+from app.services.allocation import get_allocation_drift_report
+from app.services.amortization import (
+    forecast_depreciation,
+    get_cost_per_use_report,
+    get_total_cost_of_ownership_report,
+)
+from app.services.budgets import get_budget_report
+from app.services.finance import (
+    get_balance_drift_report,
+    get_cash_flow_report,
+    get_income_statement,
+    get_liquidity_report,
+    get_purpose_report,
+    get_utility_report,
+)
+from app.services.holdings import get_realized_gains_report
+from app.services.performance import get_performance_report
+from app.services.trends import get_category_trend, get_rolling_average
 
 router = APIRouter(prefix="/reports", tags=["reports"])
 
 
-@router.get("/cash", response_model=ReportDto)
-def get_cash(periodYm: str = Query(...)) -> ReportDto:
-    return get_cash_flow_report(periodYm)
+def _range_input(from_date: str | None, to_date: str | None) -> ReportRangeInput | None:
+    if from_date is None and to_date is None:
+        return None
+    return ReportRangeInput(fromDate=from_date or "", toDate=to_date or "")
+
+
+@router.get("/cash", response_model=CashFlowReportDto)
+def get_cash(
+    periodYm: str | None = Query(default=None),
+    fromDate: str | None = Query(default=None),
+    toDate: str | None = Query(default=None),
+    topTransactions: int = Query(default=0),
+    groupByParent: bool = Query(default=False),
+) -> CashFlowReportDto:
+    return get_cash_flow_report(periodYm, topTransactions, _range_input(fromDate, toDate), groupByParent)
 
 
 @router.get("/utility", response_model=ReportDto)
-def get_utility(periodYm: str = Query(...)) -> ReportDto:
-    return get_utility_report(periodYm)
+@recorded_command
+def get_utility(
+    periodYm: str | None = Query(default=None),
+    fromDate: str | None = Query(default=None),
+    toDate: str | None = Query(default=None),
+    topTransactions: int = Query(default=0),
+) -> ReportDto:
+    return get_utility_report(periodYm, topTransactions, _range_input(fromDate, toDate))
+
+
+@router.get("/income-statement", response_model=IncomeStatementDto)
+@recorded_command
+def get_income_statement_report(
+    periodYm: str | None = Query(default=None),
+    fromDate: str | None = Query(default=None),
+    toDate: str | None = Query(default=None),
+    topTransactions: int = Query(default=0),
+) -> IncomeStatementDto:
+    return get_income_statement(periodYm, topTransactions, _range_input(fromDate, toDate))
+
+
+@router.get("/purpose", response_model=ReportDto)
+def get_purpose(
+    periodYm: str = Query(...),
+    topTransactions: int = Query(default=0),
+) -> ReportDto:
+    return get_purpose_report(periodYm, topTransactions)
+
+
+@router.get("/liquidity", response_model=LiquidityReportDto)
+def get_liquidity() -> LiquidityReportDto:
+    return get_liquidity_report()
+
+
+@router.get("/allocation-drift", response_model=AllocationDriftReportDto)
+def get_allocation_drift() -> AllocationDriftReportDto:
+    return get_allocation_drift_report()
+
+
+@router.get("/budget", response_model=BudgetReportDto)
+def get_budget(periodYm: str = Query(...), groupByParent: bool = Query(default=False)) -> BudgetReportDto:
+    return get_budget_report(periodYm, groupByParent)
+
+
+@router.get("/category-trend", response_model=CategoryTrendDto)
+def get_category_trend_report(
+    categoryIds: list[str] = Query(...),
+    months: int = Query(default=12),
+    endPeriodYm: str | None = Query(default=None),
+    topTransactions: int = Query(default=0),
+) -> CategoryTrendDto:
+    return get_category_trend(categoryIds, months, endPeriodYm, topTransactions)
+
+
+@router.get("/rolling-average", response_model=RollingAverageReportDto)
+def get_rolling_average_report(endPeriodYm: str | None = Query(default=None)) -> RollingAverageReportDto:
+    return get_rolling_average(endPeriodYm)
+
+
+@router.get("/performance", response_model=PerformanceReportDto)
+def get_performance(fromDate: str = Query(...), toDate: str = Query(...)) -> PerformanceReportDto:
+    return get_performance_report(fromDate, toDate)
+
+
+@router.get("/cost-per-use", response_model=CostPerUseReportDto)
+def get_cost_per_use(purpose: AssetPurpose | None = Query(default=None)) -> CostPerUseReportDto:
+    return get_cost_per_use_report(purpose)
+
+
+@router.get("/total-cost-of-ownership", response_model=TotalCostOfOwnershipReportDto)
+def get_total_cost_of_ownership(
+    purpose: AssetPurpose | None = Query(default=None),
+) -> TotalCostOfOwnershipReportDto:
+    return get_total_cost_of_ownership_report(purpose)
+
+
+@router.get("/depreciation-forecast", response_model=DepreciationForecastDto)
+def get_depreciation_forecast(
+    months: int = Query(default=12),
+    fromPeriodYm: str | None = Query(default=None),
+) -> DepreciationForecastDto:
+    return forecast_depreciation(months, fromPeriodYm)
+
+
+@router.get("/balance-drift", response_model=BalanceDriftReportDto)
+def get_balance_drift(accountId: str | None = Query(default=None)) -> BalanceDriftReportDto:
+    return get_balance_drift_report(accountId)
+
+
+@router.get("/realized-gains", response_model=RealizedGainsReportDto)
+def get_realized_gains(year: int = Query(...)) -> RealizedGainsReportDto:
+    return get_realized_gains_report(year)
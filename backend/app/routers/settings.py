@@ -0,0 +1,18 @@
+from fastapi import APIRouter
+
+from app.models import SettingsDto, UpdateSettingsInput
+from app.replay import recorded_command
+from app.services.settings import get_settings, update_settings
+
+router = APIRouter(prefix="/settings", tags=["settings"])
+
+
+@router.get("", response_model=SettingsDto)
+def read_settings() -> SettingsDto:
+    return get_settings()
+
+
+@router.patch("", response_model=SettingsDto)
+@recorded_command
+def patch_settings(input_data: UpdateSettingsInput) -> SettingsDto:
+    return update_settings(input_data)
@@ -1,6 +1,7 @@
 ﻿from fastapi import APIRouter
 
-from app.models import InitStateDto
+from app.migrations import list_migration_states
+from app.models import InitStateDto, MigrationStateDto
 from app.services.finance import init_state
 
 router = APIRouter(prefix="/system", tags=["system"])
@@ -9,3 +10,8 @@ router = APIRouter(prefix="/system", tags=["system"])
 @router.get("/init", response_model=InitStateDto)
 def get_init_state() -> InitStateDto:
     return InitStateDto(**init_state())
+
+
+@router.get("/migrations", response_model=list[MigrationStateDto])
+def get_migrations() -> list[MigrationStateDto]:
+    return list_migration_states()
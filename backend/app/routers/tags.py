@@ -0,0 +1,24 @@
+from fastapi import APIRouter, Query
+
+from app.models import CreateTagInput, TagDto, UpdateTagInput
+from app.replay import recorded_command
+from app.services.dimensions import create_tag, list_tags, update_tag
+
+router = APIRouter(prefix="/tags", tags=["tags"])
+
+
+@router.get("", response_model=list[TagDto])
+def get_tags(includeArchived: bool = Query(default=False)) -> list[TagDto]:
+    return list_tags(includeArchived)
+
+
+@router.post("", response_model=TagDto)
+@recorded_command
+def post_tag(input_data: CreateTagInput) -> TagDto:
+    return create_tag(input_data)
+
+
+@router.patch("/{tag_id}", response_model=TagDto)
+@recorded_command
+def patch_tag(tag_id: str, input_data: UpdateTagInput) -> TagDto:
+    return update_tag(tag_id, input_data)
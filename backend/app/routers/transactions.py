@@ -1,7 +1,49 @@
 ﻿from fastapi import APIRouter, Query
+from fastapi.responses import FileResponse
 
-from app.models import AccrualType, CreateTransactionInput, PagedTransactionsDto, TransactionDto
-from app.services.finance import create_transaction, list_transactions
+from app.models import (
+    AccrualType,
+    AssignPayeeInput,
+    CreateMultiLegTransactionInput,
+    CreateTransactionInput,
+    CreateTransactionsBatchInput,
+    DuplicateTransactionInput,
+    MarkClearedInput,
+    MarkClearedResultDto,
+    PagedTransactionsDto,
+    ReceiptSuggestionDto,
+    RecategorizeAdjustmentInput,
+    TransactionAnomalyDto,
+    TransactionAttachmentDto,
+    TransactionDto,
+    TransactionPostingDto,
+    TrashedTransactionDto,
+    UploadAttachmentInput,
+    VoidTransactionInput,
+    VoidTransactionResultDto,
+)
+from app.replay import recorded_command
+from app.services.anomalies import find_anomalies
+from app.services.attachments import (
+    delete_attachment,
+    get_attachment_file,
+    list_attachments,
+    read_receipt,
+    upload_attachment,
+)
+from app.services.finance import (
+    assign_payee,
+    create_multi_leg_transaction,
+    create_transaction,
+    create_transactions_batch,
+    duplicate_transaction,
+    list_transaction_postings,
+    list_transactions,
+    list_unreviewed_adjustments,
+    mark_cleared,
+    recategorize_adjustment,
+)
+from app.services.trash import delete_transaction, void_transaction
 
 router = APIRouter(prefix="/transactions", tags=["transactions"])
 
@@ -14,6 +56,98 @@ def get_transactions(
     return list_transactions(periodYm, accrualType)
 
 
+@router.get("/anomalies", response_model=list[TransactionAnomalyDto])
+def get_transaction_anomalies(periodYm: str | None = Query(default=None)) -> list[TransactionAnomalyDto]:
+    return find_anomalies(periodYm)
+
+
+@router.get("/unreviewed-adjustments", response_model=list[TransactionDto])
+def get_unreviewed_adjustments(accountId: str | None = Query(default=None)) -> list[TransactionDto]:
+    return list_unreviewed_adjustments(accountId)
+
+
+@router.get("/{transaction_id}/postings", response_model=list[TransactionPostingDto])
+def get_transaction_postings(transaction_id: str) -> list[TransactionPostingDto]:
+    return list_transaction_postings(transaction_id)
+
+
 @router.post("", response_model=TransactionDto)
+@recorded_command
 def post_transaction(input_data: CreateTransactionInput) -> TransactionDto:
     return create_transaction(input_data)
+
+
+@router.post("/batch", response_model=list[TransactionDto])
+@recorded_command
+def post_transactions_batch(input_data: CreateTransactionsBatchInput) -> list[TransactionDto]:
+    return create_transactions_batch(input_data)
+
+
+@router.post("/multi-leg", response_model=TransactionDto)
+@recorded_command
+def post_multi_leg_transaction(input_data: CreateMultiLegTransactionInput) -> TransactionDto:
+    return create_multi_leg_transaction(input_data)
+
+
+# Reads a file without storing anything, and what comes back depends on an outside tool.
+@router.post("/read-receipt", response_model=ReceiptSuggestionDto)
+def post_read_receipt(input_data: UploadAttachmentInput) -> ReceiptSuggestionDto:
+    return read_receipt(input_data)
+
+
+@router.post("/mark-cleared", response_model=MarkClearedResultDto)
+@recorded_command
+def post_mark_cleared(input_data: MarkClearedInput) -> MarkClearedResultDto:
+    return mark_cleared(input_data)
+
+
+@router.post("/{transaction_id}/recategorize", response_model=TransactionDto)
+@recorded_command
+def post_recategorize_adjustment(transaction_id: str, input_data: RecategorizeAdjustmentInput) -> TransactionDto:
+    return recategorize_adjustment(transaction_id, input_data)
+
+
+@router.post("/{transaction_id}/duplicate", response_model=TransactionDto)
+@recorded_command
+def post_duplicate_transaction(transaction_id: str, input_data: DuplicateTransactionInput) -> TransactionDto:
+    return duplicate_transaction(transaction_id, input_data)
+
+
+@router.post("/{transaction_id}/payee", response_model=TransactionDto)
+@recorded_command
+def post_assign_payee(transaction_id: str, input_data: AssignPayeeInput) -> TransactionDto:
+    return assign_payee(transaction_id, input_data)
+
+
+@router.get("/{transaction_id}/attachments", response_model=list[TransactionAttachmentDto])
+def get_transaction_attachments(transaction_id: str) -> list[TransactionAttachmentDto]:
+    return list_attachments(transaction_id)
+
+
+# Attachments are files rather than ledger state, so like account photos they stay out of the command log.
+@router.post("/{transaction_id}/attachments", response_model=TransactionAttachmentDto)
+def post_transaction_attachment(transaction_id: str, input_data: UploadAttachmentInput) -> TransactionAttachmentDto:
+    return upload_attachment(transaction_id, input_data)
+
+
+@router.get("/{transaction_id}/attachments/{attachment_id}")
+def get_transaction_attachment(transaction_id: str, attachment_id: str) -> FileResponse:
+    path, content_type, file_name = get_attachment_file(transaction_id, attachment_id)
+    return FileResponse(path, media_type=content_type, filename=file_name)
+
+
+@router.post("/{transaction_id}/attachments/{attachment_id}/delete", response_model=TransactionAttachmentDto)
+def post_delete_transaction_attachment(transaction_id: str, attachment_id: str) -> TransactionAttachmentDto:
+    return delete_attachment(transaction_id, attachment_id)
+
+
+@router.post("/{transaction_id}/delete", response_model=TrashedTransactionDto)
+@recorded_command
+def post_delete_transaction(transaction_id: str) -> TrashedTransactionDto:
+    return delete_transaction(transaction_id)
+
+
+@router.post("/{transaction_id}/void", response_model=VoidTransactionResultDto)
+@recorded_command
+def post_void_transaction(transaction_id: str, input_data: VoidTransactionInput) -> VoidTransactionResultDto:
+    return void_transaction(transaction_id, input_data)
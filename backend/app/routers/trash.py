@@ -0,0 +1,24 @@
+from fastapi import APIRouter
+
+from app.models import PurgeTrashInput, PurgeTrashResultDto, TrashedTransactionDto
+from app.replay import recorded_command
+from app.services.trash import list_trash, purge_trash, restore_transaction
+
+router = APIRouter(prefix="/trash", tags=["trash"])
+
+
+@router.get("", response_model=list[TrashedTransactionDto])
+def get_trash() -> list[TrashedTransactionDto]:
+    return list_trash()
+
+
+@router.post("/purge", response_model=PurgeTrashResultDto)
+@recorded_command
+def post_purge_trash(input_data: PurgeTrashInput) -> PurgeTrashResultDto:
+    return purge_trash(input_data)
+
+
+@router.post("/{transaction_id}/restore", response_model=TrashedTransactionDto)
+@recorded_command
+def post_restore_transaction(transaction_id: str) -> TrashedTransactionDto:
+    return restore_transaction(transaction_id)
@@ -0,0 +1,11 @@
+from fastapi import APIRouter
+
+from app.models import UsageStatsDto
+from app.services.usage import get_usage_stats
+
+router = APIRouter(prefix="/usage", tags=["usage"])
+
+
+@router.get("", response_model=UsageStatsDto)
+def get_usage() -> UsageStatsDto:
+    return get_usage_stats()
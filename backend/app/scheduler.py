@@ -0,0 +1,91 @@
+"""Periodic background jobs run by the instance that owns the data dir.
+
+Jobs run on a background thread once the startup backfills finish and then every configured interval
+(`scheduler_interval_seconds`, an hour by default; 0 turns them off). This is the only
+place accruals are posted from, so reports and exports stay pure reads. Each job must be
+safe to repeat: recurring generation skips occurrences it already booked; loan payments,
+interest accruals, depreciation and holding revaluations skip months already posted; and
+the spending pace check notifies at most once per month.
+"""
+
+from __future__ import annotations
+
+import logging
+import threading
+from pathlib import Path
+from typing import Callable, Optional
+
+from app import instance as instance_lock
+from app.migrations import wait_for_backfills
+from app.config import get_data_dir, get_scheduler_interval_seconds, use_data_dir
+from app.models import ApiError
+from app.services.amortization import generate_depreciation
+from app.services.holdings import generate_holding_revaluations
+from app.services.interest import generate_interest_accruals
+from app.services.loans import generate_loan_payments
+from app.services.pace import check_spending_pace
+from app.services.recurring import run_recurring
+
+
+logger = logging.getLogger(__name__)
+
+JOBS: tuple[tuple[str, Callable[[], object]], ...] = (
+    ("recurring generation", run_recurring),
+    ("loan payment generation", generate_loan_payments),
+    ("interest accrual generation", generate_interest_accruals),
+    ("depreciation generation", generate_depreciation),
+    ("holding revaluation generation", generate_holding_revaluations),
+    ("spending pace check", check_spending_pace),
+)
+
+_stop = threading.Event()
+_thread: Optional[threading.Thread] = None
+
+
+def run_jobs() -> None:
+    if not instance_lock.is_owner():
+        return
+    for name, job in JOBS:
+        # One failing job must not keep the ones after it from running.
+        try:
+            job()
+        except ApiError as exc:
+            logger.warning("%s skipped: %s", name, exc.message)
+        except Exception:
+            logger.exception("%s failed", name)
+
+
+def _run(data_dir: Path, interval_seconds: int) -> None:
+    # Jobs write, so they wait for the startup backfills to finish.
+    while not wait_for_backfills(timeout=1):
+        if _stop.is_set():
+            return
+    # Threads do not inherit the request context, so pin the data dir captured at start.
+    while True:
+        with use_data_dir(data_dir):
+            try:
+                run_jobs()
+            except Exception:
+                logger.exception("scheduled jobs failed")
+        if _stop.wait(interval_seconds):
+            return
+
+
+def start() -> None:
+    global _thread
+    interval_seconds = get_scheduler_interval_seconds()
+    if interval_seconds == 0:
+        return
+    _stop.clear()
+    _thread = threading.Thread(
+        target=_run, args=(get_data_dir(), interval_seconds), name="scheduler", daemon=True
+    )
+    _thread.start()
+
+
+def stop() -> None:
+    global _thread
+    _stop.set()
+    if _thread is not None:
+        _thread.join(timeout=5)
+        _thread = None
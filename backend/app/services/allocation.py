@@ -0,0 +1,87 @@
+from __future__ import annotations
+
+import sqlite3
+
+from app.db import get_connection, now_utc_rfc3339, transaction
+from app.models import (
+    AllocationDriftItemDto,
+    AllocationDriftReportDto,
+    AllocationTargetDto,
+    ApiError,
+    AssetPurpose,
+    SetAllocationTargetsInput,
+)
+
+
+def _load_targets(conn: sqlite3.Connection) -> dict[AssetPurpose, float]:
+    rows = conn.execute("SELECT purpose, target_percent FROM allocation_targets").fetchall()
+    return {AssetPurpose(row["purpose"]): float(row["target_percent"]) for row in rows}
+
+
+def list_allocation_targets() -> list[AllocationTargetDto]:
+    with get_connection() as conn:
+        targets = _load_targets(conn)
+    return [
+        AllocationTargetDto(purpose=purpose, targetPercent=targets[purpose])
+        for purpose in AssetPurpose
+        if purpose in targets
+    ]
+
+
+def set_allocation_targets(input_data: SetAllocationTargetsInput) -> list[AllocationTargetDto]:
+    """Replace every target; a purpose left out has no target and is not rebalanced."""
+    purposes = [target.purpose for target in input_data.targets]
+    if len(set(purposes)) != len(purposes):
+        raise ApiError("invalid_input", "each purpose can only have one target")
+    for target in input_data.targets:
+        if target.targetPercent < 0 or target.targetPercent > 100:
+            raise ApiError("invalid_input", "targetPercent must be between 0 and 100")
+    if sum(target.targetPercent for target in input_data.targets) > 100:
+        raise ApiError("invalid_input", "allocation targets add up to more than 100%")
+
+    now = now_utc_rfc3339()
+    with get_connection() as conn:
+        with transaction(conn):
+            conn.execute("DELETE FROM allocation_targets")
+            conn.executemany(
+                "INSERT INTO allocation_targets (purpose, target_percent, updated_at) VALUES (?, ?, ?)",
+                [(target.purpose.value, target.targetPercent, now) for target in input_data.targets],
+            )
+    return list_allocation_targets()
+
+
+def get_allocation_drift_report() -> AllocationDriftReportDto:
+    """Compare asset balances per purpose with their targets.
+
+    A positive `rebalanceCents` is what has to move into that purpose to hit its target,
+    a negative one what has to move out.
+    """
+    with get_connection() as conn:
+        targets = _load_targets(conn)
+        rows = conn.execute(
+            """
+            SELECT purpose, SUM(balance_cents) AS total
+            FROM accounts
+            WHERE type = 'Asset'
+            GROUP BY purpose
+            """
+        ).fetchall()
+
+    actual = {AssetPurpose(row["purpose"]): int(row["total"]) for row in rows}
+    total = sum(actual.values())
+    items: list[AllocationDriftItemDto] = []
+    for purpose in AssetPurpose:
+        actual_cents = actual.get(purpose, 0)
+        target_percent = targets.get(purpose)
+        target_cents = None if target_percent is None else round(total * target_percent / 100)
+        items.append(
+            AllocationDriftItemDto(
+                purpose=purpose,
+                actualCents=actual_cents,
+                actualPercent=actual_cents * 100 / total if total > 0 else 0.0,
+                targetPercent=target_percent,
+                targetCents=target_cents,
+                rebalanceCents=None if target_cents is None else target_cents - actual_cents,
+            )
+        )
+    return AllocationDriftReportDto(totalAssetsCents=total, items=items)
@@ -0,0 +1,726 @@
+from __future__ import annotations
+
+import sqlite3
+import uuid
+from datetime import date, timedelta
+from typing import Optional
+
+from app import events
+from app.db import (
+    format_period,
+    get_connection,
+    local_date,
+    local_today,
+    normalize_timestamp,
+    now_utc_rfc3339,
+    parse_date_ymd,
+    parse_period,
+    scratch_copy,
+    transaction,
+)
+from app.models import (
+    AccrualType,
+    AmortizationPostingDto,
+    AmortizationScheduleProgressDto,
+    AmortizationStatus,
+    AmortizationStrategy,
+    ApiError,
+    AssetRevaluationDto,
+    AssetRevaluationResultDto,
+    CancelAmortizationScheduleInput,
+    CancelAmortizationScheduleResultDto,
+    AssetPurpose,
+    CatchUpDepreciationInput,
+    CostPerUseItemDto,
+    CostPerUseReportDto,
+    CreateTransactionInput,
+    DepreciationCatchUpResultDto,
+    DepreciationForecastDto,
+    DepreciationForecastMonthDto,
+    DisposeAssetInput,
+    DisposeAssetResultDto,
+    ExpiringWarrantyDto,
+    LogUsageInput,
+    RevalueAssetInput,
+    TotalCostOfOwnershipItemDto,
+    TotalCostOfOwnershipReportDto,
+    UpdateAssetDetailsInput,
+    UsageLogDto,
+    add_months,
+    ensure_amount_in_range,
+)
+from app.services.finance import (
+    _book_adjustment,
+    _load_account,
+    _load_schedule,
+    _load_transaction,
+    _schedule_from_row,
+    depreciation_months,
+    ensure_depreciation_for_period,
+    ensure_depreciation_up_to,
+    insert_transaction,
+    publish_transactions,
+)
+from app.services.settings import get_timezone
+
+
+AMORTIZATION_POSTING_COLUMNS = "id, schedule_id, period_ym, amount_cents, transaction_id, generated_at"
+
+
+def _posting_from_row(row: sqlite3.Row) -> AmortizationPostingDto:
+    return AmortizationPostingDto(
+        id=row["id"],
+        scheduleId=row["schedule_id"],
+        periodYm=row["period_ym"],
+        amountCents=row["amount_cents"],
+        transactionId=row["transaction_id"],
+        generatedAt=row["generated_at"],
+    )
+
+
+def _schedules_with_progress(
+    conn: sqlite3.Connection,
+    status: Optional[AmortizationStatus] = None,
+    asset_account_id: Optional[str] = None,
+    schedule_id: Optional[str] = None,
+) -> list[AmortizationScheduleProgressDto]:
+    status_value = None if status is None else status.value
+    rows = conn.execute(
+        """
+        SELECT s.id, s.asset_account_id, s.strategy, s.total_periods, s.residual_cents,
+               s.start_date, s.source_transaction_id, s.status, s.declining_factor_percent, s.expected_units,
+               s.prorate_daily, s.serial_number, s.receipt_reference, s.warranty_expires_on,
+               COALESCE(s.basis_cents, t.amount_cents) AS purchase_cents,
+               s.revaluation_cents, s.rebased_period_ym, s.rebased_cents, s.disposed_at,
+               COUNT(p.id) AS periods_posted,
+               COALESCE(SUM(p.amount_cents), 0) + s.carried_depreciation_cents AS depreciated_cents
+        FROM amortization_schedules s
+        JOIN transactions t ON t.id = s.source_transaction_id
+        LEFT JOIN amortization_postings p
+          ON p.schedule_id = s.id AND p.transaction_id IN (SELECT id FROM transactions WHERE deleted_at IS NULL)
+        WHERE (? IS NULL OR s.status = ?)
+          AND (? IS NULL OR s.asset_account_id = ?)
+          AND (? IS NULL OR s.id = ?)
+        GROUP BY s.id
+        ORDER BY s.start_date ASC, s.created_at ASC
+        """,
+        (status_value, status_value, asset_account_id, asset_account_id, schedule_id, schedule_id),
+    ).fetchall()
+    return [
+        AmortizationScheduleProgressDto(
+            **_schedule_from_row(row).model_dump(),
+            purchaseAmountCents=row["purchase_cents"],
+            periodsPosted=row["periods_posted"],
+            depreciatedCents=row["depreciated_cents"],
+            revaluationCents=row["revaluation_cents"],
+            remainingBookValueCents=int(row["purchase_cents"])
+            + int(row["revaluation_cents"])
+            - int(row["depreciated_cents"]),
+            disposedAt=row["disposed_at"],
+        )
+        for row in rows
+    ]
+
+
+def list_amortization_schedules(
+    status: Optional[AmortizationStatus] = None,
+    asset_account_id: Optional[str] = None,
+) -> list[AmortizationScheduleProgressDto]:
+    with get_connection() as conn:
+        if asset_account_id is not None:
+            _load_account(conn, asset_account_id)
+        return _schedules_with_progress(conn, status, asset_account_id)
+
+
+def list_amortization_postings(schedule_id: str) -> list[AmortizationPostingDto]:
+    """Every depreciation posting a schedule has generated, oldest period first."""
+    with get_connection() as conn:
+        _load_schedule(conn, schedule_id)
+        rows = conn.execute(
+            f"""
+            SELECT {AMORTIZATION_POSTING_COLUMNS}
+            FROM amortization_postings
+            WHERE schedule_id = ?
+            ORDER BY period_ym ASC
+            """,
+            (schedule_id,),
+        ).fetchall()
+    return [_posting_from_row(row) for row in rows]
+
+
+def catch_up_depreciation(input_data: CatchUpDepreciationInput) -> DepreciationCatchUpResultDto:
+    """Generate the depreciation of every month no report has asked for yet."""
+    with get_connection() as conn:
+        with transaction(conn):
+            posted = ensure_depreciation_up_to(conn, input_data.periodYm)
+        publish_transactions(conn, posted)
+        placeholders = ", ".join("?" for _ in posted)
+        rows = conn.execute(
+            f"""
+            SELECT {AMORTIZATION_POSTING_COLUMNS}
+            FROM amortization_postings
+            WHERE transaction_id IN ({placeholders})
+            ORDER BY period_ym ASC, schedule_id ASC
+            """,
+            posted,
+        ).fetchall()
+    return DepreciationCatchUpResultDto(
+        periodYm=input_data.periodYm,
+        postings=[_posting_from_row(row) for row in rows],
+    )
+
+
+def cancel_amortization_schedule(
+    schedule_id: str, input_data: CancelAmortizationScheduleInput
+) -> CancelAmortizationScheduleResultDto:
+    """Stop a schedule from generating further depreciation.
+
+    Depreciation rows are positive and never moved a balance, so there is no negative row
+    to offset them with: reversing moves the generated rows to the trash instead. They and
+    their postings stay on record, stop counting towards reports and book value, and come
+    back one by one if restored.
+    """
+    with get_connection() as conn:
+        schedule = _load_schedule(conn, schedule_id)
+        if schedule.status == AmortizationStatus.CANCELLED.value:
+            raise ApiError("invalid_input", "schedule is already cancelled")
+        reversed_ids: list[str] = []
+        with transaction(conn):
+            conn.execute(
+                "UPDATE amortization_schedules SET status = ? WHERE id = ?",
+                (AmortizationStatus.CANCELLED.value, schedule_id),
+            )
+            if input_data.reversePostings:
+                reversed_ids = [
+                    row["transaction_id"]
+                    for row in conn.execute(
+                        """
+                        SELECT p.transaction_id
+                        FROM amortization_postings p
+                        JOIN transactions t ON t.id = p.transaction_id
+                        WHERE p.schedule_id = ? AND t.deleted_at IS NULL
+                        ORDER BY p.period_ym
+                        """,
+                        (schedule_id,),
+                    ).fetchall()
+                ]
+                deleted_at = now_utc_rfc3339()
+                conn.executemany(
+                    "UPDATE transactions SET deleted_at = ? WHERE id = ?",
+                    [(deleted_at, tx_id) for tx_id in reversed_ids],
+                )
+        for tx_id in reversed_ids:
+            events.emit("transaction:deleted", {"id": tx_id})
+        return CancelAmortizationScheduleResultDto(
+            schedule=_schedules_with_progress(conn, schedule_id=schedule_id)[0],
+            reversedTransactionIds=reversed_ids,
+        )
+
+
+def generate_depreciation() -> int:
+    """Post the depreciation of every month that has begun; returns how many rows were posted.
+
+    Each month commits on its own, so an interrupted run keeps what it posted and the next
+    one carries on from the first month still missing.
+    """
+    posted_count = 0
+    with get_connection() as conn:
+        months = depreciation_months(conn, format_period(local_today(get_timezone(conn))))
+        for index, period_ym in enumerate(months, start=1):
+            with transaction(conn):
+                posted = ensure_depreciation_for_period(conn, period_ym)
+            if not posted:
+                continue
+            publish_transactions(conn, posted)
+            posted_count += len(posted)
+            events.emit(
+                "depreciation:progress",
+                {"periodYm": period_ym, "processedCount": index, "totalCount": len(months), "postedCount": len(posted)},
+            )
+    return posted_count
+
+
+def dispose_asset(schedule_id: str, input_data: DisposeAssetInput) -> DisposeAssetResultDto:
+    """Dispose of part of an asset, splitting its schedule in proportion.
+
+    The disposed share of the purchase, residual, revaluations and depreciation so far moves to a new,
+    cancelled schedule; the original keeps depreciating what is left. The disposed cost
+    leaves the asset account, through the proceeds where there are any, and book value the
+    proceeds do not recover is booked as depreciation so it still reaches the utility report.
+    """
+    if not 0 < input_data.disposedPercent < 100:
+        raise ApiError("invalid_input", "disposedPercent must be between 0 and 100")
+    if input_data.proceedsCents < 0:
+        raise ApiError("invalid_input", "proceedsCents cannot be negative")
+    ensure_amount_in_range(input_data.proceedsCents, "proceedsCents")
+    if input_data.proceedsCents > 0 and input_data.toAccountId is None:
+        raise ApiError("invalid_input", "toAccountId is required when there are proceeds")
+    occurred_at = normalize_timestamp(input_data.occurredAt)
+
+    with get_connection() as conn:
+        tz = get_timezone(conn)
+        if input_data.toAccountId is not None:
+            _load_account(conn, input_data.toAccountId)
+        _load_schedule(conn, schedule_id)
+        schedule = _schedules_with_progress(conn, schedule_id=schedule_id)[0]
+        if schedule.status == AmortizationStatus.CANCELLED.value:
+            raise ApiError("invalid_input", "schedule is cancelled")
+
+        share = input_data.disposedPercent / 100
+        disposed_basis = round(schedule.purchaseAmountCents * share)
+        if disposed_basis <= 0 or disposed_basis >= schedule.purchaseAmountCents:
+            raise ApiError("invalid_input", "disposedPercent is too small to split this asset")
+        disposed_residual = round(schedule.residualCents * share)
+        disposed_depreciation = round(schedule.depreciatedCents * share)
+        disposed_revaluation = round(schedule.revaluationCents * share)
+        rebase = conn.execute(
+            "SELECT rebased_period_ym, rebased_cents FROM amortization_schedules WHERE id = ?", (schedule_id,)
+        ).fetchone()
+        disposed_rebased = None if rebase["rebased_cents"] is None else round(rebase["rebased_cents"] * share)
+        # Usage already logged stays with both parts; only the usage still expected is split.
+        kept_units = disposed_units = None
+        if schedule.expectedUnits is not None:
+            logged = conn.execute(
+                "SELECT COALESCE(SUM(units), 0) FROM usage_logs WHERE schedule_id = ?", (schedule_id,)
+            ).fetchone()[0]
+            kept_units = logged + (schedule.expectedUnits - logged) * (1 - share)
+            disposed_units = logged + (schedule.expectedUnits - logged) * share
+        # What the disposed part still stands at in the asset account's balance.
+        disposed_cost = disposed_basis + disposed_revaluation
+        loss = disposed_cost - disposed_depreciation - input_data.proceedsCents
+
+        disposed_id = str(uuid.uuid4())
+        tx_ids: list[str] = []
+        with transaction(conn):
+            now = now_utc_rfc3339()
+            conn.execute(
+                """
+                UPDATE amortization_schedules
+                SET basis_cents = ?, residual_cents = ?, carried_depreciation_cents = carried_depreciation_cents - ?,
+                    revaluation_cents = revaluation_cents - ?, rebased_cents = rebased_cents - ?, expected_units = ?
+                WHERE id = ?
+                """,
+                (
+                    schedule.purchaseAmountCents - disposed_basis,
+                    schedule.residualCents - disposed_residual,
+                    disposed_depreciation,
+                    disposed_revaluation,
+                    disposed_rebased,
+                    kept_units,
+                    schedule_id,
+                ),
+            )
+            conn.execute(
+                """
+                INSERT INTO amortization_schedules (
+                    id, asset_account_id, strategy, total_periods, residual_cents, start_date,
+                    source_transaction_id, status, basis_cents, carried_depreciation_cents, disposed_at,
+                    revaluation_cents, rebased_period_ym, rebased_cents, declining_factor_percent, expected_units,
+                    prorate_daily, serial_number, receipt_reference, warranty_expires_on, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                """,
+                (
+                    disposed_id,
+                    schedule.assetAccountId,
+                    schedule.strategy.value,
+                    schedule.totalPeriods,
+                    disposed_residual,
+                    schedule.startDate,
+                    schedule.sourceTransactionId,
+                    AmortizationStatus.CANCELLED.value,
+                    disposed_basis,
+                    disposed_depreciation,
+                    occurred_at,
+                    disposed_revaluation,
+                    rebase["rebased_period_ym"],
+                    disposed_rebased,
+                    schedule.decliningFactorPercent,
+                    disposed_units,
+                    int(schedule.prorateDaily),
+                    schedule.serialNumber,
+                    schedule.receiptReference,
+                    schedule.warrantyExpiresOn,
+                    now,
+                ),
+            )
+
+            # Like the purchase itself, the rows moving the cost are kept out of spending.
+            if input_data.proceedsCents > 0:
+                tx_ids.append(
+                    insert_transaction(
+                        conn,
+                        CreateTransactionInput(
+                            amountCents=input_data.proceedsCents,
+                            fromAccountId=schedule.assetAccountId,
+                            toAccountId=input_data.toAccountId,
+                            isAssetPurchase=True,
+                            note="Sale of part of an asset",
+                            occurredAt=occurred_at,
+                        ),
+                    )
+                )
+            unrecovered = disposed_cost - input_data.proceedsCents
+            if unrecovered != 0:
+                tx_ids.append(
+                    insert_transaction(
+                        conn,
+                        CreateTransactionInput(
+                            amountCents=abs(unrecovered),
+                            fromAccountId=schedule.assetAccountId if unrecovered > 0 else None,
+                            toAccountId=schedule.assetAccountId if unrecovered < 0 else None,
+                            isAssetPurchase=True,
+                            note="Disposed part of an asset" if unrecovered > 0 else "Gain on disposal of an asset",
+                            occurredAt=occurred_at,
+                        ),
+                    )
+                )
+            if loss > 0:
+                loss_tx_id = insert_transaction(
+                    conn,
+                    CreateTransactionInput(
+                        amountCents=loss,
+                        accrualType=AccrualType.DEPRECIATION,
+                        note="Loss on disposal of an asset",
+                        occurredAt=occurred_at,
+                    ),
+                )
+                conn.execute(
+                    """
+                    INSERT INTO amortization_postings (
+                        id, schedule_id, period_ym, amount_cents, transaction_id, generated_at
+                    ) VALUES (?, ?, ?, ?, ?, ?)
+                    """,
+                    (str(uuid.uuid4()), disposed_id, format_period(local_date(occurred_at, tz)), loss, loss_tx_id, now),
+                )
+                tx_ids.append(loss_tx_id)
+
+        publish_transactions(conn, tx_ids)
+        return DisposeAssetResultDto(
+            schedule=_schedules_with_progress(conn, schedule_id=schedule_id)[0],
+            disposedSchedule=_schedules_with_progress(conn, schedule_id=disposed_id)[0],
+            transactions=[_load_transaction(conn, tx_id) for tx_id in tx_ids],
+        )
+
+
+def revalue_asset(schedule_id: str, input_data: RevalueAssetInput) -> AssetRevaluationResultDto:
+    """Record an appraisal of the asset a schedule depreciates.
+
+    The gap to the current book value is booked on the asset account, and the periods after
+    the appraisal's month spread the appraised amount instead of what was left of the purchase.
+    Depreciation up to that month is posted first so the book value is current.
+    """
+    if input_data.appraisedCents < 0:
+        raise ApiError("invalid_input", "appraisedCents cannot be negative")
+    ensure_amount_in_range(input_data.appraisedCents, "appraisedCents")
+    occurred_at = normalize_timestamp(input_data.occurredAt)
+
+    with get_connection() as conn:
+        period_ym = format_period(local_date(occurred_at, get_timezone(conn)))
+        month_start, _, _ = parse_period(period_ym)
+        _load_schedule(conn, schedule_id)
+        if _schedules_with_progress(conn, schedule_id=schedule_id)[0].disposedAt is not None:
+            raise ApiError("invalid_input", "schedule covers a part that was disposed of")
+        later = conn.execute(
+            "SELECT MIN(period_ym) FROM amortization_postings WHERE schedule_id = ? AND period_ym > ?",
+            (schedule_id, period_ym),
+        ).fetchone()[0]
+        if later is not None:
+            raise ApiError("conflict", f"depreciation is already posted for {later}", status_code=409)
+
+        revaluation_id = str(uuid.uuid4())
+        with transaction(conn):
+            posted = ensure_depreciation_up_to(conn, period_ym)
+            schedule = _schedules_with_progress(conn, schedule_id=schedule_id)[0]
+            delta = input_data.appraisedCents - schedule.remainingBookValueCents
+            tx_id = _book_adjustment(
+                conn,
+                schedule.assetAccountId,
+                delta,
+                input_data.note or "Appraisal",
+                occurred_at,
+            )
+            conn.execute(
+                """
+                UPDATE amortization_schedules
+                SET revaluation_cents = revaluation_cents + ?, rebased_period_ym = ?, rebased_cents = ?
+                WHERE id = ?
+                """,
+                (delta, format_period(add_months(month_start, 1)), input_data.appraisedCents, schedule_id),
+            )
+            conn.execute(
+                """
+                INSERT INTO asset_revaluations (
+                    id, schedule_id, appraised_cents, carrying_cents, transaction_id, occurred_at, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?)
+                """,
+                (
+                    revaluation_id,
+                    schedule_id,
+                    input_data.appraisedCents,
+                    schedule.remainingBookValueCents,
+                    tx_id,
+                    occurred_at,
+                    now_utc_rfc3339(),
+                ),
+            )
+
+        publish_transactions(conn, posted + ([tx_id] if tx_id is not None else []))
+        return AssetRevaluationResultDto(
+            revaluation=AssetRevaluationDto(
+                id=revaluation_id,
+                scheduleId=schedule_id,
+                appraisedCents=input_data.appraisedCents,
+                carryingCents=schedule.remainingBookValueCents,
+                transactionId=tx_id,
+                occurredAt=occurred_at,
+            ),
+            schedule=_schedules_with_progress(conn, schedule_id=schedule_id)[0],
+            transaction=None if tx_id is None else _load_transaction(conn, tx_id),
+        )
+
+
+def update_asset_details(schedule_id: str, input_data: UpdateAssetDetailsInput) -> AmortizationScheduleProgressDto:
+    """Record the serial number, receipt and warranty of the asset a schedule covers."""
+    if input_data.warrantyExpiresOn:
+        parse_date_ymd(input_data.warrantyExpiresOn, "warrantyExpiresOn")
+
+    def merged(value: Optional[str], current: Optional[str]) -> Optional[str]:
+        return current if value is None else value.strip() or None
+
+    with get_connection() as conn:
+        schedule = _load_schedule(conn, schedule_id)
+        with transaction(conn):
+            conn.execute(
+                """
+                UPDATE amortization_schedules
+                SET serial_number = ?, receipt_reference = ?, warranty_expires_on = ?
+                WHERE id = ?
+                """,
+                (
+                    merged(input_data.serialNumber, schedule.serialNumber),
+                    merged(input_data.receiptReference, schedule.receiptReference),
+                    merged(input_data.warrantyExpiresOn, schedule.warrantyExpiresOn),
+                    schedule_id,
+                ),
+            )
+        return _schedules_with_progress(conn, schedule_id=schedule_id)[0]
+
+
+def list_expiring_warranties(within_days: int = 30, as_of: Optional[str] = None) -> list[ExpiringWarrantyDto]:
+    """Warranties running out within the coming days on assets still held, soonest first.
+
+    Returned purchases and parts already disposed of are left out.
+    """
+    if within_days < 0:
+        raise ApiError("invalid_input", "withinDays cannot be negative")
+    with get_connection() as conn:
+        today = local_today(get_timezone(conn)) if as_of is None else parse_date_ymd(as_of, "asOf")
+        until = today + timedelta(days=within_days)
+        rows = conn.execute(
+            """
+            SELECT s.id, s.asset_account_id, a.name, s.serial_number, s.warranty_expires_on
+            FROM amortization_schedules s
+            JOIN accounts a ON a.id = s.asset_account_id
+            WHERE s.warranty_expires_on BETWEEN ? AND ?
+              AND s.status != 'Cancelled'
+              AND s.disposed_at IS NULL
+            ORDER BY s.warranty_expires_on ASC, a.name ASC
+            """,
+            (today.isoformat(), until.isoformat()),
+        ).fetchall()
+    return [
+        ExpiringWarrantyDto(
+            scheduleId=row["id"],
+            assetAccountId=row["asset_account_id"],
+            label=row["name"],
+            serialNumber=row["serial_number"],
+            warrantyExpiresOn=row["warranty_expires_on"],
+            daysLeft=(date.fromisoformat(row["warranty_expires_on"]) - today).days,
+        )
+        for row in rows
+    ]
+
+
+USAGE_LOG_COLUMNS = "id, schedule_id, period_ym, units, note, created_at, updated_at"
+
+
+def _usage_log_from_row(row: sqlite3.Row) -> UsageLogDto:
+    return UsageLogDto(
+        id=row["id"],
+        scheduleId=row["schedule_id"],
+        periodYm=row["period_ym"],
+        units=row["units"],
+        note=row["note"],
+        createdAt=row["created_at"],
+        updatedAt=row["updated_at"],
+    )
+
+
+def list_usage_logs(schedule_id: str) -> list[UsageLogDto]:
+    with get_connection() as conn:
+        _load_schedule(conn, schedule_id)
+        rows = conn.execute(
+            f"SELECT {USAGE_LOG_COLUMNS} FROM usage_logs WHERE schedule_id = ? ORDER BY period_ym ASC",
+            (schedule_id,),
+        ).fetchall()
+    return [_usage_log_from_row(row) for row in rows]
+
+
+def log_usage(schedule_id: str, input_data: LogUsageInput) -> UsageLogDto:
+    """Set how much a usage-depreciated asset was used in a month, replacing any earlier entry.
+
+    The month is depreciated once it is over, so a month can be corrected until then.
+    """
+    month_start, _, _ = parse_period(input_data.periodYm)
+    if input_data.units < 0:
+        raise ApiError("invalid_input", "units cannot be negative")
+
+    with get_connection() as conn:
+        if month_start > local_today(get_timezone(conn)):
+            raise ApiError("invalid_input", "usage cannot be logged for a month that has not started")
+        schedule = _load_schedule(conn, schedule_id)
+        if schedule.strategy != AmortizationStrategy.USAGE:
+            raise ApiError("invalid_input", "schedule is not depreciated by usage")
+        if input_data.periodYm < schedule.startDate[:7]:
+            raise ApiError("invalid_input", "periodYm is before the schedule starts")
+        if conn.execute(
+            "SELECT 1 FROM amortization_postings WHERE schedule_id = ? AND period_ym = ?",
+            (schedule_id, input_data.periodYm),
+        ).fetchone() is not None:
+            raise ApiError("conflict", f"depreciation is already posted for {input_data.periodYm}", status_code=409)
+        now = now_utc_rfc3339()
+        with transaction(conn):
+            conn.execute(
+                """
+                INSERT INTO usage_logs (id, schedule_id, period_ym, units, note, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(schedule_id, period_ym) DO UPDATE SET
+                    units = excluded.units, note = excluded.note, updated_at = excluded.updated_at
+                """,
+                (str(uuid.uuid4()), schedule_id, input_data.periodYm, input_data.units, input_data.note, now, now),
+            )
+        row = conn.execute(
+            f"SELECT {USAGE_LOG_COLUMNS} FROM usage_logs WHERE schedule_id = ? AND period_ym = ?",
+            (schedule_id, input_data.periodYm),
+        ).fetchone()
+    return _usage_log_from_row(row)
+
+
+def get_cost_per_use_report(purpose: Optional[AssetPurpose] = None) -> CostPerUseReportDto:
+    """What each use of an asset with a usage log has cost so far, cheapest first.
+
+    Parts already disposed of are left out; the part kept carries its own cost.
+    """
+    purpose_value = None if purpose is None else purpose.value
+    with get_connection() as conn:
+        rows = conn.execute(
+            """
+            SELECT s.id, s.asset_account_id, a.name, a.purpose,
+                   COALESCE(s.basis_cents, t.amount_cents) - s.residual_cents AS cost_cents,
+                   SUM(u.units) AS units
+            FROM amortization_schedules s
+            JOIN accounts a ON a.id = s.asset_account_id
+            JOIN transactions t ON t.id = s.source_transaction_id
+            JOIN usage_logs u ON u.schedule_id = s.id
+            WHERE s.disposed_at IS NULL AND (? IS NULL OR a.purpose = ?)
+            GROUP BY s.id
+            """,
+            (purpose_value, purpose_value),
+        ).fetchall()
+    items = [
+        CostPerUseItemDto(
+            scheduleId=row["id"],
+            assetAccountId=row["asset_account_id"],
+            label=row["name"],
+            purpose=row["purpose"],
+            costCents=row["cost_cents"],
+            unitsToDate=row["units"],
+            costPerUseCents=round(row["cost_cents"] / row["units"]) if row["units"] > 0 else None,
+        )
+        for row in rows
+    ]
+    items.sort(key=lambda item: (item.costPerUseCents is None, item.costPerUseCents or 0, item.label))
+    return CostPerUseReportDto(items=items)
+
+
+def get_total_cost_of_ownership_report(purpose: Optional[AssetPurpose] = None) -> TotalCostOfOwnershipReportDto:
+    """What each asset account has cost to own: its purchases, their depreciation and upkeep.
+
+    Returned purchases and parts already disposed of are left out, like in the cost-per-use
+    report; maintenance counts in full.
+    """
+    purpose_value = None if purpose is None else purpose.value
+    with get_connection() as conn:
+        rows = conn.execute(
+            """
+            WITH held AS (
+                SELECT s.asset_account_id AS account_id,
+                       SUM(COALESCE(s.basis_cents, t.amount_cents)) AS purchase_cents,
+                       SUM(s.carried_depreciation_cents + COALESCE(
+                           (SELECT SUM(p.amount_cents) FROM amortization_postings p WHERE p.schedule_id = s.id), 0
+                       )) AS depreciated_cents
+                FROM amortization_schedules s
+                JOIN transactions t ON t.id = s.source_transaction_id
+                WHERE s.status != 'Cancelled' AND s.disposed_at IS NULL
+                GROUP BY s.asset_account_id
+            ),
+            upkeep AS (
+                SELECT account_id, SUM(cost_cents) AS maintenance_cents
+                FROM maintenance_events
+                GROUP BY account_id
+            )
+            SELECT a.id, a.name, a.purpose,
+                   COALESCE(h.purchase_cents, 0) AS purchase_cents,
+                   COALESCE(h.depreciated_cents, 0) AS depreciated_cents,
+                   COALESCE(u.maintenance_cents, 0) AS maintenance_cents
+            FROM accounts a
+            LEFT JOIN held h ON h.account_id = a.id
+            LEFT JOIN upkeep u ON u.account_id = a.id
+            WHERE (h.account_id IS NOT NULL OR u.account_id IS NOT NULL)
+              AND (? IS NULL OR a.purpose = ?)
+            """,
+            (purpose_value, purpose_value),
+        ).fetchall()
+    items = [
+        TotalCostOfOwnershipItemDto(
+            accountId=row["id"],
+            label=row["name"],
+            purpose=row["purpose"],
+            purchaseCents=row["purchase_cents"],
+            depreciatedCents=row["depreciated_cents"],
+            maintenanceCents=row["maintenance_cents"],
+            totalCostCents=row["purchase_cents"] + row["maintenance_cents"],
+            consumedCents=row["depreciated_cents"] + row["maintenance_cents"],
+        )
+        for row in rows
+    ]
+    items.sort(key=lambda item: (-item.totalCostCents, item.label))
+    return TotalCostOfOwnershipReportDto(items=items)
+
+
+def forecast_depreciation(months: int = 12, from_period_ym: Optional[str] = None) -> DepreciationForecastDto:
+    """Depreciation expense per month for the coming months across the active schedules.
+
+    The months are generated on a scratch copy of the database, so the projection follows
+    prorations, appraisals and declining balances exactly as the generator will while the
+    request stays a pure read. Usage schedules only
+    post once a month's usage is logged and so have nothing to project.
+    """
+    if months <= 0 or months > 120:
+        raise ApiError("invalid_input", "months must be between 1 and 120")
+
+    items: list[DepreciationForecastMonthDto] = []
+    with get_connection() as conn, scratch_copy(conn) as scratch:
+        if from_period_ym is None:
+            first_month = add_months(local_today(get_timezone(conn)).replace(day=1), 1)
+        else:
+            first_month, _, _ = parse_period(from_period_ym)
+        for i in range(months):
+            period_ym = format_period(add_months(first_month, i))
+            ensure_depreciation_for_period(scratch, period_ym)
+            amount = scratch.execute(
+                "SELECT COALESCE(SUM(amount_cents), 0) FROM amortization_postings WHERE period_ym = ?",
+                (period_ym,),
+            ).fetchone()[0]
+            items.append(DepreciationForecastMonthDto(periodYm=period_ym, amountCents=amount))
+    return DepreciationForecastDto(months=items, totalCents=sum(item.amountCents for item in items))
@@ -0,0 +1,106 @@
+from __future__ import annotations
+
+import sqlite3
+from datetime import timedelta
+from statistics import median
+from typing import Optional
+
+from app.db import day_start_utc, get_connection, local_today, parse_period, parse_rfc3339_utc, period_of
+from app.models import AnomalyReason, TransactionAnomalyDto, format_cents
+from app.services.finance import _transaction_from_row
+from app.services.settings import get_period_start_day, get_timezone
+
+
+ANOMALY_HISTORY_DAYS = 365
+# A payee or category needs this many earlier transactions before an amount can look unusual.
+ANOMALY_MIN_HISTORY = 3
+ANOMALY_AMOUNT_RATIO = 3
+DUPLICATE_WINDOW = timedelta(days=1)
+
+
+def _history_key(row: sqlite3.Row) -> Optional[tuple[str, str]]:
+    # The payee is the sharper comparison (one utility, one landlord); the category is the fallback.
+    if row["payee_id"] is not None:
+        return ("payee", row["payee_id"])
+    if row["category_id"] is not None:
+        return ("category", row["category_id"])
+    return None
+
+
+def _duplicate_key(row: sqlite3.Row) -> tuple:
+    return (
+        row["amount_cents"],
+        row["from_account_id"],
+        row["to_account_id"],
+        row["payee_id"],
+        row["category_id"],
+    )
+
+
+def find_anomalies(period_ym: Optional[str] = None) -> list[TransactionAnomalyDto]:
+    """Flag flows in a period that look like typos or double charges, each with the reason why."""
+    with get_connection() as conn:
+        start_day = get_period_start_day(conn)
+        tz = get_timezone(conn)
+        if period_ym is None:
+            period_ym = period_of(local_today(tz), start_day)
+        period_start, start_ts, end_ts = parse_period(period_ym, start_day=start_day, tz=tz)
+        history_start = period_start - timedelta(days=ANOMALY_HISTORY_DAYS)
+        rows = conn.execute(
+            """
+            SELECT id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
+                   accrual_type, is_asset_purchase, note, occurred_at, created_at, status, device_id
+            FROM transactions
+            WHERE accrual_type = 'Flow'
+              AND is_asset_purchase = 0
+              AND deleted_at IS NULL
+              AND occurred_at >= ? AND occurred_at < ?
+            ORDER BY occurred_at ASC, created_at ASC
+            """,
+            (day_start_utc(history_start, tz), end_ts),
+        ).fetchall()
+
+    anomalies: list[TransactionAnomalyDto] = []
+    history: dict[tuple[str, str], list[int]] = {}
+    last_seen: dict[tuple, sqlite3.Row] = {}
+    for row in rows:
+        in_period = row["occurred_at"] >= start_ts
+        occurred = parse_rfc3339_utc(row["occurred_at"])
+
+        earlier = last_seen.get(_duplicate_key(row))
+        if (
+            in_period
+            and earlier is not None
+            and occurred - parse_rfc3339_utc(earlier["occurred_at"]) <= DUPLICATE_WINDOW
+        ):
+            anomalies.append(
+                TransactionAnomalyDto(
+                    transaction=_transaction_from_row(row),
+                    reason=AnomalyReason.POSSIBLE_DUPLICATE,
+                    message=f"same amount and accounts as a transaction on {earlier['occurred_at'][:10]}",
+                    relatedTransactionId=earlier["id"],
+                )
+            )
+        last_seen[_duplicate_key(row)] = row
+
+        key = _history_key(row)
+        if key is None:
+            continue
+        amounts = history.setdefault(key, [])
+        if in_period and len(amounts) >= ANOMALY_MIN_HISTORY:
+            typical = round(median(amounts))
+            if row["amount_cents"] >= typical * ANOMALY_AMOUNT_RATIO:
+                anomalies.append(
+                    TransactionAnomalyDto(
+                        transaction=_transaction_from_row(row),
+                        reason=AnomalyReason.UNUSUAL_AMOUNT,
+                        message=(
+                            f"{row['amount_cents'] / typical:.1f}x the usual {format_cents(typical)} "
+                            f"for this {key[0]}"
+                        ),
+                        typicalAmountCents=typical,
+                    )
+                )
+        amounts.append(int(row["amount_cents"]))
+
+    return anomalies
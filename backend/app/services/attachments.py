@@ -0,0 +1,184 @@
+from __future__ import annotations
+
+import base64
+import binascii
+import hashlib
+import sqlite3
+import uuid
+from pathlib import Path
+
+from app.config import get_data_dir
+from app.db import get_connection, now_utc_rfc3339, transaction
+from app.models import ApiError, ReceiptSuggestionDto, TransactionAttachmentDto, UploadAttachmentInput
+from app.services.dimensions import _load_payee
+from app.services.finance import _load_transaction
+from app.services.imports import match_payees
+from app.services.receipt_readers import get_receipt_reader
+
+ATTACHMENT_CONTENT_TYPES = {
+    ".pdf": "application/pdf",
+    ".jpg": "image/jpeg",
+    ".jpeg": "image/jpeg",
+    ".png": "image/png",
+    ".webp": "image/webp",
+    ".heic": "image/heic",
+    ".txt": "text/plain",
+}
+MAX_ATTACHMENT_BYTES = 20 * 1024 * 1024
+# A payee read off a receipt is only filled in when it clearly names a known one.
+RECEIPT_PAYEE_MIN_SCORE = 0.8
+
+ATTACHMENT_COLUMNS = "id, transaction_id, file_name, content_type, size_bytes, content_hash, created_at"
+
+
+def _attachments_dir() -> Path:
+    return get_data_dir() / "attachments"
+
+
+def _attachment_from_row(row: sqlite3.Row) -> TransactionAttachmentDto:
+    return TransactionAttachmentDto(
+        id=row["id"],
+        transactionId=row["transaction_id"],
+        fileName=row["file_name"],
+        contentType=row["content_type"],
+        sizeBytes=row["size_bytes"],
+        contentHash=row["content_hash"],
+        createdAt=row["created_at"],
+    )
+
+
+def _load_attachment_row(conn: sqlite3.Connection, tx_id: str, attachment_id: str) -> sqlite3.Row:
+    row = conn.execute(
+        f"SELECT {ATTACHMENT_COLUMNS}, stored_name FROM transaction_attachments WHERE id = ? AND transaction_id = ?",
+        (attachment_id, tx_id),
+    ).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"attachment not found: {attachment_id}", status_code=404)
+    return row
+
+
+def remove_unreferenced_files(conn: sqlite3.Connection) -> None:
+    """Delete stored files no attachment points at any more, e.g. after their transactions were purged."""
+    attachments_dir = _attachments_dir()
+    if not attachments_dir.is_dir():
+        return
+    referenced = {row[0] for row in conn.execute("SELECT DISTINCT stored_name FROM transaction_attachments")}
+    for path in attachments_dir.iterdir():
+        if path.is_file() and path.name not in referenced:
+            path.unlink(missing_ok=True)
+
+
+def list_attachments(tx_id: str) -> list[TransactionAttachmentDto]:
+    with get_connection() as conn:
+        _load_transaction(conn, tx_id)
+        rows = conn.execute(
+            f"""
+            SELECT {ATTACHMENT_COLUMNS}
+            FROM transaction_attachments
+            WHERE transaction_id = ?
+            ORDER BY created_at ASC, id ASC
+            """,
+            (tx_id,),
+        ).fetchall()
+        return [_attachment_from_row(row) for row in rows]
+
+
+def _decode_upload(input_data: UploadAttachmentInput) -> tuple[str, str, str, bytes]:
+    file_name = Path(input_data.fileName).name
+    suffix = Path(file_name).suffix.lower()
+    content_type = ATTACHMENT_CONTENT_TYPES.get(suffix)
+    if content_type is None:
+        raise ApiError("invalid_input", f"unsupported attachment type: {suffix or file_name}")
+    try:
+        content = base64.b64decode(input_data.contentBase64, validate=True)
+    except (binascii.Error, ValueError) as exc:
+        raise ApiError("invalid_input", "contentBase64 is not valid base64") from exc
+    if not content:
+        raise ApiError("invalid_input", "attachment is empty")
+    if len(content) > MAX_ATTACHMENT_BYTES:
+        raise ApiError("invalid_input", f"attachment exceeds {MAX_ATTACHMENT_BYTES} bytes")
+    return file_name, suffix, content_type, content
+
+
+def upload_attachment(tx_id: str, input_data: UploadAttachmentInput) -> TransactionAttachmentDto:
+    file_name, suffix, content_type, content = _decode_upload(input_data)
+    attachment_id = str(uuid.uuid4())
+    content_hash = hashlib.sha256(content).hexdigest()
+    with get_connection() as conn:
+        _load_transaction(conn, tx_id)
+        attachments_dir = _attachments_dir()
+        attachments_dir.mkdir(parents=True, exist_ok=True)
+        stored = attachments_dir / f"{content_hash}{suffix}"
+        # Only a file written by this upload is taken back if the insert fails.
+        created_file = not stored.exists()
+        if created_file:
+            stored.write_bytes(content)
+        try:
+            with transaction(conn):
+                conn.execute(
+                    """
+                    INSERT INTO transaction_attachments (
+                        id, transaction_id, file_name, content_type, size_bytes, content_hash, stored_name, created_at
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                    """,
+                    (
+                        attachment_id,
+                        tx_id,
+                        file_name,
+                        content_type,
+                        len(content),
+                        content_hash,
+                        stored.name,
+                        now_utc_rfc3339(),
+                    ),
+                )
+        except Exception:
+            if created_file:
+                stored.unlink(missing_ok=True)
+            raise
+
+        return _attachment_from_row(_load_attachment_row(conn, tx_id, attachment_id))
+
+
+def read_receipt(input_data: UploadAttachmentInput) -> ReceiptSuggestionDto:
+    """Have the configured reader pull amount, date and payee off a receipt to pre-fill a new entry."""
+    file_name, _, content_type, content = _decode_upload(input_data)
+    receipt = get_receipt_reader().read(content, file_name, content_type)
+
+    payee_id = category_id = None
+    if receipt.payee_name is not None:
+        with get_connection() as conn:
+            candidates = match_payees(conn, receipt.payee_name)
+            if candidates and candidates[0].score >= RECEIPT_PAYEE_MIN_SCORE:
+                payee_id = candidates[0].payeeId
+                category_id = _load_payee(conn, payee_id).defaultCategoryId
+    return ReceiptSuggestionDto(
+        amountCents=receipt.amount_cents,
+        occurredOn=None if receipt.occurred_on is None else receipt.occurred_on.isoformat(),
+        payeeName=receipt.payee_name,
+        payeeId=payee_id,
+        categoryId=category_id,
+    )
+
+
+def get_attachment_file(tx_id: str, attachment_id: str) -> tuple[Path, str, str]:
+    with get_connection() as conn:
+        row = _load_attachment_row(conn, tx_id, attachment_id)
+    path = _attachments_dir() / row["stored_name"]
+    if not path.is_file():
+        raise ApiError("not_found", f"attachment file is missing: {attachment_id}", status_code=404)
+    return path, row["content_type"], row["file_name"]
+
+
+def delete_attachment(tx_id: str, attachment_id: str) -> TransactionAttachmentDto:
+    """Detach a file; the stored copy goes once no other attachment shares its content."""
+    with get_connection() as conn:
+        row = _load_attachment_row(conn, tx_id, attachment_id)
+        with transaction(conn):
+            conn.execute("DELETE FROM transaction_attachments WHERE id = ?", (attachment_id,))
+        shared = conn.execute(
+            "SELECT 1 FROM transaction_attachments WHERE stored_name = ?", (row["stored_name"],)
+        ).fetchone()
+        if shared is None:
+            (_attachments_dir() / row["stored_name"]).unlink(missing_ok=True)
+        return _attachment_from_row(row)
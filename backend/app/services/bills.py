@@ -0,0 +1,161 @@
+from __future__ import annotations
+
+import calendar
+import sqlite3
+import uuid
+from datetime import date
+from typing import Optional
+
+from app.db import (
+    format_period,
+    get_connection,
+    local_today,
+    now_utc_rfc3339,
+    parse_date_ymd,
+    parse_period,
+    transaction,
+)
+from app.models import (
+    AccountType,
+    ApiError,
+    BillDto,
+    BillStatus,
+    CreateBillInput,
+    UpcomingBillDto,
+    ensure_amount_in_range,
+)
+from app.services.finance import _load_account
+from app.services.settings import get_timezone
+
+
+BILL_COLUMNS = "id, name, payee_id, liability_account_id, expected_amount_cents, due_day, is_active"
+
+
+def _bill_from_row(row: sqlite3.Row) -> BillDto:
+    return BillDto(
+        id=row["id"],
+        name=row["name"],
+        payeeId=row["payee_id"],
+        liabilityAccountId=row["liability_account_id"],
+        expectedAmountCents=row["expected_amount_cents"],
+        dueDay=row["due_day"],
+        isActive=bool(row["is_active"]),
+    )
+
+
+def _load_bill(conn: sqlite3.Connection, bill_id: str) -> BillDto:
+    row = conn.execute(f"SELECT {BILL_COLUMNS} FROM bills WHERE id = ?", (bill_id,)).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"bill not found: {bill_id}", status_code=404)
+    return _bill_from_row(row)
+
+
+def list_bills() -> list[BillDto]:
+    with get_connection() as conn:
+        rows = conn.execute(f"SELECT {BILL_COLUMNS} FROM bills ORDER BY due_day ASC, name ASC").fetchall()
+        return [_bill_from_row(row) for row in rows]
+
+
+def create_bill(input_data: CreateBillInput) -> BillDto:
+    if not input_data.name.strip():
+        raise ApiError("invalid_input", "bill name cannot be empty")
+    if input_data.expectedAmountCents <= 0:
+        raise ApiError("invalid_input", "expectedAmountCents must be greater than 0")
+    ensure_amount_in_range(input_data.expectedAmountCents, "expectedAmountCents")
+    if input_data.dueDay < 1 or input_data.dueDay > 31:
+        raise ApiError("invalid_input", "dueDay must be between 1 and 31")
+    if input_data.payeeId is None and input_data.liabilityAccountId is None:
+        raise ApiError("invalid_input", "bill needs a payee or a liability account to match payments")
+
+    bill_id = str(uuid.uuid4())
+    now = now_utc_rfc3339()
+    with get_connection() as conn:
+        if input_data.liabilityAccountId is not None:
+            account = _load_account(conn, input_data.liabilityAccountId)
+            if account.accountType != AccountType.LIABILITY:
+                raise ApiError("invalid_input", "liabilityAccountId must reference a liability account")
+
+        with transaction(conn):
+            conn.execute(
+                """
+                INSERT INTO bills (
+                    id, name, payee_id, liability_account_id, expected_amount_cents, due_day,
+                    is_active, created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?)
+                """,
+                (
+                    bill_id,
+                    input_data.name.strip(),
+                    input_data.payeeId,
+                    input_data.liabilityAccountId,
+                    input_data.expectedAmountCents,
+                    input_data.dueDay,
+                    now,
+                    now,
+                ),
+            )
+        return _load_bill(conn, bill_id)
+
+
+def list_upcoming_bills(period_ym: Optional[str] = None, as_of: Optional[str] = None) -> list[UpcomingBillDto]:
+    with get_connection() as conn:
+        tz = get_timezone(conn)
+        today = local_today(tz) if as_of is None else parse_date_ymd(as_of, "asOf")
+        if period_ym is None:
+            period_ym = format_period(date(today.year, today.month, 1))
+        period_start, start_ts, end_ts = parse_period(period_ym, tz=tz)
+        last_day = calendar.monthrange(period_start.year, period_start.month)[1]
+
+        rows = conn.execute(
+            f"SELECT {BILL_COLUMNS} FROM bills WHERE is_active = 1 ORDER BY due_day ASC, name ASC"
+        ).fetchall()
+
+        result: list[UpcomingBillDto] = []
+        for row in rows:
+            bill = _bill_from_row(row)
+            due_date = period_start.replace(day=min(bill.dueDay, last_day))
+            # A bill counts as paid by any flow in its month that goes to the bill's payee
+            # or has a leg paying down its liability account.
+            payment = conn.execute(
+                """
+                SELECT id, amount_cents
+                FROM transactions
+                WHERE accrual_type = 'Flow'
+                  AND deleted_at IS NULL
+                  AND occurred_at >= ? AND occurred_at < ?
+                  AND (
+                      (? IS NOT NULL AND payee_id = ?)
+                      OR EXISTS (
+                          SELECT 1 FROM transaction_postings p
+                          WHERE p.transaction_id = transactions.id AND p.account_id = ? AND p.amount_cents > 0
+                      )
+                  )
+                ORDER BY occurred_at ASC
+                LIMIT 1
+                """,
+                (
+                    start_ts,
+                    end_ts,
+                    bill.payeeId,
+                    bill.payeeId,
+                    bill.liabilityAccountId,
+                ),
+            ).fetchone()
+
+            if payment is not None:
+                status = BillStatus.PAID
+            elif due_date < today:
+                status = BillStatus.OVERDUE
+            else:
+                status = BillStatus.UPCOMING
+
+            result.append(
+                UpcomingBillDto(
+                    bill=bill,
+                    dueDate=due_date.isoformat(),
+                    status=status,
+                    paidTransactionId=None if payment is None else payment["id"],
+                    paidAmountCents=None if payment is None else int(payment["amount_cents"]),
+                )
+            )
+        return result
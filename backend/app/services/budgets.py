@@ -0,0 +1,248 @@
+from __future__ import annotations
+
+import sqlite3
+import uuid
+from datetime import date
+from typing import Optional
+
+from app.db import format_period, get_connection, now_utc_rfc3339, parse_period, transaction
+from app.models import (
+    ApiError,
+    BudgetDto,
+    BudgetReportDto,
+    BudgetReportItemDto,
+    SetBudgetInput,
+    add_months,
+    ensure_amount_in_range,
+)
+from app.services.dimensions import category_roots
+from app.services.settings import get_period_start_day, get_timezone
+
+
+def _budget_from_row(row: sqlite3.Row) -> BudgetDto:
+    return BudgetDto(
+        id=row["id"],
+        categoryId=row["category_id"],
+        periodYm=row["period_ym"],
+        amountCents=row["amount_cents"],
+        rollover=bool(row["rollover"]),
+    )
+
+
+def _load_budget(conn: sqlite3.Connection, category_id: str, period_ym: str) -> Optional[sqlite3.Row]:
+    return conn.execute(
+        """
+        SELECT id, category_id, period_ym, amount_cents, rollover
+        FROM budgets
+        WHERE category_id = ? AND period_ym = ?
+        """,
+        (category_id, period_ym),
+    ).fetchone()
+
+
+def _category_spent(conn: sqlite3.Connection, category_id: str, period_ym: str) -> int:
+    start_day = get_period_start_day(conn)
+    tz = get_timezone(conn)
+    _, start_ts, end_ts = parse_period(period_ym, start_day=start_day, tz=tz)
+    if start_day == 1 and tz is None:
+        total = conn.execute(
+            """
+            SELECT COALESCE(SUM(amount_cents), 0)
+            FROM monthly_category_totals
+            WHERE period_ym = ? AND category_id = ? AND accrual_type = 'Flow' AND is_asset_purchase = 0
+            """,
+            (period_ym, category_id),
+        ).fetchone()[0]
+        return int(total)
+    total = conn.execute(
+        """
+        SELECT COALESCE(SUM(amount_cents), 0)
+        FROM transactions
+        WHERE category_id = ?
+          AND accrual_type = 'Flow'
+          AND is_asset_purchase = 0
+          AND deleted_at IS NULL
+          AND occurred_at >= ? AND occurred_at < ?
+        """,
+        (category_id, start_ts, end_ts),
+    ).fetchone()[0]
+    return int(total or 0)
+
+
+def _carried_over(conn: sqlite3.Connection, category_id: str, period_start: date) -> int:
+    # Walk back through the unbroken run of rollover rows, then replay it forward so each
+    # month's leftover (or overspend) feeds into the next one.
+    chain: list[tuple[str, int]] = []
+    month = add_months(period_start, -1)
+    while True:
+        row = _load_budget(conn, category_id, format_period(month))
+        if row is None or not row["rollover"]:
+            break
+        chain.append((row["period_ym"], int(row["amount_cents"])))
+        month = add_months(month, -1)
+
+    carry = 0
+    for period_ym, amount in reversed(chain):
+        carry = amount + carry - _category_spent(conn, category_id, period_ym)
+    return carry
+
+
+def budget_crossing(
+    conn: sqlite3.Connection,
+    category_id: str,
+    period_ym: str,
+    amount_cents: int,
+) -> Optional[dict]:
+    """Return budget details when a new spend of amount_cents pushed the category over budget."""
+    row = _load_budget(conn, category_id, period_ym)
+    if row is None:
+        return None
+
+    period_start, _, _ = parse_period(period_ym)
+    effective = int(row["amount_cents"]) + _carried_over(conn, category_id, period_start)
+    spent = _category_spent(conn, category_id, period_ym)
+    if spent <= effective or spent - amount_cents > effective:
+        return None
+    return {
+        "categoryId": category_id,
+        "periodYm": period_ym,
+        "effectiveBudgetCents": effective,
+        "spentCents": spent,
+        "overByCents": spent - effective,
+    }
+
+
+def list_budgets(period_ym: str) -> list[BudgetDto]:
+    parse_period(period_ym)
+
+    with get_connection() as conn:
+        rows = conn.execute(
+            """
+            SELECT id, category_id, period_ym, amount_cents, rollover
+            FROM budgets
+            WHERE period_ym = ?
+            ORDER BY category_id ASC
+            """,
+            (period_ym,),
+        ).fetchall()
+        return [_budget_from_row(row) for row in rows]
+
+
+def set_budget(input_data: SetBudgetInput) -> BudgetDto:
+    parse_period(input_data.periodYm)
+    if input_data.amountCents < 0:
+        raise ApiError("invalid_input", "amountCents must be >= 0")
+    ensure_amount_in_range(input_data.amountCents, "amountCents")
+
+    with get_connection() as conn:
+        category = conn.execute(
+            "SELECT id FROM categories WHERE id = ?", (input_data.categoryId,)
+        ).fetchone()
+        if category is None:
+            raise ApiError("not_found", f"category not found: {input_data.categoryId}", status_code=404)
+
+        existing = _load_budget(conn, input_data.categoryId, input_data.periodYm)
+        now = now_utc_rfc3339()
+        with transaction(conn):
+            if existing is None:
+                conn.execute(
+                    """
+                    INSERT INTO budgets (id, category_id, period_ym, amount_cents, rollover, created_at, updated_at)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                    """,
+                    (
+                        str(uuid.uuid4()),
+                        input_data.categoryId,
+                        input_data.periodYm,
+                        input_data.amountCents,
+                        1 if input_data.rollover else 0,
+                        now,
+                        now,
+                    ),
+                )
+            else:
+                rollover = bool(existing["rollover"]) if input_data.rollover is None else input_data.rollover
+                conn.execute(
+                    "UPDATE budgets SET amount_cents = ?, rollover = ?, updated_at = ? WHERE id = ?",
+                    (input_data.amountCents, 1 if rollover else 0, now, existing["id"]),
+                )
+
+        row = _load_budget(conn, input_data.categoryId, input_data.periodYm)
+        return _budget_from_row(row)
+
+
+def _roll_up_budget_items(
+    items: list[BudgetReportItemDto], roots: dict[str, sqlite3.Row]
+) -> list[BudgetReportItemDto]:
+    """Nest budgeted subcategories under their top-level category, summing their figures into it."""
+    grouped: dict[str, list[BudgetReportItemDto]] = {}
+    for item in items:
+        grouped.setdefault(roots[item.categoryId]["id"], []).append(item)
+
+    rolled: list[BudgetReportItemDto] = []
+    for root_id, members in grouped.items():
+        own = next((member for member in members if member.categoryId == root_id), None)
+        children = [member for member in members if member.categoryId != root_id]
+        budgeted = sum(member.budgetedCents for member in members)
+        carried = sum(member.carriedOverCents for member in members)
+        spent = sum(member.spentCents for member in members)
+        rolled.append(
+            BudgetReportItemDto(
+                categoryId=root_id,
+                label=roots[root_id]["name"],
+                budgetedCents=budgeted,
+                carriedOverCents=carried,
+                effectiveBudgetCents=budgeted + carried,
+                spentCents=spent,
+                remainingCents=budgeted + carried - spent,
+                rollover=False if own is None else own.rollover,
+                children=children or None,
+            )
+        )
+    rolled.sort(key=lambda item: item.label)
+    return rolled
+
+
+def get_budget_report(period_ym: str, group_by_parent: bool = False) -> BudgetReportDto:
+    period_start, _, _ = parse_period(period_ym)
+
+    with get_connection() as conn:
+        rows = conn.execute(
+            """
+            SELECT b.category_id, b.amount_cents, b.rollover, c.name
+            FROM budgets b
+            JOIN categories c ON c.id = b.category_id
+            WHERE b.period_ym = ?
+            ORDER BY c.name ASC
+            """,
+            (period_ym,),
+        ).fetchall()
+
+        items: list[BudgetReportItemDto] = []
+        for row in rows:
+            category_id = row["category_id"]
+            budgeted = int(row["amount_cents"])
+            carried = _carried_over(conn, category_id, period_start)
+            effective = budgeted + carried
+            spent = _category_spent(conn, category_id, period_ym)
+            items.append(
+                BudgetReportItemDto(
+                    categoryId=category_id,
+                    label=row["name"],
+                    budgetedCents=budgeted,
+                    carriedOverCents=carried,
+                    effectiveBudgetCents=effective,
+                    spentCents=spent,
+                    remainingCents=effective - spent,
+                    rollover=bool(row["rollover"]),
+                )
+            )
+        if group_by_parent:
+            items = _roll_up_budget_items(items, category_roots(conn))
+
+    return BudgetReportDto(
+        periodYm=period_ym,
+        totalEffectiveBudgetCents=sum(item.effectiveBudgetCents for item in items),
+        totalSpentCents=sum(item.spentCents for item in items),
+        items=items,
+    )
@@ -0,0 +1,174 @@
+from __future__ import annotations
+
+import sqlite3
+import uuid
+from decimal import Decimal, InvalidOperation
+from typing import Optional
+
+from app.db import get_connection, normalize_timestamp, now_utc_rfc3339, transaction
+from app.models import (
+    AccountDto,
+    AccountType,
+    ApiError,
+    CryptoHoldingDto,
+    CryptoRevaluationResultDto,
+    SetCryptoHoldingInput,
+    SetCryptoPriceInput,
+    ensure_amount_in_range,
+    format_cents,
+)
+from app.services.currencies import CURRENCY_MINOR_UNITS, STORED_MINOR_UNITS
+from app.services.finance import _book_adjustment, _load_account, _load_transaction, publish_transactions
+
+
+# Quantities are whole hundred-millionths of a coin, the smallest unit of BTC.
+CRYPTO_QUANTITY_DECIMALS = 8
+CRYPTO_UNITS_PER_COIN = 10**CRYPTO_QUANTITY_DECIMALS
+MAX_SYMBOL_LENGTH = 10
+
+HOLDING_COLUMNS = "account_id, symbol, quantity_units, unit_price_cents, priced_at"
+
+
+def parse_quantity(raw: str, field_name: str) -> int:
+    try:
+        quantity = Decimal(raw.strip())
+    except InvalidOperation as exc:
+        raise ApiError("invalid_input", f"{field_name} must be a decimal number") from exc
+    if not quantity.is_finite() or quantity < 0:
+        raise ApiError("invalid_input", f"{field_name} must be >= 0")
+    units = quantity.scaleb(CRYPTO_QUANTITY_DECIMALS)
+    if units != units.to_integral_value():
+        raise ApiError(
+            "invalid_input",
+            f"{field_name} allows at most {CRYPTO_QUANTITY_DECIMALS} decimal places",
+            details={"field": field_name, "value": raw},
+        )
+    return int(units)
+
+
+def format_quantity(units: int) -> str:
+    return f"{Decimal(units).scaleb(-CRYPTO_QUANTITY_DECIMALS).normalize():f}"
+
+
+def _holding_value(units: int, unit_price_cents: int, currency: Optional[str]) -> int:
+    # Rounded half up on the account currency's grid, so a yen account stays in whole yen.
+    step = 10 ** max(STORED_MINOR_UNITS - CURRENCY_MINOR_UNITS[currency], 0) if currency is not None else 1
+    divisor = CRYPTO_UNITS_PER_COIN * step
+    return (units * unit_price_cents + divisor // 2) // divisor * step
+
+
+def _holding_from_row(row: sqlite3.Row, currency: Optional[str]) -> CryptoHoldingDto:
+    units = int(row["quantity_units"])
+    price = row["unit_price_cents"]
+    return CryptoHoldingDto(
+        accountId=row["account_id"],
+        symbol=row["symbol"],
+        quantity=format_quantity(units),
+        quantityUnits=units,
+        unitPriceCents=price,
+        pricedAt=row["priced_at"],
+        valueCents=None if price is None else _holding_value(units, int(price), currency),
+    )
+
+
+def _load_holding_row(conn: sqlite3.Connection, account_id: str) -> sqlite3.Row:
+    row = conn.execute(f"SELECT {HOLDING_COLUMNS} FROM crypto_holdings WHERE account_id = ?", (account_id,)).fetchone()
+    if row is None:
+        raise ApiError("not_found", "account has no crypto holding", status_code=404)
+    return row
+
+
+def _load_crypto_account(conn: sqlite3.Connection, account_id: str) -> AccountDto:
+    account = _load_account(conn, account_id)
+    if account.accountType != AccountType.ASSET:
+        raise ApiError("invalid_input", "crypto holdings are only tracked for asset accounts")
+    return account
+
+
+def get_crypto_holding(account_id: str) -> CryptoHoldingDto:
+    with get_connection() as conn:
+        account = _load_crypto_account(conn, account_id)
+        return _holding_from_row(_load_holding_row(conn, account_id), account.currency)
+
+
+def _revalue(conn: sqlite3.Connection, account_id: str, occurred_at: Optional[str]) -> Optional[str]:
+    """Move the balance to quantity times unit price, recording the valuation it came from.
+
+    Runs inside the caller's transaction; returns the adjustment booked, if any.
+    """
+    account = _load_account(conn, account_id)
+    row = _load_holding_row(conn, account_id)
+    if row["unit_price_cents"] is None:
+        return None
+    price = int(row["unit_price_cents"])
+    value = _holding_value(int(row["quantity_units"]), price, account.currency)
+    ensure_amount_in_range(value, "valueCents")
+    note = f"{format_quantity(int(row['quantity_units']))} {row['symbol']} at {format_cents(price)}"
+    adjustment_id = _book_adjustment(conn, account_id, value - account.balanceCents, note, occurred_at)
+    conn.execute(
+        """
+        INSERT INTO account_valuations (id, account_id, value_cents, note, valued_at, created_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        """,
+        (str(uuid.uuid4()), account_id, value, note, normalize_timestamp(occurred_at), now_utc_rfc3339()),
+    )
+    return adjustment_id
+
+
+def _revaluation_result(
+    conn: sqlite3.Connection, account_id: str, adjustment_id: Optional[str]
+) -> CryptoRevaluationResultDto:
+    if adjustment_id is not None:
+        publish_transactions(conn, [adjustment_id])
+    account = _load_account(conn, account_id)
+    return CryptoRevaluationResultDto(
+        holding=_holding_from_row(_load_holding_row(conn, account_id), account.currency),
+        account=account,
+        adjustmentTransaction=_load_transaction(conn, adjustment_id) if adjustment_id else None,
+    )
+
+
+def set_crypto_holding(account_id: str, input_data: SetCryptoHoldingInput) -> CryptoRevaluationResultDto:
+    """Set what the account holds; with a known price the balance follows right away."""
+    symbol = input_data.symbol.strip().upper()
+    if not symbol or len(symbol) > MAX_SYMBOL_LENGTH or not symbol.isalnum():
+        raise ApiError("invalid_input", f"symbol must be 1 to {MAX_SYMBOL_LENGTH} letters or digits")
+    units = parse_quantity(input_data.quantity, "quantity")
+
+    with get_connection() as conn:
+        _load_crypto_account(conn, account_id)
+        with transaction(conn):
+            # A different coin makes the old price meaningless.
+            conn.execute(
+                """
+                INSERT INTO crypto_holdings (account_id, symbol, quantity_units, updated_at)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT(account_id) DO UPDATE SET
+                    quantity_units = excluded.quantity_units,
+                    unit_price_cents = CASE WHEN symbol = excluded.symbol THEN unit_price_cents END,
+                    priced_at = CASE WHEN symbol = excluded.symbol THEN priced_at END,
+                    symbol = excluded.symbol,
+                    updated_at = excluded.updated_at
+                """,
+                (account_id, symbol, units, now_utc_rfc3339()),
+            )
+            adjustment_id = _revalue(conn, account_id, None)
+        return _revaluation_result(conn, account_id, adjustment_id)
+
+
+def set_crypto_price(account_id: str, input_data: SetCryptoPriceInput) -> CryptoRevaluationResultDto:
+    if input_data.unitPriceCents < 0:
+        raise ApiError("invalid_input", "unitPriceCents must be >= 0")
+    ensure_amount_in_range(input_data.unitPriceCents, "unitPriceCents")
+    priced_at = normalize_timestamp(input_data.pricedAt)
+
+    with get_connection() as conn:
+        _load_crypto_account(conn, account_id)
+        _load_holding_row(conn, account_id)
+        with transaction(conn):
+            conn.execute(
+                "UPDATE crypto_holdings SET unit_price_cents = ?, priced_at = ?, updated_at = ? WHERE account_id = ?",
+                (input_data.unitPriceCents, priced_at, now_utc_rfc3339(), account_id),
+            )
+            adjustment_id = _revalue(conn, account_id, priced_at)
+        return _revaluation_result(conn, account_id, adjustment_id)
@@ -0,0 +1,175 @@
+from __future__ import annotations
+
+import math
+import sqlite3
+import uuid
+from datetime import date
+from typing import Iterable, Optional
+
+from app.db import get_connection, now_utc_rfc3339, parse_date_ymd, transaction
+from app.models import ApiError, ExchangeRateDto, SetExchangeRateInput
+from app.services.settings import BASE_CURRENCY_KEY, get_effective_setting
+
+
+# ISO 4217 codes with the number of digits after the decimal separator. Amounts are
+# stored in hundredths everywhere, so a currency with fewer minor units only accepts
+# multiples of the matching power of ten (whole yen are multiples of 100). Currencies
+# with three minor units (BHD, JOD, KWD, OMR, TND) cannot be stored and are rejected.
+CURRENCY_MINOR_UNITS: dict[str, int] = {
+    "AED": 2, "ARS": 2, "AUD": 2, "BGN": 2, "BRL": 2, "CAD": 2, "CHF": 2, "CLP": 0,
+    "CNY": 2, "COP": 2, "CZK": 2, "DKK": 2, "EGP": 2, "EUR": 2, "GBP": 2, "HKD": 2,
+    "HUF": 2, "IDR": 2, "ILS": 2, "INR": 2, "ISK": 0, "JPY": 0, "KRW": 0, "MAD": 2,
+    "MXN": 2, "MYR": 2, "NGN": 2, "NOK": 2, "NZD": 2, "PEN": 2, "PHP": 2, "PKR": 2,
+    "PLN": 2, "QAR": 2, "RON": 2, "RSD": 2, "RUB": 2, "SAR": 2, "SEK": 2, "SGD": 2,
+    "THB": 2, "TRY": 2, "TWD": 2, "UAH": 2, "UGX": 0, "USD": 2, "VND": 0, "XAF": 0,
+    "XOF": 0, "ZAR": 2,
+}
+STORED_MINOR_UNITS = 2
+
+
+def normalize_currency(code: str, field_name: str) -> str:
+    normalized = code.strip().upper()
+    if normalized not in CURRENCY_MINOR_UNITS:
+        raise ApiError(
+            "invalid_input",
+            f"{field_name} must be a supported ISO 4217 currency code",
+            details={"field": field_name, "value": code},
+        )
+    return normalized
+
+
+def resolve_account_currency(conn: sqlite3.Connection, currency: Optional[str]) -> Optional[str]:
+    """An explicit currency wins; otherwise accounts inherit the base currency, if one is set."""
+    if currency is not None:
+        return normalize_currency(currency, "currency")
+    return get_base_currency(conn)
+
+
+def get_base_currency(conn: sqlite3.Connection) -> Optional[str]:
+    base_currency = get_effective_setting(conn, BASE_CURRENCY_KEY)
+    return None if base_currency is None else normalize_currency(base_currency, "baseCurrency")
+
+
+def find_exchange_rate(conn: sqlite3.Connection, from_currency: str, to_currency: str, on: date) -> Optional[float]:
+    """The rate dated closest to `on`, read from either direction of the pair.
+
+    When an earlier and a later rate are equally close the earlier one wins, since it was
+    already known on that day.
+    """
+    row = conn.execute(
+        """
+        SELECT rate FROM (
+          SELECT rate, rate_date, 0 AS inverted FROM exchange_rates WHERE from_currency = ? AND to_currency = ?
+          UNION ALL
+          SELECT 1.0 / rate, rate_date, 1 FROM exchange_rates WHERE from_currency = ? AND to_currency = ?
+        )
+        ORDER BY ABS(julianday(rate_date) - julianday(?)) ASC, rate_date ASC, inverted ASC
+        LIMIT 1
+        """,
+        (from_currency, to_currency, to_currency, from_currency, on.isoformat()),
+    ).fetchone()
+    return None if row is None else float(row["rate"])
+
+
+def convert_to_base(
+    conn: sqlite3.Connection,
+    amounts: Iterable[tuple[int, Optional[str], date]],
+) -> Optional[int]:
+    """Sum (cents, currency, day) amounts in the base currency.
+
+    Accounts without a currency predate multi-currency support and are taken to be in the
+    base currency. Returns None when no base currency is set or any amount lacks a rate,
+    because a partial sum would look like a real total.
+    """
+    base_currency = get_base_currency(conn)
+    if base_currency is None:
+        return None
+    total = 0.0
+    for amount, currency, day in amounts:
+        if currency is None or currency == base_currency:
+            total += amount
+            continue
+        rate = find_exchange_rate(conn, currency, base_currency, day)
+        if rate is None:
+            return None
+        total += amount * rate
+    # Amounts are hundredths in every currency; keep the sum on the base currency's grid.
+    step = 10 ** max(STORED_MINOR_UNITS - CURRENCY_MINOR_UNITS[base_currency], 0)
+    return round(total / step) * step
+
+
+def ensure_amount_in_currency(value: int, currency: Optional[str], field_name: str) -> int:
+    if currency is None:
+        return value
+    step = 10 ** max(STORED_MINOR_UNITS - CURRENCY_MINOR_UNITS[currency], 0)
+    if value % step != 0:
+        raise ApiError(
+            "invalid_input",
+            f"{field_name} has more precision than {currency} allows",
+            details={"field": field_name, "value": value, "currency": currency},
+        )
+    return value
+
+
+RATE_COLUMNS = "id, from_currency, to_currency, rate, rate_date"
+
+
+def _rate_from_row(row: sqlite3.Row) -> ExchangeRateDto:
+    return ExchangeRateDto(
+        id=row["id"],
+        fromCurrency=row["from_currency"],
+        toCurrency=row["to_currency"],
+        rate=row["rate"],
+        rateDate=row["rate_date"],
+    )
+
+
+def set_exchange_rate(input_data: SetExchangeRateInput) -> ExchangeRateDto:
+    """Record a rate for one day; entering the same pair and day again replaces it."""
+    from_currency = normalize_currency(input_data.fromCurrency, "fromCurrency")
+    to_currency = normalize_currency(input_data.toCurrency, "toCurrency")
+    if from_currency == to_currency:
+        raise ApiError("invalid_input", "fromCurrency and toCurrency must differ")
+    if not math.isfinite(input_data.rate) or input_data.rate <= 0:
+        raise ApiError("invalid_input", "rate must be greater than 0")
+    rate_date = parse_date_ymd(input_data.rateDate, "rateDate").isoformat()
+
+    now = now_utc_rfc3339()
+    with get_connection() as conn:
+        with transaction(conn):
+            conn.execute(
+                """
+                INSERT INTO exchange_rates (
+                    id, from_currency, to_currency, rate, rate_date, created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(from_currency, to_currency, rate_date) DO UPDATE SET
+                    rate = excluded.rate, updated_at = excluded.updated_at
+                """,
+                (str(uuid.uuid4()), from_currency, to_currency, input_data.rate, rate_date, now, now),
+            )
+        row = conn.execute(
+            f"""
+            SELECT {RATE_COLUMNS} FROM exchange_rates
+            WHERE from_currency = ? AND to_currency = ? AND rate_date = ?
+            """,
+            (from_currency, to_currency, rate_date),
+        ).fetchone()
+        return _rate_from_row(row)
+
+
+def list_exchange_rates(
+    from_currency: Optional[str] = None,
+    to_currency: Optional[str] = None,
+) -> list[ExchangeRateDto]:
+    from_code = None if from_currency is None else normalize_currency(from_currency, "fromCurrency")
+    to_code = None if to_currency is None else normalize_currency(to_currency, "toCurrency")
+    with get_connection() as conn:
+        rows = conn.execute(
+            f"""
+            SELECT {RATE_COLUMNS} FROM exchange_rates
+            WHERE (? IS NULL OR from_currency = ?) AND (? IS NULL OR to_currency = ?)
+            ORDER BY rate_date DESC, from_currency ASC, to_currency ASC
+            """,
+            (from_code, from_code, to_code, to_code),
+        ).fetchall()
+    return [_rate_from_row(row) for row in rows]
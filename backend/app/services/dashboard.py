@@ -0,0 +1,92 @@
+from __future__ import annotations
+
+from app.db import get_connection, local_today, parse_period, period_of
+from app.models import DashboardDto
+from app.services.currencies import convert_to_base, get_base_currency
+from app.services.settings import get_period_start_day, get_timezone
+
+
+def get_dashboard() -> DashboardDto:
+    with get_connection() as conn:
+        start_day = get_period_start_day(conn)
+        tz = get_timezone(conn)
+        today = local_today(tz)
+        period_ym = period_of(today, start_day)
+        _, start_ts, end_ts = parse_period(period_ym, start_day=start_day, tz=tz)
+
+        balances = conn.execute(
+            """
+            SELECT
+              COALESCE(SUM(CASE WHEN type = 'Asset' THEN balance_cents END), 0) AS assets,
+              COALESCE(SUM(CASE WHEN type = 'Liability' THEN balance_cents END), 0) AS liabilities
+            FROM accounts
+            """
+        ).fetchone()
+        # Only one-sided flows count here; a transfer between two of the user's own
+        # accounts is neither income nor an expense.
+        flows = conn.execute(
+            """
+            SELECT
+              COALESCE(SUM(CASE WHEN from_account_id IS NOT NULL AND to_account_id IS NULL
+                                THEN amount_cents END), 0) AS expenses,
+              COALESCE(SUM(CASE WHEN from_account_id IS NULL AND to_account_id IS NOT NULL
+                                THEN amount_cents END), 0) AS income
+            FROM transactions
+            WHERE accrual_type = 'Flow'
+              AND is_asset_purchase = 0
+              AND deleted_at IS NULL
+              AND occurred_at >= ? AND occurred_at < ?
+            """,
+            (start_ts, end_ts),
+        ).fetchone()
+        pending_reconciliations = conn.execute(
+            """
+            SELECT COUNT(*)
+            FROM accounts a
+            WHERE a.is_archived = 0
+              AND NOT EXISTS (
+                SELECT 1 FROM balance_snapshots s
+                WHERE s.account_id = a.id AND s.captured_at >= ? AND s.captured_at < ?
+              )
+            """,
+            (start_ts, end_ts),
+        ).fetchone()[0]
+        active_amortizations = conn.execute(
+            "SELECT COUNT(*) FROM amortization_schedules WHERE status = 'Active'"
+        ).fetchone()[0]
+
+        base_currency = get_base_currency(conn)
+        converted_assets = converted_liabilities = None
+        if base_currency is not None:
+            by_currency = conn.execute(
+                "SELECT type, currency, SUM(balance_cents) AS total FROM accounts GROUP BY type, currency"
+            ).fetchall()
+            converted_assets = convert_to_base(
+                conn, [(int(row["total"]), row["currency"], today) for row in by_currency if row["type"] == "Asset"]
+            )
+            converted_liabilities = convert_to_base(
+                conn,
+                [(-int(row["total"]), row["currency"], today) for row in by_currency if row["type"] == "Liability"],
+            )
+
+    assets = int(balances["assets"])
+    # Liability balances are stored as non-positive numbers; report the amount owed.
+    liabilities = -int(balances["liabilities"])
+    return DashboardDto(
+        periodYm=period_ym,
+        netWorthCents=assets - liabilities,
+        totalAssetsCents=assets,
+        totalLiabilitiesCents=liabilities,
+        monthToDateExpenseCents=int(flows["expenses"]),
+        monthToDateIncomeCents=int(flows["income"]),
+        pendingReconciliationCount=int(pending_reconciliations),
+        activeAmortizationCount=int(active_amortizations),
+        baseCurrency=base_currency,
+        convertedNetWorthCents=(
+            None
+            if converted_assets is None or converted_liabilities is None
+            else converted_assets - converted_liabilities
+        ),
+        convertedTotalAssetsCents=converted_assets,
+        convertedTotalLiabilitiesCents=converted_liabilities,
+    )
@@ -0,0 +1,412 @@
+from __future__ import annotations
+
+import re
+import sqlite3
+import uuid
+from typing import Optional
+
+from app.db import get_connection, now_utc_rfc3339, transaction
+from app.models import (
+    ApiError,
+    CategoryDto,
+    CategorySuggestionDto,
+    CreateCategoryInput,
+    CreatePayeeInput,
+    CreateTagInput,
+    MergeCategoriesInput,
+    MergeCategoriesResultDto,
+    MergePayeesInput,
+    MergePayeesResultDto,
+    PayeeDto,
+    PayeeSuggestionDto,
+    TagDto,
+    UpdateCategoryInput,
+    UpdateTagInput,
+)
+
+CATEGORY_COLUMNS = "id, name, parent_id, color, icon, sort_order, is_active"
+TAG_COLUMNS = "id, name, color, description, is_archived"
+MAX_ICON_LENGTH = 64
+
+
+def _category_from_row(row: sqlite3.Row) -> CategoryDto:
+    return CategoryDto(
+        id=row["id"],
+        name=row["name"],
+        parentId=row["parent_id"],
+        color=row["color"],
+        icon=row["icon"],
+        sortOrder=row["sort_order"],
+        isActive=bool(row["is_active"]),
+    )
+
+
+def _load_category(conn: sqlite3.Connection, category_id: str) -> CategoryDto:
+    row = conn.execute(f"SELECT {CATEGORY_COLUMNS} FROM categories WHERE id = ?", (category_id,)).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"category not found: {category_id}", status_code=404)
+    return _category_from_row(row)
+
+
+def _payee_from_row(row: sqlite3.Row) -> PayeeDto:
+    return PayeeDto(
+        id=row["id"],
+        name=row["name"],
+        defaultCategoryId=row["default_category_id"],
+        isArchived=bool(row["is_archived"]),
+    )
+
+
+def _tag_from_row(row: sqlite3.Row) -> TagDto:
+    return TagDto(
+        id=row["id"],
+        name=row["name"],
+        color=row["color"],
+        description=row["description"],
+        isArchived=bool(row["is_archived"]),
+    )
+
+
+def normalize_color(value: Optional[str]) -> Optional[str]:
+    """A "#rrggbb" color in lower case; blank means none."""
+    if value is None or not value.strip():
+        return None
+    color = value.strip().lower()
+    if re.fullmatch(r"#[0-9a-f]{6}", color) is None:
+        raise ApiError("invalid_input", f"color must be a hex code like #1e88e5: {value}")
+    return color
+
+
+def _normalize_icon(value: Optional[str]) -> Optional[str]:
+    icon = (value or "").strip()
+    if len(icon) > MAX_ICON_LENGTH:
+        raise ApiError("invalid_input", f"icon must be at most {MAX_ICON_LENGTH} characters")
+    return icon or None
+
+
+def _load_payee(conn: sqlite3.Connection, payee_id: str) -> PayeeDto:
+    row = conn.execute(
+        "SELECT id, name, default_category_id, is_archived FROM payees WHERE id = ?",
+        (payee_id,),
+    ).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"payee not found: {payee_id}", status_code=404)
+    return _payee_from_row(row)
+
+
+def _load_tag(conn: sqlite3.Connection, tag_id: str) -> TagDto:
+    row = conn.execute(f"SELECT {TAG_COLUMNS} FROM tags WHERE id = ?", (tag_id,)).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"tag not found: {tag_id}", status_code=404)
+    return _tag_from_row(row)
+
+
+def category_roots(conn: sqlite3.Connection) -> dict[str, sqlite3.Row]:
+    """Each category's top-level ancestor (id, name), itself when it has no parent."""
+    rows = conn.execute("SELECT id, name, parent_id FROM categories").fetchall()
+    by_id = {row["id"]: row for row in rows}
+    roots: dict[str, sqlite3.Row] = {}
+    for row in rows:
+        current, seen = row, {row["id"]}
+        # The seen set stops at a parent cycle a hand edit could have left behind.
+        while current["parent_id"] in by_id and current["parent_id"] not in seen:
+            current = by_id[current["parent_id"]]
+            seen.add(current["id"])
+        roots[row["id"]] = current
+    return roots
+
+
+def list_categories(include_inactive: bool = False) -> list[CategoryDto]:
+    with get_connection() as conn:
+        rows = conn.execute(
+            f"""
+            SELECT {CATEGORY_COLUMNS}
+            FROM categories
+            WHERE (? OR is_active = 1)
+            ORDER BY sort_order ASC, name ASC
+            """,
+            (1 if include_inactive else 0,),
+        ).fetchall()
+        return [_category_from_row(row) for row in rows]
+
+
+def insert_category(conn: sqlite3.Connection, input_data: CreateCategoryInput) -> str:
+    name = input_data.name.strip()
+    if not name:
+        raise ApiError("invalid_input", "category name cannot be empty")
+    if input_data.parentId is not None:
+        _load_category(conn, input_data.parentId)
+    existing = conn.execute("SELECT id FROM categories WHERE name = ?", (name,)).fetchone()
+    if existing is not None:
+        raise ApiError("conflict", f"category already exists: {name}", status_code=409)
+
+    color = normalize_color(input_data.color)
+    icon = _normalize_icon(input_data.icon)
+
+    category_id = str(uuid.uuid4())
+    conn.execute(
+        "INSERT INTO categories (id, name, parent_id, color, icon, sort_order, is_active) VALUES (?, ?, ?, ?, ?, ?, 1)",
+        (category_id, name, input_data.parentId, color, icon, input_data.sortOrder),
+    )
+    return category_id
+
+
+def ensure_category(conn: sqlite3.Connection, name: str) -> str:
+    """Id of the category called `name`, creating it first if needed; for postings the app books itself."""
+    row = conn.execute("SELECT id FROM categories WHERE name = ?", (name,)).fetchone()
+    if row is not None:
+        return row["id"]
+    return insert_category(conn, CreateCategoryInput(name=name))
+
+
+def create_category(input_data: CreateCategoryInput) -> CategoryDto:
+    with get_connection() as conn:
+        with transaction(conn):
+            category_id = insert_category(conn, input_data)
+        return _load_category(conn, category_id)
+
+
+def update_category(category_id: str, input_data: UpdateCategoryInput) -> CategoryDto:
+    if input_data.name is not None and not input_data.name.strip():
+        raise ApiError("invalid_input", "category name cannot be empty")
+
+    with get_connection() as conn:
+        current = _load_category(conn, category_id)
+        name = current.name if input_data.name is None else input_data.name.strip()
+        color = current.color if input_data.color is None else normalize_color(input_data.color)
+        icon = current.icon if input_data.icon is None else _normalize_icon(input_data.icon)
+        sort_order = current.sortOrder if input_data.sortOrder is None else input_data.sortOrder
+        existing = conn.execute("SELECT id FROM categories WHERE name = ? AND id != ?", (name, category_id)).fetchone()
+        if existing is not None:
+            raise ApiError("conflict", f"category already exists: {name}", status_code=409)
+
+        with transaction(conn):
+            conn.execute(
+                "UPDATE categories SET name = ?, color = ?, icon = ?, sort_order = ? WHERE id = ?",
+                (name, color, icon, sort_order, category_id),
+            )
+        return _load_category(conn, category_id)
+
+
+MAX_CATEGORY_SUGGESTIONS = 10
+RECENT_AMOUNT_COUNT = 5
+
+
+def suggest_categories(prefix: str) -> list[CategorySuggestionDto]:
+    """Active categories starting with `prefix` (any case), the most used first."""
+    pattern = prefix.strip().replace("\\", "\\\\").replace("%", "\\%").replace("_", "\\_") + "%"
+    with get_connection() as conn:
+        rows = conn.execute(
+            """
+            SELECT c.id, c.name, c.parent_id, c.color, c.icon, c.sort_order, c.is_active, COUNT(t.id) AS use_count
+            FROM categories c
+            LEFT JOIN transactions t ON t.category_id = c.id AND t.deleted_at IS NULL
+            WHERE c.is_active = 1 AND c.name LIKE ? ESCAPE '\\'
+            GROUP BY c.id
+            ORDER BY use_count DESC, c.name ASC
+            LIMIT ?
+            """,
+            (pattern, MAX_CATEGORY_SUGGESTIONS),
+        ).fetchall()
+    return [CategorySuggestionDto(category=_category_from_row(row), useCount=row["use_count"]) for row in rows]
+
+
+def suggest_for_payee(payee_id: str) -> PayeeSuggestionDto:
+    """What the entry form can pre-fill once a payee is picked, from how it was booked before."""
+    with get_connection() as conn:
+        payee = _load_payee(conn, payee_id)
+        # Ties go to the category used most recently.
+        category = conn.execute(
+            """
+            SELECT category_id
+            FROM transactions
+            WHERE payee_id = ? AND category_id IS NOT NULL AND deleted_at IS NULL
+            GROUP BY category_id
+            ORDER BY COUNT(*) DESC, MAX(occurred_at) DESC
+            LIMIT 1
+            """,
+            (payee_id,),
+        ).fetchone()
+        amounts = conn.execute(
+            """
+            SELECT amount_cents
+            FROM transactions
+            WHERE payee_id = ? AND deleted_at IS NULL
+            GROUP BY amount_cents
+            ORDER BY MAX(occurred_at) DESC
+            LIMIT ?
+            """,
+            (payee_id, RECENT_AMOUNT_COUNT),
+        ).fetchall()
+    return PayeeSuggestionDto(
+        payeeId=payee_id,
+        categoryId=payee.defaultCategoryId if category is None else category["category_id"],
+        recentAmountsCents=[row["amount_cents"] for row in amounts],
+    )
+
+
+def merge_categories(input_data: MergeCategoriesInput) -> MergeCategoriesResultDto:
+    """Fold one category into another and deactivate it; past months now report under the target."""
+    source_id, target_id = input_data.sourceId, input_data.targetId
+    if source_id == target_id:
+        raise ApiError("invalid_input", "sourceId and targetId must differ")
+
+    with get_connection() as conn:
+        source = _load_category(conn, source_id)
+        target = _load_category(conn, target_id)
+        now = now_utc_rfc3339()
+        with transaction(conn):
+            moved = conn.execute(
+                "UPDATE transactions SET category_id = ? WHERE category_id = ?", (target_id, source_id)
+            ).rowcount
+            # A month budgeted under both keeps a single row holding the two amounts.
+            budgets = conn.execute(
+                "SELECT id, period_ym, amount_cents FROM budgets WHERE category_id = ?", (source_id,)
+            ).fetchall()
+            for budget in budgets:
+                merged = conn.execute(
+                    """
+                    UPDATE budgets SET amount_cents = amount_cents + ?, updated_at = ?
+                    WHERE category_id = ? AND period_ym = ?
+                    """,
+                    (budget["amount_cents"], now, target_id, budget["period_ym"]),
+                ).rowcount
+                if merged:
+                    conn.execute("DELETE FROM budgets WHERE id = ?", (budget["id"],))
+                else:
+                    conn.execute(
+                        "UPDATE budgets SET category_id = ?, updated_at = ? WHERE id = ?",
+                        (target_id, now, budget["id"]),
+                    )
+            conn.execute(
+                "UPDATE recurring_rules SET category_id = ?, updated_at = ? WHERE category_id = ?",
+                (target_id, now, source_id),
+            )
+            conn.execute(
+                "UPDATE payees SET default_category_id = ? WHERE default_category_id = ?", (target_id, source_id)
+            )
+            # Subcategories follow their parent; a target nested under the source takes the source's place.
+            conn.execute(
+                "UPDATE categories SET parent_id = ? WHERE parent_id = ? AND id != ?", (target_id, source_id, target_id)
+            )
+            if target.parentId == source_id:
+                conn.execute("UPDATE categories SET parent_id = ? WHERE id = ?", (source.parentId, target_id))
+            conn.execute("UPDATE categories SET is_active = 0 WHERE id = ?", (source_id,))
+        return MergeCategoriesResultDto(
+            category=_load_category(conn, target_id), movedTransactionCount=moved, movedBudgetCount=len(budgets)
+        )
+
+
+def list_payees(include_archived: bool = False) -> list[PayeeDto]:
+    with get_connection() as conn:
+        rows = conn.execute(
+            """
+            SELECT id, name, default_category_id, is_archived
+            FROM payees
+            WHERE (? OR is_archived = 0)
+            ORDER BY name ASC
+            """,
+            (1 if include_archived else 0,),
+        ).fetchall()
+        return [_payee_from_row(row) for row in rows]
+
+
+def create_payee(input_data: CreatePayeeInput) -> PayeeDto:
+    name = input_data.name.strip()
+    if not name:
+        raise ApiError("invalid_input", "payee name cannot be empty")
+
+    payee_id = str(uuid.uuid4())
+    with get_connection() as conn:
+        if input_data.defaultCategoryId is not None:
+            _load_category(conn, input_data.defaultCategoryId)
+        existing = conn.execute("SELECT id FROM payees WHERE name = ?", (name,)).fetchone()
+        if existing is not None:
+            raise ApiError("conflict", f"payee already exists: {name}", status_code=409)
+
+        with transaction(conn):
+            conn.execute(
+                "INSERT INTO payees (id, name, default_category_id, is_archived) VALUES (?, ?, ?, 0)",
+                (payee_id, name, input_data.defaultCategoryId),
+            )
+        return _load_payee(conn, payee_id)
+
+
+def merge_payees(input_data: MergePayeesInput) -> MergePayeesResultDto:
+    """Fold duplicate payees into one: their transactions, rules and bills move over, then they go."""
+    source_ids = list(dict.fromkeys(input_data.sourceIds))
+    if not source_ids:
+        raise ApiError("invalid_input", "sourceIds cannot be empty")
+    if input_data.targetId in source_ids:
+        raise ApiError("invalid_input", "targetId cannot be one of the sourceIds")
+
+    with get_connection() as conn:
+        for payee_id in [input_data.targetId, *source_ids]:
+            _load_payee(conn, payee_id)
+        placeholders = ", ".join("?" for _ in source_ids)
+        with transaction(conn):
+            # Trashed rows move too, so restoring one later does not bring a deleted payee back.
+            moved = conn.execute(
+                f"UPDATE transactions SET payee_id = ? WHERE payee_id IN ({placeholders})",
+                (input_data.targetId, *source_ids),
+            ).rowcount
+            for table in ("recurring_rules", "bills", "import_rows"):
+                conn.execute(
+                    f"UPDATE {table} SET payee_id = ? WHERE payee_id IN ({placeholders})",
+                    (input_data.targetId, *source_ids),
+                )
+            conn.execute(f"DELETE FROM payees WHERE id IN ({placeholders})", source_ids)
+        return MergePayeesResultDto(payee=_load_payee(conn, input_data.targetId), movedTransactionCount=moved)
+
+
+def list_tags(include_archived: bool = False) -> list[TagDto]:
+    with get_connection() as conn:
+        rows = conn.execute(
+            f"SELECT {TAG_COLUMNS} FROM tags WHERE (? OR is_archived = 0) ORDER BY name ASC",
+            (1 if include_archived else 0,),
+        ).fetchall()
+        return [_tag_from_row(row) for row in rows]
+
+
+def create_tag(input_data: CreateTagInput) -> TagDto:
+    name = input_data.name.strip()
+    if not name:
+        raise ApiError("invalid_input", "tag name cannot be empty")
+
+    color = normalize_color(input_data.color)
+    description = (input_data.description or "").strip() or None
+
+    tag_id = str(uuid.uuid4())
+    with get_connection() as conn:
+        existing = conn.execute("SELECT id FROM tags WHERE name = ?", (name,)).fetchone()
+        if existing is not None:
+            raise ApiError("conflict", f"tag already exists: {name}", status_code=409)
+
+        with transaction(conn):
+            conn.execute(
+                "INSERT INTO tags (id, name, color, description, is_archived) VALUES (?, ?, ?, ?, 0)",
+                (tag_id, name, color, description),
+            )
+        return _load_tag(conn, tag_id)
+
+
+def update_tag(tag_id: str, input_data: UpdateTagInput) -> TagDto:
+    if input_data.name is not None and not input_data.name.strip():
+        raise ApiError("invalid_input", "tag name cannot be empty")
+
+    with get_connection() as conn:
+        current = _load_tag(conn, tag_id)
+        name = current.name if input_data.name is None else input_data.name.strip()
+        color = current.color if input_data.color is None else normalize_color(input_data.color)
+        description = current.description if input_data.description is None else input_data.description.strip() or None
+        archived = current.isArchived if input_data.isArchived is None else input_data.isArchived
+        existing = conn.execute("SELECT id FROM tags WHERE name = ? AND id != ?", (name, tag_id)).fetchone()
+        if existing is not None:
+            raise ApiError("conflict", f"tag already exists: {name}", status_code=409)
+
+        with transaction(conn):
+            conn.execute(
+                "UPDATE tags SET name = ?, color = ?, description = ?, is_archived = ? WHERE id = ?",
+                (name, color, description, 1 if archived else 0, tag_id),
+            )
+        return _load_tag(conn, tag_id)
@@ -0,0 +1,349 @@
+from __future__ import annotations
+
+import csv
+import random
+import sqlite3
+from pathlib import Path
+
+from app.db import get_connection, parse_period, transaction, transaction_period_sql
+from app.models import (
+    AnonymizedExportInput,
+    ApiError,
+    ExportResultDto,
+    ReportCsvExportInput,
+    ReportKind,
+    ReportRangeInput,
+    UtilityMatrixExportInput,
+    format_cents,
+)
+from app.services.budgets import get_budget_report
+from app.services.finance import get_cash_flow_report, get_purpose_report, get_utility_report
+from app.services.settings import get_timezone
+
+
+def _resolve_export_path(raw_path: str) -> Path:
+    if not raw_path.strip():
+        raise ApiError("invalid_input", "path cannot be empty")
+    path = Path(raw_path).expanduser()
+    if not path.parent.is_dir():
+        raise ApiError("invalid_input", f"directory does not exist: {path.parent}")
+    return path
+
+
+def export_utility_matrix(input_data: UtilityMatrixExportInput) -> ExportResultDto:
+    if input_data.year < 1 or input_data.year > 9999:
+        raise ApiError("invalid_input", f"invalid year: {input_data.year}")
+    path = _resolve_export_path(input_data.path)
+    months = [f"{input_data.year:04d}-{month:02d}" for month in range(1, 13)]
+
+    with get_connection() as conn:
+        tz = get_timezone(conn)
+        if tz is None:
+            rows = conn.execute(
+                """
+                SELECT
+                  CASE WHEN m.accrual_type = 'Depreciation' THEN 'Depreciation'
+                       ELSE COALESCE(c.name, 'Uncategorized') END AS label,
+                  m.period_ym,
+                  SUM(m.amount_cents) AS total
+                FROM monthly_category_totals m
+                LEFT JOIN categories c ON m.category_id = c.id
+                WHERE m.period_ym >= ? AND m.period_ym <= ?
+                  AND ((m.accrual_type = 'Flow' AND m.is_asset_purchase = 0) OR m.accrual_type = 'Depreciation')
+                GROUP BY label, m.period_ym
+                """,
+                (months[0], months[-1]),
+            ).fetchall()
+        else:
+            # The monthly totals are per UTC month; local months are summed from the rows.
+            _, start_ts, _ = parse_period(months[0], tz=tz)
+            _, _, end_ts = parse_period(months[-1], tz=tz)
+            rows = conn.execute(
+                f"""
+                SELECT
+                  CASE WHEN t.accrual_type = 'Depreciation' THEN 'Depreciation'
+                       ELSE COALESCE(c.name, 'Uncategorized') END AS label,
+                  {transaction_period_sql(alias="t", tz=tz)} AS period_ym,
+                  SUM(t.amount_cents) AS total
+                FROM transactions t
+                LEFT JOIN categories c ON t.category_id = c.id
+                WHERE t.occurred_at >= ? AND t.occurred_at < ?
+                  AND t.deleted_at IS NULL
+                  AND ((t.accrual_type = 'Flow' AND t.is_asset_purchase = 0) OR t.accrual_type = 'Depreciation')
+                GROUP BY label, period_ym
+                """,
+                (start_ts, end_ts),
+            ).fetchall()
+
+    matrix: dict[str, dict[str, int]] = {}
+    for row in rows:
+        matrix.setdefault(row["label"], {})[row["period_ym"]] = int(row["total"])
+
+    with path.open("w", newline="", encoding="utf-8") as handle:
+        writer = csv.writer(handle)
+        writer.writerow(["Category", *months, "Total"])
+        for label in sorted(matrix):
+            cells = [matrix[label].get(period_ym, 0) for period_ym in months]
+            writer.writerow([label, *(format_cents(cell) for cell in cells), format_cents(sum(cells))])
+        totals = [sum(values.get(period_ym, 0) for values in matrix.values()) for period_ym in months]
+        writer.writerow(["Total", *(format_cents(cell) for cell in totals), format_cents(sum(totals))])
+
+    return ExportResultDto(path=str(path), rowCount=len(matrix))
+
+
+CsvRows = tuple[list[str], list[list[str]], list[list[str]]]
+
+
+def _report_csv_rows(input_data: ReportCsvExportInput) -> CsvRows:
+    """Returns the header, the report lines and the closing total rows."""
+    range_input = None
+    if input_data.fromDate is not None or input_data.toDate is not None:
+        range_input = ReportRangeInput(fromDate=input_data.fromDate or "", toDate=input_data.toDate or "")
+    if input_data.report in (ReportKind.BUDGET, ReportKind.PURPOSE) and (
+        range_input is not None or input_data.periodYm is None
+    ):
+        raise ApiError("invalid_input", f"the {input_data.report.value} report only takes periodYm")
+
+    if input_data.report == ReportKind.BUDGET:
+        budget = get_budget_report(input_data.periodYm)
+        lines = [
+            [
+                item.label,
+                *(
+                    format_cents(cents)
+                    for cents in (
+                        item.budgetedCents,
+                        item.carriedOverCents,
+                        item.effectiveBudgetCents,
+                        item.spentCents,
+                        item.remainingCents,
+                    )
+                ),
+            ]
+            for item in budget.items
+        ]
+        remaining = budget.totalEffectiveBudgetCents - budget.totalSpentCents
+        return (
+            ["Category", "Budgeted", "Carried over", "Effective", "Spent", "Remaining"],
+            lines,
+            [
+                [
+                    "Total",
+                    "",
+                    "",
+                    format_cents(budget.totalEffectiveBudgetCents),
+                    format_cents(budget.totalSpentCents),
+                    format_cents(remaining),
+                ]
+            ],
+        )
+
+    if input_data.report == ReportKind.CASH:
+        cash = get_cash_flow_report(input_data.periodYm, range_input=range_input)
+        lines = [["Income", item.label, format_cents(item.amountCents)] for item in cash.incomeItems]
+        lines += [["Expense", item.label, format_cents(item.amountCents)] for item in cash.items]
+        return (
+            ["Section", "Label", "Amount"],
+            lines,
+            [
+                ["Total", "Income", format_cents(cash.totalIncomeCents)],
+                ["Total", "Expense", format_cents(cash.totalExpenseCents)],
+                ["Total", "Transfers", format_cents(cash.totalTransferCents)],
+                ["Total", "Debt repayments", format_cents(cash.totalDebtRepaymentCents)],
+                ["Total", "Borrowing", format_cents(cash.totalBorrowingCents)],
+                ["Total", "Net", format_cents(cash.netCents)],
+            ],
+        )
+
+    if input_data.report == ReportKind.UTILITY:
+        report = get_utility_report(input_data.periodYm, range_input=range_input)
+    else:
+        report = get_purpose_report(input_data.periodYm)
+    return (
+        ["Label", "Amount"],
+        [[item.label, format_cents(item.amountCents)] for item in report.items],
+        [["Total", format_cents(report.totalExpenseCents)]],
+    )
+
+
+def export_report_csv(input_data: ReportCsvExportInput) -> ExportResultDto:
+    path = _resolve_export_path(input_data.path)
+    header, lines, totals = _report_csv_rows(input_data)
+    with path.open("w", newline="", encoding="utf-8") as handle:
+        writer = csv.writer(handle)
+        writer.writerow(header)
+        writer.writerows(lines)
+        writer.writerows(totals)
+    return ExportResultDto(path=str(path), rowCount=len(lines))
+
+
+# (table, column, fake prefix) for free text the user typed in. Rows are numbered in
+# creation order so the same database always anonymizes to the same values.
+ANONYMIZED_TEXT_COLUMNS = (
+    ("accounts", "name", "Account"),
+    ("categories", "name", "Category"),
+    ("payees", "name", "Payee"),
+    ("tags", "name", "Tag"),
+    ("tags", "description", "Description"),
+    ("transactions", "note", "Note"),
+    ("transactions", "external_id", "Reference"),
+    ("recurring_rules", "name", "Recurring rule"),
+    ("recurring_rules", "note", "Note"),
+    ("bills", "name", "Bill"),
+    ("import_profiles", "name", "Import profile"),
+    ("account_valuations", "note", "Note"),
+    ("usage_logs", "note", "Note"),
+    ("maintenance_events", "note", "Note"),
+    ("amortization_schedules", "serial_number", "Serial"),
+    ("amortization_schedules", "receipt_reference", "Receipt"),
+)
+# Money columns scaled together when amounts are perturbed; strictly positive ones stay >= 1.
+PERTURBED_AMOUNT_COLUMNS = (
+    ("transactions", "amount_cents", True),
+    ("accounts", "opening_balance_cents", False),
+    ("amortization_schedules", "residual_cents", False),
+    ("amortization_schedules", "basis_cents", True),
+    ("amortization_schedules", "carried_depreciation_cents", False),
+    ("amortization_schedules", "revaluation_cents", False),
+    ("amortization_schedules", "rebased_cents", False),
+    ("asset_revaluations", "appraised_cents", False),
+    ("asset_revaluations", "carrying_cents", False),
+    ("maintenance_events", "cost_cents", False),
+    ("amortization_postings", "amount_cents", True),
+    ("loans", "principal_cents", True),
+    ("loan_payments", "principal_cents", False),
+    ("loan_payments", "interest_cents", False),
+    ("interest_accruals", "owed_cents", True),
+    ("interest_accruals", "amount_cents", True),
+    ("balance_snapshots", "actual_balance_cents", False),
+    ("balance_snapshots", "system_balance_cents", False),
+    ("budgets", "amount_cents", False),
+    ("recurring_rules", "amount_cents", True),
+    ("bills", "expected_amount_cents", True),
+    ("account_valuations", "value_cents", False),
+)
+
+
+def _net_flows(conn: sqlite3.Connection) -> dict[str, int]:
+    net: dict[str, int] = {}
+    rows = conn.execute(
+        """
+        SELECT p.account_id, SUM(p.amount_cents) AS amount_cents
+        FROM transaction_postings p
+        JOIN transactions t ON t.id = p.transaction_id
+        WHERE t.deleted_at IS NULL AND p.account_id IS NOT NULL
+        GROUP BY p.account_id
+        """
+    ).fetchall()
+    for row in rows:
+        net[row["account_id"]] = int(row["amount_cents"])
+    return net
+
+
+def _scale_postings(conn: sqlite3.Connection, factor: float) -> None:
+    # Two-leg rows follow their already scaled amount. A multi-leg row scales each leg and the
+    # largest leg on the short side takes the rounding, so the legs still sum to zero.
+    conn.execute(
+        """
+        UPDATE transaction_postings SET amount_cents = (
+          SELECT CASE WHEN transaction_postings.amount_cents < 0 THEN -t.amount_cents ELSE t.amount_cents END
+          FROM transactions t WHERE t.id = transaction_postings.transaction_id
+        )
+        WHERE transaction_id IN (
+          SELECT id FROM transactions WHERE from_account_id IS NOT NULL OR to_account_id IS NOT NULL
+        )
+        """
+    )
+    rows = conn.execute(
+        """
+        SELECT transaction_id, leg, amount_cents FROM transaction_postings
+        WHERE transaction_id IN (
+          SELECT id FROM transactions WHERE from_account_id IS NULL AND to_account_id IS NULL
+        )
+        ORDER BY transaction_id, leg
+        """
+    ).fetchall()
+    legs: dict[str, dict[int, int]] = {}
+    for row in rows:
+        amount = int(row["amount_cents"])
+        scaled = max(1, int(abs(amount) * factor + 0.5))
+        legs.setdefault(row["transaction_id"], {})[row["leg"]] = scaled if amount > 0 else -scaled
+    for tx_id, amounts in legs.items():
+        residue = -sum(amounts.values())
+        if residue:
+            side = [leg for leg, amount in amounts.items() if (amount > 0) == (residue > 0)]
+            amounts[max(side, key=lambda leg: abs(amounts[leg]))] += residue
+        conn.executemany(
+            "UPDATE transaction_postings SET amount_cents = ? WHERE transaction_id = ? AND leg = ?",
+            [(amount, tx_id, leg) for leg, amount in amounts.items()],
+        )
+        conn.execute(
+            "UPDATE transactions SET amount_cents = ? WHERE id = ?",
+            (sum(amount for amount in amounts.values() if amount > 0), tx_id),
+        )
+
+
+def _anonymize_text(conn: sqlite3.Connection) -> None:
+    for table, column, prefix in ANONYMIZED_TEXT_COLUMNS:
+        rows = conn.execute(
+            f"SELECT id FROM {table} WHERE {column} IS NOT NULL ORDER BY rowid ASC"
+        ).fetchall()
+        for index, row in enumerate(rows, start=1):
+            conn.execute(f"UPDATE {table} SET {column} = ? WHERE id = ?", (f"{prefix} {index}", row["id"]))
+
+
+def _perturb_amounts(conn: sqlite3.Connection, factor: float) -> None:
+    # Derive each opening balance from the live rows, scale it with everything else and
+    # rebuild the balance from its scaled flows; balances then still add up exactly.
+    old_net = _net_flows(conn)
+    accounts = conn.execute("SELECT id, type, balance_cents FROM accounts").fetchall()
+
+    for table, column, positive in PERTURBED_AMOUNT_COLUMNS:
+        scaled = f"CAST(ROUND({column} * ?) AS INTEGER)"
+        conn.execute(f"UPDATE {table} SET {column} = {f'MAX(1, {scaled})' if positive else scaled}", (factor,))
+    _scale_postings(conn, factor)
+    conn.execute("UPDATE balance_snapshots SET delta_cents = actual_balance_cents - system_balance_cents")
+
+    new_net = _net_flows(conn)
+    for account in accounts:
+        opening = int(account["balance_cents"]) - old_net.get(account["id"], 0)
+        balance = round(opening * factor) + new_net.get(account["id"], 0)
+        if account["type"] == "Liability":
+            balance = min(balance, 0)
+        conn.execute("UPDATE accounts SET balance_cents = ? WHERE id = ?", (balance, account["id"]))
+
+
+def export_anonymized(input_data: AnonymizedExportInput) -> ExportResultDto:
+    """Copy the database for a bug report with everything personal replaced or dropped."""
+    path = _resolve_export_path(input_data.path)
+    if path.exists():
+        raise ApiError("conflict", f"file already exists: {path}", status_code=409)
+
+    target = sqlite3.connect(path)
+    try:
+        with get_connection() as conn:
+            conn.backup(target)
+        target.row_factory = sqlite3.Row
+        with transaction(target):
+            _anonymize_text(target)
+            # Logins, attachments and staged statements identify the user outright; the recipient's backend
+            # recreates its default admin on startup.
+            target.execute("DELETE FROM user_refresh_tokens")
+            target.execute("DELETE FROM users")
+            target.execute("DELETE FROM account_photos")
+            target.execute("DELETE FROM transaction_attachments")
+            target.execute("DELETE FROM import_rows")
+            target.execute("DELETE FROM import_batches")
+            # Hashes of statement lines could be matched against guessed descriptors.
+            target.execute("UPDATE transactions SET import_hash = NULL")
+            if input_data.perturbAmounts:
+                _perturb_amounts(target, random.SystemRandom().uniform(0.5, 2.0))
+        # Rewrite the file so pages that held the original text are not left behind.
+        target.execute("VACUUM")
+        row_count = target.execute("SELECT COUNT(*) FROM transactions").fetchone()[0]
+    except Exception:
+        target.close()
+        path.unlink(missing_ok=True)
+        raise
+    target.close()
+    return ExportResultDto(path=str(path), rowCount=row_count)
@@ -2,19 +2,32 @@
 
 import sqlite3
 import uuid
-from datetime import date
-from typing import Optional
+from datetime import date, timedelta
+from typing import Callable, Optional, Sequence
 
+from app import events
 from app.db import (
+    covers_whole_months,
+    day_start_utc,
+    format_period,
     get_connection,
+    get_device_id,
+    insert_postings,
+    local_date,
+    local_day_sql,
+    local_today,
     normalize_timestamp,
     now_utc_rfc3339,
     parse_date_ymd,
     parse_period,
+    period_of,
     transaction,
+    two_leg_postings,
+    validate_postings,
 )
 from app.models import (
     AccountDto,
+    AccountLiquidity,
     AccountType,
     AccrualType,
     AdjustmentKpiDto,
@@ -22,29 +35,95 @@ from app.models import (
     AmortizationStrategy,
     ApiError,
     AssetPurchaseResultDto,
+    AssignPayeeInput,
+    BalanceDriftPointDto,
+    BalanceDriftReportDto,
+    BalanceDriftSeriesDto,
+    BalanceGranularity,
+    BalanceHistoryDto,
+    BalancePointDto,
+    BalanceSnapshotDto,
+    CashFlowReportDto,
+    CashFlowSectionDto,
     CreateAccountInput,
     CreateAssetPurchaseInput,
+    CreateMultiLegTransactionInput,
     CreateTransactionInput,
+    CreateTransactionsBatchInput,
+    DebtToAssetKpiDto,
+    DebtToAssetPointDto,
+    DuplicateTransactionInput,
+    IncomeStatementDto,
     KpiPeriodInput,
+    LiquidityReportDto,
+    LiquidityReportItemDto,
+    MarkClearedInput,
+    MarkClearedResultDto,
     PagedTransactionsDto,
+    PostingInput,
+    RecategorizeAdjustmentInput,
     ReconcileInput,
+    RecordOpeningBalanceInput,
     ReconcileResultDto,
     ReportDto,
     ReportItemDto,
+    ReportRangeInput,
+    SetAccountBalanceInput,
     TransactionDto,
+    TransactionPostingDto,
+    TransactionStatus,
+    UpdateAccountInput,
+    add_months,
+    ensure_amount_in_range,
     months_between,
 )
+from app.services.budgets import budget_crossing
+from app.services.dimensions import _load_category, _load_payee, category_roots
+from app.services.currencies import (
+    convert_to_base,
+    ensure_amount_in_currency,
+    get_base_currency,
+    resolve_account_currency,
+)
+from app.services.settings import get_period_start_day, get_timezone
+
+
+# Depreciation rows carry no accounts, so what an account has lost is read from the
+# postings of its schedules; parts already disposed of left the balance with theirs, and
+# postings whose row is in the trash were taken back.
+ACCUMULATED_DEPRECIATION_SQL = """(
+    (SELECT COALESCE(SUM(p.amount_cents), 0)
+     FROM amortization_postings p
+     JOIN amortization_schedules s ON s.id = p.schedule_id
+     JOIN transactions t ON t.id = p.transaction_id
+     WHERE s.asset_account_id = accounts.id AND s.disposed_at IS NULL AND t.deleted_at IS NULL)
+    + (SELECT COALESCE(SUM(carried_depreciation_cents), 0)
+       FROM amortization_schedules WHERE asset_account_id = accounts.id AND disposed_at IS NULL)
+)"""
+
+ACCOUNT_COLUMNS = (
+    "id, name, type, purpose, balance_cents, exclude_from_adjustment_kpi, is_archived, currency, liquidity, "
+    f"interest_rate_percent, created_at, updated_at, device_id, {ACCUMULATED_DEPRECIATION_SQL} AS depreciated_cents"
+)
 
 
-def _account_from_row(row: sqlite3.Row) -> AccountDto:
+def _account_from_row(row: sqlite3.Row, converted_balance: Optional[int] = None) -> AccountDto:
     return AccountDto(
         id=row["id"],
         name=row["name"],
         accountType=row["type"],
         purpose=row["purpose"],
         balanceCents=row["balance_cents"],
+        excludeFromAdjustmentKpi=bool(row["exclude_from_adjustment_kpi"]),
+        isArchived=bool(row["is_archived"]),
+        currency=row["currency"],
+        convertedBalanceCents=converted_balance,
+        liquidity=row["liquidity"],
+        interestRatePercent=row["interest_rate_percent"],
+        bookValueCents=int(row["balance_cents"]) - int(row["depreciated_cents"]),
         createdAt=row["created_at"],
         updatedAt=row["updated_at"],
+        deviceId=row["device_id"],
     )
 
 
@@ -61,6 +140,8 @@ def _transaction_from_row(row: sqlite3.Row) -> TransactionDto:
         note=row["note"],
         occurredAt=row["occurred_at"],
         createdAt=row["created_at"],
+        status=row["status"],
+        deviceId=row["device_id"],
     )
 
 
@@ -74,12 +155,18 @@ def _schedule_from_row(row: sqlite3.Row) -> AmortizationScheduleDto:
         startDate=row["start_date"],
         sourceTransactionId=row["source_transaction_id"],
         status=row["status"],
+        decliningFactorPercent=row["declining_factor_percent"],
+        expectedUnits=row["expected_units"],
+        prorateDaily=bool(row["prorate_daily"]),
+        serialNumber=row["serial_number"],
+        receiptReference=row["receipt_reference"],
+        warrantyExpiresOn=row["warranty_expires_on"],
     )
 
 
 def _load_account(conn: sqlite3.Connection, account_id: str) -> AccountDto:
     row = conn.execute(
-        "SELECT id, name, type, purpose, balance_cents, created_at, updated_at FROM accounts WHERE id = ?",
+        f"SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE id = ?",
         (account_id,),
     ).fetchone()
     if row is None:
@@ -91,9 +178,9 @@ def _load_transaction(conn: sqlite3.Connection, tx_id: str) -> TransactionDto:
     row = conn.execute(
         """
         SELECT id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
-               accrual_type, is_asset_purchase, note, occurred_at, created_at
+               accrual_type, is_asset_purchase, note, occurred_at, created_at, status, device_id
         FROM transactions
-        WHERE id = ?
+        WHERE id = ? AND deleted_at IS NULL
         """,
         (tx_id,),
     ).fetchone()
@@ -106,7 +193,8 @@ def _load_schedule(conn: sqlite3.Connection, schedule_id: str) -> AmortizationSc
     row = conn.execute(
         """
         SELECT id, asset_account_id, strategy, total_periods, residual_cents,
-               start_date, source_transaction_id, status
+               start_date, source_transaction_id, status, declining_factor_percent, expected_units,
+               prorate_daily, serial_number, receipt_reference, warranty_expires_on
         FROM amortization_schedules
         WHERE id = ?
         """,
@@ -118,26 +206,58 @@ def _load_schedule(conn: sqlite3.Connection, schedule_id: str) -> AmortizationSc
 
 
 def _apply_balance_delta(conn: sqlite3.Connection, account_id: str, delta: int) -> None:
-    updated = conn.execute(
-        "UPDATE accounts SET balance_cents = balance_cents + ?, updated_at = ? WHERE id = ?",
-        (delta, now_utc_rfc3339(), account_id),
-    ).rowcount
-    if updated == 0:
-        raise ApiError("not_found", f"account not found: {account_id}", status_code=404)
-
     row = conn.execute("SELECT type, balance_cents FROM accounts WHERE id = ?", (account_id,)).fetchone()
     if row is None:
         raise ApiError("not_found", f"account not found: {account_id}", status_code=404)
-    if row["type"] == AccountType.LIABILITY.value and row["balance_cents"] > 0:
+
+    new_balance = ensure_amount_in_range(int(row["balance_cents"]) + delta, "balanceCents")
+    if row["type"] == AccountType.LIABILITY.value and new_balance > 0:
         raise ApiError("invalid_input", f"liability account balance cannot be positive: {account_id}")
 
+    conn.execute(
+        "UPDATE accounts SET balance_cents = ?, updated_at = ? WHERE id = ?",
+        (new_balance, now_utc_rfc3339(), account_id),
+    )
+
+
+def _post_legs(conn: sqlite3.Connection, tx_id: str, legs: Sequence[tuple[Optional[str], int]]) -> None:
+    """Write a transaction's postings and move the balance of every account they touch."""
+    insert_postings(conn, tx_id, legs)
+    for account_id, amount in legs:
+        if account_id is not None:
+            _apply_balance_delta(conn, account_id, amount)
+
 
 def _calculate_depreciation_amount(
     strategy: AmortizationStrategy,
     depreciable_cents: int,
     total_periods: int,
     period_index: int,
+    residual_cents: int = 0,
+    declining_factor_percent: Optional[float] = None,
+    in_service_fraction: float = 1.0,
 ) -> int:
+    if in_service_fraction < 1:
+        # A part-month start shifts every period by the same fraction of a month, so the
+        # share the first month misses lands in one extra period after the last.
+        def through(index: int) -> int:
+            if index < 0:
+                return 0
+            full = sum(
+                _calculate_depreciation_amount(
+                    strategy, depreciable_cents, total_periods, i, residual_cents, declining_factor_percent
+                )
+                for i in range(index)
+            )
+            partial = _calculate_depreciation_amount(
+                strategy, depreciable_cents, total_periods, index, residual_cents, declining_factor_percent
+            )
+            return full + round(partial * in_service_fraction)
+
+        if period_index < 0:
+            return 0
+        return through(period_index) - through(period_index - 1)
+
     if (
         depreciable_cents <= 0
         or total_periods <= 0
@@ -152,6 +272,18 @@ def _calculate_depreciation_amount(
             return base + depreciable_cents % total_periods
         return base
 
+    if strategy == AmortizationStrategy.DECLINING_BALANCE:
+        # Each period takes the factor's share of what the asset is still worth, or the
+        # straight-line share of what is left once that is more, and never dips below residual.
+        book = depreciable_cents + residual_cents
+        amount = 0
+        for i in range(period_index + 1):
+            left = book - residual_cents
+            declining = int(book * (declining_factor_percent or 0) / 100 / total_periods)
+            amount = left if i == total_periods - 1 else min(left, max(declining, left // (total_periods - i)))
+            book -= amount
+        return amount
+
     weight_sum = total_periods * (total_periods + 1) // 2
     if period_index == total_periods - 1:
         allocated = 0
@@ -164,11 +296,46 @@ def _calculate_depreciation_amount(
     return (depreciable_cents * weight) // weight_sum
 
 
-def ensure_depreciation_for_period(conn: sqlite3.Connection, period_ym: str) -> None:
-    period_start, period_start_ts, _ = parse_period(period_ym)
+def _usage_depreciation_amount(conn: sqlite3.Connection, row: sqlite3.Row, period_ym: str, is_last: bool) -> int:
+    """A usage schedule's share of its remaining book value for the usage logged in a month.
+
+    Working from what is left rather than the purchase keeps appraisals, disposals and usage
+    beyond the estimate from throwing the schedule off; the last period retires the rest.
+    """
+    remaining_cents = conn.execute(
+        """
+        SELECT ? + s.revaluation_cents - s.residual_cents - s.carried_depreciation_cents
+               - COALESCE((SELECT SUM(p.amount_cents) FROM amortization_postings p WHERE p.schedule_id = s.id), 0)
+        FROM amortization_schedules s
+        WHERE s.id = ?
+        """,
+        (row["amount_cents"], row["id"]),
+    ).fetchone()[0]
+    if remaining_cents <= 0 or is_last:
+        return max(remaining_cents, 0)
+    logged = conn.execute(
+        """
+        SELECT COALESCE(SUM(CASE WHEN period_ym < ? THEN units END), 0) AS units_before,
+               COALESCE(SUM(CASE WHEN period_ym = ? THEN units END), 0) AS units_this_month
+        FROM usage_logs
+        WHERE schedule_id = ?
+        """,
+        (period_ym, period_ym, row["id"]),
+    ).fetchone()
+    remaining_units = float(row["expected_units"]) - float(logged["units_before"])
+    if logged["units_this_month"] >= remaining_units:
+        return remaining_cents
+    return round(remaining_cents * float(logged["units_this_month"]) / remaining_units)
+
+
+def ensure_depreciation_for_period(conn: sqlite3.Connection, period_ym: str) -> list[str]:
+    period_start, period_start_ts, _ = parse_period(period_ym, tz=get_timezone(conn))
+    posted: list[str] = []
     schedules = conn.execute(
         """
-        SELECT s.id, s.strategy, s.total_periods, s.residual_cents, s.start_date, t.amount_cents
+        SELECT s.id, s.strategy, s.total_periods, s.residual_cents, s.start_date,
+               s.rebased_period_ym, s.rebased_cents, s.declining_factor_percent, s.expected_units,
+               s.prorate_daily, COALESCE(s.basis_cents, t.amount_cents) AS amount_cents
         FROM amortization_schedules s
         JOIN transactions t ON t.id = s.source_transaction_id
         WHERE s.status = 'Active'
@@ -184,8 +351,14 @@ def ensure_depreciation_for_period(conn: sqlite3.Connection, period_ym: str) ->
         start = parse_date_ymd(row["start_date"], "startDate")
         start_month = date(start.year, start.month, 1)
         period_index = months_between(start_month, period_start)
-        if period_index < 0 or period_index >= total_periods:
+        in_service_fraction = 1.0
+        if row["prorate_daily"] and start.day > 1:
+            days_in_month = (add_months(start_month, 1) - start_month).days
+            in_service_fraction = (days_in_month - start.day + 1) / days_in_month
+        span = total_periods if in_service_fraction == 1 else total_periods + 1
+        if period_index < 0 or period_index >= span:
             continue
+        is_last = period_index == span - 1
 
         existing = conn.execute(
             "SELECT id FROM amortization_postings WHERE schedule_id = ? AND period_ym = ?",
@@ -194,12 +367,31 @@ def ensure_depreciation_for_period(conn: sqlite3.Connection, period_ym: str) ->
         if existing is not None:
             continue
 
-        amount = _calculate_depreciation_amount(
-            strategy=strategy,
-            depreciable_cents=purchase_amount - residual_cents,
-            total_periods=total_periods,
-            period_index=period_index,
-        )
+        if strategy == AmortizationStrategy.USAGE:
+            # A month's usage is only known once the month is over.
+            if add_months(period_start, 1) > local_today(get_timezone(conn)):
+                continue
+            amount = _usage_depreciation_amount(conn, row, period_ym, is_last)
+        else:
+            depreciable_cents = purchase_amount - residual_cents
+            if row["rebased_period_ym"] is not None and period_ym >= row["rebased_period_ym"]:
+                # After an appraisal the periods left spread the appraised amount instead.
+                rebased_start, _, _ = parse_period(row["rebased_period_ym"])
+                offset = months_between(start_month, rebased_start)
+                depreciable_cents = int(row["rebased_cents"]) - residual_cents
+                total_periods = span - offset
+                period_index -= offset
+                in_service_fraction = 1.0
+
+            amount = _calculate_depreciation_amount(
+                strategy=strategy,
+                depreciable_cents=depreciable_cents,
+                total_periods=total_periods,
+                period_index=period_index,
+                residual_cents=residual_cents,
+                declining_factor_percent=row["declining_factor_percent"],
+                in_service_fraction=in_service_fraction,
+            )
         if amount <= 0:
             continue
 
@@ -221,63 +413,366 @@ def ensure_depreciation_for_period(conn: sqlite3.Connection, period_ym: str) ->
             """,
             (str(uuid.uuid4()), schedule_id, period_ym, amount, depreciation_tx_id, now),
         )
+        posted.append(depreciation_tx_id)
 
-        if period_index == total_periods - 1:
+        if is_last:
             conn.execute(
                 "UPDATE amortization_schedules SET status = 'Completed' WHERE id = ?",
                 (schedule_id,),
             )
 
+    return posted
+
+
+def depreciation_months(conn: sqlite3.Connection, period_ym: str) -> list[str]:
+    """Months from the earliest active schedule's start through period_ym."""
+    period_start, _, _ = parse_period(period_ym)
+    earliest = conn.execute(
+        "SELECT MIN(start_date) FROM amortization_schedules WHERE status = 'Active'"
+    ).fetchone()[0]
+    if earliest is None:
+        return []
+    return months_through(parse_date_ymd(earliest, "startDate"), period_start)
+
+
+def months_through(first_day: date, last_day: date) -> list[str]:
+    """Every calendar month from the one holding first_day through the one holding last_day."""
+    month = date(first_day.year, first_day.month, 1)
+    months: list[str] = []
+    while month <= last_day:
+        months.append(format_period(month))
+        month = add_months(month, 1)
+    return months
+
+
+def post_by_month(
+    conn: sqlite3.Connection, months: list[str], ensure: Callable[[sqlite3.Connection, str], list[str]]
+) -> int:
+    """Run a month's posting routine for each month, committing every month on its own.
+
+    An interrupted run keeps what it posted and the next one carries on from there.
+    Returns how many transactions were posted.
+    """
+    posted_count = 0
+    for period_ym in months:
+        with transaction(conn):
+            posted = ensure(conn, period_ym)
+        publish_transactions(conn, posted)
+        posted_count += len(posted)
+    return posted_count
+
+
+def ensure_depreciation_up_to(conn: sqlite3.Connection, period_ym: str) -> list[str]:
+    """Post every missing month of every active schedule, from its start through period_ym."""
+    posted: list[str] = []
+    for month in depreciation_months(conn, period_ym):
+        posted.extend(ensure_depreciation_for_period(conn, month))
+    return posted
+
+
+def publish_transactions(conn: sqlite3.Connection, tx_ids: list[str]) -> None:
+    """Emit transaction:created for committed rows, then account:updated once per touched account."""
+    touched: list[str] = []
+    for tx_id in tx_ids:
+        created = _load_transaction(conn, tx_id)
+        events.emit("transaction:created", created.model_dump(mode="json"))
+        for account_id, _ in _account_legs(conn, tx_id):
+            if account_id not in touched:
+                touched.append(account_id)
+
+    for account_id in touched:
+        events.emit("account:updated", _load_account(conn, account_id).model_dump(mode="json"))
+
 
 def init_state() -> dict:
-    from app.config import get_data_dir, get_db_path
+    from app import instance as instance_lock
+    from app.config import get_data_dir, get_db_path, is_read_only
+    from app.migrations import backfills_running
+    from app.services.settings import (
+        CURRENCY_SYMBOL_KEY,
+        LOCALE_KEY,
+        ONBOARDING_COMPLETED_KEY,
+        get_decimal_places,
+        get_effective_setting,
+        get_number_locale,
+        get_setting,
+    )
+
+    with get_connection() as conn:
+        onboarding_completed = get_setting(conn, ONBOARDING_COMPLETED_KEY) == "true"
+        locale = get_effective_setting(conn, LOCALE_KEY)
+        device_id = get_device_id(conn)
+        # Lets the frontend format cents the same way everywhere instead of assuming two decimals.
+        currency_symbol = get_effective_setting(conn, CURRENCY_SYMBOL_KEY)
+        decimal_places = get_decimal_places(conn)
+        number_locale = get_number_locale(conn)
 
     return {
         "dataDir": str(get_data_dir()),
         "databasePath": str(get_db_path()),
+        "onboardingCompleted": onboarding_completed,
+        "readOnly": is_read_only() or not instance_lock.is_owner() or backfills_running(),
+        "locale": locale,
+        "deviceId": device_id,
+        "currencySymbol": currency_symbol,
+        "decimalPlaces": decimal_places,
+        "numberLocale": number_locale,
     }
 
 
-def list_accounts() -> list[AccountDto]:
+def list_accounts(include_archived: bool = False) -> list[AccountDto]:
     with get_connection() as conn:
         rows = conn.execute(
-            "SELECT id, name, type, purpose, balance_cents, created_at, updated_at FROM accounts ORDER BY name ASC"
+            f"SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE (? OR is_archived = 0) ORDER BY name ASC",
+            (1 if include_archived else 0,),
         ).fetchall()
-        return [_account_from_row(row) for row in rows]
+        today = local_today(get_timezone(conn))
+        return [
+            _account_from_row(
+                row, convert_to_base(conn, [(int(row["balance_cents"]), row["currency"], today)])
+            )
+            for row in rows
+        ]
 
 
-def create_account(input_data: CreateAccountInput) -> AccountDto:
+MAX_INTEREST_RATE_PERCENT = 100.0
+
+
+def _validate_interest_rate(account_type: AccountType, rate: Optional[float]) -> None:
+    if rate is None:
+        return
+    if not 0 <= rate <= MAX_INTEREST_RATE_PERCENT:
+        raise ApiError("invalid_input", f"interestRatePercent must be between 0 and {MAX_INTEREST_RATE_PERCENT:g}")
+    if rate > 0 and account_type != AccountType.LIABILITY:
+        raise ApiError("invalid_input", "only liability accounts accrue interest")
+
+
+def _validate_account_input(input_data: CreateAccountInput) -> None:
     if not input_data.name.strip():
         raise ApiError("invalid_input", "account name cannot be empty")
+    ensure_amount_in_range(input_data.initialBalanceCents, "initialBalanceCents")
     if input_data.accountType == AccountType.LIABILITY and input_data.initialBalanceCents > 0:
         raise ApiError("invalid_input", "liability initial balance must be <= 0")
+    _validate_interest_rate(input_data.accountType, input_data.interestRatePercent)
+
 
+def insert_account(conn: sqlite3.Connection, input_data: CreateAccountInput) -> str:
+    _validate_account_input(input_data)
+    currency = resolve_account_currency(conn, input_data.currency)
+    ensure_amount_in_currency(input_data.initialBalanceCents, currency, "initialBalanceCents")
     account_id = str(uuid.uuid4())
     now = now_utc_rfc3339()
+    conn.execute(
+        """
+        INSERT INTO accounts (
+            id, name, type, purpose, balance_cents, opening_balance_cents, exclude_from_adjustment_kpi, currency,
+            liquidity, interest_rate_percent, created_at, updated_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        """,
+        (
+            account_id,
+            input_data.name.strip(),
+            input_data.accountType.value,
+            input_data.purpose.value,
+            input_data.initialBalanceCents,
+            input_data.initialBalanceCents,
+            1 if input_data.excludeFromAdjustmentKpi else 0,
+            currency,
+            (input_data.liquidity or AccountLiquidity.LIQUID).value,
+            input_data.interestRatePercent or None,
+            now,
+            now,
+        ),
+    )
+    if input_data.recordOpeningBalance and input_data.initialBalanceCents != 0:
+        _book_opening_balance(conn, account_id, input_data.initialBalanceCents, input_data.openedAt or now)
+    return account_id
+
+
+def _book_opening_balance(conn: sqlite3.Connection, account_id: str, amount_cents: int, occurred_at: str) -> str:
+    """Move an account's opening balance into the ledger as an OpeningBalance row.
+
+    The row moves the balance like any other, so the balance is taken back by the same amount
+    and the stored opening balance drops to 0: what the account holds stays the same, it is
+    just explained by its history now.
+    """
+    leg = {"toAccountId": account_id} if amount_cents > 0 else {"fromAccountId": account_id}
+    tx_id = insert_transaction(
+        conn,
+        CreateTransactionInput(
+            amountCents=abs(amount_cents),
+            accrualType=AccrualType.OPENING_BALANCE,
+            note="Opening balance",
+            occurredAt=occurred_at,
+            **leg,
+        ),
+    )
+    conn.execute(
+        "UPDATE accounts SET balance_cents = balance_cents - ?, opening_balance_cents = 0 WHERE id = ?",
+        (amount_cents, account_id),
+    )
+    return tx_id
+
+
+def create_account(input_data: CreateAccountInput) -> AccountDto:
+    _validate_account_input(input_data)
 
     with get_connection() as conn:
+        with transaction(conn):
+            account_id = insert_account(conn, input_data)
+        created = _load_account(conn, account_id)
+        events.emit("account:created", created.model_dump(mode="json"))
+        opening = conn.execute(
+            """
+            SELECT id FROM transactions
+            WHERE accrual_type = 'OpeningBalance' AND ? IN (from_account_id, to_account_id)
+            """,
+            (account_id,),
+        ).fetchall()
+        publish_transactions(conn, [row["id"] for row in opening])
+        return created
+
+
+def _opening_balance_date(conn: sqlite3.Connection, account_id: str, created_at: str) -> str:
+    # Dated no later than anything else on the account, so its history starts from it.
+    first = conn.execute(
+        """
+        SELECT MIN(occurred_at) FROM transactions
+        WHERE deleted_at IS NULL
+          AND id IN (SELECT transaction_id FROM transaction_postings WHERE account_id = ?)
+        """,
+        (account_id,),
+    ).fetchone()[0]
+    return created_at if first is None else min(first, created_at)
+
+
+def record_opening_balance(account_id: str, input_data: RecordOpeningBalanceInput) -> TransactionDto:
+    """Book an existing account's opening balance as an OpeningBalance transaction."""
+    with get_connection() as conn:
+        account = _load_account(conn, account_id)
+        opening_cents = conn.execute(
+            "SELECT opening_balance_cents FROM accounts WHERE id = ?", (account_id,)
+        ).fetchone()[0]
+        if not opening_cents:
+            raise ApiError("invalid_input", f"account has no opening balance left to record: {account_id}")
+        occurred_at = input_data.occurredAt or _opening_balance_date(conn, account_id, account.createdAt)
+
+        with transaction(conn):
+            tx_id = _book_opening_balance(conn, account_id, int(opening_cents), occurred_at)
+        publish_transactions(conn, [tx_id])
+        return _load_transaction(conn, tx_id)
+
+
+def update_account(account_id: str, input_data: UpdateAccountInput) -> AccountDto:
+    if input_data.name is not None and not input_data.name.strip():
+        raise ApiError("invalid_input", "account name cannot be empty")
+
+    with get_connection() as conn:
+        current = _load_account(conn, account_id)
+        name = current.name if input_data.name is None else input_data.name.strip()
+        purpose = current.purpose if input_data.purpose is None else input_data.purpose
+        exclude = (
+            current.excludeFromAdjustmentKpi
+            if input_data.excludeFromAdjustmentKpi is None
+            else input_data.excludeFromAdjustmentKpi
+        )
+        archived = current.isArchived if input_data.isArchived is None else input_data.isArchived
+        liquidity = current.liquidity if input_data.liquidity is None else input_data.liquidity
+        _validate_interest_rate(current.accountType, input_data.interestRatePercent)
+        rate = current.interestRatePercent if input_data.interestRatePercent is None else input_data.interestRatePercent
+
         with transaction(conn):
             conn.execute(
                 """
-                INSERT INTO accounts (id, name, type, purpose, balance_cents, created_at, updated_at)
-                VALUES (?, ?, ?, ?, ?, ?, ?)
+                UPDATE accounts
+                SET name = ?, purpose = ?, exclude_from_adjustment_kpi = ?, is_archived = ?, liquidity = ?,
+                    interest_rate_percent = ?, updated_at = ?
+                WHERE id = ?
                 """,
                 (
+                    name,
+                    purpose.value,
+                    1 if exclude else 0,
+                    1 if archived else 0,
+                    liquidity.value,
+                    rate or None,
+                    now_utc_rfc3339(),
                     account_id,
-                    input_data.name.strip(),
-                    input_data.accountType.value,
-                    input_data.purpose.value,
-                    input_data.initialBalanceCents,
-                    now,
-                    now,
                 ),
             )
-        return _load_account(conn, account_id)
+        updated = _load_account(conn, account_id)
+        events.emit("account:updated", updated.model_dump(mode="json"))
+        return updated
 
 
-def create_transaction(input_data: CreateTransactionInput) -> TransactionDto:
+MAX_BALANCE_HISTORY_POINTS = 1000
+
+
+def _balance_history_dates(start: date, end: date, granularity: BalanceGranularity) -> list[date]:
+    if granularity == BalanceGranularity.DAY:
+        return [start + timedelta(days=offset) for offset in range((end - start).days + 1)]
+
+    dates: list[date] = []
+    month = date(start.year, start.month, 1)
+    while month <= end:
+        dates.append(min(add_months(month, 1) - timedelta(days=1), end))
+        month = add_months(month, 1)
+    return dates
+
+
+def get_account_balance_history(
+    account_id: str,
+    from_date: str,
+    to_date: str,
+    granularity: BalanceGranularity = BalanceGranularity.DAY,
+) -> BalanceHistoryDto:
+    start = parse_date_ymd(from_date, "fromDate")
+    end = parse_date_ymd(to_date, "toDate")
+    if end < start:
+        raise ApiError("invalid_input", "toDate must not be before fromDate")
+    dates = _balance_history_dates(start, end, granularity)
+    if len(dates) > MAX_BALANCE_HISTORY_POINTS:
+        raise ApiError("invalid_input", f"balance history is limited to {MAX_BALANCE_HISTORY_POINTS} points")
+
+    with get_connection() as conn:
+        account = _load_account(conn, account_id)
+        tz = get_timezone(conn)
+        # Depreciation rows never moved a balance and have no postings to unwind.
+        rows = conn.execute(
+            f"""
+            SELECT {local_day_sql("t.occurred_at", tz)} AS day, SUM(p.amount_cents) AS delta
+            FROM transaction_postings p JOIN transactions t ON t.id = p.transaction_id
+            WHERE p.account_id = ?
+              AND t.deleted_at IS NULL
+              AND t.occurred_at >= ?
+            GROUP BY day
+            ORDER BY day DESC
+            """,
+            (account_id, day_start_utc(start, tz)),
+        ).fetchall()
+
+    # Walk backward from today's balance, unwinding each day's net movement until the
+    # requested point is reached.
+    balance = account.balanceCents
+    pending = [(date.fromisoformat(row["day"]), int(row["delta"])) for row in rows]
+    points: list[BalancePointDto] = []
+    for point in reversed(dates):
+        while pending and pending[0][0] > point:
+            balance -= pending.pop(0)[1]
+        points.append(BalancePointDto(date=point.isoformat(), balanceCents=balance))
+    points.reverse()
+
+    return BalanceHistoryDto(accountId=account_id, granularity=granularity, points=points)
+
+
+def insert_transaction(
+    conn: sqlite3.Connection,
+    input_data: CreateTransactionInput,
+    recurring_rule_id: Optional[str] = None,
+) -> str:
     if input_data.amountCents <= 0:
         raise ApiError("invalid_input", "amountCents must be greater than 0")
+    ensure_amount_in_range(input_data.amountCents, "amountCents")
 
     accrual_type = input_data.accrualType or AccrualType.FLOW
     if (
@@ -286,41 +781,245 @@ def create_transaction(input_data: CreateTransactionInput) -> TransactionDto:
         and input_data.toAccountId is None
     ):
         raise ApiError("invalid_input", "non-depreciation transaction needs from/to account")
+    status = input_data.status or TransactionStatus.CLEARED
+    if status == TransactionStatus.RECONCILED:
+        raise ApiError("invalid_input", "transactions only become Reconciled through a statement reconciliation")
+
+    # A payee's default category fills in for one left out.
+    category_id = input_data.categoryId
+    if category_id is None and input_data.payeeId is not None:
+        category_id = _load_payee(conn, input_data.payeeId).defaultCategoryId
 
     tx_id = str(uuid.uuid4())
     occurred_at = normalize_timestamp(input_data.occurredAt)
+    conn.execute(
+        """
+        INSERT INTO transactions (
+            id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
+            accrual_type, is_asset_purchase, note, occurred_at, created_at, recurring_rule_id, status
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        """,
+        (
+            tx_id,
+            input_data.amountCents,
+            input_data.fromAccountId,
+            input_data.toAccountId,
+            input_data.payeeId,
+            category_id,
+            accrual_type.value,
+            1 if input_data.isAssetPurchase else 0,
+            input_data.note,
+            occurred_at,
+            now_utc_rfc3339(),
+            recurring_rule_id,
+            status.value,
+        ),
+    )
+
+    # Depreciation rows never move a balance, so they have no legs.
+    if accrual_type != AccrualType.DEPRECIATION:
+        _post_legs(
+            conn, tx_id, two_leg_postings(input_data.fromAccountId, input_data.toAccountId, input_data.amountCents)
+        )
+    return tx_id
+
+
+def _emit_budget_warning(conn: sqlite3.Connection, created: TransactionDto) -> None:
+    if created.categoryId is None or created.accrualType != AccrualType.FLOW or created.isAssetPurchase:
+        return
+    start_day = get_period_start_day(conn)
+    tz = get_timezone(conn)
+    current_period = period_of(local_today(tz), start_day)
+    if period_of(local_date(created.occurredAt, tz), start_day) != current_period:
+        return
 
+    crossing = budget_crossing(conn, created.categoryId, current_period, created.amountCents)
+    if crossing is not None:
+        events.emit("budget:exceeded", {**crossing, "transactionId": created.id})
+
+
+def _account_legs(conn: sqlite3.Connection, tx_id: str) -> list[tuple[str, int]]:
+    """The legs of a transaction that land on one of the user's accounts, in leg order."""
+    rows = conn.execute(
+        """
+        SELECT account_id, amount_cents FROM transaction_postings
+        WHERE transaction_id = ? AND account_id IS NOT NULL
+        ORDER BY leg ASC
+        """,
+        (tx_id,),
+    ).fetchall()
+    return [(row["account_id"], int(row["amount_cents"])) for row in rows]
+
+
+def insert_multi_leg_transaction(conn: sqlite3.Connection, input_data: CreateMultiLegTransactionInput) -> str:
+    """Book money moving between several accounts at once as one transaction.
+
+    The accounts live in the postings only. Two legs are stored as an ordinary from/to row,
+    so they read exactly like a transfer booked through `insert_transaction`.
+    """
+    legs = [(posting.accountId, posting.amountCents) for posting in input_data.postings]
+    validate_postings(legs)
+    for _, amount in legs:
+        ensure_amount_in_range(amount, "amountCents")
+    if len({account_id for account_id, _ in legs}) != len(legs):
+        raise ApiError("invalid_input", "an account can only have one posting", details={"field": "postings"})
+    if len({_load_account(conn, account_id).currency for account_id, _ in legs}) > 1:
+        raise ApiError("invalid_input", "postings must all be in one currency", details={"field": "postings"})
+
+    if len(legs) == 2:
+        (source, _), (destination, amount) = sorted(legs, key=lambda leg: leg[1])
+        return insert_transaction(
+            conn,
+            CreateTransactionInput(
+                amountCents=amount,
+                fromAccountId=source,
+                toAccountId=destination,
+                payeeId=input_data.payeeId,
+                categoryId=input_data.categoryId,
+                note=input_data.note,
+                occurredAt=input_data.occurredAt,
+                status=input_data.status,
+            ),
+        )
+
+    status = input_data.status or TransactionStatus.CLEARED
+    if status == TransactionStatus.RECONCILED:
+        raise ApiError("invalid_input", "transactions only become Reconciled through a statement reconciliation")
+    category_id = input_data.categoryId
+    if category_id is None and input_data.payeeId is not None:
+        category_id = _load_payee(conn, input_data.payeeId).defaultCategoryId
+
+    tx_id = str(uuid.uuid4())
+    conn.execute(
+        """
+        INSERT INTO transactions (
+            id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
+            accrual_type, is_asset_purchase, note, occurred_at, created_at, status
+        ) VALUES (?, ?, NULL, NULL, ?, ?, 'Flow', 0, ?, ?, ?, ?)
+        """,
+        (
+            tx_id,
+            sum(amount for _, amount in legs if amount > 0),
+            input_data.payeeId,
+            category_id,
+            input_data.note,
+            normalize_timestamp(input_data.occurredAt),
+            now_utc_rfc3339(),
+            status.value,
+        ),
+    )
+    _post_legs(conn, tx_id, legs)
+    return tx_id
+
+
+def create_multi_leg_transaction(input_data: CreateMultiLegTransactionInput) -> TransactionDto:
     with get_connection() as conn:
         with transaction(conn):
+            tx_id = insert_multi_leg_transaction(conn, input_data)
+        publish_transactions(conn, [tx_id])
+        return _load_transaction(conn, tx_id)
+
+
+def create_transaction(input_data: CreateTransactionInput) -> TransactionDto:
+    if input_data.accrualType == AccrualType.OPENING_BALANCE:
+        raise ApiError("invalid_input", "opening balances are recorded through their account")
+    with get_connection() as conn:
+        with transaction(conn):
+            tx_id = insert_transaction(conn, input_data)
+        publish_transactions(conn, [tx_id])
+        created = _load_transaction(conn, tx_id)
+        _emit_budget_warning(conn, created)
+        return created
+
+
+MAX_BATCH_TRANSACTIONS = 5000
+
+
+def create_transactions_batch(input_data: CreateTransactionsBatchInput) -> list[TransactionDto]:
+    """Insert many transactions in one go: all of them, or none if any row is rejected.
+
+    Rows are checked in order against the balances the earlier rows leave behind. The
+    rejection lists every failing row by its index, so a script can fix them all at once.
+    """
+    if not input_data.transactions:
+        raise ApiError("invalid_input", "transactions cannot be empty")
+    if len(input_data.transactions) > MAX_BATCH_TRANSACTIONS:
+        raise ApiError("invalid_input", f"a batch holds at most {MAX_BATCH_TRANSACTIONS} transactions")
+
+    with get_connection() as conn:
+        tx_ids: list[str] = []
+        with transaction(conn):
+            errors: list[dict] = []
+            for index, item in enumerate(input_data.transactions):
+                try:
+                    if item.accrualType == AccrualType.OPENING_BALANCE:
+                        raise ApiError("invalid_input", "opening balances are recorded through their account")
+                    tx_ids.append(insert_transaction(conn, item))
+                except ApiError as error:
+                    errors.append({"index": index, "code": error.code, "message": error.message})
+            if errors:
+                raise ApiError(
+                    "invalid_input",
+                    f"{len(errors)} of {len(input_data.transactions)} transactions were rejected",
+                    details={"errors": errors},
+                )
+        publish_transactions(conn, tx_ids)
+        created = [_load_transaction(conn, tx_id) for tx_id in tx_ids]
+        for item in created:
+            _emit_budget_warning(conn, item)
+        return created
+
+
+def duplicate_transaction(tx_id: str, input_data: DuplicateTransactionInput) -> TransactionDto:
+    """Book a copy of a transaction, tags included, on a new date; it starts out Cleared."""
+    with get_connection() as conn:
+        source = _load_transaction(conn, tx_id)
+        # Depreciation, adjustments, opening balances and asset purchases each belong to
+        # the schedule, reconciliation or account that produced them.
+        if source.accrualType != AccrualType.FLOW:
+            raise ApiError("invalid_input", f"{source.accrualType.value} transactions cannot be duplicated")
+        if source.isAssetPurchase:
+            raise ApiError("invalid_input", "asset purchases cannot be duplicated")
+
+        with transaction(conn):
+            if source.fromAccountId is None and source.toAccountId is None:
+                copy_id = insert_multi_leg_transaction(
+                    conn,
+                    CreateMultiLegTransactionInput(
+                        postings=[
+                            PostingInput(accountId=account_id, amountCents=amount)
+                            for account_id, amount in _account_legs(conn, tx_id)
+                        ],
+                        payeeId=source.payeeId,
+                        categoryId=source.categoryId,
+                        note=source.note,
+                        occurredAt=input_data.occurredAt,
+                    ),
+                )
+            else:
+                copy_id = insert_transaction(
+                    conn,
+                    CreateTransactionInput(
+                        amountCents=source.amountCents,
+                        fromAccountId=source.fromAccountId,
+                        toAccountId=source.toAccountId,
+                        payeeId=source.payeeId,
+                        categoryId=source.categoryId,
+                        note=source.note,
+                        occurredAt=input_data.occurredAt,
+                    ),
+                )
             conn.execute(
                 """
-                INSERT INTO transactions (
-                    id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
-                    accrual_type, is_asset_purchase, note, occurred_at, created_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                INSERT INTO transaction_tags (transaction_id, tag_id)
+                SELECT ?, tag_id FROM transaction_tags WHERE transaction_id = ?
                 """,
-                (
-                    tx_id,
-                    input_data.amountCents,
-                    input_data.fromAccountId,
-                    input_data.toAccountId,
-                    input_data.payeeId,
-                    input_data.categoryId,
-                    accrual_type.value,
-                    1 if input_data.isAssetPurchase else 0,
-                    input_data.note,
-                    occurred_at,
-                    now_utc_rfc3339(),
-                ),
+                (copy_id, tx_id),
             )
-
-            if accrual_type != AccrualType.DEPRECIATION:
-                if input_data.fromAccountId is not None:
-                    _apply_balance_delta(conn, input_data.fromAccountId, -input_data.amountCents)
-                if input_data.toAccountId is not None:
-                    _apply_balance_delta(conn, input_data.toAccountId, input_data.amountCents)
-
-        return _load_transaction(conn, tx_id)
+        publish_transactions(conn, [copy_id])
+        created = _load_transaction(conn, copy_id)
+        _emit_budget_warning(conn, created)
+        return created
 
 
 def list_transactions(period_ym: Optional[str], accrual_type: Optional[AccrualType]) -> PagedTransactionsDto:
@@ -328,18 +1027,25 @@ def list_transactions(period_ym: Optional[str], accrual_type: Optional[AccrualTy
         parse_period(period_ym)
 
     with get_connection() as conn:
+        start_ts = end_ts = None
+        if period_ym is not None:
+            _, start_ts, end_ts = parse_period(
+                period_ym, start_day=get_period_start_day(conn), tz=get_timezone(conn)
+            )
         rows = conn.execute(
             """
             SELECT id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
-                   accrual_type, is_asset_purchase, note, occurred_at, created_at
+                   accrual_type, is_asset_purchase, note, occurred_at, created_at, status, device_id
             FROM transactions
-            WHERE (? IS NULL OR substr(occurred_at, 1, 7) = ?)
+            WHERE (? IS NULL OR (occurred_at >= ? AND occurred_at < ?))
               AND (? IS NULL OR accrual_type = ?)
+              AND deleted_at IS NULL
             ORDER BY occurred_at DESC, created_at DESC
             """,
             (
-                period_ym,
-                period_ym,
+                start_ts,
+                start_ts,
+                end_ts,
                 None if accrual_type is None else accrual_type.value,
                 None if accrual_type is None else accrual_type.value,
             ),
@@ -349,15 +1055,44 @@ def list_transactions(period_ym: Optional[str], accrual_type: Optional[AccrualTy
         return PagedTransactionsDto(items=items, total=len(items))
 
 
+def list_transaction_postings(tx_id: str) -> list[TransactionPostingDto]:
+    """The transaction's double-entry legs; their amounts sum to zero."""
+    with get_connection() as conn:
+        _load_transaction(conn, tx_id)
+        rows = conn.execute(
+            "SELECT leg, account_id, amount_cents FROM transaction_postings WHERE transaction_id = ? ORDER BY leg ASC",
+            (tx_id,),
+        ).fetchall()
+    return [
+        TransactionPostingDto(leg=row["leg"], accountId=row["account_id"], amountCents=row["amount_cents"])
+        for row in rows
+    ]
+
+
 def create_asset_purchase(input_data: CreateAssetPurchaseInput) -> AssetPurchaseResultDto:
     if input_data.amountCents <= 0:
         raise ApiError("invalid_input", "amountCents must be greater than 0")
+    ensure_amount_in_range(input_data.amountCents, "amountCents")
     if input_data.totalPeriods <= 0:
         raise ApiError("invalid_input", "totalPeriods must be greater than 0")
     if input_data.residualCents < 0 or input_data.residualCents > input_data.amountCents:
         raise ApiError("invalid_input", "residualCents must be between 0 and amountCents")
+    if input_data.strategy == AmortizationStrategy.DECLINING_BALANCE:
+        if input_data.decliningFactorPercent is None or input_data.decliningFactorPercent <= 100:
+            raise ApiError("invalid_input", "decliningFactorPercent must be above 100, e.g. 200 for double declining")
+    elif input_data.decliningFactorPercent is not None:
+        raise ApiError("invalid_input", "decliningFactorPercent only applies to the DecliningBalance strategy")
+    if input_data.strategy == AmortizationStrategy.USAGE:
+        if input_data.expectedUnits is None or input_data.expectedUnits <= 0:
+            raise ApiError("invalid_input", "expectedUnits must be greater than 0")
+    elif input_data.expectedUnits is not None:
+        raise ApiError("invalid_input", "expectedUnits only applies to the Usage strategy")
+    if input_data.prorateDaily and input_data.strategy == AmortizationStrategy.USAGE:
+        raise ApiError("invalid_input", "prorateDaily does not apply to the Usage strategy")
 
     parse_date_ymd(input_data.startDate, "startDate")
+    if input_data.warrantyExpiresOn is not None:
+        parse_date_ymd(input_data.warrantyExpiresOn, "warrantyExpiresOn")
     occurred_at = normalize_timestamp(input_data.occurredAt)
 
     tx_id = str(uuid.uuid4())
@@ -385,15 +1120,16 @@ def create_asset_purchase(input_data: CreateAssetPurchaseInput) -> AssetPurchase
                     now,
                 ),
             )
-            _apply_balance_delta(conn, input_data.fromAccountId, -input_data.amountCents)
-            _apply_balance_delta(conn, input_data.assetAccountId, input_data.amountCents)
+            legs = two_leg_postings(input_data.fromAccountId, input_data.assetAccountId, input_data.amountCents)
+            _post_legs(conn, tx_id, legs)
 
             conn.execute(
                 """
                 INSERT INTO amortization_schedules (
                     id, asset_account_id, strategy, total_periods, residual_cents, start_date,
-                    source_transaction_id, status, created_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, 'Active', ?)
+                    source_transaction_id, status, declining_factor_percent, expected_units, prorate_daily,
+                    serial_number, receipt_reference, warranty_expires_on, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, 'Active', ?, ?, ?, ?, ?, ?, ?)
                 """,
                 (
                     schedule_id,
@@ -403,51 +1139,120 @@ def create_asset_purchase(input_data: CreateAssetPurchaseInput) -> AssetPurchase
                     input_data.residualCents,
                     input_data.startDate,
                     tx_id,
+                    input_data.decliningFactorPercent,
+                    input_data.expectedUnits,
+                    int(input_data.prorateDaily),
+                    (input_data.serialNumber or "").strip() or None,
+                    (input_data.receiptReference or "").strip() or None,
+                    input_data.warrantyExpiresOn,
                     now,
                 ),
             )
 
+        publish_transactions(conn, [tx_id])
         return AssetPurchaseResultDto(
             transaction=_load_transaction(conn, tx_id),
             schedule=_load_schedule(conn, schedule_id),
         )
 
 
-def reconcile_account(input_data: ReconcileInput) -> ReconcileResultDto:
+def _balance_delta(conn: sqlite3.Connection, account_id: str, entered_cents: int, field_name: str) -> int:
+    row = conn.execute("SELECT balance_cents, currency FROM accounts WHERE id = ?", (account_id,)).fetchone()
+    if row is None:
+        raise ApiError("not_found", "account not found", status_code=404)
+    ensure_amount_in_currency(entered_cents, row["currency"], field_name)
+    return entered_cents - int(row["balance_cents"])
+
+
+def _book_adjustment(
+    conn: sqlite3.Connection,
+    account_id: str,
+    delta: int,
+    note: str,
+    occurred_at: Optional[str],
+) -> Optional[str]:
+    """Book a balance delta as an Adjustment transaction; returns None when there is nothing to book."""
+    if delta == 0:
+        return None
+    adjustment_id = str(uuid.uuid4())
+    from_account_id = account_id if delta < 0 else None
+    to_account_id = account_id if delta > 0 else None
+    conn.execute(
+        """
+        INSERT INTO transactions (
+            id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
+            accrual_type, is_asset_purchase, note, occurred_at, created_at
+        ) VALUES (?, ?, ?, ?, NULL, NULL, 'Adjustment', 0, ?, ?, ?)
+        """,
+        (
+            adjustment_id,
+            abs(delta),
+            from_account_id,
+            to_account_id,
+            note,
+            normalize_timestamp(occurred_at),
+            now_utc_rfc3339(),
+        ),
+    )
+    _post_legs(conn, adjustment_id, two_leg_postings(from_account_id, to_account_id, abs(delta)))
+    return adjustment_id
+
+
+def mark_cleared(input_data: MarkClearedInput) -> MarkClearedResultDto:
+    """Move transactions between Pending and Cleared as they show up on the bank's side."""
+    status = TransactionStatus.CLEARED if input_data.cleared else TransactionStatus.PENDING
     with get_connection() as conn:
-        row = conn.execute(
-            "SELECT balance_cents FROM accounts WHERE id = ?", (input_data.accountId,)
-        ).fetchone()
-        if row is None:
-            raise ApiError("not_found", "account not found", status_code=404)
+        with transaction(conn):
+            for tx_id in input_data.transactionIds:
+                row = conn.execute(
+                    "SELECT status FROM transactions WHERE id = ? AND deleted_at IS NULL", (tx_id,)
+                ).fetchone()
+                if row is None:
+                    raise ApiError("not_found", f"transaction not found: {tx_id}", status_code=404)
+                if row["status"] == TransactionStatus.RECONCILED.value:
+                    raise ApiError("conflict", f"transaction is already reconciled: {tx_id}", status_code=409)
+                conn.execute("UPDATE transactions SET status = ? WHERE id = ?", (status.value, tx_id))
+        updated = [_load_transaction(conn, tx_id) for tx_id in input_data.transactionIds]
+        return MarkClearedResultDto(transactions=updated)
+
+
+def _pending_net(conn: sqlite3.Connection, account_id: str) -> int:
+    """What the account's pending rows add to its balance."""
+    return conn.execute(
+        """
+        SELECT COALESCE(SUM(p.amount_cents), 0)
+        FROM transaction_postings p JOIN transactions t ON t.id = p.transaction_id
+        WHERE p.account_id = ? AND t.status = 'Pending' AND t.deleted_at IS NULL
+        """,
+        (account_id,),
+    ).fetchone()[0]
 
-        system_balance = int(row["balance_cents"])
-        delta = input_data.actualBalanceCents - system_balance
-        adjustment_id: Optional[str] = None
+
+def reconcile_account(input_data: ReconcileInput) -> ReconcileResultDto:
+    """Book the difference between the account and the balance the user counted.
+
+    A statement reconciliation (clearedOnly) holds the statement against the cleared balance
+    instead, since pending rows are not on the statement yet, and marks the cleared rows
+    Reconciled so the next statement starts from them.
+    """
+    ensure_amount_in_range(input_data.actualBalanceCents, "actualBalanceCents")
+
+    with get_connection() as conn:
+        pending = _pending_net(conn, input_data.accountId) if input_data.clearedOnly else 0
+        delta = _balance_delta(
+            conn, input_data.accountId, input_data.actualBalanceCents + pending, "actualBalanceCents"
+        )
+        system_balance = input_data.actualBalanceCents - delta
+        reconciled_count = 0
 
         with transaction(conn):
-            if delta != 0:
-                adjustment_id = str(uuid.uuid4())
-                from_account = input_data.accountId if delta < 0 else None
-                to_account = input_data.accountId if delta > 0 else None
-                conn.execute(
-                    """
-                    INSERT INTO transactions (
-                        id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
-                        accrual_type, is_asset_purchase, note, occurred_at, created_at
-                    ) VALUES (?, ?, ?, ?, NULL, NULL, 'Adjustment', 0, ?, ?, ?)
-                    """,
-                    (
-                        adjustment_id,
-                        abs(delta),
-                        from_account,
-                        to_account,
-                        input_data.note or "Auto adjustment from reconciliation",
-                        normalize_timestamp(input_data.occurredAt),
-                        now_utc_rfc3339(),
-                    ),
-                )
-                _apply_balance_delta(conn, input_data.accountId, delta)
+            adjustment_id = _book_adjustment(
+                conn,
+                input_data.accountId,
+                delta,
+                input_data.note or "Auto adjustment from reconciliation",
+                input_data.occurredAt,
+            )
 
             conn.execute(
                 """
@@ -466,84 +1271,709 @@ def reconcile_account(input_data: ReconcileInput) -> ReconcileResultDto:
                     adjustment_id,
                 ),
             )
+            if input_data.clearedOnly:
+                reconciled_count = conn.execute(
+                    """
+                    UPDATE transactions SET status = 'Reconciled'
+                    WHERE status = 'Cleared' AND accrual_type != 'Depreciation' AND deleted_at IS NULL
+                      AND id IN (SELECT transaction_id FROM transaction_postings WHERE account_id = ?)
+                    """,
+                    (input_data.accountId,),
+                ).rowcount
 
-        return ReconcileResultDto(
+        if adjustment_id is not None:
+            publish_transactions(conn, [adjustment_id])
+        result = ReconcileResultDto(
             account=_load_account(conn, input_data.accountId),
             deltaCents=delta,
             adjustmentTransaction=_load_transaction(conn, adjustment_id) if adjustment_id else None,
+            clearedBalanceCents=system_balance if input_data.clearedOnly else None,
+            reconciledCount=reconciled_count,
         )
+        events.emit("account:reconciled", result.model_dump(mode="json"))
+        return result
 
 
-def get_cash_flow_report(period_ym: str) -> ReportDto:
-    parse_period(period_ym)
+def list_balance_snapshots(account_id: str) -> list[BalanceSnapshotDto]:
+    """Past reconciliations of an account, newest first, with the adjustment each one booked."""
+    with get_connection() as conn:
+        _load_account(conn, account_id)
+        rows = conn.execute(
+            """
+            SELECT s.id AS snapshot_id, s.account_id, s.actual_balance_cents, s.system_balance_cents,
+                   s.delta_cents, s.captured_at,
+                   t.id, t.amount_cents, t.from_account_id, t.to_account_id, t.payee_id, t.category_id,
+                   t.accrual_type, t.is_asset_purchase, t.note, t.occurred_at, t.created_at, t.status, t.device_id
+            FROM balance_snapshots s
+            LEFT JOIN transactions t ON t.id = s.adjustment_tx_id
+            WHERE s.account_id = ?
+            ORDER BY s.captured_at DESC, s.rowid DESC
+            """,
+            (account_id,),
+        ).fetchall()
+    return [
+        BalanceSnapshotDto(
+            id=row["snapshot_id"],
+            accountId=row["account_id"],
+            actualBalanceCents=row["actual_balance_cents"],
+            systemBalanceCents=row["system_balance_cents"],
+            deltaCents=row["delta_cents"],
+            capturedAt=row["captured_at"],
+            adjustmentTransaction=None if row["id"] is None else _transaction_from_row(row),
+        )
+        for row in rows
+    ]
 
+
+def list_unreviewed_adjustments(account_id: Optional[str] = None) -> list[TransactionDto]:
+    """Reconciliation adjustments nobody has attributed to a category yet, newest first."""
     with get_connection() as conn:
+        if account_id is not None:
+            _load_account(conn, account_id)
         rows = conn.execute(
             """
-            SELECT COALESCE(c.name, 'Uncategorized') AS label, SUM(t.amount_cents) AS total
+            SELECT t.id, t.amount_cents, t.from_account_id, t.to_account_id, t.payee_id, t.category_id,
+                   t.accrual_type, t.is_asset_purchase, t.note, t.occurred_at, t.created_at, t.status, t.device_id
             FROM transactions t
-            LEFT JOIN categories c ON t.category_id = c.id
-            WHERE t.accrual_type != 'Depreciation'
-              AND substr(t.occurred_at, 1, 7) = ?
-            GROUP BY label
-            ORDER BY total DESC
+            JOIN balance_snapshots s ON s.adjustment_tx_id = t.id
+            WHERE t.accrual_type = 'Adjustment'
+              AND (? IS NULL OR s.account_id = ?)
+            ORDER BY t.occurred_at DESC, t.created_at DESC
             """,
-            (period_ym,),
+            (account_id, account_id),
         ).fetchall()
+    return [_transaction_from_row(row) for row in rows]
 
-        items = [ReportItemDto(label=row["label"], amountCents=int(row["total"])) for row in rows]
-        total = sum(item.amountCents for item in items)
-        return ReportDto(periodYm=period_ym, totalExpenseCents=total, items=items)
+
+def recategorize_adjustment(tx_id: str, input_data: RecategorizeAdjustmentInput) -> TransactionDto:
+    """Attribute a reconciliation delta to what it really was.
+
+    The row becomes an ordinary Flow in the chosen category, so the forgotten spending or
+    income shows up in reports and budgets and no longer counts as an adjustment.
+    """
+    with get_connection() as conn:
+        adjustment = _load_transaction(conn, tx_id)
+        linked = conn.execute("SELECT 1 FROM balance_snapshots WHERE adjustment_tx_id = ?", (tx_id,)).fetchone()
+        if adjustment.accrualType != AccrualType.ADJUSTMENT or linked is None:
+            raise ApiError("invalid_input", "only adjustments booked by a reconciliation can be recategorized")
+        _load_category(conn, input_data.categoryId)
+        if input_data.payeeId is not None:
+            _load_payee(conn, input_data.payeeId)
+
+        with transaction(conn):
+            conn.execute(
+                """
+                UPDATE transactions
+                SET accrual_type = 'Flow', category_id = ?, payee_id = ?, note = ?
+                WHERE id = ?
+                """,
+                (
+                    input_data.categoryId,
+                    input_data.payeeId,
+                    adjustment.note if input_data.note is None else input_data.note,
+                    tx_id,
+                ),
+            )
+        updated = _load_transaction(conn, tx_id)
+        events.emit("transaction:updated", updated.model_dump(mode="json"))
+        return updated
 
 
-def get_utility_report(period_ym: str) -> ReportDto:
-    parse_period(period_ym)
+def assign_payee(tx_id: str, input_data: AssignPayeeInput) -> TransactionDto:
+    """Link a transaction to its payee, e.g. one confirmed from an import's candidates."""
+    with get_connection() as conn:
+        existing = _load_transaction(conn, tx_id)
+        payee = _load_payee(conn, input_data.payeeId)
+        # As when booking, an uncategorized row takes the payee's default category.
+        category_id = existing.categoryId or payee.defaultCategoryId
+        with transaction(conn):
+            conn.execute(
+                "UPDATE transactions SET payee_id = ?, category_id = ? WHERE id = ?", (payee.id, category_id, tx_id)
+            )
+        updated = _load_transaction(conn, tx_id)
+        events.emit("transaction:updated", updated.model_dump(mode="json"))
+        return updated
 
+
+def apply_payee_default_category(payee_id: str) -> list[TransactionDto]:
+    """Give the payee's uncategorized transactions its default category, as new ones get."""
     with get_connection() as conn:
+        payee = _load_payee(conn, payee_id)
+        if payee.defaultCategoryId is None:
+            raise ApiError("invalid_input", f"payee has no default category: {payee_id}")
+
+        # Depreciation and adjustments are not spending with the payee, so they keep theirs.
+        rows = conn.execute(
+            """
+            SELECT id FROM transactions
+            WHERE payee_id = ? AND category_id IS NULL AND accrual_type = 'Flow' AND deleted_at IS NULL
+            ORDER BY occurred_at ASC, id ASC
+            """,
+            (payee_id,),
+        ).fetchall()
+        tx_ids = [row["id"] for row in rows]
         with transaction(conn):
-            ensure_depreciation_for_period(conn, period_ym)
+            for tx_id in tx_ids:
+                conn.execute("UPDATE transactions SET category_id = ? WHERE id = ?", (payee.defaultCategoryId, tx_id))
+        updated = [_load_transaction(conn, tx_id) for tx_id in tx_ids]
+        for item in updated:
+            events.emit("transaction:updated", item.model_dump(mode="json"))
+        return updated
+
 
+def get_balance_drift_report(account_id: Optional[str] = None) -> BalanceDriftReportDto:
+    """Each account's reconciliation deltas in the order they were captured, for charting drift."""
+    with get_connection() as conn:
+        if account_id is not None:
+            _load_account(conn, account_id)
+        rows = conn.execute(
+            """
+            SELECT s.account_id, a.name, s.captured_at, s.delta_cents
+            FROM balance_snapshots s
+            JOIN accounts a ON a.id = s.account_id
+            WHERE ? IS NULL OR s.account_id = ?
+            ORDER BY a.name ASC, s.account_id ASC, s.captured_at ASC, s.rowid ASC
+            """,
+            (account_id, account_id),
+        ).fetchall()
+
+    series: dict[str, BalanceDriftSeriesDto] = {}
+    for row in rows:
+        item = series.get(row["account_id"])
+        if item is None:
+            item = series[row["account_id"]] = BalanceDriftSeriesDto(
+                accountId=row["account_id"],
+                label=row["name"],
+                points=[],
+                totalAbsoluteDeltaCents=0,
+            )
+        item.points.append(BalanceDriftPointDto(capturedAt=row["captured_at"], deltaCents=row["delta_cents"]))
+        item.totalAbsoluteDeltaCents += abs(row["delta_cents"])
+    return BalanceDriftReportDto(series=list(series.values()))
+
+
+def set_account_balance(account_id: str, input_data: SetAccountBalanceInput) -> ReconcileResultDto:
+    """Quick correction: books the delta as an Adjustment without capturing a reconciliation snapshot."""
+    ensure_amount_in_range(input_data.newBalanceCents, "newBalanceCents")
+
+    with get_connection() as conn:
+        delta = _balance_delta(conn, account_id, input_data.newBalanceCents, "newBalanceCents")
+        with transaction(conn):
+            adjustment_id = _book_adjustment(
+                conn,
+                account_id,
+                delta,
+                input_data.note or "Manual balance adjustment",
+                input_data.occurredAt,
+            )
+
+        if adjustment_id is not None:
+            publish_transactions(conn, [adjustment_id])
+        return ReconcileResultDto(
+            account=_load_account(conn, account_id),
+            deltaCents=delta,
+            adjustmentTransaction=_load_transaction(conn, adjustment_id) if adjustment_id else None,
+        )
+
+
+def _report_items(rows: list[sqlite3.Row], top_transactions: int) -> list[ReportItemDto]:
+    """Group per-transaction rows by label; drill-down ids come from the very rows summed."""
+    grouped: dict[str, list[sqlite3.Row]] = {}
+    for row in rows:
+        grouped.setdefault(row["label"], []).append(row)
+
+    items: list[ReportItemDto] = []
+    for label, members in grouped.items():
+        top_ids = None
+        if top_transactions > 0:
+            ranked = sorted(members, key=lambda member: (-int(member["amount_cents"]), member["id"]))
+            top_ids = [member["id"] for member in ranked[:top_transactions]]
+        items.append(
+            ReportItemDto(
+                label=label,
+                amountCents=sum(int(member["amount_cents"]) for member in members),
+                topTransactionIds=top_ids,
+            )
+        )
+    items.sort(key=lambda item: (-item.amountCents, item.label))
+    return items
+
+
+def _roll_up_items(items: list[ReportItemDto], roots: dict[str, str]) -> list[ReportItemDto]:
+    """Nest category items under their top-level category, given each label's root label."""
+    grouped: dict[str, list[ReportItemDto]] = {}
+    for item in items:
+        grouped.setdefault(roots.get(item.label, item.label), []).append(item)
+
+    rolled: list[ReportItemDto] = []
+    for label, members in grouped.items():
+        own = next((member for member in members if member.label == label), None)
+        children = [member for member in members if member.label != label]
+        rolled.append(
+            ReportItemDto(
+                label=label,
+                amountCents=sum(member.amountCents for member in members),
+                topTransactionIds=None if own is None else own.topTransactionIds,
+                children=children or None,
+            )
+        )
+    rolled.sort(key=lambda item: (-item.amountCents, item.label))
+    return rolled
+
+
+def _validate_top_transactions(top_transactions: int) -> None:
+    if top_transactions < 0:
+        raise ApiError("invalid_input", "topTransactions must be >= 0")
+
+
+MAX_REPORT_RANGE_DAYS = 3660
+
+
+def _resolve_report_range(
+    conn: sqlite3.Connection,
+    period_ym: Optional[str],
+    range_input: Optional[ReportRangeInput],
+) -> tuple[date, date]:
+    """Returns the first and last day (both inclusive) a report covers."""
+    if (period_ym is None) == (range_input is None):
+        raise ApiError("invalid_input", "provide either periodYm or fromDate and toDate")
+    if period_ym is not None:
+        period_start, _, period_end_ts = parse_period(
+            period_ym, allow_spans=True, start_day=get_period_start_day(conn)
+        )
+        return period_start, date.fromisoformat(period_end_ts[:10]) - timedelta(days=1)
+
+    first_day = parse_date_ymd(range_input.fromDate, "fromDate")
+    last_day = parse_date_ymd(range_input.toDate, "toDate")
+    if last_day < first_day:
+        raise ApiError("invalid_input", "toDate must not be before fromDate")
+    if (last_day - first_day).days >= MAX_REPORT_RANGE_DAYS:
+        raise ApiError("invalid_input", f"report range must be shorter than {MAX_REPORT_RANGE_DAYS} days")
+    return first_day, last_day
+
+
+def _range_bounds(conn: sqlite3.Connection, first_day: date, last_day: date) -> tuple[str, str]:
+    tz = get_timezone(conn)
+    return day_start_utc(first_day, tz), day_start_utc(last_day + timedelta(days=1), tz)
+
+
+def _converted_cash_flow(
+    conn: sqlite3.Connection, start_ts: str, end_ts: str
+) -> tuple[Optional[int], Optional[int]]:
+    """Income and expense in the base currency, each day converted at that day's nearest rate.
+
+    Income is in the currency of the account it lands in, an expense in that of the
+    account it leaves.
+    """
+    rows = conn.execute(
+        f"""
+        SELECT
+          CASE WHEN t.from_account_id IS NULL THEN 'Inflow' ELSE 'Outflow' END AS direction,
+          a.currency,
+          {local_day_sql("t.occurred_at", get_timezone(conn))} AS day,
+          SUM(t.amount_cents) AS amount_cents
+        FROM transactions t
+        JOIN accounts a ON a.id = COALESCE(t.from_account_id, t.to_account_id)
+        WHERE t.accrual_type = 'Flow'
+          AND t.deleted_at IS NULL
+          AND (t.from_account_id IS NULL) != (t.to_account_id IS NULL)
+          AND t.is_asset_purchase = 0
+          AND t.occurred_at >= ? AND t.occurred_at < ?
+        GROUP BY direction, a.currency, day
+        """,
+        (start_ts, end_ts),
+    ).fetchall()
+    amounts: dict[str, list[tuple[int, Optional[str], date]]] = {"Inflow": [], "Outflow": []}
+    for row in rows:
+        amounts[row["direction"]].append((int(row["amount_cents"]), row["currency"], date.fromisoformat(row["day"])))
+    return convert_to_base(conn, amounts["Inflow"]), convert_to_base(conn, amounts["Outflow"])
+
+
+def _cash_flow_sections(
+    conn: sqlite3.Connection, start_ts: str, end_ts: str, top_transactions: int
+) -> dict[str, CashFlowSectionDto]:
+    """Classify the period's movements into operating, investing and financing flows."""
+    loan_accounts = {row[0] for row in conn.execute("SELECT liability_account_id FROM loans").fetchall()}
+    asset_accounts = {
+        row[0] for row in conn.execute("SELECT DISTINCT asset_account_id FROM amortization_schedules").fetchall()
+    }
+    rows = conn.execute(
+        """
+        SELECT t.id, t.amount_cents, t.from_account_id, t.to_account_id, t.is_asset_purchase,
+               fa.type AS from_type, ta.type AS to_type, COALESCE(c.name, 'Uncategorized') AS category
+        FROM transactions t
+        LEFT JOIN accounts fa ON fa.id = t.from_account_id
+        LEFT JOIN accounts ta ON ta.id = t.to_account_id
+        LEFT JOIN categories c ON t.category_id = c.id
+        WHERE t.accrual_type = 'Flow'
+          AND t.deleted_at IS NULL
+          AND t.occurred_at >= ? AND t.occurred_at < ?
+        """,
+        (start_ts, end_ts),
+    ).fetchall()
+
+    classified: dict[str, list[dict[str, object]]] = {"Operating": [], "Investing": [], "Financing": []}
+    for row in rows:
+        amount = int(row["amount_cents"])
+        from_id, to_id = row["from_account_id"], row["to_account_id"]
+        is_transfer = from_id is not None and to_id is not None
+        if row["is_asset_purchase"]:
+            # Write-offs and gains on disposal move book value only; no cash changes hands.
+            if not is_transfer:
+                continue
+            section = "Investing"
+            label, signed = ("Asset purchases", -amount) if to_id in asset_accounts else ("Asset sales", amount)
+        elif is_transfer and row["from_type"] != row["to_type"]:
+            # Money moving between an asset and a liability takes on or pays down debt.
+            section = "Financing"
+            is_loan = from_id in loan_accounts or to_id in loan_accounts
+            if row["from_type"] == AccountType.LIABILITY.value:
+                label, signed = ("Loan draws" if is_loan else "Borrowing"), amount
+            else:
+                label, signed = ("Loan repayments" if is_loan else "Debt repayments"), -amount
+        elif not is_transfer:
+            section, label, signed = "Operating", row["category"], amount if from_id is None else -amount
+        else:
+            continue
+        classified[section].append({"id": row["id"], "amount_cents": signed, "label": label})
+
+    sections: dict[str, CashFlowSectionDto] = {}
+    for section, members in classified.items():
+        items = _report_items(members, top_transactions)
+        sections[section] = CashFlowSectionDto(items=items, netCents=sum(item.amountCents for item in items))
+    return sections
+
+
+def _cash_flow_rows(
+    conn: sqlite3.Connection, start_ts: str, end_ts: str, between_accounts_only: bool
+) -> list[sqlite3.Row]:
+    return conn.execute(
+        f"""
+        SELECT
+          t.id,
+          t.amount_cents,
+          CASE
+            WHEN t.from_account_id IS NULL AND t.to_account_id IS NULL THEN 'Transfer'
+            WHEN t.to_account_id IS NULL THEN 'Outflow'
+            WHEN t.from_account_id IS NULL THEN 'Inflow'
+            WHEN fa.type = ta.type THEN 'Transfer'
+            WHEN ta.type = '{AccountType.LIABILITY.value}' THEN 'Repayment'
+            ELSE 'Borrowing'
+          END AS direction,
+          COALESCE(c.name, 'Uncategorized') AS label
+        FROM transactions t
+        LEFT JOIN accounts fa ON fa.id = t.from_account_id
+        LEFT JOIN accounts ta ON ta.id = t.to_account_id
+        LEFT JOIN categories c ON t.category_id = c.id
+        WHERE t.accrual_type = 'Flow'
+          AND t.deleted_at IS NULL
+          AND t.occurred_at >= ? AND t.occurred_at < ?
+          AND (? = 0 OR (t.from_account_id IS NULL) = (t.to_account_id IS NULL))
+          AND NOT (t.is_asset_purchase = 1 AND (t.from_account_id IS NULL) != (t.to_account_id IS NULL))
+        """,
+        (start_ts, end_ts, int(between_accounts_only)),
+    ).fetchall()
+
+
+def get_cash_flow_report(
+    period_ym: Optional[str],
+    top_transactions: int = 0,
+    range_input: Optional[ReportRangeInput] = None,
+    group_by_parent: bool = False,
+) -> CashFlowReportDto:
+    _validate_top_transactions(top_transactions)
+
+    with get_connection() as conn:
+        first_day, last_day = _resolve_report_range(conn, period_ym, range_input)
+        start_ts, end_ts = _range_bounds(conn, first_day, last_day)
+        # A row with only a to-account brings money in and one with only a from-account sends
+        # it out. Between two of the user's own accounts the account types decide: the same
+        # type just moves money around, into a liability pays debt down and out of one borrows.
+        # A multi-leg row has neither and only moves money between the user's own accounts.
+        # Only flows count; adjustments, depreciation and opening balances earn or cost nothing,
+        # and FX, holding and asset revaluations are booked as adjustments.
+        # Nor does the cost of an asset part written off or marked up on disposal: no cash moves.
+        if top_transactions == 0 and covers_whole_months(first_day, last_day, get_timezone(conn)):
+            rows = conn.execute(
+                """
+                SELECT NULL AS id, SUM(m.amount_cents) AS amount_cents, m.direction,
+                       COALESCE(c.name, 'Uncategorized') AS label
+                FROM monthly_category_totals m
+                LEFT JOIN categories c ON m.category_id = c.id
+                WHERE m.accrual_type = 'Flow' AND m.direction IN ('Inflow', 'Outflow')
+                  AND m.is_asset_purchase = 0
+                  AND m.period_ym >= ? AND m.period_ym <= ?
+                GROUP BY m.direction, label
+                HAVING SUM(m.amount_cents) != 0
+                """,
+                (format_period(first_day), format_period(last_day)),
+            ).fetchall()
+            rows += _cash_flow_rows(conn, start_ts, end_ts, between_accounts_only=True)
+        else:
+            rows = _cash_flow_rows(conn, start_ts, end_ts, between_accounts_only=False)
+        statement = _cash_flow_sections(conn, start_ts, end_ts, top_transactions)
+        roots: dict[str, str] = {}
+        if group_by_parent:
+            names = {row["id"]: row["name"] for row in conn.execute("SELECT id, name FROM categories").fetchall()}
+            roots = {names[category_id]: root["name"] for category_id, root in category_roots(conn).items()}
+        base_currency = get_base_currency(conn)
+        converted_income = converted_expense = None
+        if base_currency is not None:
+            converted_income, converted_expense = _converted_cash_flow(conn, start_ts, end_ts)
+
+    sections = {
+        direction: _report_items([row for row in rows if row["direction"] == direction], top_transactions)
+        for direction in ("Inflow", "Outflow", "Transfer", "Repayment", "Borrowing")
+    }
+
+    if group_by_parent:
+        sections = {direction: _roll_up_items(items, roots) for direction, items in sections.items()}
+        operating = statement["Operating"]
+        operating.items = _roll_up_items(operating.items, roots)
+
+    income = sum(item.amountCents for item in sections["Inflow"])
+    expense = sum(item.amountCents for item in sections["Outflow"])
+    return CashFlowReportDto(
+        periodYm=period_ym,
+        fromDate=first_day.isoformat(),
+        toDate=last_day.isoformat(),
+        totalExpenseCents=expense,
+        items=sections["Outflow"],
+        totalIncomeCents=income,
+        incomeItems=sections["Inflow"],
+        totalTransferCents=sum(item.amountCents for item in sections["Transfer"]),
+        totalDebtRepaymentCents=sum(item.amountCents for item in sections["Repayment"]),
+        totalBorrowingCents=sum(item.amountCents for item in sections["Borrowing"]),
+        netCents=income - expense,
+        operating=statement["Operating"],
+        investing=statement["Investing"],
+        financing=statement["Financing"],
+        baseCurrency=base_currency,
+        convertedTotalIncomeCents=converted_income,
+        convertedTotalExpenseCents=converted_expense,
+        convertedNetCents=(
+            None if converted_income is None or converted_expense is None else converted_income - converted_expense
+        ),
+    )
+
+
+def get_utility_report(
+    period_ym: Optional[str],
+    top_transactions: int = 0,
+    range_input: Optional[ReportRangeInput] = None,
+) -> ReportDto:
+    _validate_top_transactions(top_transactions)
+
+    with get_connection() as conn:
+        first_day, last_day = _resolve_report_range(conn, period_ym, range_input)
+        start_ts, end_ts = _range_bounds(conn, first_day, last_day)
+        # Depreciation, loan installments, interest and revaluations are generated in the
+        # background; depreciation is posted on the first of each month, so a custom range
+        # only includes the months whose first day it covers.
+
+        if top_transactions == 0 and covers_whole_months(first_day, last_day, get_timezone(conn)):
+            rows = conn.execute(
+                """
+                SELECT
+                  NULL AS id,
+                  SUM(m.amount_cents) AS amount_cents,
+                  CASE WHEN m.accrual_type = 'Depreciation' THEN 'Depreciation' ELSE COALESCE(c.name, 'Uncategorized') END AS label
+                FROM monthly_category_totals m
+                LEFT JOIN categories c ON m.category_id = c.id
+                WHERE m.period_ym >= ? AND m.period_ym <= ?
+                  AND ((m.accrual_type = 'Flow' AND m.is_asset_purchase = 0) OR m.accrual_type = 'Depreciation')
+                GROUP BY label
+                """,
+                (format_period(first_day), format_period(last_day)),
+            ).fetchall()
+        else:
+            rows = conn.execute(
+                """
+                SELECT
+                  t.id,
+                  t.amount_cents,
+                  CASE WHEN t.accrual_type = 'Depreciation' THEN 'Depreciation' ELSE COALESCE(c.name, 'Uncategorized') END AS label
+                FROM transactions t
+                LEFT JOIN categories c ON t.category_id = c.id
+                WHERE t.occurred_at >= ? AND t.occurred_at < ?
+                  AND t.deleted_at IS NULL
+                  AND ((t.accrual_type = 'Flow' AND t.is_asset_purchase = 0) OR t.accrual_type = 'Depreciation')
+                """,
+                (start_ts, end_ts),
+            ).fetchall()
+
+        items = _report_items(rows, top_transactions)
+        total = sum(item.amountCents for item in items)
+        return ReportDto(
+            periodYm=period_ym,
+            fromDate=first_day.isoformat(),
+            toDate=last_day.isoformat(),
+            totalExpenseCents=total,
+            items=items,
+        )
+
+
+def get_income_statement(
+    period_ym: Optional[str],
+    top_transactions: int = 0,
+    range_input: Optional[ReportRangeInput] = None,
+) -> IncomeStatementDto:
+    """Accrual-basis result: assets cost what they depreciate, not what was paid for them."""
+    _validate_top_transactions(top_transactions)
+
+    with get_connection() as conn:
+        first_day, last_day = _resolve_report_range(conn, period_ym, range_input)
+        start_ts, end_ts = _range_bounds(conn, first_day, last_day)
+        # Transfers, asset purchases, adjustments and opening balances neither earn nor cost
+        # anything; holding and asset revaluations are adjustments too.
         rows = conn.execute(
             """
             SELECT
-              CASE WHEN t.accrual_type = 'Depreciation' THEN 'Depreciation' ELSE COALESCE(c.name, 'Uncategorized') END AS label,
-              SUM(t.amount_cents) AS total
+              t.id,
+              t.amount_cents,
+              CASE
+                WHEN t.accrual_type = 'Depreciation' THEN 'Depreciation'
+                WHEN t.from_account_id IS NULL THEN 'Income'
+                ELSE 'Expense'
+              END AS section,
+              COALESCE(c.name, 'Uncategorized') AS label
             FROM transactions t
             LEFT JOIN categories c ON t.category_id = c.id
-            WHERE substr(t.occurred_at, 1, 7) = ?
-              AND ((t.accrual_type = 'Flow' AND t.is_asset_purchase = 0) OR t.accrual_type = 'Depreciation')
-            GROUP BY label
-            ORDER BY total DESC
+            WHERE t.occurred_at >= ? AND t.occurred_at < ?
+              AND t.deleted_at IS NULL
+              AND (
+                t.accrual_type = 'Depreciation'
+                OR (
+                  t.accrual_type = 'Flow' AND t.is_asset_purchase = 0
+                  AND (t.from_account_id IS NULL) != (t.to_account_id IS NULL)
+                )
+              )
             """,
-            (period_ym,),
+            (start_ts, end_ts),
         ).fetchall()
 
-        items = [ReportItemDto(label=row["label"], amountCents=int(row["total"])) for row in rows]
-        total = sum(item.amountCents for item in items)
-        return ReportDto(periodYm=period_ym, totalExpenseCents=total, items=items)
+    income_items = _report_items([row for row in rows if row["section"] == "Income"], top_transactions)
+    expense_items = _report_items([row for row in rows if row["section"] == "Expense"], top_transactions)
+    income = sum(item.amountCents for item in income_items)
+    expense = sum(item.amountCents for item in expense_items)
+    depreciation = sum(int(row["amount_cents"]) for row in rows if row["section"] == "Depreciation")
+    return IncomeStatementDto(
+        periodYm=period_ym,
+        fromDate=first_day.isoformat(),
+        toDate=last_day.isoformat(),
+        incomeItems=income_items,
+        totalIncomeCents=income,
+        expenseItems=expense_items,
+        totalExpenseCents=expense,
+        depreciationCents=depreciation,
+        netResultCents=income - expense - depreciation,
+    )
+
+
+def get_purpose_report(period_ym: str, top_transactions: int = 0) -> ReportDto:
+    _validate_top_transactions(top_transactions)
+
+    with get_connection() as conn:
+        first_day, last_day = _resolve_report_range(conn, period_ym, None)
+        start_ts, end_ts = _range_bounds(conn, first_day, last_day)
+        # Money leaving one account for an asset account is labelled by what that asset is for;
+        # adjustments and depreciation never move money, so they stay out.
+        rows = conn.execute(
+            """
+            SELECT t.id, t.amount_cents, a.purpose AS label
+            FROM transactions t
+            JOIN accounts a ON t.to_account_id = a.id
+            WHERE t.accrual_type = 'Flow'
+              AND t.deleted_at IS NULL
+              AND t.from_account_id IS NOT NULL
+              AND a.type = 'Asset'
+              AND t.occurred_at >= ? AND t.occurred_at < ?
+            """,
+            (start_ts, end_ts),
+        ).fetchall()
+
+    items = _report_items(rows, top_transactions)
+    return ReportDto(
+        periodYm=period_ym,
+        fromDate=first_day.isoformat(),
+        toDate=last_day.isoformat(),
+        totalExpenseCents=sum(item.amountCents for item in items),
+        items=items,
+    )
+
+
+def get_liquidity_report() -> LiquidityReportDto:
+    """Net worth split by how quickly each account could be turned into cash."""
+    with get_connection() as conn:
+        rows = conn.execute(
+            """
+            SELECT
+              liquidity,
+              COALESCE(SUM(CASE WHEN type = 'Asset' THEN balance_cents END), 0) AS assets,
+              COALESCE(SUM(CASE WHEN type = 'Liability' THEN balance_cents END), 0) AS liabilities
+            FROM accounts
+            GROUP BY liquidity
+            """
+        ).fetchall()
+
+    by_liquidity = {row["liquidity"]: row for row in rows}
+    items: list[LiquidityReportItemDto] = []
+    for liquidity in AccountLiquidity:
+        row = by_liquidity.get(liquidity.value)
+        assets = int(row["assets"]) if row is not None else 0
+        # Liability balances are stored as non-positive numbers; report the amount owed.
+        liabilities = -int(row["liabilities"]) if row is not None else 0
+        items.append(
+            LiquidityReportItemDto(
+                liquidity=liquidity,
+                assetsCents=assets,
+                liabilitiesCents=liabilities,
+                netCents=assets - liabilities,
+            )
+        )
+    return LiquidityReportDto(netWorthCents=sum(item.netCents for item in items), items=items)
 
 
 def list_adjustment_kpi(input_data: Optional[KpiPeriodInput]) -> AdjustmentKpiDto:
-    where_clauses = []
+    with get_connection() as conn:
+        start_day = get_period_start_day(conn)
+        tz = get_timezone(conn)
+    where_clauses = ["deleted_at IS NULL"]
     params: list[str] = []
 
+    # Bounds on occurred_at itself rather than on a derived period key keep the index usable.
     if input_data is not None:
         if input_data.fromPeriodYm is not None:
-            parse_period(input_data.fromPeriodYm)
-            where_clauses.append("substr(occurred_at, 1, 7) >= ?")
-            params.append(input_data.fromPeriodYm)
+            _, start_ts, _ = parse_period(input_data.fromPeriodYm, start_day=start_day, tz=tz)
+            where_clauses.append("occurred_at >= ?")
+            params.append(start_ts)
         if input_data.toPeriodYm is not None:
-            parse_period(input_data.toPeriodYm)
-            where_clauses.append("substr(occurred_at, 1, 7) <= ?")
-            params.append(input_data.toPeriodYm)
+            _, _, end_ts = parse_period(input_data.toPeriodYm, start_day=start_day, tz=tz)
+            where_clauses.append("occurred_at < ?")
+            params.append(end_ts)
 
-    suffix = ""
-    if where_clauses:
-        suffix = " AND " + " AND ".join(where_clauses)
+    # Transactions with a leg on an excluded account (e.g. a market-valued investment account
+    # that is reconciled monthly by design) are left out of both sides of the ratio.
+    where_clauses.append(
+        """
+        NOT EXISTS (
+            SELECT 1 FROM transaction_postings p JOIN accounts a ON a.id = p.account_id
+            WHERE p.transaction_id = transactions.id AND a.exclude_from_adjustment_kpi = 1
+        )
+        """
+    )
+    suffix = " AND " + " AND ".join(where_clauses)
 
     with get_connection() as conn:
+        # FX, holding and asset revaluations are not corrections of the books, so they do not count as drift.
         adjustment_total = conn.execute(
-            f"SELECT COALESCE(SUM(ABS(amount_cents)), 0) FROM transactions WHERE accrual_type = 'Adjustment'{suffix}",
+            f"""
+            SELECT COALESCE(SUM(ABS(amount_cents)), 0) FROM transactions
+            WHERE accrual_type = 'Adjustment'
+              AND NOT EXISTS (SELECT 1 FROM fx_revaluations r WHERE r.transaction_id = transactions.id)
+              AND NOT EXISTS (SELECT 1 FROM holding_revaluations r WHERE r.transaction_id = transactions.id)
+              AND NOT EXISTS (SELECT 1 FROM asset_revaluations r WHERE r.transaction_id = transactions.id){suffix}
+            """,
             params,
         ).fetchone()[0]
         expense_total = conn.execute(
@@ -562,3 +1992,67 @@ def list_adjustment_kpi(input_data: Optional[KpiPeriodInput]) -> AdjustmentKpiDt
         expenseTotalCents=expense_total_cents,
         ratio=ratio,
     )
+
+
+MAX_DEBT_TO_ASSET_MONTHS = 120
+
+
+def _debt_to_asset_ratio(assets: int, liabilities: int) -> Optional[float]:
+    return liabilities / assets if assets > 0 else None
+
+
+def get_debt_to_asset_kpi(months: int = 12) -> DebtToAssetKpiDto:
+    """Liabilities over assets today, plus the same ratio at each of the last `months` month ends."""
+    if months < 1 or months > MAX_DEBT_TO_ASSET_MONTHS:
+        raise ApiError("invalid_input", f"months must be between 1 and {MAX_DEBT_TO_ASSET_MONTHS}")
+    with get_connection() as conn:
+        tz = get_timezone(conn)
+        today = local_today(tz)
+        start = add_months(date(today.year, today.month, 1), 1 - months)
+        dates = _balance_history_dates(start, today, BalanceGranularity.MONTH)
+        totals = {
+            row["type"]: int(row["total"])
+            for row in conn.execute("SELECT type, SUM(balance_cents) AS total FROM accounts GROUP BY type").fetchall()
+        }
+        # Same unwinding as the balance history, summed per account type instead of per account.
+        rows = conn.execute(
+            f"""
+            SELECT {local_day_sql("t.occurred_at", tz)} AS day, a.type, SUM(p.amount_cents) AS delta
+            FROM transaction_postings p
+            JOIN transactions t ON t.id = p.transaction_id
+            JOIN accounts a ON a.id = p.account_id
+            WHERE t.deleted_at IS NULL AND t.occurred_at >= ?
+            GROUP BY day, a.type
+            ORDER BY day DESC
+            """,
+            (day_start_utc(start, tz),),
+        ).fetchall()
+
+    # Liability balances are stored as negatives; the KPI reports what is owed as a positive amount.
+    current_assets = assets = totals.get(AccountType.ASSET.value, 0)
+    current_liabilities = liabilities = -totals.get(AccountType.LIABILITY.value, 0)
+    pending = [(date.fromisoformat(row["day"]), row["type"], int(row["delta"])) for row in rows]
+    series: list[DebtToAssetPointDto] = []
+    for point in reversed(dates):
+        while pending and pending[0][0] > point:
+            _, account_type, delta = pending.pop(0)
+            if account_type == AccountType.ASSET.value:
+                assets -= delta
+            else:
+                liabilities += delta
+        series.append(
+            DebtToAssetPointDto(
+                date=point.isoformat(),
+                assetsCents=assets,
+                liabilitiesCents=liabilities,
+                ratio=_debt_to_asset_ratio(assets, liabilities),
+            )
+        )
+    series.reverse()
+
+    return DebtToAssetKpiDto(
+        assetsCents=current_assets,
+        liabilitiesCents=current_liabilities,
+        ratio=_debt_to_asset_ratio(current_assets, current_liabilities),
+        series=series,
+    )
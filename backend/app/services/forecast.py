@@ -0,0 +1,118 @@
+from __future__ import annotations
+
+from datetime import timedelta
+from typing import Optional
+
+from app.db import day_start_utc, get_connection, local_today, parse_date_ymd, parse_period, period_of
+from app.models import SpendingForecastDto, SpendingForecastItemDto, add_months
+from app.services.budgets import _carried_over
+from app.services.recurring import RULE_COLUMNS, pending_occurrences
+from app.services.settings import get_period_start_day, get_timezone
+
+
+def forecast_spending(as_of: Optional[str] = None) -> SpendingForecastDto:
+    """Project each category's end-of-period spend from the month-to-date run rate.
+
+    Transactions a recurring rule created are not part of the run rate; they are counted
+    once as they are, and the rule's remaining occurrences this period are added on top.
+    Spend follows the budget definition, so a projection can be held against the budget.
+    """
+    requested_day = parse_date_ymd(as_of, "asOf") if as_of is not None else None
+
+    with get_connection() as conn:
+        start_day = get_period_start_day(conn)
+        tz = get_timezone(conn)
+        as_of_day = requested_day or local_today(tz)
+        period_ym = period_of(as_of_day, start_day)
+        first_day, start_ts, _ = parse_period(period_ym, start_day=start_day, tz=tz)
+        last_day = add_months(first_day, 1).replace(day=start_day) - timedelta(days=1)
+        days_elapsed = (as_of_day - first_day).days + 1
+        days_in_period = (last_day - first_day).days + 1
+
+        spent_rows = conn.execute(
+            """
+            SELECT
+              t.category_id,
+              c.name,
+              SUM(CASE WHEN t.recurring_rule_id IS NULL THEN t.amount_cents ELSE 0 END) AS run_rate_cents,
+              SUM(CASE WHEN t.recurring_rule_id IS NULL THEN 0 ELSE t.amount_cents END) AS recurring_cents
+            FROM transactions t
+            LEFT JOIN categories c ON t.category_id = c.id
+            WHERE t.accrual_type = 'Flow'
+              AND t.is_asset_purchase = 0
+              AND t.deleted_at IS NULL
+              AND t.occurred_at >= ? AND t.occurred_at < ?
+            GROUP BY t.category_id
+            """,
+            (start_ts, day_start_utc(as_of_day + timedelta(days=1), tz)),
+        ).fetchall()
+        rule_rows = conn.execute(
+            f"""
+            SELECT {RULE_COLUMNS}, (SELECT name FROM categories WHERE id = category_id) AS category_name
+            FROM recurring_rules
+            WHERE is_active = 1 AND category_id IS NOT NULL
+            """
+        ).fetchall()
+        budget_rows = conn.execute(
+            """
+            SELECT b.category_id, b.amount_cents, c.name
+            FROM budgets b
+            JOIN categories c ON c.id = b.category_id
+            WHERE b.period_ym = ?
+            """,
+            (period_ym,),
+        ).fetchall()
+
+        labels: dict[Optional[str], str] = {row["category_id"]: row["name"] for row in budget_rows}
+        run_rate: dict[Optional[str], int] = {}
+        posted: dict[Optional[str], int] = {}
+        for row in spent_rows:
+            labels[row["category_id"]] = row["name"] or "Uncategorized"
+            run_rate[row["category_id"]] = int(row["run_rate_cents"])
+            posted[row["category_id"]] = int(row["recurring_cents"])
+
+        scheduled: dict[Optional[str], int] = {}
+        for row in rule_rows:
+            due = [day for day in pending_occurrences(row, last_day) if day >= first_day]
+            if due:
+                labels[row["category_id"]] = row["category_name"]
+                scheduled[row["category_id"]] = scheduled.get(row["category_id"], 0) + len(due) * row["amount_cents"]
+
+        # Budgets are keyed by the month the period starts in, like the budget report.
+        budget_month = first_day.replace(day=1)
+        budgets: dict[Optional[str], int] = {
+            row["category_id"]: int(row["amount_cents"]) + _carried_over(conn, row["category_id"], budget_month)
+            for row in budget_rows
+        }
+
+    items: list[SpendingForecastItemDto] = []
+    for category_id, label in labels.items():
+        month_to_date = run_rate.get(category_id, 0) + posted.get(category_id, 0)
+        projected = (
+            round(run_rate.get(category_id, 0) * days_in_period / days_elapsed)
+            + posted.get(category_id, 0)
+            + scheduled.get(category_id, 0)
+        )
+        budget = budgets.get(category_id)
+        items.append(
+            SpendingForecastItemDto(
+                categoryId=category_id,
+                label=label,
+                monthToDateCents=month_to_date,
+                scheduledCents=scheduled.get(category_id, 0),
+                projectedCents=projected,
+                budgetCents=budget,
+                isOverBudget=budget is not None and projected > budget,
+            )
+        )
+    items.sort(key=lambda item: (-item.projectedCents, item.label))
+
+    return SpendingForecastDto(
+        periodYm=period_ym,
+        asOfDate=as_of_day.isoformat(),
+        daysElapsed=days_elapsed,
+        daysInPeriod=days_in_period,
+        monthToDateCents=sum(item.monthToDateCents for item in items),
+        projectedCents=sum(item.projectedCents for item in items),
+        items=items,
+    )
@@ -0,0 +1,540 @@
+from __future__ import annotations
+
+import sqlite3
+import uuid
+from datetime import date, timedelta
+from typing import Optional
+
+from app.db import (
+    day_end_utc,
+    day_start_utc,
+    get_connection,
+    local_date,
+    local_day_sql,
+    local_time_sql,
+    local_today,
+    now_utc_rfc3339,
+    parse_date_ymd,
+    parse_period,
+    transaction,
+)
+from app.models import (
+    AccountType,
+    ApiError,
+    BalanceGranularity,
+    CreateTransactionInput,
+    HoldingDto,
+    HoldingRevaluationDto,
+    HoldingRevaluationResultDto,
+    LotDto,
+    PortfolioValueHistoryDto,
+    PortfolioValuePointDto,
+    RealizedGainDto,
+    RealizedGainsReportDto,
+    RevalueHoldingsInput,
+    SecurityTradeInput,
+    SecurityTradeResultDto,
+    add_months,
+    format_cents,
+)
+from app.services.crypto import format_quantity, parse_quantity
+from app.services.dimensions import ensure_category
+from app.services.finance import (
+    MAX_BALANCE_HISTORY_POINTS,
+    _balance_history_dates,
+    _book_adjustment,
+    _load_account,
+    _load_transaction,
+    insert_transaction,
+    months_through,
+    post_by_month,
+    publish_transactions,
+)
+from app.services.prices import latest_price, normalize_instrument, units_value
+from app.services.settings import get_timezone
+
+
+# Gains and losses on sales are booked under this category so reports keep them apart from income.
+REALIZED_GAINS_CATEGORY = "Realized gains"
+
+
+def _load_holding(conn: sqlite3.Connection, holding_id: str) -> HoldingDto:
+    holding = conn.execute(
+        "SELECT id, account_id, ticker FROM holdings WHERE id = ?",
+        (holding_id,),
+    ).fetchone()
+    if holding is None:
+        raise ApiError("not_found", "holding not found", status_code=404)
+    rows = conn.execute(
+        """
+        SELECT id, quantity_units, remaining_units, remaining_cost_cents, acquired_at
+        FROM lots
+        WHERE holding_id = ? AND remaining_units > 0
+        ORDER BY acquired_at ASC, created_at ASC
+        """,
+        (holding_id,),
+    ).fetchall()
+    lots = [
+        LotDto(
+            id=row["id"],
+            quantity=format_quantity(int(row["remaining_units"])),
+            quantityUnits=row["remaining_units"],
+            originalQuantityUnits=row["quantity_units"],
+            costBasisCents=row["remaining_cost_cents"],
+            acquiredAt=row["acquired_at"],
+        )
+        for row in rows
+    ]
+    units = sum(lot.quantityUnits for lot in lots)
+    price = latest_price(conn, holding["ticker"], local_today(get_timezone(conn)))
+    return HoldingDto(
+        id=holding["id"],
+        accountId=holding["account_id"],
+        ticker=holding["ticker"],
+        quantity=format_quantity(units),
+        quantityUnits=units,
+        costBasisCents=sum(lot.costBasisCents for lot in lots),
+        lots=lots,
+        priceCents=None if price is None else price["price_cents"],
+        priceDate=None if price is None else price["price_date"],
+        marketValueCents=None if price is None else units_value(units, int(price["price_cents"])),
+    )
+
+
+def list_holdings(account_id: Optional[str] = None) -> list[HoldingDto]:
+    with get_connection() as conn:
+        rows = conn.execute(
+            "SELECT id FROM holdings WHERE (? IS NULL OR account_id = ?) ORDER BY ticker ASC, account_id ASC",
+            (account_id, account_id),
+        ).fetchall()
+        return [_load_holding(conn, row["id"]) for row in rows]
+
+
+def _validate_trade(conn: sqlite3.Connection, input_data: SecurityTradeInput) -> tuple[str, int]:
+    ticker = normalize_instrument(input_data.ticker, "ticker")
+    units = parse_quantity(input_data.quantity, "quantity")
+    if units == 0:
+        raise ApiError("invalid_input", "quantity must be greater than 0")
+    if input_data.amountCents <= 0:
+        raise ApiError("invalid_input", "amountCents must be greater than 0")
+    if input_data.accountId == input_data.cashAccountId:
+        raise ApiError("invalid_input", "cashAccountId must differ from accountId")
+    account = _load_account(conn, input_data.accountId)
+    cash_account = _load_account(conn, input_data.cashAccountId)
+    if account.accountType != AccountType.ASSET:
+        raise ApiError("invalid_input", "holdings are only tracked for asset accounts")
+    # The cash leg is a single amount, so both sides have to count in the same money.
+    if account.currency != cash_account.currency:
+        raise ApiError("invalid_input", "the cash account must be in the holding account's currency")
+    return ticker, units
+
+
+def buy_security(input_data: SecurityTradeInput) -> SecurityTradeResultDto:
+    """Open a lot at the price paid and move the money out of the cash account."""
+    with get_connection() as conn:
+        ticker, units = _validate_trade(conn, input_data)
+        now = now_utc_rfc3339()
+        with transaction(conn):
+            tx_id = insert_transaction(
+                conn,
+                CreateTransactionInput(
+                    amountCents=input_data.amountCents,
+                    fromAccountId=input_data.cashAccountId,
+                    toAccountId=input_data.accountId,
+                    note=f"Buy {format_quantity(units)} {ticker}",
+                    occurredAt=input_data.occurredAt,
+                ),
+            )
+            occurred_at = conn.execute("SELECT occurred_at FROM transactions WHERE id = ?", (tx_id,)).fetchone()[0]
+            conn.execute(
+                """
+                INSERT INTO holdings (id, account_id, ticker, created_at, updated_at) VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT(account_id, ticker) DO UPDATE SET updated_at = excluded.updated_at
+                """,
+                (str(uuid.uuid4()), input_data.accountId, ticker, now, now),
+            )
+            holding_id = conn.execute(
+                "SELECT id FROM holdings WHERE account_id = ? AND ticker = ?",
+                (input_data.accountId, ticker),
+            ).fetchone()[0]
+            conn.execute(
+                """
+                INSERT INTO lots (
+                    id, holding_id, quantity_units, remaining_units, cost_cents, remaining_cost_cents,
+                    acquired_at, transaction_id, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                """,
+                (
+                    str(uuid.uuid4()),
+                    holding_id,
+                    units,
+                    units,
+                    input_data.amountCents,
+                    input_data.amountCents,
+                    occurred_at,
+                    tx_id,
+                    now,
+                ),
+            )
+        publish_transactions(conn, [tx_id])
+        return SecurityTradeResultDto(
+            holding=_load_holding(conn, holding_id),
+            transaction=_load_transaction(conn, tx_id),
+        )
+
+
+def sell_security(input_data: SecurityTradeInput) -> SecurityTradeResultDto:
+    """Close the oldest lots first and pay the proceeds into the cash account.
+
+    The holding account keeps the remaining cost basis as its balance: the difference
+    between the proceeds and the cost of the lots sold is booked as a realized gain or loss.
+    """
+    with get_connection() as conn:
+        ticker, units = _validate_trade(conn, input_data)
+        holding = conn.execute(
+            "SELECT id FROM holdings WHERE account_id = ? AND ticker = ?",
+            (input_data.accountId, ticker),
+        ).fetchone()
+        if holding is None:
+            raise ApiError("not_found", f"no {ticker} holding in this account", status_code=404)
+        lots = conn.execute(
+            """
+            SELECT id, remaining_units, remaining_cost_cents
+            FROM lots
+            WHERE holding_id = ? AND remaining_units > 0
+            ORDER BY acquired_at ASC, created_at ASC
+            """,
+            (holding["id"],),
+        ).fetchall()
+        held = sum(int(lot["remaining_units"]) for lot in lots)
+        if units > held:
+            raise ApiError(
+                "invalid_input",
+                f"cannot sell {format_quantity(units)} {ticker}; only {format_quantity(held)} held",
+            )
+
+        posted: list[str] = []
+        with transaction(conn):
+            tx_id = insert_transaction(
+                conn,
+                CreateTransactionInput(
+                    amountCents=input_data.amountCents,
+                    fromAccountId=input_data.accountId,
+                    toAccountId=input_data.cashAccountId,
+                    note=f"Sell {format_quantity(units)} {ticker}",
+                    occurredAt=input_data.occurredAt,
+                ),
+            )
+            posted.append(tx_id)
+            occurred_at = conn.execute("SELECT occurred_at FROM transactions WHERE id = ?", (tx_id,)).fetchone()[0]
+
+            cost = 0
+            remaining = units
+            for lot in lots:
+                if remaining == 0:
+                    break
+                lot_units = int(lot["remaining_units"])
+                lot_cost = int(lot["remaining_cost_cents"])
+                taken = min(remaining, lot_units)
+                # A lot sold in full gives up exactly its cost; a partial sale a pro-rata share.
+                taken_cost = lot_cost if taken == lot_units else round(lot_cost * taken / lot_units)
+                conn.execute(
+                    "UPDATE lots SET remaining_units = ?, remaining_cost_cents = ? WHERE id = ?",
+                    (lot_units - taken, lot_cost - taken_cost, lot["id"]),
+                )
+                conn.execute(
+                    """
+                    INSERT INTO lot_disposals (id, lot_id, transaction_id, quantity_units, cost_cents, disposed_at)
+                    VALUES (?, ?, ?, ?, ?, ?)
+                    """,
+                    (str(uuid.uuid4()), lot["id"], tx_id, taken, taken_cost, occurred_at),
+                )
+                cost += taken_cost
+                remaining -= taken
+
+            gain = input_data.amountCents - cost
+            if gain != 0:
+                posted.append(
+                    insert_transaction(
+                        conn,
+                        CreateTransactionInput(
+                            amountCents=abs(gain),
+                            fromAccountId=input_data.accountId if gain < 0 else None,
+                            toAccountId=input_data.accountId if gain > 0 else None,
+                            categoryId=ensure_category(conn, REALIZED_GAINS_CATEGORY),
+                            note=f"Realized {'gain' if gain > 0 else 'loss'} of {format_cents(abs(gain))} on {ticker}",
+                            occurredAt=occurred_at,
+                        ),
+                    )
+                )
+            conn.execute("UPDATE holdings SET updated_at = ? WHERE id = ?", (now_utc_rfc3339(), holding["id"]))
+        publish_transactions(conn, posted)
+        return SecurityTradeResultDto(
+            holding=_load_holding(conn, holding["id"]),
+            transaction=_load_transaction(conn, tx_id),
+            realizedGainCents=gain,
+        )
+
+
+def get_portfolio_value_history(
+    from_date: str,
+    to_date: str,
+    granularity: BalanceGranularity = BalanceGranularity.DAY,
+    account_id: Optional[str] = None,
+) -> PortfolioValueHistoryDto:
+    """Market value and cost basis of the holdings at the end of each day or month.
+
+    Quantities are replayed from the lots and their disposals; each point prices the
+    position at the last close recorded on or before that day.
+    """
+    start = parse_date_ymd(from_date, "fromDate")
+    end = parse_date_ymd(to_date, "toDate")
+    if end < start:
+        raise ApiError("invalid_input", "toDate must not be before fromDate")
+    dates = _balance_history_dates(start, end, granularity)
+    if len(dates) > MAX_BALANCE_HISTORY_POINTS:
+        raise ApiError("invalid_input", f"portfolio history is limited to {MAX_BALANCE_HISTORY_POINTS} points")
+
+    with get_connection() as conn:
+        if account_id is not None:
+            _load_account(conn, account_id)
+        tz = get_timezone(conn)
+        # Signed quantity and cost movements per ticker: buys add, disposals take away.
+        movements = conn.execute(
+            f"""
+            SELECT
+              h.ticker, {local_day_sql("l.acquired_at", tz)} AS day, l.quantity_units AS units, l.cost_cents AS cost
+            FROM lots l JOIN holdings h ON h.id = l.holding_id
+            WHERE (? IS NULL OR h.account_id = ?)
+            UNION ALL
+            SELECT h.ticker, {local_day_sql("d.disposed_at", tz)}, -d.quantity_units, -d.cost_cents
+            FROM lot_disposals d JOIN lots l ON l.id = d.lot_id JOIN holdings h ON h.id = l.holding_id
+            WHERE (? IS NULL OR h.account_id = ?)
+            """,
+            (account_id, account_id, account_id, account_id),
+        ).fetchall()
+
+        points: list[PortfolioValuePointDto] = []
+        unpriced: set[str] = set()
+        tickers = sorted({row["ticker"] for row in movements})
+        for point in dates:
+            value = 0
+            cost = 0
+            for ticker in tickers:
+                held = [row for row in movements if row["ticker"] == ticker and date.fromisoformat(row["day"]) <= point]
+                units = sum(int(row["units"]) for row in held)
+                cost += sum(int(row["cost"]) for row in held)
+                if units == 0:
+                    continue
+                price = latest_price(conn, ticker, point)
+                if price is None:
+                    unpriced.add(ticker)
+                    continue
+                value += units_value(units, int(price["price_cents"]))
+            points.append(PortfolioValuePointDto(date=point.isoformat(), valueCents=value, costBasisCents=cost))
+
+    return PortfolioValueHistoryDto(
+        accountId=account_id,
+        granularity=granularity,
+        points=points,
+        unpricedTickers=sorted(unpriced),
+    )
+
+
+def get_realized_gains_report(year: int) -> RealizedGainsReportDto:
+    """Every sale in `year` with its proceeds and the FIFO cost basis of the lots it closed."""
+    if year < 1 or year > 9999:
+        raise ApiError("invalid_input", f"invalid year: {year}")
+    with get_connection() as conn:
+        tz = get_timezone(conn)
+        rows = conn.execute(
+            f"""
+            SELECT t.id, t.occurred_at, t.amount_cents, h.account_id, h.ticker,
+                   SUM(d.quantity_units) AS units, SUM(d.cost_cents) AS cost
+            FROM lot_disposals d
+            JOIN lots l ON l.id = d.lot_id
+            JOIN holdings h ON h.id = l.holding_id
+            JOIN transactions t ON t.id = d.transaction_id
+            WHERE substr({local_time_sql("t.occurred_at", tz)}, 1, 4) = ?
+            GROUP BY t.id
+            ORDER BY t.occurred_at ASC, t.created_at ASC
+            """,
+            (f"{year:04d}",),
+        ).fetchall()
+    items = [
+        RealizedGainDto(
+            transactionId=row["id"],
+            accountId=row["account_id"],
+            ticker=row["ticker"],
+            soldAt=row["occurred_at"],
+            quantity=format_quantity(int(row["units"])),
+            quantityUnits=row["units"],
+            proceedsCents=row["amount_cents"],
+            costBasisCents=row["cost"],
+            gainCents=int(row["amount_cents"]) - int(row["cost"]),
+        )
+        for row in rows
+    ]
+    return RealizedGainsReportDto(
+        year=year,
+        items=items,
+        totalProceedsCents=sum(item.proceedsCents for item in items),
+        totalCostBasisCents=sum(item.costBasisCents for item in items),
+        totalGainCents=sum(item.gainCents for item in items),
+    )
+
+
+HOLDING_REVALUATION_COLUMNS = (
+    "id, account_id, period_ym, market_value_cents, cost_basis_cents, adjustment_cents, transaction_id"
+)
+
+
+def _holding_revaluation_from_row(row: sqlite3.Row) -> HoldingRevaluationDto:
+    return HoldingRevaluationDto(
+        id=row["id"],
+        accountId=row["account_id"],
+        periodYm=row["period_ym"],
+        marketValueCents=row["market_value_cents"],
+        costBasisCents=row["cost_basis_cents"],
+        adjustmentCents=row["adjustment_cents"],
+        transactionId=row["transaction_id"],
+    )
+
+
+def _account_holdings_value(conn: sqlite3.Connection, account_id: str, on: date) -> tuple[int, int]:
+    """(market value, cost basis) of the account's holdings at the end of `on`; unpriced ones count at cost."""
+    rows = conn.execute(
+        """
+        SELECT h.ticker, SUM(m.units) AS units, SUM(m.cost) AS cost
+        FROM holdings h
+        JOIN (
+          SELECT holding_id, quantity_units AS units, cost_cents AS cost FROM lots WHERE acquired_at < ?
+          UNION ALL
+          SELECT l.holding_id, -d.quantity_units, -d.cost_cents
+          FROM lot_disposals d JOIN lots l ON l.id = d.lot_id
+          WHERE d.disposed_at < ?
+        ) m ON m.holding_id = h.id
+        WHERE h.account_id = ?
+        GROUP BY h.id
+        """,
+        (day_start_utc(on + timedelta(days=1), get_timezone(conn)),) * 2 + (account_id,),
+    ).fetchall()
+    market = cost = 0
+    for row in rows:
+        units = int(row["units"])
+        cost += int(row["cost"])
+        price = latest_price(conn, row["ticker"], on) if units > 0 else None
+        market += int(row["cost"]) if price is None else units_value(units, int(price["price_cents"]))
+    return market, cost
+
+
+def ensure_holding_revaluations_for_period(conn: sqlite3.Connection, period_ym: str) -> list[str]:
+    """Book the month's change in unrealized gains of every account holding securities.
+
+    Runs inside the caller's transaction and only for months that have ended. An account
+    is revalued once per month, and never for a month before one it was already revalued
+    for: that later adjustment already carries the moves in between.
+    """
+    month_start, _, _ = parse_period(period_ym)
+    month_end = add_months(month_start, 1) - timedelta(days=1)
+    tz = get_timezone(conn)
+    if month_end >= local_today(tz):
+        return []
+    accounts = conn.execute(
+        """
+        SELECT DISTINCT h.account_id
+        FROM holdings h
+        WHERE NOT EXISTS (
+          SELECT 1 FROM holding_revaluations r WHERE r.account_id = h.account_id AND r.period_ym >= ?
+        )
+        ORDER BY h.account_id ASC
+        """,
+        (period_ym,),
+    ).fetchall()
+
+    posted: list[str] = []
+    for account in accounts:
+        account_id = account["account_id"]
+        market, cost = _account_holdings_value(conn, account_id, month_end)
+        booked = conn.execute(
+            "SELECT COALESCE(SUM(adjustment_cents), 0) FROM holding_revaluations WHERE account_id = ?",
+            (account_id,),
+        ).fetchone()[0]
+        adjustment = market - cost - int(booked)
+        if adjustment == 0:
+            continue
+        kind = "gain" if adjustment > 0 else "loss"
+        tx_id = _book_adjustment(
+            conn,
+            account_id,
+            adjustment,
+            f"Unrealized {kind} of {format_cents(abs(adjustment))} on holdings for {period_ym}",
+            day_end_utc(month_end, tz),
+        )
+        conn.execute(
+            """
+            INSERT INTO holding_revaluations (
+                id, account_id, period_ym, market_value_cents, cost_basis_cents, adjustment_cents,
+                transaction_id, generated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            """,
+            (str(uuid.uuid4()), account_id, period_ym, market, cost, adjustment, tx_id, now_utc_rfc3339()),
+        )
+        posted.append(tx_id)
+    return posted
+
+
+def generate_holding_revaluations() -> int:
+    """Mark every ended month to market; the scheduler runs this in the background.
+
+    Each account starts after its latest revaluation, or with the month of its first lot.
+    """
+    with get_connection() as conn:
+        tz = get_timezone(conn)
+        rows = conn.execute(
+            """
+            SELECT
+              (SELECT MAX(r.period_ym) FROM holding_revaluations r WHERE r.account_id = h.account_id)
+                AS last_period_ym,
+              MIN(l.acquired_at) AS first_at
+            FROM holdings h
+            JOIN lots l ON l.holding_id = h.id
+            GROUP BY h.account_id
+            """
+        ).fetchall()
+        starts = [
+            add_months(parse_period(row["last_period_ym"])[0], 1)
+            if row["last_period_ym"] is not None
+            else local_date(row["first_at"], tz)
+            for row in rows
+        ]
+        if not starts:
+            return 0
+        return post_by_month(
+            conn, months_through(min(starts), local_today(tz)), ensure_holding_revaluations_for_period
+        )
+
+
+def revalue_holdings(input_data: RevalueHoldingsInput) -> HoldingRevaluationResultDto:
+    """Mark every holding account to market at the end of a past month."""
+    month_start, _, _ = parse_period(input_data.periodYm)
+
+    with get_connection() as conn:
+        if add_months(month_start, 1) - timedelta(days=1) >= local_today(get_timezone(conn)):
+            raise ApiError("invalid_input", "periodYm must be a month that has already ended")
+        with transaction(conn):
+            posted = ensure_holding_revaluations_for_period(conn, input_data.periodYm)
+        publish_transactions(conn, posted)
+        placeholders = ", ".join("?" for _ in posted)
+        rows = conn.execute(
+            f"""
+            SELECT {HOLDING_REVALUATION_COLUMNS} FROM holding_revaluations
+            WHERE transaction_id IN ({placeholders})
+            ORDER BY account_id ASC
+            """,
+            posted,
+        ).fetchall()
+    return HoldingRevaluationResultDto(
+        periodYm=input_data.periodYm,
+        revaluations=[_holding_revaluation_from_row(row) for row in rows],
+    )
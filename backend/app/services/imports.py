@@ -0,0 +1,526 @@
+from __future__ import annotations
+
+import csv
+import hashlib
+import io
+import re
+import sqlite3
+import unicodedata
+import uuid
+from datetime import date, datetime
+from decimal import ROUND_HALF_UP, Decimal, InvalidOperation
+
+from app.db import day_start_utc, get_connection, now_utc_rfc3339, parse_date_ymd, transaction
+from app.models import (
+    ApiError,
+    CreateImportProfileInput,
+    CreateTransactionInput,
+    ImportedLineDto,
+    ImportPreviewDto,
+    ImportProfileDto,
+    ImportSignConvention,
+    ImportStatementInput,
+    ImportStatementResultDto,
+    PayeeCandidateDto,
+    PreviewCsvImportInput,
+    StagedLineDto,
+    StatementLineDto,
+    StatementLineInput,
+    UpdateImportProfileInput,
+)
+from app.services.finance import _load_account, _load_transaction, insert_transaction, publish_transactions
+from app.services.settings import get_timezone
+
+MAX_PAYEE_CANDIDATES = 3
+# Below this a payee is too unlike the descriptor to be worth offering.
+MIN_CANDIDATE_SCORE = 0.3
+# Score for a payee whose whole name appears in a longer descriptor, e.g. "CARD 4411 STARBUCKS SEATTLE".
+CONTAINED_NAME_SCORE = 0.9
+
+
+def normalize_descriptor(text: str) -> str:
+    """Case-, width- and punctuation-insensitive form; digits such as store numbers are dropped."""
+    folded = unicodedata.normalize("NFKC", text).casefold()
+    return " ".join(re.sub(r"[\d\W_]+", " ", folded).split())
+
+
+def _trigrams(text: str) -> set[str]:
+    padded = f"  {text} "
+    return {padded[index : index + 3] for index in range(len(padded) - 2)}
+
+
+def descriptor_similarity(descriptor: str, name: str) -> float:
+    """Dice coefficient of the two normalized strings' trigrams, raised when the name is contained."""
+    left, right = normalize_descriptor(descriptor), normalize_descriptor(name)
+    if not left or not right:
+        return 0.0
+    if left == right:
+        return 1.0
+    left_grams, right_grams = _trigrams(left), _trigrams(right)
+    score = 2 * len(left_grams & right_grams) / (len(left_grams) + len(right_grams))
+    if f" {right} " in f" {left} ":
+        score = max(score, CONTAINED_NAME_SCORE)
+    return round(score, 4)
+
+
+def match_payees(conn: sqlite3.Connection, descriptor: str) -> list[PayeeCandidateDto]:
+    """Active payees resembling the descriptor, best first."""
+    rows = conn.execute("SELECT id, name FROM payees WHERE is_archived = 0").fetchall()
+    candidates = [
+        PayeeCandidateDto(payeeId=row["id"], name=row["name"], score=descriptor_similarity(descriptor, row["name"]))
+        for row in rows
+    ]
+    candidates = [candidate for candidate in candidates if candidate.score >= MIN_CANDIDATE_SCORE]
+    candidates.sort(key=lambda candidate: (-candidate.score, candidate.name))
+    return candidates[:MAX_PAYEE_CANDIDATES]
+
+
+def import_hash(account_id: str, line: StatementLineInput, occurrence: int) -> str:
+    """Fingerprint of a statement line; `occurrence` tells identical lines of one statement apart."""
+    key = "|".join(
+        [account_id, line.date, str(line.amountCents), normalize_descriptor(line.description or ""), str(occurrence)]
+    )
+    return hashlib.sha256(key.encode("utf-8")).hexdigest()
+
+
+def _already_imported(
+    conn: sqlite3.Connection, account_id: str, external_id: str | None, line_hash: str
+) -> bool:
+    # Trashed rows count too: a line the user deleted stays deleted when the statement comes again.
+    if external_id is not None:
+        return (
+            conn.execute(
+                """
+                SELECT 1 FROM transactions
+                WHERE external_id = ? AND ? IN (from_account_id, to_account_id)
+                """,
+                (external_id, account_id),
+            ).fetchone()
+            is not None
+        )
+    return conn.execute("SELECT 1 FROM transactions WHERE import_hash = ?", (line_hash,)).fetchone() is not None
+
+
+def _line_from_row(row: sqlite3.Row) -> StatementLineDto:
+    return StatementLineDto(
+        index=row["line_index"],
+        date=row["occurred_on"],
+        amountCents=row["amount_cents"],
+        description=row["description"],
+        externalId=row["external_id"],
+    )
+
+
+def _stage_lines(conn: sqlite3.Connection, input_data: ImportStatementInput) -> str:
+    if not input_data.lines:
+        raise ApiError("invalid_input", "statement has no lines")
+    if not 0 < input_data.autoLinkThreshold <= 1:
+        raise ApiError("invalid_input", "autoLinkThreshold must be above 0 and at most 1")
+    for line in input_data.lines:
+        parse_date_ymd(line.date, "date")
+        if line.amountCents == 0:
+            raise ApiError("invalid_input", "statement line amountCents cannot be 0")
+    _load_account(conn, input_data.accountId)
+
+    batch_id = str(uuid.uuid4())
+    conn.execute(
+        "INSERT INTO import_batches (id, account_id, created_at) VALUES (?, ?, ?)",
+        (batch_id, input_data.accountId, now_utc_rfc3339()),
+    )
+    occurrences: dict[tuple[str, int, str], int] = {}
+    for index, line in enumerate(input_data.lines):
+        same = (line.date, line.amountCents, normalize_descriptor(line.description or ""))
+        occurrences[same] = occurrences.get(same, 0) + 1
+        candidates = match_payees(conn, line.description or "")
+        payee_id = None
+        if candidates and candidates[0].score >= input_data.autoLinkThreshold:
+            payee_id = candidates[0].payeeId
+        conn.execute(
+            """
+            INSERT INTO import_rows (
+                batch_id, line_index, occurred_on, amount_cents, description, external_id, import_hash, payee_id
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            """,
+            (
+                batch_id,
+                index,
+                line.date,
+                line.amountCents,
+                line.description,
+                line.externalId,
+                import_hash(input_data.accountId, line, occurrences[same]),
+                payee_id,
+            ),
+        )
+    return batch_id
+
+
+def _load_batch(conn: sqlite3.Connection, batch_id: str) -> sqlite3.Row:
+    row = conn.execute("SELECT * FROM import_batches WHERE id = ?", (batch_id,)).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"import not found: {batch_id}", status_code=404)
+    return row
+
+
+def _load_preview(conn: sqlite3.Connection, batch_id: str) -> ImportPreviewDto:
+    batch = _load_batch(conn, batch_id)
+    rows = conn.execute(
+        """
+        SELECT r.*, p.default_category_id
+        FROM import_rows r
+        LEFT JOIN payees p ON p.id = r.payee_id
+        WHERE r.batch_id = ?
+        ORDER BY r.line_index ASC
+        """,
+        (batch_id,),
+    ).fetchall()
+    # Duplicates and categories are worked out on every read, so the preview follows
+    # whatever was booked or recategorized since the statement was staged.
+    lines = [
+        StagedLineDto(
+            **_line_from_row(row).model_dump(),
+            payeeId=row["payee_id"],
+            categoryId=row["default_category_id"],
+            candidates=[] if row["payee_id"] is not None else match_payees(conn, row["description"] or ""),
+            isDuplicate=_already_imported(conn, batch["account_id"], row["external_id"], row["import_hash"]),
+        )
+        for row in rows
+    ]
+    return ImportPreviewDto(id=batch["id"], accountId=batch["account_id"], createdAt=batch["created_at"], lines=lines)
+
+
+def _book_batch(
+    conn: sqlite3.Connection, batch_id: str
+) -> tuple[list[tuple[int, str, list[PayeeCandidateDto]]], list[StatementLineDto]]:
+    account_id = _load_batch(conn, batch_id)["account_id"]
+    tz = get_timezone(conn)
+    rows = conn.execute(
+        "SELECT * FROM import_rows WHERE batch_id = ? ORDER BY line_index ASC", (batch_id,)
+    ).fetchall()
+    booked: list[tuple[int, str, list[PayeeCandidateDto]]] = []
+    skipped: list[StatementLineDto] = []
+    for row in rows:
+        if _already_imported(conn, account_id, row["external_id"], row["import_hash"]):
+            skipped.append(_line_from_row(row))
+            continue
+
+        amount = int(row["amount_cents"])
+        tx_id = insert_transaction(
+            conn,
+            CreateTransactionInput(
+                amountCents=abs(amount),
+                fromAccountId=account_id if amount < 0 else None,
+                toAccountId=account_id if amount > 0 else None,
+                payeeId=row["payee_id"],
+                note=row["description"],
+                occurredAt=day_start_utc(date.fromisoformat(row["occurred_on"]), tz),
+            ),
+        )
+        conn.execute(
+            "UPDATE transactions SET external_id = ?, import_hash = ? WHERE id = ?",
+            (row["external_id"], row["import_hash"], tx_id),
+        )
+        candidates = [] if row["payee_id"] is not None else match_payees(conn, row["description"] or "")
+        booked.append((row["line_index"], tx_id, candidates))
+    conn.execute("DELETE FROM import_batches WHERE id = ?", (batch_id,))
+    return booked, skipped
+
+
+def _import_result(
+    conn: sqlite3.Connection, booked: list[tuple[int, str, list[PayeeCandidateDto]]], skipped: list[StatementLineDto]
+) -> ImportStatementResultDto:
+    publish_transactions(conn, [tx_id for _, tx_id, _ in booked])
+    return ImportStatementResultDto(
+        lines=[
+            ImportedLineDto(index=index, transaction=_load_transaction(conn, tx_id), candidates=candidates)
+            for index, tx_id, candidates in booked
+        ],
+        skippedLines=skipped,
+    )
+
+
+def preview_import(input_data: ImportStatementInput) -> ImportPreviewDto:
+    """Stage statement lines for review; the ledger is left alone until the import is committed."""
+    with get_connection() as conn:
+        with transaction(conn):
+            batch_id = _stage_lines(conn, input_data)
+        return _load_preview(conn, batch_id)
+
+
+def list_imports() -> list[ImportPreviewDto]:
+    with get_connection() as conn:
+        rows = conn.execute("SELECT id FROM import_batches ORDER BY created_at ASC, id ASC").fetchall()
+        return [_load_preview(conn, row["id"]) for row in rows]
+
+
+def get_import(batch_id: str) -> ImportPreviewDto:
+    with get_connection() as conn:
+        return _load_preview(conn, batch_id)
+
+
+def commit_import(batch_id: str) -> ImportStatementResultDto:
+    """Book a staged import, skipping lines that reached the books in the meantime."""
+    with get_connection() as conn:
+        with transaction(conn):
+            booked, skipped = _book_batch(conn, batch_id)
+        return _import_result(conn, booked, skipped)
+
+
+def discard_import(batch_id: str) -> ImportPreviewDto:
+    with get_connection() as conn:
+        preview = _load_preview(conn, batch_id)
+        with transaction(conn):
+            conn.execute("DELETE FROM import_batches WHERE id = ?", (batch_id,))
+        return preview
+
+
+def import_statement(input_data: ImportStatementInput) -> ImportStatementResultDto:
+    """Book statement lines into the account, linking each to the payee its descriptor names.
+
+    Money in arrives in the account and money out leaves it. A confident match links the
+    payee, whose default category then applies; otherwise the line is booked without a payee
+    and the closest candidates come back for the user to pick from. Lines imported before,
+    known by the bank's id or else by their content, are skipped. This is a preview and its
+    commit in one step.
+    """
+    with get_connection() as conn:
+        with transaction(conn):
+            booked, skipped = _book_batch(conn, _stage_lines(conn, input_data))
+        return _import_result(conn, booked, skipped)
+
+
+def _profile_from_row(row: sqlite3.Row) -> ImportProfileDto:
+    return ImportProfileDto(
+        id=row["id"],
+        name=row["name"],
+        accountId=row["account_id"],
+        delimiter=row["delimiter"],
+        skipRows=row["skip_rows"],
+        dateColumn=row["date_column"],
+        dateFormat=row["date_format"],
+        amountColumn=row["amount_column"],
+        debitColumn=row["debit_column"],
+        creditColumn=row["credit_column"],
+        descriptionColumn=row["description_column"],
+        externalIdColumn=row["external_id_column"],
+        signConvention=ImportSignConvention(row["sign_convention"]),
+        decimalSeparator=row["decimal_separator"],
+        createdAt=row["created_at"],
+        updatedAt=row["updated_at"],
+    )
+
+
+def _load_profile(conn: sqlite3.Connection, profile_id: str) -> ImportProfileDto:
+    row = conn.execute("SELECT * FROM import_profiles WHERE id = ?", (profile_id,)).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"import profile not found: {profile_id}", status_code=404)
+    return _profile_from_row(row)
+
+
+def _validate_profile(conn: sqlite3.Connection, profile: CreateImportProfileInput, profile_id: str) -> None:
+    if not profile.name.strip():
+        raise ApiError("invalid_input", "import profile name cannot be empty")
+    if len(profile.delimiter) != 1:
+        raise ApiError("invalid_input", "delimiter must be a single character")
+    if profile.skipRows < 0:
+        raise ApiError("invalid_input", "skipRows cannot be negative")
+    if not profile.dateColumn.strip():
+        raise ApiError("invalid_input", "dateColumn cannot be empty")
+    if "%" not in profile.dateFormat:
+        raise ApiError("invalid_input", "dateFormat must be a strptime format such as %d.%m.%Y")
+    if profile.decimalSeparator not in (".", ","):
+        raise ApiError("invalid_input", "decimalSeparator must be '.' or ','")
+    if profile.signConvention == ImportSignConvention.DEBIT_CREDIT:
+        if not profile.debitColumn or not profile.creditColumn:
+            raise ApiError("invalid_input", "debitColumn and creditColumn are required for DebitCredit")
+    elif not profile.amountColumn:
+        raise ApiError("invalid_input", "amountColumn is required unless signConvention is DebitCredit")
+    if profile.accountId is not None:
+        _load_account(conn, profile.accountId)
+    existing = conn.execute(
+        "SELECT id FROM import_profiles WHERE name = ? AND id != ?", (profile.name.strip(), profile_id)
+    ).fetchone()
+    if existing is not None:
+        raise ApiError("conflict", f"import profile already exists: {profile.name.strip()}", status_code=409)
+
+
+def list_import_profiles() -> list[ImportProfileDto]:
+    with get_connection() as conn:
+        rows = conn.execute("SELECT * FROM import_profiles ORDER BY name ASC").fetchall()
+        return [_profile_from_row(row) for row in rows]
+
+
+def create_import_profile(input_data: CreateImportProfileInput) -> ImportProfileDto:
+    profile_id = str(uuid.uuid4())
+    now = now_utc_rfc3339()
+    with get_connection() as conn:
+        _validate_profile(conn, input_data, profile_id)
+        with transaction(conn):
+            conn.execute(
+                """
+                INSERT INTO import_profiles (
+                    id, name, account_id, delimiter, skip_rows, date_column, date_format, amount_column,
+                    debit_column, credit_column, description_column, external_id_column, sign_convention,
+                    decimal_separator, created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                """,
+                (
+                    profile_id,
+                    input_data.name.strip(),
+                    input_data.accountId,
+                    input_data.delimiter,
+                    input_data.skipRows,
+                    input_data.dateColumn.strip(),
+                    input_data.dateFormat,
+                    input_data.amountColumn,
+                    input_data.debitColumn,
+                    input_data.creditColumn,
+                    input_data.descriptionColumn,
+                    input_data.externalIdColumn,
+                    input_data.signConvention.value,
+                    input_data.decimalSeparator,
+                    now,
+                    now,
+                ),
+            )
+        return _load_profile(conn, profile_id)
+
+
+def update_import_profile(profile_id: str, input_data: UpdateImportProfileInput) -> ImportProfileDto:
+    with get_connection() as conn:
+        current = _load_profile(conn, profile_id)
+        merged = CreateImportProfileInput(
+            **{
+                **current.model_dump(include=set(CreateImportProfileInput.model_fields)),
+                **input_data.model_dump(exclude_none=True),
+            }
+        )
+        _validate_profile(conn, merged, profile_id)
+        with transaction(conn):
+            conn.execute(
+                """
+                UPDATE import_profiles
+                SET name = ?, account_id = ?, delimiter = ?, skip_rows = ?, date_column = ?, date_format = ?,
+                    amount_column = ?, debit_column = ?, credit_column = ?, description_column = ?,
+                    external_id_column = ?, sign_convention = ?, decimal_separator = ?, updated_at = ?
+                WHERE id = ?
+                """,
+                (
+                    merged.name.strip(),
+                    merged.accountId,
+                    merged.delimiter,
+                    merged.skipRows,
+                    merged.dateColumn.strip(),
+                    merged.dateFormat,
+                    merged.amountColumn,
+                    merged.debitColumn,
+                    merged.creditColumn,
+                    merged.descriptionColumn,
+                    merged.externalIdColumn,
+                    merged.signConvention.value,
+                    merged.decimalSeparator,
+                    now_utc_rfc3339(),
+                    profile_id,
+                ),
+            )
+        return _load_profile(conn, profile_id)
+
+
+def delete_import_profile(profile_id: str) -> ImportProfileDto:
+    with get_connection() as conn:
+        profile = _load_profile(conn, profile_id)
+        with transaction(conn):
+            conn.execute("DELETE FROM import_profiles WHERE id = ?", (profile_id,))
+        return profile
+
+
+def _parse_amount(text: str, decimal_separator: str, line_number: int) -> int:
+    cleaned = re.sub(r"[^\d,.()+-]", "", text)
+    if not cleaned:
+        return 0
+    # Accounting exports write money out as "(12.50)" or "12,50-".
+    negative = cleaned.startswith("(") or cleaned.endswith("-")
+    cleaned = cleaned.strip("()").rstrip("-")
+    thousands_separator = "," if decimal_separator == "." else "."
+    cleaned = cleaned.replace(thousands_separator, "").replace(decimal_separator, ".")
+    try:
+        cents = int((Decimal(cleaned) * 100).quantize(Decimal(1), rounding=ROUND_HALF_UP))
+    except InvalidOperation:
+        raise ApiError("invalid_input", f"line {line_number}: amount is not a number: {text}") from None
+    return -cents if negative else cents
+
+
+def _cell(cells: dict[str, str], column: str | None) -> str:
+    return cells.get(column, "") if column is not None else ""
+
+
+def parse_csv_lines(profile: ImportProfileDto, content: str) -> list[StatementLineInput]:
+    """Read a bank's CSV export into signed statement lines using the profile's mapping."""
+    rows = list(csv.reader(io.StringIO(content.lstrip("\ufeff")), delimiter=profile.delimiter))[profile.skipRows :]
+    if not rows:
+        raise ApiError("invalid_input", "file has no header row")
+    header = [cell.strip() for cell in rows[0]]
+    columns = [
+        profile.dateColumn,
+        profile.amountColumn,
+        profile.debitColumn,
+        profile.creditColumn,
+        profile.descriptionColumn,
+        profile.externalIdColumn,
+    ]
+    for column in columns:
+        if column is not None and column not in header:
+            raise ApiError("invalid_input", f"column not found in header: {column}")
+
+    lines: list[StatementLineInput] = []
+    # Line numbers count from the top of the file, as a spreadsheet shows them.
+    for line_number, row in enumerate(rows[1:], start=profile.skipRows + 2):
+        if not any(value.strip() for value in row):
+            continue
+        cells = {name: row[position].strip() for position, name in enumerate(header) if position < len(row)}
+        date_text = _cell(cells, profile.dateColumn)
+        try:
+            occurred_on = datetime.strptime(date_text, profile.dateFormat).date()
+        except ValueError:
+            raise ApiError(
+                "invalid_input", f"line {line_number}: date {date_text!r} does not match {profile.dateFormat}"
+            ) from None
+
+        separator = profile.decimalSeparator
+        if profile.signConvention == ImportSignConvention.DEBIT_CREDIT:
+            credit = _parse_amount(_cell(cells, profile.creditColumn), separator, line_number)
+            debit = _parse_amount(_cell(cells, profile.debitColumn), separator, line_number)
+            amount = abs(credit) - abs(debit)
+        else:
+            amount = _parse_amount(_cell(cells, profile.amountColumn), separator, line_number)
+            if profile.signConvention == ImportSignConvention.INVERTED:
+                amount = -amount
+        if amount == 0:
+            raise ApiError("invalid_input", f"line {line_number}: amount cannot be 0")
+        lines.append(
+            StatementLineInput(
+                date=occurred_on.isoformat(),
+                amountCents=amount,
+                description=_cell(cells, profile.descriptionColumn) or None,
+                externalId=_cell(cells, profile.externalIdColumn) or None,
+            )
+        )
+    return lines
+
+
+def preview_csv_import(input_data: PreviewCsvImportInput) -> ImportPreviewDto:
+    """Parse an exported file with a saved profile and stage it like any other statement."""
+    with get_connection() as conn:
+        profile = _load_profile(conn, input_data.profileId)
+        account_id = input_data.accountId or profile.accountId
+        if account_id is None:
+            raise ApiError("invalid_input", "accountId is required when the profile has no account")
+        lines = parse_csv_lines(profile, input_data.content)
+        with transaction(conn):
+            batch_id = _stage_lines(
+                conn,
+                ImportStatementInput(
+                    accountId=account_id, lines=lines, autoLinkThreshold=input_data.autoLinkThreshold
+                ),
+            )
+        return _load_preview(conn, batch_id)
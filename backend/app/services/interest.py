@@ -0,0 +1,167 @@
+from __future__ import annotations
+
+import sqlite3
+import uuid
+from datetime import timedelta
+
+from app.db import (
+    day_end_utc,
+    day_start_utc,
+    get_connection,
+    local_date,
+    local_today,
+    now_utc_rfc3339,
+    parse_period,
+    transaction,
+)
+from app.models import (
+    AccrueInterestInput,
+    ApiError,
+    CreateTransactionInput,
+    InterestAccrualDto,
+    InterestAccrualResultDto,
+    add_months,
+)
+from app.services.dimensions import ensure_category
+from app.services.finance import insert_transaction, months_through, post_by_month, publish_transactions
+from app.services.settings import get_timezone
+
+
+# Accrued interest is booked as an expense under this category.
+INTEREST_CATEGORY = "Interest"
+
+INTEREST_ACCRUAL_COLUMNS = "id, account_id, period_ym, owed_cents, rate_percent, amount_cents, transaction_id"
+
+
+def _accrual_from_row(row: sqlite3.Row) -> InterestAccrualDto:
+    return InterestAccrualDto(
+        id=row["id"],
+        accountId=row["account_id"],
+        periodYm=row["period_ym"],
+        owedCents=row["owed_cents"],
+        ratePercent=row["rate_percent"],
+        amountCents=row["amount_cents"],
+        transactionId=row["transaction_id"],
+    )
+
+
+def ensure_interest_accruals_for_period(conn: sqlite3.Connection, period_ym: str) -> list[str]:
+    """Charge the month's interest to every liability with a rate, once the month has ended.
+
+    Runs inside the caller's transaction. The interest is a twelfth of the annual rate on
+    what was owed at month end; it adds to the debt as an expense. Liabilities paid off
+    through an active loan already book their interest with each installment.
+    """
+    month_start, _, _ = parse_period(period_ym)
+    month_end = add_months(month_start, 1) - timedelta(days=1)
+    tz = get_timezone(conn)
+    if month_end >= local_today(tz):
+        return []
+    accounts = conn.execute(
+        """
+        SELECT a.id, a.balance_cents, a.interest_rate_percent
+        FROM accounts a
+        WHERE a.type = 'Liability' AND a.interest_rate_percent > 0 AND a.is_archived = 0
+          AND NOT EXISTS (SELECT 1 FROM interest_accruals r WHERE r.account_id = a.id AND r.period_ym = ?)
+          AND NOT EXISTS (SELECT 1 FROM loans l WHERE l.liability_account_id = a.id AND l.status = 'Active')
+        ORDER BY a.id ASC
+        """,
+        (period_ym,),
+    ).fetchall()
+
+    posted: list[str] = []
+    for account in accounts:
+        # Depreciation rows never moved a balance and have no postings to unwind.
+        later = conn.execute(
+            """
+            SELECT COALESCE(SUM(p.amount_cents), 0)
+            FROM transaction_postings p JOIN transactions t ON t.id = p.transaction_id
+            WHERE p.account_id = ?
+              AND t.deleted_at IS NULL
+              AND t.occurred_at >= ?
+            """,
+            (account["id"], day_start_utc(month_end + timedelta(days=1), tz)),
+        ).fetchone()[0]
+        owed = -(int(account["balance_cents"]) - int(later))
+        rate = float(account["interest_rate_percent"])
+        amount = round(owed * rate / 1200)
+        if amount <= 0:
+            continue
+        tx_id = insert_transaction(
+            conn,
+            CreateTransactionInput(
+                amountCents=amount,
+                fromAccountId=account["id"],
+                categoryId=ensure_category(conn, INTEREST_CATEGORY),
+                note=f"Interest for {period_ym}",
+                occurredAt=day_end_utc(month_end, tz),
+            ),
+        )
+        conn.execute(
+            """
+            INSERT INTO interest_accruals (
+                id, account_id, period_ym, owed_cents, rate_percent, amount_cents, transaction_id, generated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            """,
+            (str(uuid.uuid4()), account["id"], period_ym, owed, rate, amount, tx_id, now_utc_rfc3339()),
+        )
+        posted.append(tx_id)
+    return posted
+
+
+def generate_interest_accruals() -> int:
+    """Accrue every ended month not yet charged; the scheduler runs this in the background.
+
+    Each liability starts after its latest accrual, or with the month of its first posting.
+    """
+    with get_connection() as conn:
+        tz = get_timezone(conn)
+        rows = conn.execute(
+            """
+            SELECT
+              (SELECT MAX(r.period_ym) FROM interest_accruals r WHERE r.account_id = a.id) AS last_period_ym,
+              COALESCE(
+                (
+                  SELECT MIN(t.occurred_at)
+                  FROM transaction_postings p JOIN transactions t ON t.id = p.transaction_id
+                  WHERE p.account_id = a.id AND t.deleted_at IS NULL
+                ),
+                a.created_at
+              ) AS first_at
+            FROM accounts a
+            WHERE a.type = 'Liability' AND a.interest_rate_percent > 0 AND a.is_archived = 0
+            """
+        ).fetchall()
+        starts = [
+            add_months(parse_period(row["last_period_ym"])[0], 1)
+            if row["last_period_ym"] is not None
+            else local_date(row["first_at"], tz)
+            for row in rows
+        ]
+        if not starts:
+            return 0
+        return post_by_month(conn, months_through(min(starts), local_today(tz)), ensure_interest_accruals_for_period)
+
+
+def accrue_interest(input_data: AccrueInterestInput) -> InterestAccrualResultDto:
+    month_start, _, _ = parse_period(input_data.periodYm)
+
+    with get_connection() as conn:
+        if add_months(month_start, 1) - timedelta(days=1) >= local_today(get_timezone(conn)):
+            raise ApiError("invalid_input", "periodYm must be a month that has already ended")
+        with transaction(conn):
+            posted = ensure_interest_accruals_for_period(conn, input_data.periodYm)
+        publish_transactions(conn, posted)
+        placeholders = ", ".join("?" for _ in posted)
+        rows = conn.execute(
+            f"""
+            SELECT {INTEREST_ACCRUAL_COLUMNS} FROM interest_accruals
+            WHERE transaction_id IN ({placeholders})
+            ORDER BY account_id ASC
+            """,
+            posted,
+        ).fetchall()
+    return InterestAccrualResultDto(
+        periodYm=input_data.periodYm,
+        accruals=[_accrual_from_row(row) for row in rows],
+    )
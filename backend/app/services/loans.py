@@ -0,0 +1,359 @@
+from __future__ import annotations
+
+import math
+import sqlite3
+import uuid
+from typing import Optional
+
+from app.db import day_start_utc, format_period, get_connection, local_today, now_utc_rfc3339, parse_period, transaction
+from app.models import (
+    AccountType,
+    ApiError,
+    CreateLoanInput,
+    CreateTransactionInput,
+    LoanCreatedDto,
+    LoanDto,
+    LoanPaymentsResultDto,
+    LoanScheduleDto,
+    LoanScheduleEntryDto,
+    PostLoanPaymentsInput,
+    PrepaymentSimulationDto,
+    add_months,
+    ensure_amount_in_range,
+    months_between,
+)
+from app.services.dimensions import ensure_category
+from app.services.finance import (
+    _load_account,
+    _load_transaction,
+    insert_transaction,
+    months_through,
+    post_by_month,
+    publish_transactions,
+)
+from app.services.settings import get_timezone
+
+
+# Interest on loan payments is booked as an expense under this category.
+LOAN_INTEREST_CATEGORY = "Loan interest"
+MAX_LOAN_TERM_MONTHS = 600
+MAX_APR_PERCENT = 100.0
+# Payment days stop at the 28th so every month has one.
+MAX_PAYMENT_DAY = 28
+
+LOAN_COLUMNS = (
+    "id, liability_account_id, payment_account_id, principal_cents, apr_percent, term_months, "
+    "payment_day, start_period_ym, status"
+)
+
+# (payment, principal part, interest part, principal left afterwards) per month.
+ScheduleRow = tuple[int, int, int, int]
+
+
+def _level_payment(principal_cents: int, apr_percent: float, term_months: int) -> int:
+    rate = apr_percent / 1200
+    if rate == 0:
+        return math.ceil(principal_cents / term_months)
+    return round(principal_cents * rate / (1 - (1 + rate) ** -term_months))
+
+
+def _amortize(principal_cents: int, apr_percent: float, term_months: int) -> list[ScheduleRow]:
+    """Split each level installment into the month's interest on what is still owed and principal."""
+    rate = apr_percent / 1200
+    payment = _level_payment(principal_cents, apr_percent, term_months)
+    remaining = principal_cents
+    rows: list[ScheduleRow] = []
+    for index in range(term_months):
+        interest = round(remaining * rate)
+        principal = remaining if index == term_months - 1 else min(remaining, max(payment - interest, 0))
+        remaining -= principal
+        rows.append((principal + interest, principal, interest, remaining))
+    return rows
+
+
+def _loan_from_row(row: sqlite3.Row) -> LoanDto:
+    return LoanDto(
+        id=row["id"],
+        liabilityAccountId=row["liability_account_id"],
+        paymentAccountId=row["payment_account_id"],
+        principalCents=row["principal_cents"],
+        aprPercent=row["apr_percent"],
+        termMonths=row["term_months"],
+        paymentDay=row["payment_day"],
+        startPeriodYm=row["start_period_ym"],
+        paymentCents=_level_payment(int(row["principal_cents"]), float(row["apr_percent"]), int(row["term_months"])),
+        status=row["status"],
+    )
+
+
+def _load_loan_row(conn: sqlite3.Connection, loan_id: str) -> sqlite3.Row:
+    row = conn.execute(f"SELECT {LOAN_COLUMNS} FROM loans WHERE id = ?", (loan_id,)).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"loan not found: {loan_id}", status_code=404)
+    return row
+
+
+def list_loans() -> list[LoanDto]:
+    with get_connection() as conn:
+        rows = conn.execute(f"SELECT {LOAN_COLUMNS} FROM loans ORDER BY created_at ASC").fetchall()
+        return [_loan_from_row(row) for row in rows]
+
+
+def create_loan(input_data: CreateLoanInput) -> LoanCreatedDto:
+    if input_data.principalCents <= 0:
+        raise ApiError("invalid_input", "principalCents must be greater than 0")
+    ensure_amount_in_range(input_data.principalCents, "principalCents")
+    if not 0 <= input_data.aprPercent <= MAX_APR_PERCENT:
+        raise ApiError("invalid_input", f"aprPercent must be between 0 and {MAX_APR_PERCENT:g}")
+    if not 0 < input_data.termMonths <= MAX_LOAN_TERM_MONTHS:
+        raise ApiError("invalid_input", f"termMonths must be between 1 and {MAX_LOAN_TERM_MONTHS}")
+    if not 1 <= input_data.paymentDay <= MAX_PAYMENT_DAY:
+        raise ApiError("invalid_input", f"paymentDay must be between 1 and {MAX_PAYMENT_DAY}")
+    parse_period(input_data.startPeriodYm)
+    if input_data.liabilityAccountId == input_data.paymentAccountId:
+        raise ApiError("invalid_input", "paymentAccountId must differ from liabilityAccountId")
+
+    loan_id = str(uuid.uuid4())
+    with get_connection() as conn:
+        if _load_account(conn, input_data.liabilityAccountId).accountType != AccountType.LIABILITY:
+            raise ApiError("invalid_input", "liabilityAccountId must be a liability account")
+        _load_account(conn, input_data.paymentAccountId)
+        disbursement_id: Optional[str] = None
+        with transaction(conn):
+            conn.execute(
+                """
+                INSERT INTO loans (
+                    id, liability_account_id, payment_account_id, principal_cents, apr_percent,
+                    term_months, payment_day, start_period_ym, status, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'Active', ?)
+                """,
+                (
+                    loan_id,
+                    input_data.liabilityAccountId,
+                    input_data.paymentAccountId,
+                    input_data.principalCents,
+                    input_data.aprPercent,
+                    input_data.termMonths,
+                    input_data.paymentDay,
+                    input_data.startPeriodYm,
+                    now_utc_rfc3339(),
+                ),
+            )
+            if input_data.disbursedToAccountId is not None:
+                disbursement_id = insert_transaction(
+                    conn,
+                    CreateTransactionInput(
+                        amountCents=input_data.principalCents,
+                        fromAccountId=input_data.liabilityAccountId,
+                        toAccountId=input_data.disbursedToAccountId,
+                        note="Loan disbursement",
+                        occurredAt=input_data.occurredAt,
+                    ),
+                )
+        if disbursement_id is not None:
+            publish_transactions(conn, [disbursement_id])
+        return LoanCreatedDto(
+            loan=_loan_from_row(_load_loan_row(conn, loan_id)),
+            disbursement=None if disbursement_id is None else _load_transaction(conn, disbursement_id),
+        )
+
+
+def get_loan_schedule(loan_id: str) -> LoanScheduleDto:
+    with get_connection() as conn:
+        row = _load_loan_row(conn, loan_id)
+        posted = {
+            posting["period_ym"]
+            for posting in conn.execute("SELECT period_ym FROM loan_payments WHERE loan_id = ?", (loan_id,))
+        }
+    start_month, _, _ = parse_period(row["start_period_ym"])
+    entries: list[LoanScheduleEntryDto] = []
+    for index, (payment, principal, interest, remaining) in enumerate(
+        _amortize(int(row["principal_cents"]), float(row["apr_percent"]), int(row["term_months"]))
+    ):
+        month = add_months(start_month, index)
+        entries.append(
+            LoanScheduleEntryDto(
+                periodYm=format_period(month),
+                paymentDate=month.replace(day=int(row["payment_day"])).isoformat(),
+                paymentCents=payment,
+                principalCents=principal,
+                interestCents=interest,
+                remainingPrincipalCents=remaining,
+                posted=format_period(month) in posted,
+            )
+        )
+    return LoanScheduleDto(loan=_loan_from_row(row), entries=entries)
+
+
+def ensure_loan_payments_for_period(conn: sqlite3.Connection, period_ym: str) -> list[str]:
+    """Post the month's installment of every active loan whose payment day has come.
+
+    Runs inside the caller's transaction. The principal moves from the payment account into
+    the liability; the interest leaves the payment account as an expense.
+    """
+    period_start, _, _ = parse_period(period_ym)
+    tz = get_timezone(conn)
+    today = local_today(tz)
+    posted: list[str] = []
+    loans = conn.execute(f"SELECT {LOAN_COLUMNS} FROM loans WHERE status = 'Active'").fetchall()
+    for loan in loans:
+        start_month, _, _ = parse_period(loan["start_period_ym"])
+        index = months_between(start_month, period_start)
+        term_months = int(loan["term_months"])
+        if index < 0 or index >= term_months:
+            continue
+        payment_date = period_start.replace(day=int(loan["payment_day"]))
+        if payment_date > today:
+            continue
+        existing = conn.execute(
+            "SELECT id FROM loan_payments WHERE loan_id = ? AND period_ym = ?",
+            (loan["id"], period_ym),
+        ).fetchone()
+        if existing is not None:
+            continue
+
+        schedule = _amortize(int(loan["principal_cents"]), float(loan["apr_percent"]), term_months)
+        _, principal, interest, _ = schedule[index]
+        owed = -int(_load_account(conn, loan["liability_account_id"]).balanceCents)
+        # A loan already paid down by hand is not paid past zero.
+        principal = min(principal, max(owed, 0))
+        occurred_at = day_start_utc(payment_date, tz)
+        principal_tx_id = interest_tx_id = None
+        if principal > 0:
+            principal_tx_id = insert_transaction(
+                conn,
+                CreateTransactionInput(
+                    amountCents=principal,
+                    fromAccountId=loan["payment_account_id"],
+                    toAccountId=loan["liability_account_id"],
+                    note=f"Loan principal for {period_ym}",
+                    occurredAt=occurred_at,
+                ),
+            )
+            posted.append(principal_tx_id)
+        if interest > 0:
+            interest_tx_id = insert_transaction(
+                conn,
+                CreateTransactionInput(
+                    amountCents=interest,
+                    fromAccountId=loan["payment_account_id"],
+                    categoryId=ensure_category(conn, LOAN_INTEREST_CATEGORY),
+                    note=f"Loan interest for {period_ym}",
+                    occurredAt=occurred_at,
+                ),
+            )
+            posted.append(interest_tx_id)
+        conn.execute(
+            """
+            INSERT INTO loan_payments (
+                id, loan_id, period_ym, principal_cents, interest_cents,
+                principal_transaction_id, interest_transaction_id, generated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            """,
+            (
+                str(uuid.uuid4()),
+                loan["id"],
+                period_ym,
+                principal,
+                interest,
+                principal_tx_id,
+                interest_tx_id,
+                now_utc_rfc3339(),
+            ),
+        )
+        if index == term_months - 1:
+            conn.execute("UPDATE loans SET status = 'Completed' WHERE id = ?", (loan["id"],))
+    return posted
+
+
+def generate_loan_payments() -> int:
+    """Post every installment that has fallen due; the scheduler runs this in the background."""
+    with get_connection() as conn:
+        earliest = conn.execute("SELECT MIN(start_period_ym) FROM loans WHERE status = 'Active'").fetchone()[0]
+        if earliest is None:
+            return 0
+        first_month, _, _ = parse_period(earliest)
+        months = months_through(first_month, local_today(get_timezone(conn)))
+        return post_by_month(conn, months, ensure_loan_payments_for_period)
+
+
+def post_loan_payments(input_data: PostLoanPaymentsInput) -> LoanPaymentsResultDto:
+    with get_connection() as conn:
+        with transaction(conn):
+            posted = ensure_loan_payments_for_period(conn, input_data.periodYm)
+        publish_transactions(conn, posted)
+        return LoanPaymentsResultDto(
+            periodYm=input_data.periodYm,
+            transactions=[_load_transaction(conn, tx_id) for tx_id in posted],
+        )
+
+
+def simulate_prepayment(
+    loan_id: str,
+    lump_sum_cents: int = 0,
+    lump_sum_period_ym: Optional[str] = None,
+    monthly_extra_cents: int = 0,
+) -> PrepaymentSimulationDto:
+    """Replay the unposted part of a schedule with extra principal paid in; nothing is stored.
+
+    The installment stays the same, so paying extra shortens the term rather than the payment.
+    """
+    if lump_sum_cents < 0 or monthly_extra_cents < 0:
+        raise ApiError("invalid_input", "prepayment amounts must be >= 0")
+    ensure_amount_in_range(lump_sum_cents, "lumpSumCents")
+    ensure_amount_in_range(monthly_extra_cents, "monthlyExtraCents")
+
+    with get_connection() as conn:
+        row = _load_loan_row(conn, loan_id)
+        posted = {
+            posting["period_ym"]
+            for posting in conn.execute("SELECT period_ym FROM loan_payments WHERE loan_id = ?", (loan_id,))
+        }
+    principal_cents = int(row["principal_cents"])
+    apr_percent = float(row["apr_percent"])
+    term_months = int(row["term_months"])
+    start_month, _, _ = parse_period(row["start_period_ym"])
+    schedule = _amortize(principal_cents, apr_percent, term_months)
+    first = next(
+        (index for index in range(term_months) if format_period(add_months(start_month, index)) not in posted),
+        None,
+    )
+    if first is None:
+        raise ApiError("invalid_input", "every installment of this loan has been posted")
+    remaining = principal_cents if first == 0 else schedule[first - 1][3]
+    from_month = add_months(start_month, first)
+    lump_period = format_period(from_month) if lump_sum_period_ym is None else lump_sum_period_ym
+    lump_offset = months_between(from_month, parse_period(lump_period)[0])
+    if not 0 <= lump_offset < term_months - first:
+        raise ApiError("invalid_input", "lumpSumPeriodYm must fall within the unposted part of the schedule")
+
+    rate = apr_percent / 1200
+    payment = _level_payment(principal_cents, apr_percent, term_months)
+    balance = remaining
+    months = interest_total = 0
+    while balance > 0 and months < term_months - first:
+        interest = round(balance * rate)
+        extra = monthly_extra_cents + (lump_sum_cents if months == lump_offset else 0)
+        last = months == term_months - first - 1
+        balance -= balance if last else min(balance, max(payment - interest, 0) + extra)
+        interest_total += interest
+        months += 1
+
+    baseline = schedule[first:]
+    baseline_interest = sum(interest for _, _, interest, _ in baseline)
+    return PrepaymentSimulationDto(
+        loanId=loan_id,
+        fromPeriodYm=format_period(from_month),
+        remainingPrincipalCents=remaining,
+        lumpSumCents=lump_sum_cents,
+        lumpSumPeriodYm=lump_period,
+        monthlyExtraCents=monthly_extra_cents,
+        baselineMonths=len(baseline),
+        baselineInterestCents=baseline_interest,
+        baselinePayoffPeriodYm=format_period(add_months(start_month, term_months - 1)),
+        simulatedMonths=months,
+        simulatedInterestCents=interest_total,
+        simulatedPayoffPeriodYm=format_period(add_months(from_month, max(months, 1) - 1)),
+        monthsSaved=len(baseline) - months,
+        interestSavedCents=baseline_interest - interest_total,
+    )
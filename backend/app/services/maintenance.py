@@ -0,0 +1,275 @@
+from __future__ import annotations
+
+import sqlite3
+from datetime import date
+
+from app import events
+from app.db import day_start_utc, get_connection, local_today, now_utc_rfc3339, transaction
+from app.models import (
+    AccountType,
+    ApiError,
+    ArchiveInput,
+    ArchiveResultDto,
+    AuditFindingDto,
+    BalanceDiscrepancyDto,
+    CleanupItemDto,
+    CleanupSuggestionsDto,
+    DatabaseMaintenanceResultDto,
+    RebuildBalancesInput,
+    RebuildBalancesResultDto,
+    add_months,
+)
+from app.services.finance import _load_account
+from app.services.settings import get_timezone
+
+
+def _cleanup_items(conn: sqlite3.Connection, sql: str, cutoff_ts: str) -> list[CleanupItemDto]:
+    rows = conn.execute(sql, (cutoff_ts,)).fetchall()
+    return [CleanupItemDto(id=row["id"], name=row["name"], lastUsedAt=row["last_used_at"]) for row in rows]
+
+
+def suggest_cleanup(months: int = 6) -> CleanupSuggestionsDto:
+    if months <= 0:
+        raise ApiError("invalid_input", "months must be greater than 0")
+
+    with get_connection() as conn:
+        tz = get_timezone(conn)
+        today = local_today(tz)
+        cutoff = add_months(date(today.year, today.month, 1), -months)
+        cutoff_ts = day_start_utc(cutoff, tz)
+        payees = _cleanup_items(
+            conn,
+            """
+            SELECT p.id, p.name, MAX(t.occurred_at) AS last_used_at
+            FROM payees p
+            LEFT JOIN transactions t ON t.payee_id = p.id AND t.deleted_at IS NULL
+            WHERE p.is_archived = 0
+            GROUP BY p.id
+            HAVING last_used_at IS NULL OR last_used_at < ?
+            ORDER BY p.name ASC
+            """,
+            cutoff_ts,
+        )
+        tags = _cleanup_items(
+            conn,
+            """
+            SELECT g.id, g.name, MAX(t.occurred_at) AS last_used_at
+            FROM tags g
+            LEFT JOIN transaction_tags tt ON tt.tag_id = g.id
+            LEFT JOIN transactions t ON t.id = tt.transaction_id AND t.deleted_at IS NULL
+            WHERE g.is_archived = 0
+            GROUP BY g.id
+            HAVING last_used_at IS NULL OR last_used_at < ?
+            ORDER BY g.name ASC
+            """,
+            cutoff_ts,
+        )
+        categories = _cleanup_items(
+            conn,
+            """
+            SELECT c.id, c.name, MAX(t.occurred_at) AS last_used_at
+            FROM categories c
+            LEFT JOIN transactions t ON t.category_id = c.id AND t.deleted_at IS NULL
+            WHERE c.is_active = 1
+              -- Still referenced as a parent, by a budget, by a rule that books it or as a payee default.
+              AND NOT EXISTS (SELECT 1 FROM categories child WHERE child.parent_id = c.id)
+              AND NOT EXISTS (SELECT 1 FROM budgets b WHERE b.category_id = c.id)
+              AND NOT EXISTS (SELECT 1 FROM recurring_rules r WHERE r.category_id = c.id AND r.is_active = 1)
+              AND NOT EXISTS (SELECT 1 FROM payees p WHERE p.default_category_id = c.id)
+            GROUP BY c.id
+            HAVING last_used_at IS NULL OR last_used_at < ?
+            ORDER BY c.name ASC
+            """,
+            cutoff_ts,
+        )
+        accounts = _cleanup_items(
+            conn,
+            """
+            SELECT a.id, a.name, MAX(t.occurred_at) AS last_used_at
+            FROM accounts a
+            LEFT JOIN transaction_postings p ON p.account_id = a.id
+            LEFT JOIN transactions t ON t.id = p.transaction_id AND t.deleted_at IS NULL
+            WHERE a.is_archived = 0 AND a.balance_cents = 0
+            GROUP BY a.id
+            HAVING last_used_at IS NULL OR last_used_at < ?
+            ORDER BY a.name ASC
+            """,
+            cutoff_ts,
+        )
+
+    return CleanupSuggestionsDto(
+        cutoffDate=cutoff.isoformat(),
+        payees=payees,
+        tags=tags,
+        categories=categories,
+        accounts=accounts,
+    )
+
+
+def archive_items(input_data: ArchiveInput) -> ArchiveResultDto:
+    targets = (
+        ("payee", "UPDATE payees SET is_archived = 1 WHERE id = ?", input_data.payeeIds),
+        ("tag", "UPDATE tags SET is_archived = 1 WHERE id = ?", input_data.tagIds),
+        ("category", "UPDATE categories SET is_active = 0 WHERE id = ?", input_data.categoryIds),
+        ("account", "UPDATE accounts SET is_archived = 1 WHERE id = ?", input_data.accountIds),
+    )
+
+    archived = 0
+    with get_connection() as conn:
+        with transaction(conn):
+            for kind, sql, ids in targets:
+                for item_id in ids:
+                    if conn.execute(sql, (item_id,)).rowcount == 0:
+                        raise ApiError("not_found", f"{kind} not found: {item_id}", status_code=404)
+                    archived += 1
+
+    return ArchiveResultDto(archivedCount=archived)
+
+
+def rebuild_balances(input_data: RebuildBalancesInput) -> RebuildBalancesResultDto:
+    """Recompute every balance from its opening balance and the full history, fixing drifted ones."""
+    with get_connection() as conn:
+        rows = conn.execute(
+            """
+            SELECT a.id, a.name, a.type, a.balance_cents, a.opening_balance_cents + COALESCE((
+                SELECT SUM(p.amount_cents)
+                FROM transaction_postings p JOIN transactions t ON t.id = p.transaction_id
+                WHERE p.account_id = a.id AND t.deleted_at IS NULL
+            ), 0) AS rebuilt_cents
+            FROM accounts a
+            ORDER BY a.name ASC, a.id ASC
+            """
+        ).fetchall()
+        discrepancies = [
+            BalanceDiscrepancyDto(
+                accountId=row["id"],
+                name=row["name"],
+                storedCents=int(row["balance_cents"]),
+                rebuiltCents=int(row["rebuilt_cents"]),
+                differenceCents=int(row["rebuilt_cents"]) - int(row["balance_cents"]),
+            )
+            for row in rows
+            if int(row["rebuilt_cents"]) != int(row["balance_cents"])
+        ]
+        if input_data.dryRun or not discrepancies:
+            return RebuildBalancesResultDto(checkedCount=len(rows), discrepancies=discrepancies, applied=False)
+
+        liabilities = {row["id"] for row in rows if row["type"] == AccountType.LIABILITY.value}
+        with transaction(conn):
+            for item in discrepancies:
+                if item.accountId in liabilities and item.rebuiltCents > 0:
+                    raise ApiError(
+                        "conflict",
+                        f"rebuilt liability balance would be positive: {item.accountId}",
+                        status_code=409,
+                    )
+                conn.execute(
+                    "UPDATE accounts SET balance_cents = ?, updated_at = ? WHERE id = ?",
+                    (item.rebuiltCents, now_utc_rfc3339(), item.accountId),
+                )
+        for item in discrepancies:
+            events.emit("account:updated", _load_account(conn, item.accountId).model_dump(mode="json"))
+
+    return RebuildBalancesResultDto(checkedCount=len(rows), discrepancies=discrepancies, applied=True)
+
+
+# Each check lists the ids of rows breaking an invariant the services otherwise keep;
+# they can only come from older versions, hand edits or constraints being switched off.
+AUDIT_CHECKS: tuple[tuple[str, str, str], ...] = (
+    (
+        "orphaned_posting",
+        "Depreciation postings whose schedule or transaction no longer exists.",
+        """
+        SELECT p.id
+        FROM amortization_postings p
+        LEFT JOIN amortization_schedules s ON s.id = p.schedule_id
+        LEFT JOIN transactions t ON t.id = p.transaction_id
+        WHERE s.id IS NULL OR t.id IS NULL
+        ORDER BY p.period_ym ASC, p.id ASC
+        """,
+    ),
+    (
+        "over_depreciated_schedule",
+        "Amortization schedules that posted more than their depreciable value.",
+        """
+        SELECT s.id
+        FROM amortization_schedules s
+        JOIN transactions t ON t.id = s.source_transaction_id
+        WHERE s.carried_depreciation_cents
+              + COALESCE((SELECT SUM(p.amount_cents) FROM amortization_postings p WHERE p.schedule_id = s.id), 0)
+              > COALESCE(s.basis_cents, t.amount_cents) + s.revaluation_cents - s.residual_cents
+        ORDER BY s.start_date ASC, s.id ASC
+        """,
+    ),
+    (
+        "positive_liability",
+        "Liability accounts with a positive balance.",
+        """
+        SELECT id
+        FROM accounts
+        WHERE type = 'Liability' AND balance_cents > 0
+        ORDER BY name ASC, id ASC
+        """,
+    ),
+    (
+        "missing_category",
+        "Transactions referencing a category that does not exist.",
+        """
+        SELECT t.id
+        FROM transactions t
+        LEFT JOIN categories c ON c.id = t.category_id
+        WHERE t.category_id IS NOT NULL AND c.id IS NULL
+        ORDER BY t.occurred_at ASC, t.id ASC
+        """,
+    ),
+    (
+        "unbalanced_transaction",
+        "Transactions whose postings are missing or do not sum to zero.",
+        """
+        SELECT t.id
+        FROM transactions t
+        LEFT JOIN transaction_postings p ON p.transaction_id = t.id
+        WHERE t.accrual_type != 'Depreciation'
+        GROUP BY t.id
+        HAVING COUNT(p.leg) < 2 OR COALESCE(SUM(p.amount_cents), 0) != 0
+        ORDER BY t.occurred_at ASC, t.id ASC
+        """,
+    ),
+)
+
+
+def audit_data() -> list[AuditFindingDto]:
+    """Check the stored data against its invariants; only checks with offending rows are reported."""
+    with get_connection() as conn:
+        findings: list[AuditFindingDto] = []
+        for check_id, description, sql in AUDIT_CHECKS:
+            row_ids = [row[0] for row in conn.execute(sql).fetchall()]
+            if row_ids:
+                findings.append(AuditFindingDto(checkId=check_id, description=description, rowIds=row_ids))
+        return findings
+
+
+def _database_size(conn: sqlite3.Connection) -> int:
+    return int(conn.execute("PRAGMA page_count").fetchone()[0]) * int(conn.execute("PRAGMA page_size").fetchone()[0])
+
+
+def maintain_database() -> DatabaseMaintenanceResultDto:
+    """Check the file's integrity, then compact it and refresh the query planner's statistics."""
+    with get_connection() as conn:
+        size_before = _database_size(conn)
+        messages = [row[0] for row in conn.execute("PRAGMA integrity_check").fetchall()]
+        integrity_ok = messages == ["ok"]
+        if integrity_ok:
+            # Rewriting a damaged file could lose what is left of it, so only a sound one is compacted.
+            conn.execute("VACUUM")
+            conn.execute("ANALYZE")
+        size_after = _database_size(conn)
+
+    return DatabaseMaintenanceResultDto(
+        integrityOk=integrity_ok,
+        integrityMessages=messages,
+        compacted=integrity_ok,
+        sizeBeforeBytes=size_before,
+        sizeAfterBytes=size_after,
+        reclaimedBytes=size_before - size_after,
+    )
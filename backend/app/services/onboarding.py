@@ -0,0 +1,48 @@
+from __future__ import annotations
+
+from app import events
+from app.db import get_connection, transaction
+from app.models import ApiError, OnboardingInput, OnboardingResultDto
+from app.services.currencies import normalize_currency
+from app.services.dimensions import _load_category, insert_category
+from app.services.finance import _load_account, insert_account
+from app.services.settings import (
+    BASE_CURRENCY_KEY,
+    ONBOARDING_COMPLETED_KEY,
+    PERIOD_START_DAY_KEY,
+    get_setting,
+    set_setting,
+)
+
+
+def complete_onboarding(input_data: OnboardingInput) -> OnboardingResultDto:
+    base_currency = normalize_currency(input_data.baseCurrency, "baseCurrency")
+    if input_data.periodStartDay < 1 or input_data.periodStartDay > 28:
+        raise ApiError("invalid_input", "periodStartDay must be between 1 and 28")
+    if not input_data.accounts:
+        raise ApiError("invalid_input", "onboarding needs at least one account")
+
+    with get_connection() as conn:
+        if get_setting(conn, ONBOARDING_COMPLETED_KEY) == "true":
+            raise ApiError("conflict", "onboarding already completed", status_code=409)
+
+        # Everything lands in one transaction so a failure halfway leaves the
+        # database untouched and the wizard can simply be resubmitted.
+        with transaction(conn):
+            set_setting(conn, BASE_CURRENCY_KEY, base_currency)
+            set_setting(conn, PERIOD_START_DAY_KEY, str(input_data.periodStartDay))
+            # Accounts created before a base currency existed adopt it now.
+            conn.execute("UPDATE accounts SET currency = ? WHERE currency IS NULL", (base_currency,))
+            account_ids = [insert_account(conn, account) for account in input_data.accounts]
+            category_ids = [insert_category(conn, category) for category in input_data.categories]
+            set_setting(conn, ONBOARDING_COMPLETED_KEY, "true")
+
+        accounts = [_load_account(conn, account_id) for account_id in account_ids]
+        for account in accounts:
+            events.emit("account:created", account.model_dump(mode="json"))
+        return OnboardingResultDto(
+            baseCurrency=base_currency,
+            periodStartDay=input_data.periodStartDay,
+            accounts=accounts,
+            categories=[_load_category(conn, category_id) for category_id in category_ids],
+        )
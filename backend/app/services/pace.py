@@ -0,0 +1,118 @@
+from __future__ import annotations
+
+import sqlite3
+from datetime import date, timedelta
+from typing import Optional
+
+from app import events
+from app.db import (
+    day_start_utc,
+    format_period,
+    get_connection,
+    local_date,
+    local_today,
+    parse_date_ymd,
+    parse_period,
+    transaction,
+)
+from app.models import ApiError, SpendingPaceDto, add_months
+from app.services.settings import (
+    SPENDING_PACE_ALERTED_PERIOD_KEY,
+    get_setting,
+    get_spending_pace_alert_percent,
+    get_timezone,
+    set_setting,
+)
+
+
+PACE_HISTORY_MONTHS = 6
+
+
+def _outflow_cents(conn: sqlite3.Connection, first_day: date, last_day: date) -> int:
+    # Same definition as the dashboard: one-sided outflows, asset purchases excluded.
+    tz = get_timezone(conn)
+    return int(
+        conn.execute(
+            """
+            SELECT COALESCE(SUM(amount_cents), 0)
+            FROM transactions
+            WHERE accrual_type = 'Flow'
+              AND is_asset_purchase = 0
+              AND deleted_at IS NULL
+              AND from_account_id IS NOT NULL
+              AND to_account_id IS NULL
+              AND occurred_at >= ? AND occurred_at < ?
+            """,
+            (day_start_utc(first_day, tz), day_start_utc(last_day + timedelta(days=1), tz)),
+        ).fetchone()[0]
+    )
+
+
+def _resolve_as_of(period_ym: Optional[str], as_of: Optional[str], today: date) -> date:
+    as_of_day = parse_date_ymd(as_of, "asOf") if as_of is not None else None
+    if period_ym is None:
+        return as_of_day or today
+
+    period_start, _, _ = parse_period(period_ym)
+    period_end = add_months(period_start, 1) - timedelta(days=1)
+    if as_of_day is None:
+        as_of_day = min(today, period_end)
+    if as_of_day < period_start or as_of_day > period_end:
+        raise ApiError("invalid_input", "asOf must fall inside periodYm")
+    return as_of_day
+
+
+def compute_spending_pace(conn: sqlite3.Connection, as_of_day: date) -> SpendingPaceDto:
+    period_start = date(as_of_day.year, as_of_day.month, 1)
+    month_to_date = _outflow_cents(conn, period_start, as_of_day)
+
+    # Months before the first recorded transaction would only drag the baseline down.
+    first_row = conn.execute(
+        "SELECT MIN(occurred_at) FROM transactions WHERE accrual_type = 'Flow' AND deleted_at IS NULL"
+    ).fetchone()[0]
+    first_month = None if first_row is None else local_date(first_row, get_timezone(conn)).replace(day=1)
+
+    history: list[int] = []
+    for offset in range(1, PACE_HISTORY_MONTHS + 1):
+        month_start = add_months(period_start, -offset)
+        if first_month is None or month_start < first_month:
+            break
+        # Day 31 in a 30-day month compares against the whole month.
+        days_in_month = (add_months(month_start, 1) - month_start).days
+        same_day = month_start.replace(day=min(as_of_day.day, days_in_month))
+        history.append(_outflow_cents(conn, month_start, same_day))
+
+    threshold = get_spending_pace_alert_percent(conn)
+    baseline = round(sum(history) / len(history)) if history else None
+    over_percent = None
+    if baseline:
+        over_percent = round((month_to_date - baseline) * 100 / baseline)
+    return SpendingPaceDto(
+        periodYm=format_period(period_start),
+        asOfDate=as_of_day.isoformat(),
+        dayOfMonth=as_of_day.day,
+        monthToDateCents=month_to_date,
+        baselineCents=baseline,
+        historyMonthCount=len(history),
+        thresholdPercent=threshold,
+        overPacePercent=over_percent,
+        isOverPace=bool(baseline) and month_to_date * 100 >= baseline * (100 + threshold),
+    )
+
+
+def get_spending_pace(period_ym: Optional[str] = None, as_of: Optional[str] = None) -> SpendingPaceDto:
+    with get_connection() as conn:
+        as_of_day = _resolve_as_of(period_ym, as_of, local_today(get_timezone(conn)))
+        return compute_spending_pace(conn, as_of_day)
+
+
+def check_spending_pace() -> Optional[SpendingPaceDto]:
+    """Scheduler job: notify once per month when month-to-date spend runs ahead of the usual pace."""
+    with get_connection() as conn:
+        pace = compute_spending_pace(conn, local_today(get_timezone(conn)))
+        if not pace.isOverPace or get_setting(conn, SPENDING_PACE_ALERTED_PERIOD_KEY) == pace.periodYm:
+            return None
+        with transaction(conn):
+            set_setting(conn, SPENDING_PACE_ALERTED_PERIOD_KEY, pace.periodYm)
+    events.emit("spending:over_pace", pace.model_dump(mode="json"))
+    return pace
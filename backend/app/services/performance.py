@@ -0,0 +1,233 @@
+from __future__ import annotations
+
+import sqlite3
+from datetime import date, timedelta
+from typing import Callable, Optional
+
+from app.db import get_connection, local_day_sql, parse_date_ymd
+from app.models import ApiError, AssetPurpose, PerformanceItemDto, PerformanceReportDto
+from app.services.prices import latest_price, units_value
+from app.services.settings import get_timezone
+
+
+# Bounds for the XIRR search; a return beyond them is reported as unsolvable.
+XIRR_MIN_RATE = -0.9999
+XIRR_MAX_RATE = 1_000.0
+XIRR_ITERATIONS = 200
+
+# (day, cents) with money going into the investment as positive.
+CashFlows = list[tuple[date, int]]
+
+
+def _xirr(flows: CashFlows) -> Optional[float]:
+    """Annual rate that discounts the investor's cash flows to zero, found by bisection."""
+    if not any(amount < 0 for _, amount in flows) or not any(amount > 0 for _, amount in flows):
+        return None
+    first = min(day for day, _ in flows)
+
+    def npv(rate: float) -> float:
+        return sum(amount / (1 + rate) ** ((day - first).days / 365) for day, amount in flows)
+
+    low, high = XIRR_MIN_RATE, XIRR_MAX_RATE
+    if npv(low) * npv(high) > 0:
+        return None
+    for _ in range(XIRR_ITERATIONS):
+        middle = (low + high) / 2
+        if npv(low) * npv(middle) <= 0:
+            high = middle
+        else:
+            low = middle
+    return round((low + high) / 2, 6)
+
+
+def _performance(
+    label: str,
+    start: date,
+    end: date,
+    flows: CashFlows,
+    value_at: Callable[[date], int],
+) -> PerformanceItemDto:
+    """Both returns for one position from its external flows and end-of-day values.
+
+    Flows are taken to happen at the end of their day, so each sub-period of the
+    time-weighted return runs from one flow day's closing value to the next one's,
+    less the money that arrived with it.
+    """
+    in_range = sorted((day, amount) for day, amount in flows if start <= day <= end)
+    start_value = value_at(start - timedelta(days=1))
+    end_value = value_at(end)
+
+    growth = 1.0
+    invested = False
+    previous = start_value
+    by_day: dict[date, int] = {}
+    for day, amount in in_range:
+        by_day[day] = by_day.get(day, 0) + amount
+    for day in [*sorted(by_day), end]:
+        value = value_at(day)
+        if previous > 0:
+            growth *= (value - by_day.get(day, 0)) / previous
+            invested = True
+        previous = value
+
+    investor_flows: CashFlows = [(start, -start_value)] if start_value else []
+    investor_flows += [(day, -amount) for day, amount in in_range]
+    investor_flows.append((end, end_value))
+    return PerformanceItemDto(
+        label=label,
+        startValueCents=start_value,
+        endValueCents=end_value,
+        netContributionsCents=sum(amount for _, amount in in_range),
+        xirr=_xirr(investor_flows),
+        twr=round(growth - 1, 6) if invested else None,
+    )
+
+
+def _holding_positions(conn: sqlite3.Connection, holding_ids: list[str]) -> dict[str, list[tuple[date, int, int]]]:
+    """Per holding, (day, units, cost) movements: lots bought add, disposals take away."""
+    tz = get_timezone(conn)
+    placeholders = ", ".join("?" for _ in holding_ids)
+    rows = conn.execute(
+        f"""
+        SELECT holding_id, {local_day_sql("acquired_at", tz)} AS day, quantity_units AS units, cost_cents AS cost
+        FROM lots WHERE holding_id IN ({placeholders})
+        UNION ALL
+        SELECT l.holding_id, {local_day_sql("d.disposed_at", tz)}, -d.quantity_units, -d.cost_cents
+        FROM lot_disposals d JOIN lots l ON l.id = d.lot_id
+        WHERE l.holding_id IN ({placeholders})
+        """,
+        (*holding_ids, *holding_ids),
+    ).fetchall()
+    positions: dict[str, list[tuple[date, int, int]]] = {holding_id: [] for holding_id in holding_ids}
+    for row in rows:
+        positions[row["holding_id"]].append((date.fromisoformat(row["day"]), int(row["units"]), int(row["cost"])))
+    return positions
+
+
+def _position_value(
+    conn: sqlite3.Connection, ticker: str, movements: list[tuple[date, int, int]], day: date
+) -> tuple[int, int]:
+    """(market value, cost basis) at the end of `day`; without any price yet the position counts at cost."""
+    units = sum(delta for moved, delta, _ in movements if moved <= day)
+    cost = sum(delta for moved, _, delta in movements if moved <= day)
+    if units == 0:
+        return 0, cost
+    price = latest_price(conn, ticker, day)
+    return (cost if price is None else units_value(units, int(price["price_cents"]))), cost
+
+
+def get_performance_report(from_date: str, to_date: str) -> PerformanceReportDto:
+    start = parse_date_ymd(from_date, "fromDate")
+    end = parse_date_ymd(to_date, "toDate")
+    if end < start:
+        raise ApiError("invalid_input", "toDate must not be before fromDate")
+
+    with get_connection() as conn:
+        tz = get_timezone(conn)
+        holdings = conn.execute(
+            """
+            SELECT h.id, h.account_id, h.ticker, a.purpose
+            FROM holdings h JOIN accounts a ON a.id = h.account_id
+            ORDER BY h.ticker ASC, h.account_id ASC
+            """
+        ).fetchall()
+        positions = _holding_positions(conn, [row["id"] for row in holdings])
+
+        items: list[PerformanceItemDto] = []
+        for holding in holdings:
+            movements = positions[holding["id"]]
+            trades = conn.execute(
+                f"""
+                SELECT {local_day_sql("acquired_at", tz)} AS day, cost_cents AS amount FROM lots WHERE holding_id = ?
+                UNION ALL
+                SELECT {local_day_sql("t.occurred_at", tz)}, -t.amount_cents
+                FROM transactions t
+                WHERE t.id IN (
+                  SELECT d.transaction_id FROM lot_disposals d JOIN lots l ON l.id = d.lot_id WHERE l.holding_id = ?
+                )
+                """,
+                (holding["id"], holding["id"]),
+            ).fetchall()
+            item = _performance(
+                holding["ticker"],
+                start,
+                end,
+                [(date.fromisoformat(row["day"]), int(row["amount"])) for row in trades],
+                lambda day, ticker=holding["ticker"], movements=movements: _position_value(
+                    conn, ticker, movements, day
+                )[0],
+            )
+            items.append(item.model_copy(update={"holdingId": holding["id"], "accountId": holding["account_id"]}))
+
+        group = _investment_group(conn, start, end, holdings, positions)
+
+    return PerformanceReportDto(
+        fromDate=start.isoformat(),
+        toDate=end.isoformat(),
+        holdings=items,
+        investmentGroup=group,
+    )
+
+
+def _investment_group(
+    conn: sqlite3.Connection,
+    start: date,
+    end: date,
+    holdings: list[sqlite3.Row],
+    positions: dict[str, list[tuple[date, int, int]]],
+) -> PerformanceItemDto:
+    """Every Investment-purpose account taken together.
+
+    Only transfers across the group's edge are contributions or withdrawals; dividends,
+    fees and realized gains stay inside and count towards the return. Balances are taken
+    with holdings at cost, so each day the holdings' market premium is added on top.
+    """
+    accounts = conn.execute(
+        "SELECT id, balance_cents FROM accounts WHERE purpose = ?",
+        (AssetPurpose.INVESTMENT.value,),
+    ).fetchall()
+    group_ids = {row["id"] for row in accounts}
+    placeholders = ", ".join("?" for _ in group_ids)
+    # Month-end revaluations already put part of the market premium into the balances;
+    # they are taken back out so it is not counted twice.
+    revalued = conn.execute(
+        f"SELECT COALESCE(SUM(adjustment_cents), 0) FROM holding_revaluations WHERE account_id IN ({placeholders})",
+        tuple(group_ids),
+    ).fetchone()[0]
+    current_total = sum(int(row["balance_cents"]) for row in accounts) - int(revalued)
+    rows = conn.execute(
+        f"""
+        SELECT
+          {local_day_sql("t.occurred_at", get_timezone(conn))} AS day,
+          SUM(CASE WHEN p.account_id IN ({placeholders}) THEN p.amount_cents ELSE 0 END) AS delta_cents,
+          MAX(p.account_id IS NULL) AS external
+        FROM transactions t
+        JOIN transaction_postings p ON p.transaction_id = t.id
+        WHERE t.deleted_at IS NULL
+          AND t.id IN (SELECT transaction_id FROM transaction_postings WHERE account_id IN ({placeholders}))
+          AND NOT EXISTS (SELECT 1 FROM holding_revaluations r WHERE r.transaction_id = t.id)
+        GROUP BY t.id
+        """,
+        (*group_ids, *group_ids),
+    ).fetchall()
+
+    deltas: list[tuple[date, int]] = []
+    flows: CashFlows = []
+    for row in rows:
+        day = date.fromisoformat(row["day"])
+        delta = int(row["delta_cents"])
+        deltas.append((day, delta))
+        # Money from the user's other accounts is contributed; income and expenses are returns.
+        if delta and not row["external"]:
+            flows.append((day, delta))
+
+    group_holdings = [holding for holding in holdings if holding["account_id"] in group_ids]
+
+    def value_at(day: date) -> int:
+        balance = current_total - sum(delta for moved, delta in deltas if moved > day)
+        for holding in group_holdings:
+            market, cost = _position_value(conn, holding["ticker"], positions[holding["id"]], day)
+            balance += market - cost
+        return balance
+
+    return _performance(AssetPurpose.INVESTMENT.value, start, end, flows, value_at)
@@ -0,0 +1,87 @@
+from __future__ import annotations
+
+from dataclasses import dataclass
+from datetime import date
+from decimal import ROUND_HALF_UP, Decimal, InvalidOperation
+from typing import Any, Optional, Protocol
+
+import httpx
+
+from app.config import get_price_provider_url
+from app.models import ApiError
+
+
+PRICE_FETCH_TIMEOUT_SECONDS = 10.0
+
+
+@dataclass(frozen=True)
+class FetchedPrice:
+    instrument: str
+    price_date: date
+    price_cents: int
+
+
+class PriceProvider(Protocol):
+    def fetch_closes(self, instruments: list[str], on: date) -> list[FetchedPrice]:
+        """End-of-day prices for `instruments` on `on`, or the last close before it.
+
+        Instruments the provider does not know are simply left out of the result.
+        """
+        ...
+
+
+def _close_to_cents(value: Any) -> Optional[int]:
+    try:
+        close = Decimal(str(value))
+    except InvalidOperation:
+        return None
+    if not close.is_finite() or close < 0:
+        return None
+    return int((close * 100).quantize(Decimal(1), rounding=ROUND_HALF_UP))
+
+
+class HttpPriceProvider:
+    """Asks a JSON endpoint for closes: `GET <endpoint>?symbols=A,B&date=YYYY-MM-DD`.
+
+    The answer is `{"prices": [{"symbol": "A", "date": "YYYY-MM-DD", "close": "12.34"}]}`,
+    with closes in whole currency units; `date` may be earlier than the one asked for.
+    """
+
+    def __init__(self, endpoint: str, timeout: float = PRICE_FETCH_TIMEOUT_SECONDS) -> None:
+        self.endpoint = endpoint
+        self.timeout = timeout
+
+    def fetch_closes(self, instruments: list[str], on: date) -> list[FetchedPrice]:
+        try:
+            response = httpx.get(
+                self.endpoint,
+                params={"symbols": ",".join(instruments), "date": on.isoformat()},
+                timeout=self.timeout,
+            )
+            response.raise_for_status()
+            payload = response.json()
+        except (httpx.HTTPError, ValueError) as exc:
+            raise ApiError("upstream_error", f"price provider request failed: {exc}", status_code=502) from exc
+
+        wanted = set(instruments)
+        fetched: list[FetchedPrice] = []
+        for item in payload.get("prices", []) if isinstance(payload, dict) else []:
+            if not isinstance(item, dict):
+                continue
+            instrument = str(item.get("symbol", "")).strip().upper()
+            cents = _close_to_cents(item.get("close"))
+            try:
+                price_date = date.fromisoformat(str(item.get("date", on.isoformat())))
+            except ValueError:
+                continue
+            # A close dated after the requested day is not an end-of-day price for it.
+            if instrument in wanted and cents is not None and price_date <= on:
+                fetched.append(FetchedPrice(instrument=instrument, price_date=price_date, price_cents=cents))
+        return fetched
+
+
+def get_price_provider() -> PriceProvider:
+    endpoint = get_price_provider_url()
+    if endpoint is None:
+        raise ApiError("invalid_input", "no price provider is configured; set price_provider_url")
+    return HttpPriceProvider(endpoint)
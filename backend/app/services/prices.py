@@ -0,0 +1,171 @@
+from __future__ import annotations
+
+import sqlite3
+import uuid
+from datetime import date
+from typing import Optional
+
+from app.db import get_connection, local_today, now_utc_rfc3339, parse_date_ymd, transaction
+from app.models import (
+    ApiError,
+    PriceDto,
+    RefreshPricesInput,
+    RefreshPricesResultDto,
+    SetPriceInput,
+    ensure_amount_in_range,
+)
+from app.services.crypto import CRYPTO_UNITS_PER_COIN
+from app.services.price_providers import FetchedPrice, get_price_provider
+from app.services.settings import get_timezone
+
+
+MAX_INSTRUMENT_LENGTH = 12
+
+PRICE_COLUMNS = "id, instrument, price_date, price_cents"
+
+
+def normalize_instrument(raw: str, field_name: str) -> str:
+    instrument = raw.strip().upper()
+    if (
+        not instrument
+        or len(instrument) > MAX_INSTRUMENT_LENGTH
+        or not all(ch.isalnum() or ch in ".-" for ch in instrument)
+    ):
+        raise ApiError(
+            "invalid_input",
+            f"{field_name} must be 1 to {MAX_INSTRUMENT_LENGTH} letters, digits, dots or dashes",
+        )
+    return instrument
+
+
+def units_value(units: int, price_cents: int) -> int:
+    """Worth of `units` hundred-millionths at a whole-unit price, rounded half up."""
+    return (units * price_cents + CRYPTO_UNITS_PER_COIN // 2) // CRYPTO_UNITS_PER_COIN
+
+
+def latest_price(conn: sqlite3.Connection, instrument: str, on: date) -> Optional[sqlite3.Row]:
+    """The last price recorded on or before `on`; a holding is valued at its last known close."""
+    return conn.execute(
+        f"""
+        SELECT {PRICE_COLUMNS} FROM prices
+        WHERE instrument = ? AND price_date <= ?
+        ORDER BY price_date DESC
+        LIMIT 1
+        """,
+        (instrument, on.isoformat()),
+    ).fetchone()
+
+
+def _price_from_row(row: sqlite3.Row) -> PriceDto:
+    return PriceDto(
+        id=row["id"],
+        instrument=row["instrument"],
+        priceDate=row["price_date"],
+        priceCents=row["price_cents"],
+    )
+
+
+def _upsert_price(conn: sqlite3.Connection, instrument: str, price_date: str, price_cents: int) -> None:
+    now = now_utc_rfc3339()
+    conn.execute(
+        """
+        INSERT INTO prices (id, instrument, price_date, price_cents, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(instrument, price_date) DO UPDATE SET
+            price_cents = excluded.price_cents, updated_at = excluded.updated_at
+        """,
+        (str(uuid.uuid4()), instrument, price_date, price_cents, now, now),
+    )
+
+
+def _load_price(conn: sqlite3.Connection, instrument: str, price_date: str) -> PriceDto:
+    row = conn.execute(
+        f"SELECT {PRICE_COLUMNS} FROM prices WHERE instrument = ? AND price_date = ?",
+        (instrument, price_date),
+    ).fetchone()
+    return _price_from_row(row)
+
+
+def set_price(input_data: SetPriceInput) -> PriceDto:
+    """Record the price for one day; entering the same instrument and day again replaces it."""
+    instrument = normalize_instrument(input_data.instrument, "instrument")
+    price_date = parse_date_ymd(input_data.priceDate, "priceDate").isoformat()
+    if input_data.priceCents < 0:
+        raise ApiError("invalid_input", "priceCents must be >= 0")
+    ensure_amount_in_range(input_data.priceCents, "priceCents")
+
+    with get_connection() as conn:
+        with transaction(conn):
+            _upsert_price(conn, instrument, price_date, input_data.priceCents)
+        return _load_price(conn, instrument, price_date)
+
+
+def list_prices(
+    instrument: Optional[str] = None,
+    from_date: Optional[str] = None,
+    to_date: Optional[str] = None,
+) -> list[PriceDto]:
+    code = None if instrument is None else normalize_instrument(instrument, "instrument")
+    start = None if from_date is None else parse_date_ymd(from_date, "fromDate").isoformat()
+    end = None if to_date is None else parse_date_ymd(to_date, "toDate").isoformat()
+    with get_connection() as conn:
+        rows = conn.execute(
+            f"""
+            SELECT {PRICE_COLUMNS} FROM prices
+            WHERE (? IS NULL OR instrument = ?)
+              AND (? IS NULL OR price_date >= ?)
+              AND (? IS NULL OR price_date <= ?)
+            ORDER BY price_date DESC, instrument ASC
+            """,
+            (code, code, start, start, end, end),
+        ).fetchall()
+    return [_price_from_row(row) for row in rows]
+
+
+def _held_instruments(conn: sqlite3.Connection) -> list[str]:
+    rows = conn.execute(
+        """
+        SELECT h.ticker AS instrument
+        FROM holdings h
+        WHERE EXISTS (SELECT 1 FROM lots l WHERE l.holding_id = h.id AND l.remaining_units > 0)
+        UNION
+        SELECT symbol FROM crypto_holdings WHERE quantity_units > 0
+        ORDER BY instrument ASC
+        """
+    ).fetchall()
+    return [row["instrument"] for row in rows]
+
+
+def refresh_prices(input_data: RefreshPricesInput) -> RefreshPricesResultDto:
+    """Fetch end-of-day prices for everything currently held and store them."""
+    with get_connection() as conn:
+        on = (
+            local_today(get_timezone(conn))
+            if input_data.priceDate is None
+            else parse_date_ymd(input_data.priceDate, "priceDate")
+        )
+        instruments = _held_instruments(conn)
+    if not instruments:
+        return RefreshPricesResultDto(prices=[], missingInstruments=[])
+
+    # The provider may be slow or remote, so no connection is held open while it answers.
+    fetched = get_price_provider().fetch_closes(instruments, on)
+    latest: dict[str, FetchedPrice] = {}
+    for price in fetched:
+        if price.instrument not in latest or price.price_date > latest[price.instrument].price_date:
+            latest[price.instrument] = price
+
+    with get_connection() as conn:
+        with transaction(conn):
+            for price in latest.values():
+                ensure_amount_in_range(price.price_cents, "priceCents")
+                _upsert_price(conn, price.instrument, price.price_date.isoformat(), price.price_cents)
+        prices = [
+            _load_price(conn, instrument, latest[instrument].price_date.isoformat())
+            for instrument in instruments
+            if instrument in latest
+        ]
+    return RefreshPricesResultDto(
+        prices=prices,
+        missingInstruments=[instrument for instrument in instruments if instrument not in latest],
+    )
@@ -0,0 +1,107 @@
+from __future__ import annotations
+
+import json
+import shlex
+import subprocess
+import tempfile
+from dataclasses import dataclass
+from datetime import date
+from decimal import ROUND_HALF_UP, Decimal, InvalidOperation
+from pathlib import Path
+from typing import Any, Optional, Protocol
+
+import httpx
+
+from app.config import get_receipt_reader_command, get_receipt_reader_url
+from app.models import ApiError
+
+
+RECEIPT_READ_TIMEOUT_SECONDS = 30.0
+
+
+@dataclass(frozen=True)
+class ReadReceipt:
+    amount_cents: Optional[int]
+    occurred_on: Optional[date]
+    payee_name: Optional[str]
+
+
+class ReceiptReader(Protocol):
+    def read(self, content: bytes, file_name: str, content_type: str) -> ReadReceipt:
+        """What the reader made of a receipt; fields it could not make out are None."""
+        ...
+
+
+def _parse_receipt(payload: Any) -> ReadReceipt:
+    """Reads `{"amount": "12.34", "date": "YYYY-MM-DD", "payee": "Bakery"}`, ignoring unusable fields."""
+    if not isinstance(payload, dict):
+        raise ApiError("upstream_error", "receipt reader did not answer with a JSON object", status_code=502)
+
+    amount_cents = None
+    try:
+        amount = Decimal(str(payload["amount"]))
+        if amount.is_finite() and amount != 0:
+            # Receipts show what was paid; the direction comes from the accounts picked.
+            amount_cents = abs(int((amount * 100).quantize(Decimal(1), rounding=ROUND_HALF_UP)))
+    except (KeyError, InvalidOperation):
+        pass
+    try:
+        occurred_on = date.fromisoformat(str(payload["date"]))
+    except (KeyError, ValueError):
+        occurred_on = None
+    payee = payload.get("payee")
+    payee_name = str(payee).strip() if payee is not None else ""
+    return ReadReceipt(amount_cents=amount_cents, occurred_on=occurred_on, payee_name=payee_name or None)
+
+
+class HttpReceiptReader:
+    """Posts the file as the request body, with its type in Content-Type and name in X-File-Name."""
+
+    def __init__(self, endpoint: str, timeout: float = RECEIPT_READ_TIMEOUT_SECONDS) -> None:
+        self.endpoint = endpoint
+        self.timeout = timeout
+
+    def read(self, content: bytes, file_name: str, content_type: str) -> ReadReceipt:
+        try:
+            response = httpx.post(
+                self.endpoint,
+                content=content,
+                headers={"Content-Type": content_type, "X-File-Name": file_name},
+                timeout=self.timeout,
+            )
+            response.raise_for_status()
+            payload = response.json()
+        except (httpx.HTTPError, ValueError) as exc:
+            raise ApiError("upstream_error", f"receipt reader request failed: {exc}", status_code=502) from exc
+        return _parse_receipt(payload)
+
+
+class CommandReceiptReader:
+    """Runs a local command with the path of a temporary copy of the file appended; it prints the JSON."""
+
+    def __init__(self, command: str, timeout: float = RECEIPT_READ_TIMEOUT_SECONDS) -> None:
+        self.args = shlex.split(command)
+        self.timeout = timeout
+
+    def read(self, content: bytes, file_name: str, content_type: str) -> ReadReceipt:
+        with tempfile.TemporaryDirectory(prefix="oikonomos-receipt-") as scratch:
+            path = Path(scratch) / file_name
+            path.write_bytes(content)
+            try:
+                completed = subprocess.run(
+                    [*self.args, str(path)], capture_output=True, timeout=self.timeout, check=True
+                )
+                payload = json.loads(completed.stdout)
+            except (OSError, subprocess.SubprocessError, ValueError) as exc:
+                raise ApiError("upstream_error", f"receipt reader command failed: {exc}", status_code=502) from exc
+        return _parse_receipt(payload)
+
+
+def get_receipt_reader() -> ReceiptReader:
+    endpoint = get_receipt_reader_url()
+    if endpoint is not None:
+        return HttpReceiptReader(endpoint)
+    command = get_receipt_reader_command()
+    if command is not None:
+        return CommandReceiptReader(command)
+    raise ApiError("invalid_input", "no receipt reader is configured; set receipt_reader_url or receipt_reader_command")
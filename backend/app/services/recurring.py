@@ -0,0 +1,256 @@
+from __future__ import annotations
+
+import calendar
+import logging
+import sqlite3
+import uuid
+from datetime import date, timedelta
+from typing import Optional
+
+from app.db import day_start_utc, get_connection, local_today, now_utc_rfc3339, parse_date_ymd, savepoint, transaction
+from app.models import (
+    ApiError,
+    CreateRecurringRuleInput,
+    CreateTransactionInput,
+    RecurrenceFrequency,
+    RecurringRuleDto,
+    RecurringRuleFailureDto,
+    RunRecurringInput,
+    RunRecurringResultDto,
+    add_months,
+    ensure_amount_in_range,
+)
+from app.services.finance import _load_transaction, insert_transaction, publish_transactions
+from app.services.settings import get_timezone
+
+
+logger = logging.getLogger(__name__)
+
+# A run books at most this many occurrences per rule; a rule further behind catches up on
+# the next runs instead of holding one write transaction open for years of history.
+MAX_OCCURRENCES_PER_RUN = 500
+# How far past today a run may book ahead.
+MAX_RUN_AHEAD_DAYS = 366
+
+RULE_COLUMNS = """
+    id, name, amount_cents, from_account_id, to_account_id, payee_id, category_id, note,
+    frequency, interval_count, start_date, end_date, generated_count, is_active
+"""
+
+
+def occurrence_date(start: date, frequency: RecurrenceFrequency, interval: int, index: int) -> date:
+    steps = interval * index
+    if frequency == RecurrenceFrequency.DAILY:
+        return start + timedelta(days=steps)
+    if frequency == RecurrenceFrequency.WEEKLY:
+        return start + timedelta(weeks=steps)
+
+    months = steps if frequency == RecurrenceFrequency.MONTHLY else steps * 12
+    month_start = add_months(date(start.year, start.month, 1), months)
+    # Anchor on the start day so a rule starting on the 31st lands on the last day of
+    # shorter months instead of drifting earlier every time it is clamped.
+    last_day = calendar.monthrange(month_start.year, month_start.month)[1]
+    return month_start.replace(day=min(start.day, last_day))
+
+
+def _next_run_date(row: sqlite3.Row) -> Optional[date]:
+    if not row["is_active"]:
+        return None
+    next_date = occurrence_date(
+        parse_date_ymd(row["start_date"], "startDate"),
+        RecurrenceFrequency(row["frequency"]),
+        int(row["interval_count"]),
+        int(row["generated_count"]),
+    )
+    if row["end_date"] is not None and next_date > parse_date_ymd(row["end_date"], "endDate"):
+        return None
+    return next_date
+
+
+def pending_occurrences(row: sqlite3.Row, through: date) -> list[date]:
+    """Occurrences the rule has not materialized yet, up to and including `through`."""
+    if not row["is_active"]:
+        return []
+    start = parse_date_ymd(row["start_date"], "startDate")
+    end = parse_date_ymd(row["end_date"], "endDate") if row["end_date"] is not None else None
+    frequency = RecurrenceFrequency(row["frequency"])
+    pending: list[date] = []
+    index = int(row["generated_count"])
+    while True:
+        due = occurrence_date(start, frequency, int(row["interval_count"]), index)
+        if due > through or (end is not None and due > end):
+            return pending
+        pending.append(due)
+        index += 1
+
+
+def _rule_from_row(row: sqlite3.Row) -> RecurringRuleDto:
+    next_date = _next_run_date(row)
+    return RecurringRuleDto(
+        id=row["id"],
+        name=row["name"],
+        amountCents=row["amount_cents"],
+        fromAccountId=row["from_account_id"],
+        toAccountId=row["to_account_id"],
+        payeeId=row["payee_id"],
+        categoryId=row["category_id"],
+        note=row["note"],
+        frequency=row["frequency"],
+        interval=row["interval_count"],
+        startDate=row["start_date"],
+        endDate=row["end_date"],
+        nextRunDate=None if next_date is None else next_date.isoformat(),
+        isActive=bool(row["is_active"]),
+    )
+
+
+def _load_rule_row(conn: sqlite3.Connection, rule_id: str) -> sqlite3.Row:
+    row = conn.execute(f"SELECT {RULE_COLUMNS} FROM recurring_rules WHERE id = ?", (rule_id,)).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"recurring rule not found: {rule_id}", status_code=404)
+    return row
+
+
+def list_recurring_rules() -> list[RecurringRuleDto]:
+    with get_connection() as conn:
+        rows = conn.execute(f"SELECT {RULE_COLUMNS} FROM recurring_rules ORDER BY name ASC").fetchall()
+        return [_rule_from_row(row) for row in rows]
+
+
+def create_recurring_rule(input_data: CreateRecurringRuleInput) -> RecurringRuleDto:
+    if not input_data.name.strip():
+        raise ApiError("invalid_input", "rule name cannot be empty")
+    if input_data.amountCents <= 0:
+        raise ApiError("invalid_input", "amountCents must be greater than 0")
+    ensure_amount_in_range(input_data.amountCents, "amountCents")
+    if input_data.fromAccountId is None and input_data.toAccountId is None:
+        raise ApiError("invalid_input", "recurring rule needs from/to account")
+    if input_data.interval <= 0:
+        raise ApiError("invalid_input", "interval must be greater than 0")
+    start = parse_date_ymd(input_data.startDate, "startDate")
+    if input_data.endDate is not None and parse_date_ymd(input_data.endDate, "endDate") < start:
+        raise ApiError("invalid_input", "endDate must not be before startDate")
+
+    rule_id = str(uuid.uuid4())
+    now = now_utc_rfc3339()
+    with get_connection() as conn:
+        with transaction(conn):
+            conn.execute(
+                """
+                INSERT INTO recurring_rules (
+                    id, name, amount_cents, from_account_id, to_account_id, payee_id, category_id, note,
+                    frequency, interval_count, start_date, end_date, generated_count, is_active,
+                    created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0, 1, ?, ?)
+                """,
+                (
+                    rule_id,
+                    input_data.name.strip(),
+                    input_data.amountCents,
+                    input_data.fromAccountId,
+                    input_data.toAccountId,
+                    input_data.payeeId,
+                    input_data.categoryId,
+                    input_data.note,
+                    input_data.frequency.value,
+                    input_data.interval,
+                    input_data.startDate,
+                    input_data.endDate,
+                    now,
+                    now,
+                ),
+            )
+        return _rule_from_row(_load_rule_row(conn, rule_id))
+
+
+def deactivate_recurring_rule(rule_id: str) -> RecurringRuleDto:
+    with get_connection() as conn:
+        _load_rule_row(conn, rule_id)
+        with transaction(conn):
+            conn.execute(
+                "UPDATE recurring_rules SET is_active = 0, updated_at = ? WHERE id = ?",
+                (now_utc_rfc3339(), rule_id),
+            )
+        return _rule_from_row(_load_rule_row(conn, rule_id))
+
+
+def _materialize_rule(conn: sqlite3.Connection, row: sqlite3.Row, through: date) -> tuple[list[str], bool]:
+    """Books the rule's due occurrences; the flag tells whether some were left for the next run."""
+    tz = get_timezone(conn)
+    created: list[str] = []
+    while True:
+        due = _next_run_date(row)
+        if due is None or due > through:
+            return created, False
+        if len(created) == MAX_OCCURRENCES_PER_RUN:
+            return created, True
+        created.append(
+            insert_transaction(
+                conn,
+                CreateTransactionInput(
+                    amountCents=row["amount_cents"],
+                    fromAccountId=row["from_account_id"],
+                    toAccountId=row["to_account_id"],
+                    payeeId=row["payee_id"],
+                    categoryId=row["category_id"],
+                    note=row["note"] or row["name"],
+                    occurredAt=day_start_utc(due, tz),
+                ),
+                recurring_rule_id=row["id"],
+            )
+        )
+        conn.execute(
+            "UPDATE recurring_rules SET generated_count = generated_count + 1, updated_at = ? WHERE id = ?",
+            (now_utc_rfc3339(), row["id"]),
+        )
+        row = _load_rule_row(conn, row["id"])
+
+
+def materialize_due_transactions(
+    conn: sqlite3.Connection, through: date
+) -> tuple[list[str], list[RecurringRuleFailureDto], bool]:
+    """Books every active rule's due occurrences, each rule in its own savepoint.
+
+    A rule that cannot be booked (an account gone, an amount no longer valid) is rolled back
+    and reported, so it does not hold back the other rules on every run.
+    """
+    created: list[str] = []
+    failures: list[RecurringRuleFailureDto] = []
+    has_more = False
+    rows = conn.execute(f"SELECT {RULE_COLUMNS} FROM recurring_rules WHERE is_active = 1 ORDER BY name").fetchall()
+    for row in rows:
+        try:
+            with savepoint(conn, "recurring_rule"):
+                rule_created, rule_has_more = _materialize_rule(conn, row, through)
+        except (ApiError, sqlite3.IntegrityError) as exc:
+            code, message = (exc.code, exc.message) if isinstance(exc, ApiError) else ("db_integrity_error", str(exc))
+            logger.warning("recurring rule %s (%s) failed: %s", row["id"], row["name"], message)
+            failures.append(RecurringRuleFailureDto(ruleId=row["id"], name=row["name"], code=code, message=message))
+            continue
+        created.extend(rule_created)
+        has_more = has_more or rule_has_more
+    return created, failures, has_more
+
+
+def run_recurring(input_data: Optional[RunRecurringInput] = None) -> RunRecurringResultDto:
+    through = None
+    if input_data is not None and input_data.throughDate is not None:
+        through = parse_date_ymd(input_data.throughDate, "throughDate")
+
+    with get_connection() as conn:
+        today = local_today(get_timezone(conn))
+        through = through or today
+        if (through - today).days > MAX_RUN_AHEAD_DAYS:
+            raise ApiError(
+                "invalid_input",
+                f"throughDate must be at most {MAX_RUN_AHEAD_DAYS} days ahead",
+                details={"field": "throughDate", "value": through.isoformat()},
+            )
+        with transaction(conn):
+            created, failures, has_more = materialize_due_transactions(conn, through)
+        publish_transactions(conn, created)
+        return RunRecurringResultDto(
+            createdTransactions=[_load_transaction(conn, tx_id) for tx_id in created],
+            failedRules=failures,
+            hasMore=has_more,
+        )
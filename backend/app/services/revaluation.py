@@ -0,0 +1,185 @@
+from __future__ import annotations
+
+import sqlite3
+import uuid
+from datetime import date, timedelta
+from typing import Optional
+
+from app.db import (
+    day_end_utc,
+    day_start_utc,
+    get_connection,
+    insert_postings,
+    local_day_sql,
+    local_today,
+    now_utc_rfc3339,
+    parse_period,
+    transaction,
+    two_leg_postings,
+)
+from app.models import (
+    ApiError,
+    FxRevaluationDto,
+    FxRevaluationResultDto,
+    RevalueForeignAccountsInput,
+    add_months,
+    format_cents,
+)
+from app.services.currencies import convert_to_base, get_base_currency
+from app.services.dimensions import ensure_category
+from app.services.finance import publish_transactions
+from app.services.settings import get_timezone
+
+
+FX_GAIN_CATEGORY = "Unrealized FX gains"
+FX_LOSS_CATEGORY = "Unrealized FX losses"
+REVALUATION_COLUMNS = (
+    "id, account_id, period_ym, base_currency, balance_cents, value_cents, gain_cents, transaction_id"
+)
+
+
+def _revaluation_from_row(row: sqlite3.Row) -> FxRevaluationDto:
+    return FxRevaluationDto(
+        id=row["id"],
+        accountId=row["account_id"],
+        periodYm=row["period_ym"],
+        baseCurrency=row["base_currency"],
+        balanceCents=row["balance_cents"],
+        valueCents=row["value_cents"],
+        gainCents=row["gain_cents"],
+        transactionId=row["transaction_id"],
+    )
+
+
+def _month_gain(
+    conn: sqlite3.Connection, account: sqlite3.Row, month_start: date, month_end: date
+) -> Optional[tuple[int, int, int]]:
+    """(native month-end balance, its base value, unrealized gain), or None when a rate is missing.
+
+    The gain is what the month-end value adds on top of the opening balance at the previous
+    month-end rate plus each movement at the rate of its own day.
+    """
+    tz = get_timezone(conn)
+    # Depreciation rows never moved a balance and have no postings to unwind.
+    rows = conn.execute(
+        f"""
+        SELECT {local_day_sql("t.occurred_at", tz)} AS day, SUM(p.amount_cents) AS delta
+        FROM transaction_postings p JOIN transactions t ON t.id = p.transaction_id
+        WHERE p.account_id = ?
+          AND t.deleted_at IS NULL
+          AND t.occurred_at >= ?
+        GROUP BY day
+        """,
+        (account["id"], day_start_utc(month_start, tz)),
+    ).fetchall()
+    movements = [(int(row["delta"]), date.fromisoformat(row["day"])) for row in rows]
+    closing = int(account["balance_cents"]) - sum(delta for delta, day in movements if day > month_end)
+    in_month = [(delta, day) for delta, day in movements if day <= month_end]
+    opening = closing - sum(delta for delta, _ in in_month)
+
+    currency = account["currency"]
+    value = convert_to_base(conn, [(closing, currency, month_end)])
+    carried = convert_to_base(
+        conn,
+        [(opening, currency, month_start - timedelta(days=1)), *((delta, currency, day) for delta, day in in_month)],
+    )
+    if value is None or carried is None:
+        return None
+    return closing, value, value - carried
+
+
+def revalue_foreign_accounts(input_data: RevalueForeignAccountsInput) -> FxRevaluationResultDto:
+    """Book the month's unrealized FX gain or loss of every foreign-currency account.
+
+    Each account is revalued once per month; running the routine again only picks up
+    accounts that were skipped for want of a rate.
+    """
+    month_start, _, _ = parse_period(input_data.periodYm)
+    month_end = add_months(month_start, 1) - timedelta(days=1)
+
+    posted: list[str] = []
+    skipped: list[str] = []
+    with get_connection() as conn:
+        if month_end >= local_today(get_timezone(conn)):
+            raise ApiError("invalid_input", "periodYm must be a month that has already ended")
+        base_currency = get_base_currency(conn)
+        if base_currency is None:
+            raise ApiError("invalid_input", "set a base currency before revaluing accounts")
+        accounts = conn.execute(
+            """
+            SELECT a.id, a.name, a.currency, a.balance_cents
+            FROM accounts a
+            WHERE a.currency IS NOT NULL AND a.currency != ?
+              AND NOT EXISTS (SELECT 1 FROM fx_revaluations r WHERE r.account_id = a.id AND r.period_ym = ?)
+            ORDER BY a.name ASC
+            """,
+            (base_currency, input_data.periodYm),
+        ).fetchall()
+
+        with transaction(conn):
+            for account in accounts:
+                result = _month_gain(conn, account, month_start, month_end)
+                if result is None:
+                    skipped.append(account["id"])
+                    continue
+                balance, value, gain = result
+                if gain == 0:
+                    continue
+                tx_id = str(uuid.uuid4())
+                now = now_utc_rfc3339()
+                kind = "gain" if gain > 0 else "loss"
+                category_id = ensure_category(conn, FX_GAIN_CATEGORY if gain > 0 else FX_LOSS_CATEGORY)
+                conn.execute(
+                    """
+                    INSERT INTO transactions (
+                        id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
+                        accrual_type, is_asset_purchase, note, occurred_at, created_at
+                    ) VALUES (?, ?, NULL, NULL, NULL, ?, 'Adjustment', 0, ?, ?, ?)
+                    """,
+                    (
+                        tx_id,
+                        abs(gain),
+                        category_id,
+                        f"Unrealized FX {kind} of {format_cents(abs(gain))} {base_currency} "
+                        f"on {account['name']} for {input_data.periodYm}",
+                        day_end_utc(month_end, get_timezone(conn)),
+                        now,
+                    ),
+                )
+                # Only the value in the base currency moved, so both legs sit outside the books;
+                # the category and the signed second leg tell a gain from a loss.
+                insert_postings(conn, tx_id, two_leg_postings(None, None, gain))
+                conn.execute(
+                    """
+                    INSERT INTO fx_revaluations (
+                        id, account_id, period_ym, base_currency, balance_cents, value_cents,
+                        gain_cents, transaction_id, generated_at
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    """,
+                    (
+                        str(uuid.uuid4()),
+                        account["id"],
+                        input_data.periodYm,
+                        base_currency,
+                        balance,
+                        value,
+                        gain,
+                        tx_id,
+                        now,
+                    ),
+                )
+                posted.append(tx_id)
+        publish_transactions(conn, posted)
+
+        placeholders = ", ".join("?" for _ in posted)
+        rows = conn.execute(
+            f"SELECT {REVALUATION_COLUMNS} FROM fx_revaluations WHERE transaction_id IN ({placeholders})",
+            posted,
+        ).fetchall()
+
+    revaluations = sorted((_revaluation_from_row(row) for row in rows), key=lambda item: item.accountId)
+    return FxRevaluationResultDto(
+        periodYm=input_data.periodYm,
+        revaluations=revaluations,
+        skippedAccountIds=skipped,
+    )
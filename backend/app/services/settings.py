@@ -0,0 +1,200 @@
+from __future__ import annotations
+
+import re
+import sqlite3
+from typing import Optional
+from zoneinfo import ZoneInfo, ZoneInfoNotFoundError
+
+from app.config import get_configured_setting
+from app.db import get_connection, now_utc_rfc3339, transaction
+from app.models import ApiError, SettingsDto, UpdateSettingsInput
+
+
+BASE_CURRENCY_KEY = "base_currency"
+PERIOD_START_DAY_KEY = "period_start_day"
+ONBOARDING_COMPLETED_KEY = "onboarding_completed"
+LOCALE_KEY = "locale"
+SPENDING_PACE_ALERT_PERCENT_KEY = "spending_pace_alert_percent"
+SPENDING_PACE_ALERTED_PERIOD_KEY = "spending_pace_alerted_period"
+BACKUP_RETENTION_KEY = "backup_retention"
+CURRENCY_SYMBOL_KEY = "currency_symbol"
+DECIMAL_PLACES_KEY = "decimal_places"
+NUMBER_LOCALE_KEY = "number_locale"
+TIMEZONE_KEY = "timezone"
+
+DEFAULT_PACE_ALERT_PERCENT = 20
+DEFAULT_BACKUP_RETENTION = 10
+MAX_BACKUP_RETENTION = 365
+MAX_CURRENCY_SYMBOL_LENGTH = 8
+# Language tags such as "en", "zh-CN" or "sr-Latn-RS".
+LOCALE_PATTERN = re.compile(r"^[A-Za-z]{2,3}(-[A-Za-z0-9]{2,8})*$")
+
+
+def get_setting(conn: sqlite3.Connection, key: str) -> Optional[str]:
+    row = conn.execute("SELECT value FROM settings WHERE key = ?", (key,)).fetchone()
+    return None if row is None else row["value"]
+
+
+def set_setting(conn: sqlite3.Connection, key: str, value: str) -> None:
+    conn.execute(
+        """
+        INSERT INTO settings (key, value, updated_at) VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        """,
+        (key, value, now_utc_rfc3339()),
+    )
+
+
+def get_effective_setting(conn: sqlite3.Connection, key: str) -> Optional[str]:
+    """Settings saved from the app win; config.toml only provides the starting value."""
+    value = get_setting(conn, key)
+    if value is None:
+        return get_configured_setting(key)
+    return value
+
+
+def _check_range(value: int, name: str, minimum: int, maximum: Optional[int]) -> int:
+    if maximum is None:
+        if value < minimum:
+            raise ApiError("invalid_input", f"{name} must be >= {minimum}")
+    elif value < minimum or value > maximum:
+        raise ApiError("invalid_input", f"{name} must be between {minimum} and {maximum}")
+    return value
+
+
+def _get_int_setting(
+    conn: sqlite3.Connection, key: str, default: int, minimum: int, maximum: Optional[int] = None
+) -> int:
+    value = get_effective_setting(conn, key)
+    if value is None:
+        return default
+    try:
+        number = int(value)
+    except ValueError as exc:
+        raise ApiError("invalid_input", f"{key} must be a whole number") from exc
+    return _check_range(number, key, minimum, maximum)
+
+
+def get_period_start_day(conn: sqlite3.Connection) -> int:
+    """Day of the month reporting periods begin on; 1 means calendar months."""
+    return _get_int_setting(conn, PERIOD_START_DAY_KEY, 1, 1, 28)
+
+
+def get_spending_pace_alert_percent(conn: sqlite3.Connection) -> int:
+    """How far above the usual pace spending may run before an alert goes out."""
+    return _get_int_setting(conn, SPENDING_PACE_ALERT_PERCENT_KEY, DEFAULT_PACE_ALERT_PERCENT, 0)
+
+
+def get_backup_retention(conn: sqlite3.Connection) -> int:
+    """Number of database backups to keep before the oldest are removed."""
+    return _get_int_setting(conn, BACKUP_RETENTION_KEY, DEFAULT_BACKUP_RETENTION, 1, MAX_BACKUP_RETENTION)
+
+
+def normalize_locale(locale: str) -> str:
+    normalized = locale.strip().replace("_", "-")
+    if not LOCALE_PATTERN.match(normalized):
+        raise ApiError("invalid_input", "locale must be a language tag such as en or zh-CN")
+    return normalized
+
+
+def get_decimal_places(conn: sqlite3.Connection) -> int:
+    """Digits shown after the decimal point; by default those of the base currency."""
+    from app.services.currencies import CURRENCY_MINOR_UNITS, STORED_MINOR_UNITS, get_base_currency
+
+    base_currency = get_base_currency(conn)
+    default = STORED_MINOR_UNITS if base_currency is None else CURRENCY_MINOR_UNITS[base_currency]
+    # Amounts are stored as hundredths, so more digits than that would only show zeros.
+    default = min(default, STORED_MINOR_UNITS)
+    return _get_int_setting(conn, DECIMAL_PLACES_KEY, default, 0, STORED_MINOR_UNITS)
+
+
+def get_number_locale(conn: sqlite3.Connection) -> Optional[str]:
+    """Locale amounts are formatted in; follows the display locale unless set on its own."""
+    return get_effective_setting(conn, NUMBER_LOCALE_KEY) or get_effective_setting(conn, LOCALE_KEY)
+
+
+def normalize_timezone(name: str) -> ZoneInfo:
+    try:
+        return ZoneInfo(name.strip())
+    except (ZoneInfoNotFoundError, ValueError) as exc:
+        raise ApiError("invalid_input", "timezone must be an IANA name such as Europe/Berlin") from exc
+
+
+def get_timezone(conn: sqlite3.Connection) -> Optional[ZoneInfo]:
+    """Timezone days and periods are counted in; None keeps the stored UTC days."""
+    value = get_effective_setting(conn, TIMEZONE_KEY)
+    if value is None:
+        return None
+    tz = normalize_timezone(value)
+    return None if tz.key in ("UTC", "Etc/UTC") else tz
+
+
+def _load_settings(conn: sqlite3.Connection) -> SettingsDto:
+    from app.services.currencies import get_base_currency
+
+    return SettingsDto(
+        baseCurrency=get_base_currency(conn),
+        locale=get_effective_setting(conn, LOCALE_KEY),
+        periodStartDay=get_period_start_day(conn),
+        spendingPaceAlertPercent=get_spending_pace_alert_percent(conn),
+        backupRetention=get_backup_retention(conn),
+        currencySymbol=get_effective_setting(conn, CURRENCY_SYMBOL_KEY),
+        decimalPlaces=get_decimal_places(conn),
+        numberLocale=get_number_locale(conn),
+        timezone=get_effective_setting(conn, TIMEZONE_KEY) or "UTC",
+    )
+
+
+def get_settings() -> SettingsDto:
+    with get_connection() as conn:
+        return _load_settings(conn)
+
+
+def update_settings(input_data: UpdateSettingsInput) -> SettingsDto:
+    """Save the given preferences; fields left out keep their value and an empty text field
+    falls back to its default."""
+    from app.services.currencies import STORED_MINOR_UNITS, normalize_currency
+
+    updates: list[tuple[str, Optional[str]]] = []
+    base_currency = None
+    if input_data.baseCurrency is not None:
+        base_currency = normalize_currency(input_data.baseCurrency, "baseCurrency")
+        updates.append((BASE_CURRENCY_KEY, base_currency))
+    if input_data.locale is not None:
+        locale = normalize_locale(input_data.locale) if input_data.locale.strip() else None
+        updates.append((LOCALE_KEY, locale))
+    if input_data.periodStartDay is not None:
+        _check_range(input_data.periodStartDay, "periodStartDay", 1, 28)
+        updates.append((PERIOD_START_DAY_KEY, str(input_data.periodStartDay)))
+    if input_data.spendingPaceAlertPercent is not None:
+        _check_range(input_data.spendingPaceAlertPercent, "spendingPaceAlertPercent", 0, None)
+        updates.append((SPENDING_PACE_ALERT_PERCENT_KEY, str(input_data.spendingPaceAlertPercent)))
+    if input_data.backupRetention is not None:
+        _check_range(input_data.backupRetention, "backupRetention", 1, MAX_BACKUP_RETENTION)
+        updates.append((BACKUP_RETENTION_KEY, str(input_data.backupRetention)))
+    if input_data.currencySymbol is not None:
+        symbol = input_data.currencySymbol.strip()
+        if len(symbol) > MAX_CURRENCY_SYMBOL_LENGTH:
+            raise ApiError("invalid_input", f"currencySymbol must be at most {MAX_CURRENCY_SYMBOL_LENGTH} characters")
+        updates.append((CURRENCY_SYMBOL_KEY, symbol or None))
+    if input_data.decimalPlaces is not None:
+        _check_range(input_data.decimalPlaces, "decimalPlaces", 0, STORED_MINOR_UNITS)
+        updates.append((DECIMAL_PLACES_KEY, str(input_data.decimalPlaces)))
+    if input_data.numberLocale is not None:
+        number_locale = normalize_locale(input_data.numberLocale) if input_data.numberLocale.strip() else None
+        updates.append((NUMBER_LOCALE_KEY, number_locale))
+    if input_data.timezone is not None:
+        tz_name = normalize_timezone(input_data.timezone).key if input_data.timezone.strip() else None
+        updates.append((TIMEZONE_KEY, tz_name))
+
+    with get_connection() as conn:
+        with transaction(conn):
+            for key, value in updates:
+                if value is None:
+                    conn.execute("DELETE FROM settings WHERE key = ?", (key,))
+                else:
+                    set_setting(conn, key, value)
+            if base_currency is not None:
+                # Same as onboarding: accounts created before a base currency existed adopt it.
+                conn.execute("UPDATE accounts SET currency = ? WHERE currency IS NULL", (base_currency,))
+        return _load_settings(conn)
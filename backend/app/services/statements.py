@@ -0,0 +1,97 @@
+from __future__ import annotations
+
+from datetime import date, timedelta
+
+from app.db import get_connection, parse_date_ymd, parse_rfc3339_utc, transaction
+from app.models import (
+    ApiError,
+    MatchStatementInput,
+    StatementLineDto,
+    StatementMatchDto,
+    StatementMatchResultDto,
+    TransactionStatus,
+)
+from app.services.finance import _load_account, _transaction_from_row
+
+
+MAX_MATCH_WINDOW_DAYS = 31
+
+
+def match_statement(input_data: MatchStatementInput) -> StatementMatchResultDto:
+    """Pair imported statement lines with the account's transactions by amount and date.
+
+    A line matches an unreconciled transaction with the same signed amount whose date is
+    within the window, the nearest one first. Matched pending rows are marked Cleared; what
+    is left over on either side is returned for the user to book or chase up rather than
+    being folded into one adjustment.
+    """
+    if input_data.dateWindowDays < 0 or input_data.dateWindowDays > MAX_MATCH_WINDOW_DAYS:
+        raise ApiError("invalid_input", f"dateWindowDays must be between 0 and {MAX_MATCH_WINDOW_DAYS}")
+    if not input_data.lines:
+        raise ApiError("invalid_input", "statement has no lines")
+    lines = [StatementLineDto(index=index, **line.model_dump()) for index, line in enumerate(input_data.lines)]
+    for line in lines:
+        parse_date_ymd(line.date, "date")
+        if line.amountCents == 0:
+            raise ApiError("invalid_input", "statement line amountCents cannot be 0")
+
+    window = timedelta(days=input_data.dateWindowDays)
+    first_day = min(date.fromisoformat(line.date) for line in lines) - window
+    last_day = max(date.fromisoformat(line.date) for line in lines) + window
+
+    with get_connection() as conn:
+        _load_account(conn, input_data.accountId)
+        rows = conn.execute(
+            """
+            SELECT t.id, t.amount_cents, t.from_account_id, t.to_account_id, t.payee_id, t.category_id,
+                   t.accrual_type, t.is_asset_purchase, t.note, t.occurred_at, t.created_at, t.status, t.device_id,
+                   p.amount_cents AS signed_cents
+            FROM transactions t
+            JOIN transaction_postings p ON p.transaction_id = t.id AND p.account_id = ?
+            WHERE t.accrual_type NOT IN ('Depreciation', 'OpeningBalance')
+              AND t.status != 'Reconciled'
+              AND t.deleted_at IS NULL
+              AND t.occurred_at >= ? AND t.occurred_at < ?
+            ORDER BY t.occurred_at ASC, t.created_at ASC
+            """,
+            (
+                input_data.accountId,
+                f"{first_day.isoformat()}T00:00:00Z",
+                f"{(last_day + timedelta(days=1)).isoformat()}T00:00:00Z",
+            ),
+        ).fetchall()
+
+        candidates = {row["id"]: row for row in rows}
+        matches: list[StatementMatchDto] = []
+        unmatched_lines: list[StatementLineDto] = []
+        for line in sorted(lines, key=lambda item: (item.date, item.index)):
+            line_day = date.fromisoformat(line.date)
+            best = None
+            for row in candidates.values():
+                signed = row["signed_cents"]
+                distance = abs((parse_rfc3339_utc(row["occurred_at"]).date() - line_day).days)
+                if signed == line.amountCents and distance <= input_data.dateWindowDays:
+                    if best is None or distance < best[0]:
+                        best = (distance, row)
+            if best is None:
+                unmatched_lines.append(line)
+                continue
+            row = candidates.pop(best[1]["id"])
+            matches.append(StatementMatchDto(line=line, transaction=_transaction_from_row(row)))
+
+        with transaction(conn):
+            for match in matches:
+                if match.transaction.status == TransactionStatus.PENDING:
+                    conn.execute(
+                        "UPDATE transactions SET status = 'Cleared' WHERE id = ?",
+                        (match.transaction.id,),
+                    )
+                    match.transaction.status = TransactionStatus.CLEARED
+
+    matches.sort(key=lambda match: match.line.index)
+    unmatched_lines.sort(key=lambda line: line.index)
+    return StatementMatchResultDto(
+        matches=matches,
+        unmatchedLines=unmatched_lines,
+        unmatchedTransactions=[_transaction_from_row(row) for row in candidates.values()],
+    )
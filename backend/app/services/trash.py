@@ -0,0 +1,205 @@
+from __future__ import annotations
+
+import sqlite3
+from datetime import datetime, timedelta, timezone
+
+from app import events
+from app.db import get_connection, now_utc_rfc3339, transaction
+from app.models import (
+    AccrualType,
+    ApiError,
+    CreateMultiLegTransactionInput,
+    CreateTransactionInput,
+    PurgeTrashInput,
+    PostingInput,
+    PurgeTrashResultDto,
+    TransactionStatus,
+    TrashedTransactionDto,
+    VoidTransactionInput,
+    VoidTransactionResultDto,
+)
+from app.services.attachments import remove_unreferenced_files
+from app.services.finance import (
+    _account_legs,
+    _apply_balance_delta,
+    _load_account,
+    _load_transaction,
+    _transaction_from_row,
+    insert_multi_leg_transaction,
+    insert_transaction,
+    publish_transactions,
+)
+
+TRASHED_TRANSACTION_COLUMNS = """
+    id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
+    accrual_type, is_asset_purchase, note, occurred_at, created_at, status, device_id, deleted_at
+"""
+
+# Rows another record was booked through. They come and go with that record, so deleting
+# one on its own would leave the record pointing at money that no longer counts.
+TRANSACTION_OWNERS: tuple[tuple[str, str, str], ...] = (
+    ("amortization_schedules", "source_transaction_id", "an asset purchase"),
+    ("amortization_postings", "transaction_id", "a depreciation posting"),
+    ("asset_revaluations", "transaction_id", "an asset revaluation"),
+    ("balance_snapshots", "adjustment_tx_id", "a reconciliation"),
+    ("loan_payments", "principal_transaction_id", "a loan payment"),
+    ("loan_payments", "interest_transaction_id", "a loan payment"),
+    ("interest_accruals", "transaction_id", "an interest accrual"),
+    ("lots", "transaction_id", "a holding lot"),
+    ("lot_disposals", "transaction_id", "a lot disposal"),
+    ("fx_revaluations", "transaction_id", "an FX revaluation"),
+    ("holding_revaluations", "transaction_id", "a holding revaluation"),
+)
+
+
+def _trashed_from_row(row: sqlite3.Row) -> TrashedTransactionDto:
+    return TrashedTransactionDto(transaction=_transaction_from_row(row), deletedAt=row["deleted_at"])
+
+
+def _load_trashed(conn: sqlite3.Connection, tx_id: str) -> TrashedTransactionDto:
+    row = conn.execute(
+        f"SELECT {TRASHED_TRANSACTION_COLUMNS} FROM transactions WHERE id = ? AND deleted_at IS NOT NULL",
+        (tx_id,),
+    ).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"transaction not in trash: {tx_id}", status_code=404)
+    return _trashed_from_row(row)
+
+
+def _ensure_not_owned(conn: sqlite3.Connection, tx_id: str) -> None:
+    for table, column, owner in TRANSACTION_OWNERS:
+        if conn.execute(f"SELECT 1 FROM {table} WHERE {column} = ?", (tx_id,)).fetchone() is not None:
+            raise ApiError("conflict", f"transaction was booked by {owner}: {tx_id}", status_code=409)
+
+
+def _live_reversal_id(conn: sqlite3.Connection, tx_id: str) -> str | None:
+    row = conn.execute(
+        "SELECT id FROM transactions WHERE reversal_of_id = ? AND deleted_at IS NULL", (tx_id,)
+    ).fetchone()
+    return None if row is None else row["id"]
+
+
+def delete_transaction(tx_id: str) -> TrashedTransactionDto:
+    """Move a transaction to the trash, taking its amount back out of the account balances."""
+    with get_connection() as conn:
+        current = _load_transaction(conn, tx_id)
+        if current.status == TransactionStatus.RECONCILED:
+            raise ApiError("conflict", f"reconciled transactions cannot be deleted: {tx_id}", status_code=409)
+        _ensure_not_owned(conn, tx_id)
+        # Its reversal would otherwise go on moving the balances on its own.
+        if _live_reversal_id(conn, tx_id) is not None:
+            raise ApiError("conflict", f"transaction was voided; delete its reversal first: {tx_id}", status_code=409)
+
+        # Depreciation rows have no legs, having never moved a balance.
+        legs = _account_legs(conn, tx_id)
+        with transaction(conn):
+            for account_id, amount in legs:
+                _apply_balance_delta(conn, account_id, -amount)
+            conn.execute("UPDATE transactions SET deleted_at = ? WHERE id = ?", (now_utc_rfc3339(), tx_id))
+
+        events.emit("transaction:deleted", {"id": tx_id})
+        for account_id, _ in legs:
+            events.emit("account:updated", _load_account(conn, account_id).model_dump(mode="json"))
+        return _load_trashed(conn, tx_id)
+
+
+def void_transaction(tx_id: str, input_data: VoidTransactionInput) -> VoidTransactionResultDto:
+    """Cancel a transaction with an offsetting entry linked to it, leaving the original in place.
+
+    Unlike deleting, this also works on reconciled rows and keeps both visible, so the
+    history shows what was booked and when it was taken back.
+    """
+    with get_connection() as conn:
+        original = _load_transaction(conn, tx_id)
+        if original.accrualType in (AccrualType.DEPRECIATION, AccrualType.OPENING_BALANCE):
+            raise ApiError("invalid_input", f"{original.accrualType.value} transactions cannot be voided")
+        _ensure_not_owned(conn, tx_id)
+        row = conn.execute("SELECT reversal_of_id FROM transactions WHERE id = ?", (tx_id,)).fetchone()
+        if row["reversal_of_id"] is not None:
+            raise ApiError("conflict", f"transaction is itself a reversal: {tx_id}", status_code=409)
+        if _live_reversal_id(conn, tx_id) is not None:
+            raise ApiError("conflict", f"transaction is already voided: {tx_id}", status_code=409)
+
+        note = input_data.note
+        if note is None:
+            note = f"Void: {original.note}" if original.note else "Void"
+        with transaction(conn):
+            if original.fromAccountId is None and original.toAccountId is None:
+                reversal_id = insert_multi_leg_transaction(
+                    conn,
+                    CreateMultiLegTransactionInput(
+                        postings=[
+                            PostingInput(accountId=account_id, amountCents=-amount)
+                            for account_id, amount in _account_legs(conn, tx_id)
+                        ],
+                        payeeId=original.payeeId,
+                        categoryId=original.categoryId,
+                        note=note,
+                        occurredAt=input_data.occurredAt or original.occurredAt,
+                    ),
+                )
+            else:
+                reversal_id = insert_transaction(
+                    conn,
+                    CreateTransactionInput(
+                        amountCents=original.amountCents,
+                        fromAccountId=original.toAccountId,
+                        toAccountId=original.fromAccountId,
+                        payeeId=original.payeeId,
+                        categoryId=original.categoryId,
+                        accrualType=original.accrualType,
+                        note=note,
+                        occurredAt=input_data.occurredAt or original.occurredAt,
+                    ),
+                )
+            # Set directly so an uncategorized original is not given its payee's default on the way.
+            conn.execute(
+                "UPDATE transactions SET reversal_of_id = ?, category_id = ? WHERE id = ?",
+                (tx_id, original.categoryId, reversal_id),
+            )
+        publish_transactions(conn, [reversal_id])
+        return VoidTransactionResultDto(original=original, reversal=_load_transaction(conn, reversal_id))
+
+
+def list_trash() -> list[TrashedTransactionDto]:
+    with get_connection() as conn:
+        rows = conn.execute(
+            f"""
+            SELECT {TRASHED_TRANSACTION_COLUMNS}
+            FROM transactions
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, occurred_at DESC
+            """
+        ).fetchall()
+    return [_trashed_from_row(row) for row in rows]
+
+
+def restore_transaction(tx_id: str) -> TrashedTransactionDto:
+    """Take a transaction out of the trash and put its amount back into the balances."""
+    with get_connection() as conn:
+        trashed = _load_trashed(conn, tx_id).transaction
+        with transaction(conn):
+            for account_id, amount in _account_legs(conn, tx_id):
+                _apply_balance_delta(conn, account_id, amount)
+            conn.execute("UPDATE transactions SET deleted_at = NULL WHERE id = ?", (tx_id,))
+        publish_transactions(conn, [tx_id])
+        return TrashedTransactionDto(transaction=_load_transaction(conn, tx_id), deletedAt=None)
+
+
+def purge_trash(input_data: PurgeTrashInput) -> PurgeTrashResultDto:
+    """Delete trashed transactions for good; olderThanDays keeps the recently deleted ones."""
+    if input_data.olderThanDays is not None and input_data.olderThanDays < 0:
+        raise ApiError("invalid_input", "olderThanDays must be 0 or more")
+    cutoff = None
+    if input_data.olderThanDays is not None:
+        cutoff_at = datetime.now(timezone.utc) - timedelta(days=input_data.olderThanDays)
+        cutoff = cutoff_at.replace(microsecond=0).isoformat().replace("+00:00", "Z")
+
+    with get_connection() as conn:
+        with transaction(conn):
+            purged = conn.execute(
+                "DELETE FROM transactions WHERE deleted_at IS NOT NULL AND (? IS NULL OR deleted_at <= ?)",
+                (cutoff, cutoff),
+            ).rowcount
+        remove_unreferenced_files(conn)
+    return PurgeTrashResultDto(purgedCount=purged)
@@ -0,0 +1,175 @@
+from __future__ import annotations
+
+from typing import Optional
+
+from app.db import format_period, get_connection, local_today, parse_period, period_of, transaction_period_sql
+from app.models import (
+    ApiError,
+    CategoryTrendDto,
+    CategoryTrendSeriesDto,
+    RollingAverageItemDto,
+    RollingAverageReportDto,
+    TrendPointDto,
+    add_months,
+)
+from app.services.dimensions import _load_category
+from app.services.settings import get_period_start_day, get_timezone
+
+
+MAX_TREND_MONTHS = 120
+
+
+def get_category_trend(
+    category_ids: list[str],
+    months: int = 12,
+    end_period_ym: Optional[str] = None,
+    top_transactions: int = 0,
+) -> CategoryTrendDto:
+    if not category_ids:
+        raise ApiError("invalid_input", "categoryIds cannot be empty")
+    if months < 1 or months > MAX_TREND_MONTHS:
+        raise ApiError("invalid_input", f"months must be between 1 and {MAX_TREND_MONTHS}")
+    if top_transactions < 0:
+        raise ApiError("invalid_input", "topTransactions must be >= 0")
+
+    if end_period_ym is not None:
+        parse_period(end_period_ym)
+
+    with get_connection() as conn:
+        start_day = get_period_start_day(conn)
+        tz = get_timezone(conn)
+        end_period = end_period_ym or period_of(local_today(tz), start_day)
+        end_month, _, _ = parse_period(end_period)
+        periods = [format_period(add_months(end_month, offset)) for offset in range(1 - months, 1)]
+        _, window_start_ts, _ = parse_period(periods[0], start_day=start_day, tz=tz)
+        _, _, window_end_ts = parse_period(periods[-1], start_day=start_day, tz=tz)
+
+        categories = [_load_category(conn, category_id) for category_id in dict.fromkeys(category_ids)]
+        placeholders = ", ".join("?" for _ in categories)
+        # Same spending definition as the budget report, so a trend bar and a budget line agree.
+        if top_transactions == 0 and start_day == 1 and tz is None:
+            rows = conn.execute(
+                f"""
+                SELECT NULL AS id, category_id, amount_cents, period_ym
+                FROM monthly_category_totals
+                WHERE category_id IN ({placeholders})
+                  AND accrual_type = 'Flow'
+                  AND is_asset_purchase = 0
+                  AND period_ym >= ?
+                  AND period_ym <= ?
+                """,
+                (*[category.id for category in categories], periods[0], periods[-1]),
+            ).fetchall()
+        else:
+            rows = conn.execute(
+                f"""
+                SELECT id, category_id, amount_cents, {transaction_period_sql(start_day, tz=tz)} AS period_ym
+                FROM transactions
+                WHERE category_id IN ({placeholders})
+                  AND accrual_type = 'Flow'
+                  AND is_asset_purchase = 0
+                  AND deleted_at IS NULL
+                  AND occurred_at >= ?
+                  AND occurred_at < ?
+                """,
+                (*[category.id for category in categories], window_start_ts, window_end_ts),
+            ).fetchall()
+
+    series: list[CategoryTrendSeriesDto] = []
+    for category in categories:
+        points: list[TrendPointDto] = []
+        for period_ym in periods:
+            members = [row for row in rows if row["category_id"] == category.id and row["period_ym"] == period_ym]
+            top_ids = None
+            if top_transactions > 0:
+                ranked = sorted(members, key=lambda row: (-int(row["amount_cents"]), row["id"]))
+                top_ids = [row["id"] for row in ranked[:top_transactions]]
+            points.append(
+                TrendPointDto(
+                    periodYm=period_ym,
+                    amountCents=sum(int(row["amount_cents"]) for row in members),
+                    topTransactionIds=top_ids,
+                )
+            )
+        series.append(CategoryTrendSeriesDto(categoryId=category.id, label=category.name, points=points))
+
+    return CategoryTrendDto(fromPeriodYm=periods[0], toPeriodYm=periods[-1], series=series)
+
+
+# The longer of the two trailing windows; the three-month one is its tail.
+ROLLING_WINDOW_MONTHS = 6
+
+
+def get_rolling_average(end_period_ym: Optional[str] = None) -> RollingAverageReportDto:
+    """Average monthly spend per category over the trailing three and six periods.
+
+    Without `end_period_ym` the window ends at the last complete period, so a half-spent
+    current month does not drag the averages down.
+    """
+    if end_period_ym is not None:
+        parse_period(end_period_ym)
+
+    with get_connection() as conn:
+        start_day = get_period_start_day(conn)
+        tz = get_timezone(conn)
+        if end_period_ym is None:
+            current, _, _ = parse_period(period_of(local_today(tz), start_day))
+            end_period_ym = format_period(add_months(current, -1))
+        end_month, _, _ = parse_period(end_period_ym)
+        periods = [format_period(add_months(end_month, offset)) for offset in range(1 - ROLLING_WINDOW_MONTHS, 1)]
+
+        if start_day == 1 and tz is None:
+            rows = conn.execute(
+                """
+                SELECT NULLIF(m.category_id, '') AS category_id, c.name, m.period_ym, SUM(m.amount_cents) AS total
+                FROM monthly_category_totals m
+                LEFT JOIN categories c ON m.category_id = c.id
+                WHERE m.accrual_type = 'Flow' AND m.is_asset_purchase = 0
+                  AND m.period_ym >= ? AND m.period_ym <= ?
+                GROUP BY m.category_id, m.period_ym
+                """,
+                (periods[0], periods[-1]),
+            ).fetchall()
+        else:
+            _, window_start_ts, _ = parse_period(periods[0], start_day=start_day, tz=tz)
+            _, _, window_end_ts = parse_period(periods[-1], start_day=start_day, tz=tz)
+            rows = conn.execute(
+                f"""
+                SELECT t.category_id, c.name, {transaction_period_sql(start_day, "t", tz)} AS period_ym,
+                       SUM(t.amount_cents) AS total
+                FROM transactions t
+                LEFT JOIN categories c ON t.category_id = c.id
+                WHERE t.accrual_type = 'Flow' AND t.is_asset_purchase = 0 AND t.deleted_at IS NULL
+                  AND t.occurred_at >= ? AND t.occurred_at < ?
+                GROUP BY t.category_id, period_ym
+                """,
+                (window_start_ts, window_end_ts),
+            ).fetchall()
+
+    labels: dict[Optional[str], str] = {}
+    spent: dict[Optional[str], dict[str, int]] = {}
+    for row in rows:
+        labels[row["category_id"]] = row["name"] or "Uncategorized"
+        spent.setdefault(row["category_id"], {})[row["period_ym"]] = int(row["total"])
+
+    def average(by_period: dict[str, int], months: int) -> int:
+        return round(sum(by_period.get(period_ym, 0) for period_ym in periods[-months:]) / months)
+
+    overall = {period_ym: sum(values.get(period_ym, 0) for values in spent.values()) for period_ym in periods}
+    items = [
+        RollingAverageItemDto(
+            categoryId=category_id,
+            label=labels[category_id],
+            threeMonthAverageCents=average(by_period, 3),
+            sixMonthAverageCents=average(by_period, 6),
+        )
+        for category_id, by_period in spent.items()
+    ]
+    items.sort(key=lambda item: (-item.sixMonthAverageCents, item.label))
+    return RollingAverageReportDto(
+        fromPeriodYm=periods[0],
+        toPeriodYm=periods[-1],
+        threeMonthAverageCents=average(overall, 3),
+        sixMonthAverageCents=average(overall, 6),
+        items=items,
+    )
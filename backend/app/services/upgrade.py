@@ -0,0 +1,286 @@
+from __future__ import annotations
+
+import sqlite3
+from dataclasses import dataclass
+from typing import Callable, Optional
+
+from app.db import get_connection, insert_postings, transaction, two_leg_postings
+from app.models import ApiError, UpgradeConversionInput, UpgradeConversionResultDto, UpgradeFindingDto
+from app.services.finance import _book_opening_balance, _opening_balance_date
+
+
+@dataclass(frozen=True)
+class UpgradeCheck:
+    check_id: str
+    description: str
+    detect: Callable[[sqlite3.Connection], list[str]]
+    convert: Optional[Callable[[sqlite3.Connection, list[str]], None]] = None
+
+
+def _ids(conn: sqlite3.Connection, sql: str) -> list[str]:
+    return [row[0] for row in conn.execute(sql).fetchall()]
+
+
+def _detect_unflagged_asset_purchases(conn: sqlite3.Connection) -> list[str]:
+    return _ids(
+        conn,
+        """
+        SELECT t.id
+        FROM transactions t
+        JOIN amortization_schedules s ON s.source_transaction_id = t.id
+        WHERE t.is_asset_purchase = 0
+        ORDER BY t.occurred_at ASC
+        """,
+    )
+
+
+def _convert_unflagged_asset_purchases(conn: sqlite3.Connection, ids: list[str]) -> None:
+    conn.executemany("UPDATE transactions SET is_asset_purchase = 1 WHERE id = ?", [(tx_id,) for tx_id in ids])
+
+
+def _detect_unlinked_depreciation(conn: sqlite3.Connection) -> list[str]:
+    return _ids(
+        conn,
+        """
+        SELECT t.id
+        FROM transactions t
+        WHERE t.accrual_type = 'Depreciation'
+          AND NOT EXISTS (SELECT 1 FROM amortization_postings p WHERE p.transaction_id = t.id)
+        ORDER BY t.occurred_at ASC
+        """,
+    )
+
+
+def _split_transfer_pairs(conn: sqlite3.Connection) -> list[tuple[str, str]]:
+    # Before transfers were kept out of income and expense, moving money between two of the
+    # user's accounts was entered as an uncategorized outflow and a matching inflow.
+    rows = conn.execute(
+        """
+        SELECT t.id, t.amount_cents, t.from_account_id, t.to_account_id, substr(t.occurred_at, 1, 10) AS day,
+               a.currency
+        FROM transactions t
+        JOIN accounts a ON a.id = COALESCE(t.from_account_id, t.to_account_id)
+        WHERE t.accrual_type = 'Flow' AND t.deleted_at IS NULL AND t.is_asset_purchase = 0
+          AND t.category_id IS NULL
+          AND (t.from_account_id IS NULL) != (t.to_account_id IS NULL)
+        ORDER BY t.occurred_at ASC, t.created_at ASC
+        """
+    ).fetchall()
+    inflows: dict[tuple[int, str, str], list[sqlite3.Row]] = {}
+    for row in rows:
+        if row["to_account_id"] is not None:
+            inflows.setdefault((row["amount_cents"], row["day"], row["currency"]), []).append(row)
+    pairs: list[tuple[str, str]] = []
+    for row in rows:
+        if row["from_account_id"] is None:
+            continue
+        candidates = inflows.get((row["amount_cents"], row["day"], row["currency"]), [])
+        match = next((item for item in candidates if item["to_account_id"] != row["from_account_id"]), None)
+        if match is not None:
+            candidates.remove(match)
+            pairs.append((row["id"], match["id"]))
+    return pairs
+
+
+def _detect_split_transfers(conn: sqlite3.Connection) -> list[str]:
+    return [tx_id for pair in _split_transfer_pairs(conn) for tx_id in pair]
+
+
+def _convert_split_transfers(conn: sqlite3.Connection, ids: list[str]) -> None:
+    # The outflow becomes the transfer and takes over the inflow's account, tags and
+    # attachments; the inflow goes. Both accounts end where they were.
+    for outflow_id, inflow_id in _split_transfer_pairs(conn):
+        if outflow_id not in ids or inflow_id not in ids:
+            continue
+        to_account_id = conn.execute(
+            "SELECT to_account_id FROM transactions WHERE id = ?", (inflow_id,)
+        ).fetchone()[0]
+        conn.execute("UPDATE transactions SET to_account_id = ? WHERE id = ?", (to_account_id, outflow_id))
+        conn.execute(
+            "UPDATE transaction_postings SET account_id = ? WHERE transaction_id = ? AND account_id IS NULL",
+            (to_account_id, outflow_id),
+        )
+        conn.execute(
+            "UPDATE OR IGNORE transaction_tags SET transaction_id = ? WHERE transaction_id = ?", (outflow_id, inflow_id)
+        )
+        conn.execute(
+            "UPDATE transaction_attachments SET transaction_id = ? WHERE transaction_id = ?", (outflow_id, inflow_id)
+        )
+        conn.execute("DELETE FROM transactions WHERE id = ?", (inflow_id,))
+
+
+def _detect_depreciation_on_accounts(conn: sqlite3.Connection) -> list[str]:
+    return _ids(
+        conn,
+        """
+        SELECT id FROM transactions
+        WHERE accrual_type = 'Depreciation'
+          AND (from_account_id IS NOT NULL OR to_account_id IS NOT NULL)
+        ORDER BY occurred_at ASC
+        """,
+    )
+
+
+def _convert_depreciation_on_accounts(conn: sqlite3.Connection, ids: list[str]) -> None:
+    # Depreciation never moved a balance, so dropping the accounts changes no balance.
+    conn.executemany(
+        "UPDATE transactions SET from_account_id = NULL, to_account_id = NULL WHERE id = ?",
+        [(tx_id,) for tx_id in ids],
+    )
+
+
+def _detect_undated_disposals(conn: sqlite3.Connection) -> list[str]:
+    # The split-off part of a disposed asset is the cancelled schedule that came after the
+    # asset's original one.
+    return _ids(
+        conn,
+        """
+        SELECT s.id
+        FROM amortization_schedules s
+        WHERE s.status = 'Cancelled' AND s.disposed_at IS NULL
+          AND EXISTS (
+            SELECT 1 FROM amortization_schedules o
+            WHERE o.source_transaction_id = s.source_transaction_id AND o.rowid < s.rowid
+          )
+        ORDER BY s.rowid ASC
+        """,
+    )
+
+
+def _convert_undated_disposals(conn: sqlite3.Connection, ids: list[str]) -> None:
+    conn.executemany(
+        "UPDATE amortization_schedules SET disposed_at = created_at WHERE id = ?",
+        [(schedule_id,) for schedule_id in ids],
+    )
+
+
+def _detect_unbooked_opening_balances(conn: sqlite3.Connection) -> list[str]:
+    return _ids(
+        conn,
+        "SELECT id FROM accounts WHERE opening_balance_cents != 0 ORDER BY created_at ASC",
+    )
+
+
+def _convert_unbooked_opening_balances(conn: sqlite3.Connection, ids: list[str]) -> None:
+    for account_id in ids:
+        row = conn.execute(
+            "SELECT opening_balance_cents, created_at FROM accounts WHERE id = ?", (account_id,)
+        ).fetchone()
+        occurred_at = _opening_balance_date(conn, account_id, row["created_at"])
+        _book_opening_balance(conn, account_id, int(row["opening_balance_cents"]), occurred_at)
+
+
+def _detect_missing_postings(conn: sqlite3.Connection) -> list[str]:
+    return _ids(
+        conn,
+        """
+        SELECT t.id
+        FROM transactions t
+        WHERE t.accrual_type != 'Depreciation'
+          AND NOT EXISTS (SELECT 1 FROM transaction_postings p WHERE p.transaction_id = t.id)
+        ORDER BY t.occurred_at ASC
+        """,
+    )
+
+
+def _convert_missing_postings(conn: sqlite3.Connection, ids: list[str]) -> None:
+    # Their balances already moved when they were written; only the legs are missing.
+    for tx_id in ids:
+        row = conn.execute(
+            "SELECT from_account_id, to_account_id, amount_cents FROM transactions WHERE id = ?", (tx_id,)
+        ).fetchone()
+        insert_postings(conn, tx_id, two_leg_postings(row[0], row[1], int(row[2])))
+
+
+# Each check describes rows written under semantics that later versions changed.
+# Checks without a converter are report-only and need a manual decision.
+UPGRADE_CHECKS: list[UpgradeCheck] = [
+    UpgradeCheck(
+        check_id="unflagged_asset_purchase",
+        description="Asset purchase source transactions not flagged as asset purchases; "
+        "they are counted as utility expense on top of their depreciation.",
+        detect=_detect_unflagged_asset_purchases,
+        convert=_convert_unflagged_asset_purchases,
+    ),
+    UpgradeCheck(
+        check_id="unlinked_depreciation",
+        description="Depreciation transactions without an amortization schedule posting; "
+        "they cannot be traced back to an asset.",
+        detect=_detect_unlinked_depreciation,
+    ),
+    UpgradeCheck(
+        check_id="split_transfer",
+        description="Transfers entered as an uncategorized outflow and a matching inflow; "
+        "they are counted as both expense and income instead of as one transfer.",
+        detect=_detect_split_transfers,
+        convert=_convert_split_transfers,
+    ),
+    UpgradeCheck(
+        check_id="depreciation_on_account",
+        description="Depreciation transactions that name an account; "
+        "depreciation reaches an asset through its schedule, so the accounts on the row are ignored.",
+        detect=_detect_depreciation_on_accounts,
+        convert=_convert_depreciation_on_accounts,
+    ),
+    UpgradeCheck(
+        check_id="undated_disposal",
+        description="Schedules split off by an asset disposal without a disposal date; "
+        "their depreciation is still taken off the asset's book value.",
+        detect=_detect_undated_disposals,
+        convert=_convert_undated_disposals,
+    ),
+    UpgradeCheck(
+        check_id="unbooked_opening_balance",
+        description="Accounts whose opening balance is kept outside the ledger; "
+        "their register and net worth history start from zero.",
+        detect=_detect_unbooked_opening_balances,
+        convert=_convert_unbooked_opening_balances,
+    ),
+    UpgradeCheck(
+        check_id="missing_postings",
+        description="Transactions without postings; reports and balances read from postings leave them out.",
+        detect=_detect_missing_postings,
+        convert=_convert_missing_postings,
+    ),
+]
+
+
+def _find_check(check_id: str) -> UpgradeCheck:
+    for check in UPGRADE_CHECKS:
+        if check.check_id == check_id:
+            return check
+    raise ApiError("not_found", f"upgrade check not found: {check_id}", status_code=404)
+
+
+def scan_legacy_data() -> list[UpgradeFindingDto]:
+    with get_connection() as conn:
+        findings: list[UpgradeFindingDto] = []
+        for check in UPGRADE_CHECKS:
+            row_ids = check.detect(conn)
+            if row_ids:
+                findings.append(
+                    UpgradeFindingDto(
+                        checkId=check.check_id,
+                        description=check.description,
+                        convertible=check.convert is not None,
+                        rowIds=row_ids,
+                    )
+                )
+        return findings
+
+
+def convert_legacy_data(input_data: UpgradeConversionInput) -> list[UpgradeConversionResultDto]:
+    checks = [_find_check(check_id) for check_id in input_data.checkIds]
+    for check in checks:
+        if check.convert is None:
+            raise ApiError("invalid_input", f"upgrade check is report-only: {check.check_id}")
+
+    results: list[UpgradeConversionResultDto] = []
+    with get_connection() as conn:
+        with transaction(conn):
+            for check in checks:
+                row_ids = check.detect(conn)
+                if row_ids:
+                    check.convert(conn, row_ids)
+                results.append(UpgradeConversionResultDto(checkId=check.check_id, convertedCount=len(row_ids)))
+    return results
@@ -0,0 +1,65 @@
+from __future__ import annotations
+
+from datetime import datetime, timedelta, timezone
+
+from app.db import get_connection, parse_rfc3339_utc
+from app.models import UsageScoreDto, UsageStatsDto
+
+
+USAGE_HALF_LIFE_DAYS = 30
+USAGE_LOOKBACK_DAYS = 365
+
+
+def _rank(scores: dict[str, list[float]], last_used: dict[str, str]) -> list[UsageScoreDto]:
+    ranked = [
+        UsageScoreDto(id=item_id, score=round(sum(weights), 4), useCount=len(weights), lastUsedAt=last_used[item_id])
+        for item_id, weights in scores.items()
+    ]
+    ranked.sort(key=lambda item: (-item.score, item.id))
+    return ranked
+
+
+def get_usage_stats() -> UsageStatsDto:
+    """Each use counts 1.0 today and half as much every `USAGE_HALF_LIFE_DAYS` after.
+
+    Only entries the user typed in count: adjustments and depreciation are booked by the
+    app itself and would push reconciled or amortized accounts up the list.
+    """
+    now = datetime.now(timezone.utc)
+    since = (now - timedelta(days=USAGE_LOOKBACK_DAYS)).isoformat().replace("+00:00", "Z")
+
+    with get_connection() as conn:
+        rows = conn.execute(
+            """
+            SELECT
+              category_id,
+              occurred_at,
+              (SELECT GROUP_CONCAT(account_id) FROM transaction_postings p WHERE p.transaction_id = transactions.id)
+                AS account_ids
+            FROM transactions
+            WHERE accrual_type = 'Flow' AND deleted_at IS NULL AND occurred_at >= ?
+            ORDER BY occurred_at ASC
+            """,
+            (since,),
+        ).fetchall()
+
+    account_scores: dict[str, list[float]] = {}
+    category_scores: dict[str, list[float]] = {}
+    account_last_used: dict[str, str] = {}
+    category_last_used: dict[str, str] = {}
+    for row in rows:
+        age_days = max((now - parse_rfc3339_utc(row["occurred_at"])).total_seconds() / 86400, 0.0)
+        weight = 0.5 ** (age_days / USAGE_HALF_LIFE_DAYS)
+        used_accounts = set((row["account_ids"] or "").split(",")) - {""}
+        for account_id in used_accounts:
+            account_scores.setdefault(account_id, []).append(weight)
+            account_last_used[account_id] = row["occurred_at"]
+        if row["category_id"] is not None:
+            category_scores.setdefault(row["category_id"], []).append(weight)
+            category_last_used[row["category_id"]] = row["occurred_at"]
+
+    return UsageStatsDto(
+        halfLifeDays=USAGE_HALF_LIFE_DAYS,
+        accounts=_rank(account_scores, account_last_used),
+        categories=_rank(category_scores, category_last_used),
+    )
@@ -0,0 +1,280 @@
+from __future__ import annotations
+
+import base64
+import binascii
+import sqlite3
+import uuid
+from pathlib import Path
+
+from app.config import get_data_dir
+from app.db import get_connection, normalize_timestamp, now_utc_rfc3339, parse_date_ymd, transaction
+from app.models import (
+    AccountPhotoDto,
+    AccountType,
+    AccountValuationDto,
+    AccountValuationTimelineDto,
+    ApiError,
+    CreateAccountValuationInput,
+    CreateMaintenanceEventInput,
+    MaintenanceEventDto,
+    UploadAccountPhotoInput,
+    ensure_amount_in_range,
+)
+from app.services.finance import _load_account, _transaction_from_row
+
+
+PHOTO_CONTENT_TYPES = {
+    ".jpg": "image/jpeg",
+    ".jpeg": "image/jpeg",
+    ".png": "image/png",
+    ".webp": "image/webp",
+    ".heic": "image/heic",
+}
+MAX_PHOTO_BYTES = 10 * 1024 * 1024
+
+PHOTO_COLUMNS = "id, account_id, valuation_id, file_name, content_type, size_bytes, created_at"
+
+
+def _photos_dir() -> Path:
+    return get_data_dir() / "photos"
+
+
+def _photo_from_row(row: sqlite3.Row) -> AccountPhotoDto:
+    return AccountPhotoDto(
+        id=row["id"],
+        accountId=row["account_id"],
+        valuationId=row["valuation_id"],
+        fileName=row["file_name"],
+        contentType=row["content_type"],
+        sizeBytes=row["size_bytes"],
+        createdAt=row["created_at"],
+    )
+
+
+def _valuation_from_row(row: sqlite3.Row, photo_ids: list[str]) -> AccountValuationDto:
+    return AccountValuationDto(
+        id=row["id"],
+        accountId=row["account_id"],
+        valueCents=row["value_cents"],
+        note=row["note"],
+        valuedAt=row["valued_at"],
+        photoIds=photo_ids,
+    )
+
+
+def _load_valued_account(conn: sqlite3.Connection, account_id: str) -> None:
+    account = _load_account(conn, account_id)
+    if account.accountType != AccountType.ASSET:
+        raise ApiError("invalid_input", "valuations are only tracked for asset accounts")
+
+
+def list_account_valuations(account_id: str) -> AccountValuationTimelineDto:
+    with get_connection() as conn:
+        _load_valued_account(conn, account_id)
+        photos = [
+            _photo_from_row(row)
+            for row in conn.execute(
+                f"SELECT {PHOTO_COLUMNS} FROM account_photos WHERE account_id = ? ORDER BY created_at ASC",
+                (account_id,),
+            ).fetchall()
+        ]
+        valuation_rows = conn.execute(
+            """
+            SELECT id, account_id, value_cents, note, valued_at
+            FROM account_valuations
+            WHERE account_id = ?
+            ORDER BY valued_at ASC, created_at ASC
+            """,
+            (account_id,),
+        ).fetchall()
+        adjustment_rows = conn.execute(
+            """
+            SELECT id, amount_cents, from_account_id, to_account_id, payee_id, category_id,
+                   accrual_type, is_asset_purchase, note, occurred_at, created_at, status, device_id
+            FROM transactions
+            WHERE accrual_type = 'Adjustment' AND deleted_at IS NULL AND ? IN (from_account_id, to_account_id)
+            ORDER BY occurred_at ASC, created_at ASC
+            """,
+            (account_id,),
+        ).fetchall()
+
+    return AccountValuationTimelineDto(
+        accountId=account_id,
+        valuations=[
+            _valuation_from_row(row, [photo.id for photo in photos if photo.valuationId == row["id"]])
+            for row in valuation_rows
+        ],
+        photos=photos,
+        adjustments=[_transaction_from_row(row) for row in adjustment_rows],
+    )
+
+
+def create_account_valuation(account_id: str, input_data: CreateAccountValuationInput) -> AccountValuationDto:
+    if input_data.valueCents < 0:
+        raise ApiError("invalid_input", "valueCents must be >= 0")
+    ensure_amount_in_range(input_data.valueCents, "valueCents")
+    valued_at = normalize_timestamp(input_data.valuedAt)
+
+    valuation_id = str(uuid.uuid4())
+    with get_connection() as conn:
+        _load_valued_account(conn, account_id)
+        with transaction(conn):
+            conn.execute(
+                """
+                INSERT INTO account_valuations (id, account_id, value_cents, note, valued_at, created_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                """,
+                (valuation_id, account_id, input_data.valueCents, input_data.note, valued_at, now_utc_rfc3339()),
+            )
+        row = conn.execute(
+            "SELECT id, account_id, value_cents, note, valued_at FROM account_valuations WHERE id = ?",
+            (valuation_id,),
+        ).fetchone()
+        return _valuation_from_row(row, [])
+
+
+MAINTENANCE_EVENT_COLUMNS = "id, account_id, occurred_on, cost_cents, note, transaction_id, created_at"
+
+
+def _maintenance_event_from_row(row: sqlite3.Row) -> MaintenanceEventDto:
+    return MaintenanceEventDto(
+        id=row["id"],
+        accountId=row["account_id"],
+        occurredOn=row["occurred_on"],
+        costCents=row["cost_cents"],
+        note=row["note"],
+        transactionId=row["transaction_id"],
+        createdAt=row["created_at"],
+    )
+
+
+def _load_maintained_account(conn: sqlite3.Connection, account_id: str) -> None:
+    account = _load_account(conn, account_id)
+    if account.accountType != AccountType.ASSET:
+        raise ApiError("invalid_input", "maintenance is only tracked for asset accounts")
+
+
+def list_maintenance_events(account_id: str) -> list[MaintenanceEventDto]:
+    with get_connection() as conn:
+        _load_maintained_account(conn, account_id)
+        rows = conn.execute(
+            f"""
+            SELECT {MAINTENANCE_EVENT_COLUMNS}
+            FROM maintenance_events
+            WHERE account_id = ?
+            ORDER BY occurred_on ASC, created_at ASC
+            """,
+            (account_id,),
+        ).fetchall()
+    return [_maintenance_event_from_row(row) for row in rows]
+
+
+def create_maintenance_event(account_id: str, input_data: CreateMaintenanceEventInput) -> MaintenanceEventDto:
+    if input_data.costCents < 0:
+        raise ApiError("invalid_input", "costCents must be >= 0")
+    ensure_amount_in_range(input_data.costCents, "costCents")
+    parse_date_ymd(input_data.occurredOn, "occurredOn")
+
+    event_id = str(uuid.uuid4())
+    with get_connection() as conn:
+        _load_maintained_account(conn, account_id)
+        if input_data.transactionId is not None:
+            linked = conn.execute(
+                "SELECT id FROM transactions WHERE id = ? AND deleted_at IS NULL", (input_data.transactionId,)
+            ).fetchone()
+            if linked is None:
+                raise ApiError("not_found", f"transaction not found: {input_data.transactionId}", status_code=404)
+
+        with transaction(conn):
+            conn.execute(
+                """
+                INSERT INTO maintenance_events (
+                    id, account_id, occurred_on, cost_cents, note, transaction_id, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?)
+                """,
+                (
+                    event_id,
+                    account_id,
+                    input_data.occurredOn,
+                    input_data.costCents,
+                    input_data.note,
+                    input_data.transactionId,
+                    now_utc_rfc3339(),
+                ),
+            )
+        row = conn.execute(
+            f"SELECT {MAINTENANCE_EVENT_COLUMNS} FROM maintenance_events WHERE id = ?",
+            (event_id,),
+        ).fetchone()
+        return _maintenance_event_from_row(row)
+
+
+def upload_account_photo(account_id: str, input_data: UploadAccountPhotoInput) -> AccountPhotoDto:
+    file_name = Path(input_data.fileName).name
+    suffix = Path(file_name).suffix.lower()
+    content_type = PHOTO_CONTENT_TYPES.get(suffix)
+    if content_type is None:
+        raise ApiError("invalid_input", f"unsupported photo type: {suffix or file_name}")
+    try:
+        content = base64.b64decode(input_data.contentBase64, validate=True)
+    except (binascii.Error, ValueError) as exc:
+        raise ApiError("invalid_input", "contentBase64 is not valid base64") from exc
+    if not content:
+        raise ApiError("invalid_input", "photo is empty")
+    if len(content) > MAX_PHOTO_BYTES:
+        raise ApiError("invalid_input", f"photo exceeds {MAX_PHOTO_BYTES} bytes")
+
+    photo_id = str(uuid.uuid4())
+    with get_connection() as conn:
+        _load_valued_account(conn, account_id)
+        if input_data.valuationId is not None:
+            valuation = conn.execute(
+                "SELECT id FROM account_valuations WHERE id = ? AND account_id = ?",
+                (input_data.valuationId, account_id),
+            ).fetchone()
+            if valuation is None:
+                raise ApiError("not_found", f"valuation not found: {input_data.valuationId}", status_code=404)
+
+        photos_dir = _photos_dir()
+        photos_dir.mkdir(parents=True, exist_ok=True)
+        stored = photos_dir / f"{photo_id}{suffix}"
+        stored.write_bytes(content)
+        try:
+            with transaction(conn):
+                conn.execute(
+                    """
+                    INSERT INTO account_photos (
+                        id, account_id, valuation_id, file_name, content_type, size_bytes, stored_name, created_at
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                    """,
+                    (
+                        photo_id,
+                        account_id,
+                        input_data.valuationId,
+                        file_name,
+                        content_type,
+                        len(content),
+                        stored.name,
+                        now_utc_rfc3339(),
+                    ),
+                )
+        except Exception:
+            stored.unlink(missing_ok=True)
+            raise
+
+        row = conn.execute(f"SELECT {PHOTO_COLUMNS} FROM account_photos WHERE id = ?", (photo_id,)).fetchone()
+        return _photo_from_row(row)
+
+
+def get_account_photo_file(account_id: str, photo_id: str) -> tuple[Path, str]:
+    with get_connection() as conn:
+        row = conn.execute(
+            "SELECT stored_name, content_type FROM account_photos WHERE id = ? AND account_id = ?",
+            (photo_id, account_id),
+        ).fetchone()
+    if row is None:
+        raise ApiError("not_found", f"photo not found: {photo_id}", status_code=404)
+    path = _photos_dir() / row["stored_name"]
+    if not path.is_file():
+        raise ApiError("not_found", f"photo file is missing: {photo_id}", status_code=404)
+    return path, row["content_type"]
@@ -0,0 +1,78 @@
+from pathlib import Path
+from typing import Iterator
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app import migrations
+from app.main import app
+
+
+
+
+@pytest.fixture()
+def client(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Iterator[TestClient]:
+    monkeypatch.setenv("OIKONOMOS_DATA_DIR", str(tmp_path / ".oikonomos"))
+    monkeypatch.setenv("OIKONOMOS_CONFIG", str(tmp_path / "config.toml"))
+    monkeypatch.setenv("OIKONOMOS_DEFAULT_ADMIN_EMAIL", "admin@test.local")
+    monkeypatch.setenv("OIKONOMOS_DEFAULT_ADMIN_PASSWORD", "Secret123!")
+    # Tests run the background jobs themselves, so a scheduler thread cannot race them.
+    monkeypatch.setenv("OIKONOMOS_SCHEDULER_INTERVAL_SECONDS", "0")
+    with TestClient(app) as test_client:
+        # Startup backfills run in the background; tests start once writes are allowed.
+        migrations.wait_for_backfills()
+        yield test_client
+
+
+def auth_headers(client: TestClient) -> dict[str, str]:
+    response = client.post(
+        "/api/auth/login",
+        json={"email": "admin@test.local", "password": "Secret123!"},
+    )
+    assert response.status_code == 200
+    token = response.json()["accessToken"]
+    return {"Authorization": f"Bearer {token}"}
+
+
+def create_account(
+    client: TestClient,
+    headers: dict[str, str],
+    name: str,
+    balance_cents: int = 0,
+    account_type: str = "Asset",
+    purpose: str = "LifeSupport",
+    **extra: object,
+) -> dict:
+    response = client.post(
+        "/api/accounts",
+        json={
+            "name": name,
+            "accountType": account_type,
+            "purpose": purpose,
+            "initialBalanceCents": balance_cents,
+            **extra,
+        },
+        headers=headers,
+    )
+    assert response.status_code == 200, response.text
+    return response.json()
+
+
+def record_transaction(
+    client: TestClient,
+    headers: dict[str, str],
+    amount_cents: int,
+    occurred_at: str,
+    **extra: object,
+) -> dict:
+    response = client.post(
+        "/api/transactions",
+        json={"amountCents": amount_cents, "occurredAt": occurred_at, **extra},
+        headers=headers,
+    )
+    assert response.status_code == 200, response.text
+    return response.json()
+
+
+def balances(client: TestClient, headers: dict[str, str]) -> dict[str, int]:
+    return {account["id"]: account["balanceCents"] for account in client.get("/api/accounts", headers=headers).json()}
@@ -0,0 +1,153 @@
+from fastapi.testclient import TestClient
+
+from app.db import get_connection
+from conftest import auth_headers, balances, create_account, record_transaction
+
+
+def test_balance_history_unwinds_transactions_from_current_balance(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=10_000)
+    record_transaction(client, headers, 1_000, "2025-03-05T10:00:00Z", fromAccountId=wallet["id"])
+    record_transaction(client, headers, 5_000, "2025-03-20T10:00:00Z", toAccountId=wallet["id"])
+    record_transaction(client, headers, 500, "2025-04-02T10:00:00Z", fromAccountId=wallet["id"])
+
+    monthly = client.get(
+        f"/api/accounts/{wallet['id']}/balance-history",
+        params={"fromDate": "2025-02-01", "toDate": "2025-04-30", "granularity": "Month"},
+        headers=headers,
+    ).json()
+    assert [(point["date"], point["balanceCents"]) for point in monthly["points"]] == [
+        ("2025-02-28", 10_000),
+        ("2025-03-31", 14_000),
+        ("2025-04-30", 13_500),
+    ]
+
+    daily = client.get(
+        f"/api/accounts/{wallet['id']}/balance-history",
+        params={"fromDate": "2025-03-04", "toDate": "2025-03-06"},
+        headers=headers,
+    ).json()
+    assert [point["balanceCents"] for point in daily["points"]] == [10_000, 9_000, 9_000]
+
+
+def test_account_valuations_keep_photos_and_show_adjustments(client: TestClient) -> None:
+    headers = auth_headers(client)
+    car = create_account(client, headers, "Car", balance_cents=1_500_000, purpose="Productivity")
+    base = f"/api/accounts/{car['id']}"
+
+    valuation = client.post(
+        f"{base}/valuations",
+        json={"valueCents": 1_400_000, "valuedAt": "2025-03-01T00:00:00Z", "note": "dealer quote"},
+        headers=headers,
+    ).json()
+    photo = client.post(
+        f"{base}/photos",
+        json={"fileName": "front.jpg", "contentBase64": "aGVsbG8=", "valuationId": valuation["id"]},
+        headers=headers,
+    )
+    assert photo.status_code == 200
+    assert photo.json()["sizeBytes"] == 5
+    rejected = client.post(f"{base}/photos", json={"fileName": "notes.txt", "contentBase64": "aGk="}, headers=headers)
+    assert rejected.status_code == 400
+
+    client.post(
+        "/api/reconciliations",
+        json={"accountId": car["id"], "actualBalanceCents": 1_400_000, "occurredAt": "2025-03-01T00:00:00Z"},
+        headers=headers,
+    )
+
+    timeline = client.get(f"{base}/valuations", headers=headers).json()
+    assert [(item["valueCents"], item["note"]) for item in timeline["valuations"]] == [(1_400_000, "dealer quote")]
+    assert timeline["valuations"][0]["photoIds"] == [photo.json()["id"]]
+    assert [adjustment["amountCents"] for adjustment in timeline["adjustments"]] == [100_000]
+
+
+def test_set_account_balance_books_adjustment_without_snapshot(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=10_000)
+    cursor = max([event["seq"] for event in client.get("/api/events", headers=headers).json()], default=0)
+
+    result = client.post(
+        f"/api/accounts/{wallet['id']}/balance",
+        json={"newBalanceCents": 6_300, "occurredAt": "2025-03-31T00:00:00Z"},
+        headers=headers,
+    ).json()
+    assert result["deltaCents"] == -3_700
+    assert result["account"]["balanceCents"] == 6_300
+    adjustment = result["adjustmentTransaction"]
+    assert (adjustment["accrualType"], adjustment["amountCents"]) == ("Adjustment", 3_700)
+    assert adjustment["fromAccountId"] == wallet["id"]
+
+    events = client.get("/api/events", params={"afterSeq": cursor}, headers=headers).json()
+    assert [event["type"] for event in events] == ["transaction:created", "account:updated"]
+    with get_connection() as conn:
+        assert conn.execute("SELECT COUNT(*) FROM balance_snapshots").fetchone()[0] == 0
+
+    unchanged = client.post(f"/api/accounts/{wallet['id']}/balance", json={"newBalanceCents": 6_300}, headers=headers)
+    assert unchanged.json()["adjustmentTransaction"] is None
+
+
+def test_accounts_default_to_base_currency_and_respect_minor_units(client: TestClient) -> None:
+    headers = auth_headers(client)
+    early = create_account(client, headers, "Early")
+    assert early["currency"] is None
+
+    account = {"name": "Wallet", "accountType": "Asset", "purpose": "LifeSupport", "initialBalanceCents": 3_700_00}
+    onboarded = client.post("/api/onboarding", json={"baseCurrency": "jpy", "accounts": [account]}, headers=headers)
+    assert onboarded.json()["accounts"][0]["currency"] == "JPY"
+    assert {a["name"]: a["currency"] for a in client.get("/api/accounts", headers=headers).json()} == {
+        "Early": "JPY",
+        "Wallet": "JPY",
+    }
+
+    fractional_yen = client.post(
+        "/api/accounts", json={**account, "name": "Coins", "initialBalanceCents": 1_050}, headers=headers
+    )
+    assert fractional_yen.status_code == 400
+    assert fractional_yen.json()["details"]["currency"] == "JPY"
+    assert create_account(client, headers, "Euro cash", 1_050, currency="eur")["currency"] == "EUR"
+
+    unknown = client.post("/api/accounts", json={**account, "name": "Play", "currency": "XYZ"}, headers=headers)
+    assert unknown.status_code == 400
+    # Three-decimal currencies would need thousandths, which amounts cannot hold.
+    for code in ("BHD", "JOD", "KWD", "OMR", "TND"):
+        response = client.post("/api/accounts", json={**account, "name": code, "currency": code}, headers=headers)
+        assert response.status_code == 400, code
+        rate = {"fromCurrency": code, "toCurrency": "JPY", "rate": 480.0, "rateDate": "2026-01-02"}
+        assert client.post("/api/exchange-rates", json=rate, headers=headers).status_code == 400
+
+
+def test_opening_balances_can_be_booked_as_transactions(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(
+        client, headers, "Wallet", 10_000, recordOpeningBalance=True, openedAt="2025-03-01T00:00:00Z"
+    )
+    savings = create_account(client, headers, "Savings", 5_000)
+    transfer = {"fromAccountId": wallet["id"], "toAccountId": savings["id"]}
+    record_transaction(client, headers, 1_000, "2025-03-05T10:00:00Z", **transfer)
+
+    march = client.get("/api/transactions", params={"periodYm": "2025-03"}, headers=headers).json()["items"]
+    assert [(tx["accrualType"], tx["amountCents"]) for tx in march] == [("Flow", 1_000), ("OpeningBalance", 10_000)]
+    history = client.get(
+        f"/api/accounts/{wallet['id']}/balance-history",
+        params={"fromDate": "2025-02-01", "toDate": "2025-03-31", "granularity": "Month"},
+        headers=headers,
+    ).json()
+    assert [point["balanceCents"] for point in history["points"]] == [0, 9_000]
+    cash = client.get("/api/reports/cash", params={"periodYm": "2025-03"}, headers=headers).json()
+    assert cash["totalIncomeCents"] == 0
+    manual = client.post(
+        "/api/transactions",
+        json={"amountCents": 100, "toAccountId": wallet["id"], "accrualType": "OpeningBalance"},
+        headers=headers,
+    )
+    assert manual.status_code == 400
+
+    booked = client.post(f"/api/accounts/{savings['id']}/opening-balance", json={}, headers=headers).json()
+    assert (booked["amountCents"], booked["toAccountId"]) == (5_000, savings["id"])
+    assert booked["occurredAt"] == "2025-03-05T10:00:00Z"
+    assert balances(client, headers) == {wallet["id"]: 9_000, savings["id"]: 6_000}
+    again = client.post(f"/api/accounts/{savings['id']}/opening-balance", json={}, headers=headers)
+    assert again.status_code == 400
+    rebuilt = client.post("/api/maintenance/rebuild-balances", json={"dryRun": True}, headers=headers).json()
+    assert rebuilt["discrepancies"] == []
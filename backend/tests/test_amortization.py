@@ -0,0 +1,598 @@
+from fastapi.testclient import TestClient
+
+from app.services import amortization as amortization_service
+from conftest import auth_headers, balances, create_account, record_transaction
+
+
+def test_list_amortization_schedules_reports_progress(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    camera = create_account(client, headers, "Camera", purpose="Spiritual")
+    bike = create_account(client, headers, "Bike", purpose="Productivity")
+    for asset, periods in ((camera, 12), (bike, 2)):
+        purchase = {
+            "fromAccountId": wallet["id"],
+            "assetAccountId": asset["id"],
+            "amountCents": 12_000,
+            "occurredAt": "2025-01-01T00:00:00Z",
+            "strategy": "Linear",
+            "totalPeriods": periods,
+            "residualCents": 0,
+            "startDate": "2025-01-01",
+        }
+        client.post("/api/asset-purchases", json=purchase, headers=headers)
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-03"}, headers=headers)
+
+    schedules = client.get("/api/amortization-schedules", params={"assetAccountId": camera["id"]}, headers=headers)
+    assert [(s["periodsPosted"], s["depreciatedCents"], s["remainingBookValueCents"]) for s in schedules.json()] == [
+        (3, 3_000, 9_000)
+    ]
+    completed = client.get("/api/amortization-schedules", params={"status": "Completed"}, headers=headers).json()
+    assert [(s["assetAccountId"], s["remainingBookValueCents"]) for s in completed] == [(bike["id"], 0)]
+
+
+def test_list_amortization_postings_links_each_period_to_its_transaction(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    camera = create_account(client, headers, "Camera", purpose="Spiritual")
+    purchase = {
+        "fromAccountId": wallet["id"],
+        "assetAccountId": camera["id"],
+        "amountCents": 12_000,
+        "occurredAt": "2025-01-01T00:00:00Z",
+        "strategy": "Linear",
+        "totalPeriods": 12,
+        "residualCents": 0,
+        "startDate": "2025-01-01",
+    }
+    schedule_id = client.post("/api/asset-purchases", json=purchase, headers=headers).json()["schedule"]["id"]
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-02"}, headers=headers)
+
+    postings = client.get(f"/api/amortization-schedules/{schedule_id}/postings", headers=headers).json()
+    assert [(p["periodYm"], p["amountCents"]) for p in postings] == [("2025-01", 1_000), ("2025-02", 1_000)]
+    depreciation = client.get("/api/transactions", params={"accrualType": "Depreciation"}, headers=headers).json()
+    assert {p["transactionId"] for p in postings} == {tx["id"] for tx in depreciation["items"]}
+    assert client.get("/api/amortization-schedules/missing/postings", headers=headers).status_code == 404
+
+
+def test_cancel_amortization_schedule_can_take_back_its_depreciation(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    schedule_ids = []
+    for name in ("Camera", "Lens"):
+        asset = create_account(client, headers, name, purpose="Spiritual")
+        purchase = {
+            "fromAccountId": wallet["id"],
+            "assetAccountId": asset["id"],
+            "amountCents": 12_000,
+            "occurredAt": "2025-01-01T00:00:00Z",
+            "strategy": "Linear",
+            "totalPeriods": 12,
+            "residualCents": 0,
+            "startDate": "2025-01-01",
+        }
+        created = client.post("/api/asset-purchases", json=purchase, headers=headers).json()
+        schedule_ids.append(created["schedule"]["id"])
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-02"}, headers=headers)
+
+    kept = client.post(f"/api/amortization-schedules/{schedule_ids[0]}/cancel", json={}, headers=headers).json()
+    assert (kept["schedule"]["status"], kept["schedule"]["periodsPosted"], kept["reversedTransactionIds"]) == (
+        "Cancelled",
+        2,
+        [],
+    )
+    undone = client.post(
+        f"/api/amortization-schedules/{schedule_ids[1]}/cancel", json={"reversePostings": True}, headers=headers
+    ).json()
+    assert (len(undone["reversedTransactionIds"]), undone["schedule"]["remainingBookValueCents"]) == (2, 12_000)
+    # The reversed rows stay on record in the trash, and the postings still show what was generated.
+    trashed = [item["transaction"]["id"] for item in client.get("/api/trash", headers=headers).json()]
+    assert sorted(trashed) == sorted(undone["reversedTransactionIds"])
+    postings = client.get(f"/api/amortization-schedules/{schedule_ids[1]}/postings", headers=headers).json()
+    assert sorted(posting["transactionId"] for posting in postings) == sorted(undone["reversedTransactionIds"])
+    again = client.post(f"/api/amortization-schedules/{schedule_ids[1]}/cancel", json={}, headers=headers)
+    assert again.status_code == 400
+
+    # March generates nothing for either cancelled schedule.
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-03"}, headers=headers)
+    params = {"fromDate": "2025-01-01", "toDate": "2025-03-31"}
+    utility = client.get("/api/reports/utility", params=params, headers=headers).json()
+    assert {item["label"]: item["amountCents"] for item in utility["items"]}["Depreciation"] == 2_000
+
+
+def test_dispose_part_of_an_asset_splits_its_schedule(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    monitors = create_account(client, headers, "Monitors", purpose="Spiritual")
+    purchase = {
+        "fromAccountId": wallet["id"],
+        "assetAccountId": monitors["id"],
+        "amountCents": 24_000,
+        "occurredAt": "2025-01-01T00:00:00Z",
+        "strategy": "Linear",
+        "totalPeriods": 12,
+        "residualCents": 0,
+        "startDate": "2025-01-01",
+    }
+    schedule_id = client.post("/api/asset-purchases", json=purchase, headers=headers).json()["schedule"]["id"]
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-02"}, headers=headers)
+
+    sale = {
+        "disposedPercent": 50,
+        "proceedsCents": 7_000,
+        "toAccountId": wallet["id"],
+        "occurredAt": "2025-03-01T12:00:00Z",
+    }
+    result = client.post(f"/api/amortization-schedules/{schedule_id}/dispose", json=sale, headers=headers).json()
+    kept, disposed = result["schedule"], result["disposedSchedule"]
+    assert (kept["purchaseAmountCents"], kept["depreciatedCents"], kept["status"]) == (12_000, 2_000, "Active")
+    # Half the book value (10_000) left for 7_000, so 3_000 is written off as depreciation.
+    assert (disposed["depreciatedCents"], disposed["remainingBookValueCents"], disposed["status"]) == (
+        5_000,
+        7_000,
+        "Cancelled",
+    )
+    accounts = client.get("/api/accounts", headers=headers).json()
+    balances = {account["id"]: account["balanceCents"] for account in accounts}
+    assert (balances[wallet["id"]], balances[monitors["id"]]) == (83_000, 12_000)
+    # Only the proceeds moved cash; the cost written off with the part is not spending.
+    for params in ({"periodYm": "2025-03"}, {"fromDate": "2025-03-01", "toDate": "2025-03-15"}):
+        cash_flow = client.get("/api/reports/cash", params=params, headers=headers).json()
+        assert (cash_flow["totalExpenseCents"], cash_flow["totalTransferCents"]) == (0, 7_000)
+
+    # The kept monitor depreciates at half the rate from March on.
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-04"}, headers=headers)
+    params = {"fromDate": "2025-01-01", "toDate": "2025-04-30"}
+    utility = client.get("/api/reports/utility", params=params, headers=headers).json()
+    assert {item["label"]: item["amountCents"] for item in utility["items"]}["Depreciation"] == 9_000
+    whole = client.post(
+        f"/api/amortization-schedules/{schedule_id}/dispose", json={"disposedPercent": 100}, headers=headers
+    )
+    assert whole.status_code == 400
+
+
+def test_catch_up_depreciation_posts_every_missed_month(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    for name, start in (("Camera", "2025-01-01"), ("Lens", "2025-03-01")):
+        asset = create_account(client, headers, name, purpose="Spiritual")
+        purchase = {
+            "fromAccountId": wallet["id"],
+            "assetAccountId": asset["id"],
+            "amountCents": 12_000,
+            "occurredAt": f"{start}T00:00:00Z",
+            "strategy": "Linear",
+            "totalPeriods": 12,
+            "residualCents": 0,
+            "startDate": start,
+        }
+        client.post("/api/asset-purchases", json=purchase, headers=headers)
+
+    first = client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-02"}, headers=headers).json()
+    assert [posting["periodYm"] for posting in first["postings"]] == ["2025-01", "2025-02"]
+    result = client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-04"}, headers=headers).json()
+    assert [posting["periodYm"] for posting in result["postings"]] == ["2025-03", "2025-03", "2025-04", "2025-04"]
+    again = client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-04"}, headers=headers).json()
+    assert again["postings"] == []
+
+
+def test_depreciation_is_generated_in_the_background_not_by_reports(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    camera = create_account(client, headers, "Camera", purpose="Spiritual")
+    purchase = {
+        "fromAccountId": wallet["id"],
+        "assetAccountId": camera["id"],
+        "amountCents": 12_000,
+        "occurredAt": "2025-01-01T00:00:00Z",
+        "strategy": "Linear",
+        "totalPeriods": 12,
+        "residualCents": 0,
+        "startDate": "2025-01-01",
+    }
+    client.post("/api/asset-purchases", json=purchase, headers=headers)
+    params = {"fromDate": "2025-01-01", "toDate": "2025-12-31"}
+    before = client.get("/api/reports/utility", params=params, headers=headers).json()
+    assert "Depreciation" not in {item["label"] for item in before["items"]}
+
+    cursor = max([event["seq"] for event in client.get("/api/events", headers=headers).json()], default=0)
+    assert amortization_service.generate_depreciation() == 12
+    assert amortization_service.generate_depreciation() == 0
+    events = client.get("/api/events", params={"afterSeq": cursor}, headers=headers).json()
+    progress = [event["payload"]["periodYm"] for event in events if event["type"] == "depreciation:progress"]
+    assert (progress[0], progress[-1], len(progress)) == ("2025-01", "2025-12", 12)
+    after = client.get("/api/reports/utility", params=params, headers=headers).json()
+    assert {item["label"]: item["amountCents"] for item in after["items"]}["Depreciation"] == 12_000
+
+
+def test_account_book_value_is_reduced_by_depreciation(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    monitors = create_account(client, headers, "Monitors", purpose="Spiritual")
+    purchase = {
+        "fromAccountId": wallet["id"],
+        "assetAccountId": monitors["id"],
+        "amountCents": 24_000,
+        "occurredAt": "2025-01-01T00:00:00Z",
+        "strategy": "Linear",
+        "totalPeriods": 12,
+        "residualCents": 0,
+        "startDate": "2025-01-01",
+    }
+    schedule_id = client.post("/api/asset-purchases", json=purchase, headers=headers).json()["schedule"]["id"]
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-02"}, headers=headers)
+
+    def values(account_id: str) -> tuple[int, int]:
+        accounts = {account["id"]: account for account in client.get("/api/accounts", headers=headers).json()}
+        return accounts[account_id]["balanceCents"], accounts[account_id]["bookValueCents"]
+
+    assert values(monitors["id"]) == (24_000, 20_000)
+    assert values(wallet["id"]) == (76_000, 76_000)
+
+    # The monitor sold took its share of the depreciation with it.
+    sale = {"disposedPercent": 50, "proceedsCents": 7_000, "toAccountId": wallet["id"]}
+    client.post(f"/api/amortization-schedules/{schedule_id}/dispose", json=sale, headers=headers)
+    assert values(monitors["id"]) == (12_000, 10_000)
+
+
+def test_revalue_asset_books_the_gap_and_rebases_remaining_depreciation(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    camera = create_account(client, headers, "Camera", purpose="Spiritual")
+    purchase = {
+        "fromAccountId": wallet["id"],
+        "assetAccountId": camera["id"],
+        "amountCents": 12_000,
+        "occurredAt": "2025-01-01T00:00:00Z",
+        "strategy": "Linear",
+        "totalPeriods": 12,
+        "residualCents": 0,
+        "startDate": "2025-01-01",
+    }
+    schedule_id = client.post("/api/asset-purchases", json=purchase, headers=headers).json()["schedule"]["id"]
+
+    # January to March are posted first, so the camera stands at 9_000 when appraised.
+    appraisal = {"appraisedCents": 6_000, "occurredAt": "2025-03-15T12:00:00Z"}
+    result = client.post(
+        f"/api/amortization-schedules/{schedule_id}/revaluations", json=appraisal, headers=headers
+    ).json()
+    assert (result["revaluation"]["carryingCents"], result["schedule"]["remainingBookValueCents"]) == (9_000, 6_000)
+    assert (result["transaction"]["accrualType"], result["transaction"]["amountCents"]) == ("Adjustment", 3_000)
+
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-12"}, headers=headers)
+    postings = client.get(f"/api/amortization-schedules/{schedule_id}/postings", headers=headers).json()
+    assert [p["amountCents"] for p in postings] == [1_000] * 3 + [666] * 8 + [672]
+    accounts = {account["id"]: account for account in client.get("/api/accounts", headers=headers).json()}
+    assert (accounts[camera["id"]]["balanceCents"], accounts[camera["id"]]["bookValueCents"]) == (9_000, 0)
+
+    late = {"appraisedCents": 1_000, "occurredAt": "2025-06-15T12:00:00Z"}
+    assert (
+        client.post(f"/api/amortization-schedules/{schedule_id}/revaluations", json=late, headers=headers).status_code
+        == 409
+    )
+
+
+def test_revalue_asset_takes_the_month_in_the_configured_timezone(client: TestClient) -> None:
+    headers = auth_headers(client)
+    client.patch("/api/settings", json={"timezone": "Pacific/Auckland"}, headers=headers)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    camera = create_account(client, headers, "Camera", purpose="Spiritual")
+    purchase = {
+        "fromAccountId": wallet["id"],
+        "assetAccountId": camera["id"],
+        "amountCents": 12_000,
+        "occurredAt": "2025-01-01T00:00:00Z",
+        "strategy": "Linear",
+        "totalPeriods": 12,
+        "residualCents": 0,
+        "startDate": "2025-01-01",
+    }
+    schedule_id = client.post("/api/asset-purchases", json=purchase, headers=headers).json()["schedule"]["id"]
+
+    # Still February in UTC, but already March 1st in Auckland, so March is posted first.
+    appraisal = {"appraisedCents": 6_000, "occurredAt": "2025-02-28T12:00:00Z"}
+    result = client.post(
+        f"/api/amortization-schedules/{schedule_id}/revaluations", json=appraisal, headers=headers
+    ).json()
+    assert result["revaluation"]["carryingCents"] == 9_000
+
+
+def test_declining_balance_switches_to_straight_line(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    laptop = create_account(client, headers, "Laptop", purpose="Productivity")
+    purchase = {
+        "fromAccountId": wallet["id"],
+        "assetAccountId": laptop["id"],
+        "amountCents": 10_000,
+        "occurredAt": "2025-01-01T00:00:00Z",
+        "strategy": "DecliningBalance",
+        "totalPeriods": 5,
+        "residualCents": 0,
+        "startDate": "2025-01-01",
+    }
+    missing = client.post("/api/asset-purchases", json=purchase, headers=headers)
+    assert missing.status_code == 400
+    created = client.post(
+        "/api/asset-purchases", json={**purchase, "decliningFactorPercent": 200}, headers=headers
+    ).json()
+    assert created["schedule"]["decliningFactorPercent"] == 200
+
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-05"}, headers=headers)
+    postings = client.get(f"/api/amortization-schedules/{created['schedule']['id']}/postings", headers=headers).json()
+    # 40% of the book value until straight line over the two periods left is more.
+    assert [p["amountCents"] for p in postings] == [4_000, 2_400, 1_440, 1_080, 1_080]
+
+
+def test_usage_schedule_depreciates_by_logged_usage(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    drill = create_account(client, headers, "Drill", purpose="Productivity")
+    purchase = {
+        "fromAccountId": wallet["id"],
+        "assetAccountId": drill["id"],
+        "amountCents": 10_000,
+        "occurredAt": "2025-01-01T00:00:00Z",
+        "strategy": "Usage",
+        "totalPeriods": 60,
+        "residualCents": 0,
+        "startDate": "2025-01-01",
+        "expectedUnits": 100,
+    }
+    schedule_id = client.post("/api/asset-purchases", json=purchase, headers=headers).json()["schedule"]["id"]
+    usage_url = f"/api/amortization-schedules/{schedule_id}/usage"
+    client.post(usage_url, json={"periodYm": "2025-01", "units": 5}, headers=headers)
+    client.post(usage_url, json={"periodYm": "2025-01", "units": 10, "note": "Deck"}, headers=headers)
+    client.post(usage_url, json={"periodYm": "2025-03", "units": 45}, headers=headers)
+    assert [(log["periodYm"], log["units"]) for log in client.get(usage_url, headers=headers).json()] == [
+        ("2025-01", 10),
+        ("2025-03", 45),
+    ]
+
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-03"}, headers=headers)
+    postings = client.get(f"/api/amortization-schedules/{schedule_id}/postings", headers=headers).json()
+    assert [(p["periodYm"], p["amountCents"]) for p in postings] == [("2025-01", 1_000), ("2025-03", 4_500)]
+    late = client.post(usage_url, json={"periodYm": "2025-03", "units": 1}, headers=headers)
+    assert late.status_code == 409
+
+
+def test_cost_per_use_report_divides_cost_by_logged_usage(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    schedule_ids = {}
+    for name, amount in (("Camera", 30_000), ("Drill", 10_000)):
+        asset = create_account(client, headers, name, purpose="Productivity")
+        purchase = {
+            "fromAccountId": wallet["id"],
+            "assetAccountId": asset["id"],
+            "amountCents": amount,
+            "occurredAt": "2025-01-01T00:00:00Z",
+            "strategy": "Usage",
+            "totalPeriods": 60,
+            "residualCents": 2_000,
+            "startDate": "2025-01-01",
+            "expectedUnits": 1_000,
+        }
+        created = client.post("/api/asset-purchases", json=purchase, headers=headers).json()
+        schedule_ids[name] = created["schedule"]["id"]
+    for name, units in (("Camera", 400), ("Drill", 16)):
+        usage = {"periodYm": "2025-01", "units": units}
+        client.post(f"/api/amortization-schedules/{schedule_ids[name]}/usage", json=usage, headers=headers)
+
+    report = client.get("/api/reports/cost-per-use", params={"purpose": "Productivity"}, headers=headers).json()
+    assert [(item["label"], item["costCents"], item["costPerUseCents"]) for item in report["items"]] == [
+        ("Camera", 28_000, 70),
+        ("Drill", 8_000, 500),
+    ]
+    spiritual = client.get("/api/reports/cost-per-use", params={"purpose": "Spiritual"}, headers=headers).json()
+    assert spiritual["items"] == []
+
+
+def test_prorated_schedule_charges_part_of_the_first_month(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    bike = create_account(client, headers, "Bike", purpose="Productivity")
+    purchase = {
+        "fromAccountId": wallet["id"],
+        "assetAccountId": bike["id"],
+        "amountCents": 9_000,
+        "occurredAt": "2025-04-21T00:00:00Z",
+        "strategy": "Linear",
+        "totalPeriods": 3,
+        "residualCents": 0,
+        "startDate": "2025-04-21",
+        "prorateDaily": True,
+    }
+    usage = {**purchase, "strategy": "Usage", "expectedUnits": 100}
+    assert client.post("/api/asset-purchases", json=usage, headers=headers).status_code == 400
+    created = client.post("/api/asset-purchases", json=purchase, headers=headers).json()
+    assert created["schedule"]["prorateDaily"] is True
+
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-08"}, headers=headers)
+    schedule_id = created["schedule"]["id"]
+    postings = client.get(f"/api/amortization-schedules/{schedule_id}/postings", headers=headers).json()
+    # In service for 10 of April's 30 days; the missing two thirds of a month land in July.
+    assert [(p["periodYm"], p["amountCents"]) for p in postings] == [
+        ("2025-04", 1_000),
+        ("2025-05", 3_000),
+        ("2025-06", 3_000),
+        ("2025-07", 2_000),
+    ]
+    schedules = client.get("/api/amortization-schedules", headers=headers).json()
+    assert next(s for s in schedules if s["id"] == schedule_id)["status"] == "Completed"
+
+
+def test_depreciation_forecast_projects_without_posting(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    laptop = create_account(client, headers, "Laptop", purpose="Productivity")
+    created = client.post(
+        "/api/asset-purchases",
+        json={
+            "fromAccountId": wallet["id"],
+            "assetAccountId": laptop["id"],
+            "amountCents": 1_200,
+            "occurredAt": "2025-01-01T00:00:00Z",
+            "strategy": "Linear",
+            "totalPeriods": 12,
+            "residualCents": 0,
+            "startDate": "2025-01-01",
+        },
+        headers=headers,
+    ).json()
+
+    forecast = client.get(
+        "/api/reports/depreciation-forecast", params={"months": 3, "fromPeriodYm": "2025-11"}, headers=headers
+    ).json()
+    assert forecast["months"] == [
+        {"periodYm": "2025-11", "amountCents": 100},
+        {"periodYm": "2025-12", "amountCents": 100},
+        {"periodYm": "2026-01", "amountCents": 0},
+    ]
+    assert forecast["totalCents"] == 200
+    postings = client.get(f"/api/amortization-schedules/{created['schedule']['id']}/postings", headers=headers)
+    assert postings.json() == []
+    assert client.get("/api/reports/depreciation-forecast", params={"months": 0}, headers=headers).status_code == 400
+
+
+def test_asset_details_and_expiring_warranties(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    laptop = create_account(client, headers, "Laptop", purpose="Productivity")
+    phone = create_account(client, headers, "Phone", purpose="Productivity")
+    purchase = {
+        "fromAccountId": wallet["id"],
+        "amountCents": 1_200,
+        "occurredAt": "2025-01-01T00:00:00Z",
+        "strategy": "Linear",
+        "totalPeriods": 12,
+        "residualCents": 0,
+        "startDate": "2025-01-01",
+    }
+    created = client.post(
+        "/api/asset-purchases",
+        json={**purchase, "assetAccountId": laptop["id"], "serialNumber": " SN-1 ", "warrantyExpiresOn": "2026-02-10"},
+        headers=headers,
+    ).json()
+    assert created["schedule"]["serialNumber"] == "SN-1"
+    client.post(
+        "/api/asset-purchases",
+        json={**purchase, "assetAccountId": phone["id"], "warrantyExpiresOn": "2027-01-01"},
+        headers=headers,
+    )
+
+    schedule_id = created["schedule"]["id"]
+    bad_date = client.patch(
+        f"/api/amortization-schedules/{schedule_id}", json={"warrantyExpiresOn": "soon"}, headers=headers
+    )
+    assert bad_date.status_code == 400
+    updated = client.patch(
+        f"/api/amortization-schedules/{schedule_id}", json={"receiptReference": "INV-42", "serialNumber": ""},
+        headers=headers,
+    ).json()
+    assert (updated["serialNumber"], updated["receiptReference"], updated["warrantyExpiresOn"]) == (
+        None,
+        "INV-42",
+        "2026-02-10",
+    )
+
+    expiring = client.get(
+        "/api/amortization-schedules/expiring-warranties", params={"withinDays": 30, "asOf": "2026-01-15"},
+        headers=headers,
+    ).json()
+    assert [(item["label"], item["daysLeft"]) for item in expiring] == [("Laptop", 26)]
+
+
+def test_maintenance_log_feeds_total_cost_of_ownership(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    car = create_account(client, headers, "Car", purpose="LifeSupport")
+    client.post(
+        "/api/asset-purchases",
+        json={
+            "fromAccountId": wallet["id"],
+            "assetAccountId": car["id"],
+            "amountCents": 12_000,
+            "occurredAt": "2025-01-01T00:00:00Z",
+            "strategy": "Linear",
+            "totalPeriods": 12,
+            "residualCents": 0,
+            "startDate": "2025-01-01",
+        },
+        headers=headers,
+    ).json()
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2025-03"}, headers=headers)
+
+    service = record_transaction(client, headers, 3_000, "2025-02-14T00:00:00Z", fromAccountId=wallet["id"])
+    event = client.post(
+        f"/api/accounts/{car['id']}/maintenance",
+        json={"occurredOn": "2025-02-14", "costCents": 3_000, "note": "Tyres", "transactionId": service["id"]},
+        headers=headers,
+    )
+    assert event.status_code == 200
+    missing = client.post(
+        f"/api/accounts/{car['id']}/maintenance",
+        json={"occurredOn": "2025-02-14", "costCents": 100, "transactionId": "nope"},
+        headers=headers,
+    )
+    assert missing.status_code == 404
+    events = client.get(f"/api/accounts/{car['id']}/maintenance", headers=headers).json()
+    assert [(e["occurredOn"], e["costCents"], e["note"]) for e in events] == [("2025-02-14", 3_000, "Tyres")]
+
+    report = client.get("/api/reports/total-cost-of-ownership", headers=headers).json()
+    assert report["items"] == [
+        {
+            "accountId": car["id"],
+            "label": "Car",
+            "purpose": "LifeSupport",
+            "purchaseCents": 12_000,
+            "depreciatedCents": 3_000,
+            "maintenanceCents": 3_000,
+            "totalCostCents": 15_000,
+            "consumedCents": 6_000,
+        }
+    ]
+
+
+def test_income_statement_books_depreciation_instead_of_purchases(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    savings = create_account(client, headers, "Savings")
+    camera = create_account(client, headers, "Camera", purpose="Spiritual")
+    food = client.post("/api/categories", json={"name": "Food"}, headers=headers).json()
+    salary = client.post("/api/categories", json={"name": "Salary"}, headers=headers).json()
+    record_transaction(
+        client, headers, 300_000, "2026-03-01T09:00:00Z", toAccountId=wallet["id"], categoryId=salary["id"]
+    )
+    record_transaction(
+        client, headers, 1_250, "2026-03-03T12:00:00Z", fromAccountId=wallet["id"], categoryId=food["id"]
+    )
+    record_transaction(client, headers, 750, "2026-03-04T12:00:00Z", fromAccountId=wallet["id"])
+    record_transaction(
+        client, headers, 50_000, "2026-03-05T12:00:00Z", fromAccountId=wallet["id"], toAccountId=savings["id"]
+    )
+    client.post(
+        "/api/asset-purchases",
+        json={
+            "fromAccountId": wallet["id"],
+            "assetAccountId": camera["id"],
+            "amountCents": 12_000,
+            "occurredAt": "2026-03-01T00:00:00Z",
+            "strategy": "Linear",
+            "totalPeriods": 12,
+            "residualCents": 0,
+            "startDate": "2026-03-01",
+        },
+        headers=headers,
+    )
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2026-03"}, headers=headers)
+
+    statement = client.get("/api/reports/income-statement", params={"periodYm": "2026-03"}, headers=headers).json()
+    assert [(item["label"], item["amountCents"]) for item in statement["incomeItems"]] == [("Salary", 300_000)]
+    assert [(item["label"], item["amountCents"]) for item in statement["expenseItems"]] == [
+        ("Food", 1_250),
+        ("Uncategorized", 750),
+    ]
+    assert (statement["totalIncomeCents"], statement["totalExpenseCents"]) == (300_000, 2_000)
+    assert statement["depreciationCents"] == 1_000
+    assert statement["netResultCents"] == 297_000
@@ -0,0 +1,180 @@
+from fastapi.testclient import TestClient
+
+from app.db import get_connection
+from conftest import auth_headers, create_account, record_transaction
+
+
+def test_payee_default_category_fills_in_and_backfills(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    coffee = client.post("/api/categories", json={"name": "Coffee"}, headers=headers).json()
+    snacks = client.post("/api/categories", json={"name": "Snacks"}, headers=headers).json()
+    cafe = client.post("/api/payees", json={"name": "Cafe"}, headers=headers).json()
+    spend = {"fromAccountId": wallet["id"], "payeeId": cafe["id"]}
+    old = record_transaction(client, headers, 350, "2026-01-05T08:00:00Z", **spend)
+    assert old["categoryId"] is None
+
+    with get_connection() as conn:
+        conn.execute("UPDATE payees SET default_category_id = ? WHERE id = ?", (coffee["id"], cafe["id"]))
+    new = record_transaction(client, headers, 400, "2026-01-06T08:00:00Z", **spend)
+    chosen = record_transaction(client, headers, 250, "2026-01-07T08:00:00Z", **spend, categoryId=snacks["id"])
+    assert (new["categoryId"], chosen["categoryId"]) == (coffee["id"], snacks["id"])
+
+    backfilled = client.post(f"/api/payees/{cafe['id']}/apply-default-category", headers=headers).json()
+    assert [(tx["id"], tx["categoryId"]) for tx in backfilled] == [(old["id"], coffee["id"])]
+    assert client.post(f"/api/payees/{cafe['id']}/apply-default-category", headers=headers).json() == []
+    plain = client.post("/api/payees", json={"name": "Kiosk"}, headers=headers).json()
+    assert client.post(f"/api/payees/{plain['id']}/apply-default-category", headers=headers).status_code == 400
+
+
+def test_merge_payees_moves_transactions_and_removes_duplicates(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    names = ["Starbucks", "STARBUCKS 0123", "星巴克"]
+    target, *sources = [client.post("/api/payees", json={"name": name}, headers=headers).json() for name in names]
+    for index, payee in enumerate([target, *sources]):
+        occurred_at = f"2026-01-0{index + 1}T08:00:00Z"
+        record_transaction(client, headers, 100 + index, occurred_at, fromAccountId=wallet["id"], payeeId=payee["id"])
+
+    request = {"sourceIds": [source["id"] for source in sources], "targetId": target["id"]}
+    merged = client.post("/api/payees/merge", json=request, headers=headers).json()
+    assert (merged["payee"]["id"], merged["movedTransactionCount"]) == (target["id"], 2)
+    assert [payee["name"] for payee in client.get("/api/payees", headers=headers).json()] == ["Starbucks"]
+    items = client.get("/api/transactions", headers=headers).json()["items"]
+    assert {tx["payeeId"] for tx in items} == {target["id"]}
+
+    assert client.post("/api/payees/merge", json=request, headers=headers).status_code == 404
+    itself = {"sourceIds": [target["id"]], "targetId": target["id"]}
+    assert client.post("/api/payees/merge", json=itself, headers=headers).status_code == 400
+
+
+def test_merge_categories_moves_history_budgets_and_children(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    food = client.post("/api/categories", json={"name": "Food"}, headers=headers).json()
+    meals = client.post("/api/categories", json={"name": "Meals"}, headers=headers).json()
+    client.post("/api/categories", json={"name": "Lunch", "parentId": meals["id"]}, headers=headers)
+    budgets = ((food, "2026-01", 10_000), (meals, "2026-01", 5_000), (meals, "2026-02", 7_000))
+    for category, period_ym, amount in budgets:
+        budget = {"categoryId": category["id"], "periodYm": period_ym, "amountCents": amount}
+        client.post("/api/budgets", json=budget, headers=headers)
+    for category, amount in ((food, 1_200), (meals, 800)):
+        record_transaction(
+            client, headers, amount, "2026-01-10T12:00:00Z", fromAccountId=wallet["id"], categoryId=category["id"]
+        )
+
+    request = {"sourceId": meals["id"], "targetId": food["id"]}
+    merged = client.post("/api/categories/merge", json=request, headers=headers).json()
+    assert (merged["movedTransactionCount"], merged["movedBudgetCount"]) == (1, 2)
+
+    cash = client.get("/api/reports/cash", params={"periodYm": "2026-01"}, headers=headers).json()
+    assert [(item["label"], item["amountCents"]) for item in cash["items"]] == [("Food", 2_000)]
+    january = client.get("/api/budgets", params={"periodYm": "2026-01"}, headers=headers).json()
+    assert [(budget["categoryId"], budget["amountCents"]) for budget in january] == [(food["id"], 15_000)]
+    february = client.get("/api/budgets", params={"periodYm": "2026-02"}, headers=headers).json()
+    assert [(budget["categoryId"], budget["amountCents"]) for budget in february] == [(food["id"], 7_000)]
+    listed = client.get("/api/categories", params={"includeInactive": True}, headers=headers).json()
+    categories = {category["name"]: category for category in listed}
+    assert categories["Meals"]["isActive"] is False
+    assert categories["Lunch"]["parentId"] == food["id"]
+    itself = {"sourceId": food["id"], "targetId": food["id"]}
+    assert client.post("/api/categories/merge", json=itself, headers=headers).status_code == 400
+
+
+def test_reports_roll_subcategories_up_into_their_parent(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    food = client.post("/api/categories", json={"name": "Food"}, headers=headers).json()
+    lunch = client.post("/api/categories", json={"name": "Lunch", "parentId": food["id"]}, headers=headers).json()
+    coffee = client.post("/api/categories", json={"name": "Coffee", "parentId": lunch["id"]}, headers=headers).json()
+    rent = client.post("/api/categories", json={"name": "Rent"}, headers=headers).json()
+    for category, amount in ((food, 1_000), (lunch, 600), (coffee, 300), (rent, 50_000)):
+        record_transaction(
+            client, headers, amount, "2026-04-10T12:00:00Z", fromAccountId=wallet["id"], categoryId=category["id"]
+        )
+        budget = {"categoryId": category["id"], "periodYm": "2026-04", "amountCents": 2 * amount}
+        client.post("/api/budgets", json=budget, headers=headers)
+
+    params = {"periodYm": "2026-04", "groupByParent": True}
+    cash = client.get("/api/reports/cash", params=params, headers=headers).json()
+    assert [(item["label"], item["amountCents"]) for item in cash["items"]] == [("Rent", 50_000), ("Food", 1_900)]
+    assert [(child["label"], child["amountCents"]) for child in cash["items"][1]["children"]] == [
+        ("Lunch", 600),
+        ("Coffee", 300),
+    ]
+    assert cash["items"][0]["children"] is None
+    flat = client.get("/api/reports/cash", params={"periodYm": "2026-04"}, headers=headers).json()
+    assert len(flat["items"]) == 4
+
+    budget = client.get("/api/reports/budget", params=params, headers=headers).json()
+    assert [(item["label"], item["budgetedCents"], item["spentCents"]) for item in budget["items"]] == [
+        ("Food", 3_800, 1_900),
+        ("Rent", 100_000, 50_000),
+    ]
+    assert sorted(child["label"] for child in budget["items"][0]["children"]) == ["Coffee", "Lunch"]
+    assert budget["totalSpentCents"] == 51_900
+
+
+def test_suggestions_come_from_booking_history(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    coffee = client.post("/api/categories", json={"name": "Coffee"}, headers=headers).json()
+    cooking = client.post("/api/categories", json={"name": "Cooking"}, headers=headers).json()
+    client.post("/api/categories", json={"name": "Clothes"}, headers=headers)
+    cafe = client.post("/api/payees", json={"name": "Cafe", "defaultCategoryId": cooking["id"]}, headers=headers).json()
+    assert client.get(f"/api/payees/{cafe['id']}/suggestion", headers=headers).json() == {
+        "payeeId": cafe["id"],
+        "categoryId": cooking["id"],
+        "recentAmountsCents": [],
+    }
+    spend = {"fromAccountId": wallet["id"], "payeeId": cafe["id"]}
+    record_transaction(client, headers, 350, "2026-01-05T08:00:00Z", **spend, categoryId=coffee["id"])
+    record_transaction(client, headers, 350, "2026-01-06T08:00:00Z", **spend)
+
+    # One each; the later booking wins the tie.
+    suggestion = client.get(f"/api/payees/{cafe['id']}/suggestion", headers=headers).json()
+    assert (suggestion["categoryId"], suggestion["recentAmountsCents"]) == (cooking["id"], [350])
+    record_transaction(client, headers, 420, "2026-01-04T08:00:00Z", **spend, categoryId=coffee["id"])
+    suggestion = client.get(f"/api/payees/{cafe['id']}/suggestion", headers=headers).json()
+    assert (suggestion["categoryId"], suggestion["recentAmountsCents"]) == (coffee["id"], [350, 420])
+
+    listed = client.get("/api/categories/suggestions", params={"prefix": "c"}, headers=headers).json()
+    assert [(item["category"]["name"], item["useCount"]) for item in listed] == [
+        ("Coffee", 2),
+        ("Cooking", 1),
+        ("Clothes", 0),
+    ]
+    assert client.get("/api/categories/suggestions", params={"prefix": "co_"}, headers=headers).json() == []
+
+
+def test_tags_carry_color_and_description_and_can_be_retired(client: TestClient) -> None:
+    headers = auth_headers(client)
+    created = client.post(
+        "/api/tags", json={"name": "Holiday", "color": "#1E88E5", "description": " Trips away "}, headers=headers
+    ).json()
+    assert (created["color"], created["description"], created["isArchived"]) == ("#1e88e5", "Trips away", False)
+    assert client.post("/api/tags", json={"name": "Bad", "color": "blue"}, headers=headers).status_code == 400
+
+    retired = client.patch(f"/api/tags/{created['id']}", json={"isArchived": True, "color": ""}, headers=headers).json()
+    assert (retired["color"], retired["description"], retired["isArchived"]) == (None, "Trips away", True)
+    assert client.get("/api/tags", headers=headers).json() == []
+    listed = client.get("/api/tags", params={"includeArchived": "true"}, headers=headers).json()
+    assert [tag["id"] for tag in listed] == [created["id"]]
+
+
+def test_categories_carry_color_icon_and_sort_order(client: TestClient) -> None:
+    headers = auth_headers(client)
+    client.post("/api/categories", json={"name": "Housing"}, headers=headers)
+    food = client.post(
+        "/api/categories",
+        json={"name": "Food", "color": "#43A047", "icon": "utensils", "sortOrder": 2},
+        headers=headers,
+    ).json()
+    assert (food["color"], food["icon"], food["sortOrder"]) == ("#43a047", "utensils", 2)
+
+    updated = client.patch(
+        f"/api/categories/{food['id']}", json={"sortOrder": -1, "icon": "", "name": "Groceries"}, headers=headers
+    ).json()
+    assert (updated["name"], updated["color"], updated["icon"]) == ("Groceries", "#43a047", None)
+    assert [item["name"] for item in client.get("/api/categories", headers=headers).json()] == ["Groceries", "Housing"]
+    assert client.patch(f"/api/categories/{food['id']}", json={"color": "green"}, headers=headers).status_code == 400
@@ -0,0 +1,107 @@
+import sqlite3
+from pathlib import Path
+
+from fastapi.testclient import TestClient
+
+from conftest import auth_headers, create_account, record_transaction
+
+
+def test_export_utility_matrix_writes_category_by_month_csv(client: TestClient, tmp_path: Path) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    camera = create_account(client, headers, "Camera", purpose="Spiritual")
+    food = client.post("/api/categories", json={"name": "Food"}, headers=headers).json()
+    record_transaction(
+        client, headers, 1_250, "2026-02-03T12:00:00Z", fromAccountId=wallet["id"], categoryId=food["id"]
+    )
+    purchase = client.post(
+        "/api/asset-purchases",
+        json={
+            "fromAccountId": wallet["id"],
+            "assetAccountId": camera["id"],
+            "amountCents": 12_000,
+            "occurredAt": "2026-11-01T00:00:00Z",
+            "strategy": "Linear",
+            "totalPeriods": 12,
+            "residualCents": 0,
+            "startDate": "2026-11-01",
+        },
+        headers=headers,
+    )
+    assert purchase.status_code == 200
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2026-12"}, headers=headers)
+
+    target = tmp_path / "utility-2026.csv"
+    response = client.post("/api/exports/utility-matrix", json={"year": 2026, "path": str(target)}, headers=headers)
+    assert response.status_code == 200
+    assert response.json()["rowCount"] == 2
+
+    lines = target.read_text(encoding="utf-8").splitlines()
+    assert lines[0].startswith("Category,2026-01,2026-02")
+    assert lines[1] == "Depreciation," + ",".join(["0.00"] * 10 + ["10.00", "10.00"]) + ",20.00"
+    assert lines[2] == "Food,0.00,12.50," + ",".join(["0.00"] * 10) + ",12.50"
+    assert lines[3].startswith("Total,0.00,12.50")
+
+
+def test_export_report_csv_writes_cash_flow_rows(client: TestClient, tmp_path: Path) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    food = client.post("/api/categories", json={"name": "Food"}, headers=headers).json()
+    record_transaction(
+        client, headers, 1_250, "2026-02-03T12:00:00Z", fromAccountId=wallet["id"], categoryId=food["id"]
+    )
+    record_transaction(client, headers, 5_000, "2026-02-10T12:00:00Z", toAccountId=wallet["id"])
+
+    target = tmp_path / "cash-2026-02.csv"
+    request = {"report": "Cash", "path": str(target), "periodYm": "2026-02"}
+    response = client.post("/api/exports/report-csv", json=request, headers=headers)
+    assert response.status_code == 200
+    assert response.json()["rowCount"] == 2
+
+    lines = target.read_text(encoding="utf-8").splitlines()
+    assert lines == [
+        "Section,Label,Amount",
+        "Income,Uncategorized,50.00",
+        "Expense,Food,12.50",
+        "Total,Income,50.00",
+        "Total,Expense,12.50",
+        "Total,Transfers,0.00",
+        "Total,Debt repayments,0.00",
+        "Total,Borrowing,0.00",
+        "Total,Net,37.50",
+    ]
+
+    budget = {"report": "Budget", "path": str(target), "fromDate": "2026-02-01", "toDate": "2026-02-28"}
+    assert client.post("/api/exports/report-csv", json=budget, headers=headers).status_code == 400
+
+
+def test_anonymized_export_replaces_personal_text(client: TestClient, tmp_path: Path) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Secret savings", balance_cents=50_000)
+    card = create_account(client, headers, "Private card", balance_cents=-1_000, account_type="Liability")
+    category = client.post("/api/categories", json={"name": "Therapy"}, headers=headers).json()
+    therapy = {"fromAccountId": wallet["id"], "categoryId": category["id"], "note": "Dr. X"}
+    record_transaction(client, headers, 12_345, "2026-02-01T09:00:00Z", **therapy)
+    payoff = {"fromAccountId": wallet["id"], "toAccountId": card["id"]}
+    record_transaction(client, headers, 1_000, "2026-02-02T09:00:00Z", **payoff)
+
+    target = tmp_path / "repro.db"
+    request = {"path": str(target), "perturbAmounts": True}
+    assert client.post("/api/exports/anonymized", json=request, headers=headers).json() == {
+        "path": str(target),
+        "rowCount": 2,
+    }
+    assert client.post("/api/exports/anonymized", json={"path": str(target)}, headers=headers).status_code == 409
+
+    copy = sqlite3.connect(target)
+    assert sorted(row[0] for row in copy.execute("SELECT name FROM accounts")) == ["Account 1", "Account 2"]
+    assert copy.execute("SELECT name FROM categories").fetchall() == [("Category 1",)]
+    assert copy.execute("SELECT note FROM transactions WHERE note IS NOT NULL").fetchall() == [("Note 1",)]
+    assert copy.execute("SELECT COUNT(*) FROM users").fetchone()[0] == 0
+    assert b"Therapy" not in target.read_bytes()
+
+    # The card started at -10.00 and was paid off in full, so it must still land on zero.
+    assert copy.execute("SELECT balance_cents FROM accounts WHERE id = ?", (card["id"],)).fetchone()[0] == 0
+    # The ledger legs were scaled with their rows.
+    legs = "SELECT COUNT(*) FROM transaction_postings p JOIN transactions t ON t.id = p.transaction_id"
+    assert copy.execute(f"{legs} WHERE abs(p.amount_cents) != t.amount_cents").fetchone()[0] == 0
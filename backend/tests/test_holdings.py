@@ -0,0 +1,312 @@
+from datetime import timedelta
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app.services import holdings as holdings_service
+from app.services import prices as price_service
+from app.services.price_providers import FetchedPrice
+from conftest import auth_headers, balances, create_account, record_transaction
+
+
+def test_exchange_rates_are_stored_per_pair_and_day(client: TestClient) -> None:
+    headers = auth_headers(client)
+    first = {"fromCurrency": "usd", "toCurrency": "EUR", "rate": 0.91, "rateDate": "2026-03-01"}
+    created = client.post("/api/exchange-rates", json=first, headers=headers).json()
+    assert (created["fromCurrency"], created["toCurrency"], created["rate"]) == ("USD", "EUR", 0.91)
+    corrected = client.post("/api/exchange-rates", json={**first, "rate": 0.92}, headers=headers).json()
+    assert corrected["id"] == created["id"]
+    later = {"fromCurrency": "USD", "toCurrency": "JPY", "rate": 149.5, "rateDate": "2026-03-02"}
+    client.post("/api/exchange-rates", json=later, headers=headers)
+
+    rates = client.get("/api/exchange-rates", headers=headers).json()
+    assert [(rate["toCurrency"], rate["rate"], rate["rateDate"]) for rate in rates] == [
+        ("JPY", 149.5, "2026-03-02"),
+        ("EUR", 0.92, "2026-03-01"),
+    ]
+    only_eur = client.get("/api/exchange-rates", params={"toCurrency": "eur"}, headers=headers).json()
+    assert [rate["id"] for rate in only_eur] == [created["id"]]
+    invalid = client.post("/api/exchange-rates", json={**first, "rate": 0}, headers=headers)
+    assert invalid.status_code == 400
+
+
+def test_reports_convert_foreign_amounts_at_the_nearest_rate(client: TestClient) -> None:
+    headers = auth_headers(client)
+    account = {"name": "Wallet", "accountType": "Asset", "purpose": "LifeSupport", "initialBalanceCents": 1_000_00}
+    wallet = client.post("/api/onboarding", json={"baseCurrency": "EUR", "accounts": [account]}, headers=headers)
+    wallet = wallet.json()["accounts"][0]
+    dollars = create_account(client, headers, "Dollars", 500_00, currency="USD")
+    for rate in (
+        {"fromCurrency": "USD", "toCurrency": "EUR", "rate": 0.9, "rateDate": "2025-06-01"},
+        {"fromCurrency": "EUR", "toCurrency": "USD", "rate": 1.25, "rateDate": "2025-06-20"},
+    ):
+        client.post("/api/exchange-rates", json=rate, headers=headers)
+    record_transaction(client, headers, 100_00, "2025-06-18T10:00:00Z", fromAccountId=dollars["id"])
+    record_transaction(client, headers, 10_00, "2025-06-03T10:00:00Z", fromAccountId=wallet["id"])
+    record_transaction(client, headers, 50_00, "2025-06-05T10:00:00Z", toAccountId=wallet["id"])
+
+    cash = client.get("/api/reports/cash", params={"periodYm": "2025-06"}, headers=headers).json()
+    assert (cash["totalExpenseCents"], cash["baseCurrency"]) == (110_00, "EUR")
+    # The inverse EUR/USD rate two days later is closer than the direct one from June 1st.
+    assert (cash["convertedTotalIncomeCents"], cash["convertedTotalExpenseCents"], cash["convertedNetCents"]) == (
+        50_00,
+        90_00,
+        -40_00,
+    )
+    balances = {a["name"]: a["convertedBalanceCents"] for a in client.get("/api/accounts", headers=headers).json()}
+    assert balances == {"Dollars": 320_00, "Wallet": 1_040_00}
+    dashboard = client.get("/api/dashboard", headers=headers).json()
+    assert (dashboard["netWorthCents"], dashboard["convertedNetWorthCents"]) == (1_440_00, 1_360_00)
+
+    create_account(client, headers, "Pounds", 100_00, currency="GBP")
+    assert client.get("/api/dashboard", headers=headers).json()["convertedTotalAssetsCents"] is None
+
+
+def test_month_end_revaluation_books_unrealized_fx_gains(client: TestClient) -> None:
+    headers = auth_headers(client)
+    account = {"name": "Wallet", "accountType": "Asset", "purpose": "LifeSupport", "initialBalanceCents": 0}
+    client.post("/api/onboarding", json={"baseCurrency": "EUR", "accounts": [account]}, headers=headers)
+    dollars = create_account(client, headers, "Dollars", 1_000_00, currency="USD")
+    pounds = create_account(client, headers, "Pounds", 100_00, currency="GBP")
+    for rate, rate_date in ((0.90, "2025-04-30"), (0.92, "2025-05-10"), (0.95, "2025-05-31")):
+        rate_input = {"fromCurrency": "USD", "toCurrency": "EUR", "rate": rate, "rateDate": rate_date}
+        client.post("/api/exchange-rates", json=rate_input, headers=headers)
+    record_transaction(client, headers, 200_00, "2025-05-10T09:00:00Z", fromAccountId=dollars["id"])
+
+    result = client.post("/api/exchange-rates/revaluations", json={"periodYm": "2025-05"}, headers=headers).json()
+    # 800 USD are worth 760 EUR at month end; they stood at 900 - 184 = 716 EUR along the way.
+    [revaluation] = result["revaluations"]
+    assert (revaluation["accountId"], revaluation["balanceCents"], revaluation["valueCents"]) == (
+        dollars["id"],
+        800_00,
+        760_00,
+    )
+    assert revaluation["gainCents"] == 44_00
+    assert result["skippedAccountIds"] == [pounds["id"]]
+    params = {"periodYm": "2025-05", "accrualType": "Adjustment"}
+    items = client.get("/api/transactions", params=params, headers=headers).json()["items"]
+    booked = next(item for item in items if item["id"] == revaluation["transactionId"])
+    categories = {c["id"]: c["name"] for c in client.get("/api/categories", headers=headers).json()}
+    assert (booked["amountCents"], categories[booked["categoryId"]]) == (44_00, "Unrealized FX gains")
+    postings = client.get(f"/api/transactions/{booked['id']}/postings", headers=headers).json()
+    assert [posting["amountCents"] for posting in postings] == [-44_00, 44_00]
+    again = client.post("/api/exchange-rates/revaluations", json={"periodYm": "2025-05"}, headers=headers).json()
+    assert again["revaluations"] == []
+    assert client.get("/api/accounts", headers=headers).json()[0]["balanceCents"] == 800_00
+
+    for top in (0, 1):
+        params = {"periodYm": "2025-05", "topTransactions": top}
+        cash = client.get("/api/reports/cash", params=params, headers=headers).json()
+        assert (cash["totalIncomeCents"], cash["incomeItems"], cash["totalExpenseCents"]) == (0, [], 200_00)
+    kpi = client.get("/api/kpis/adjustment", headers=headers).json()
+    assert kpi["adjustmentTotalCents"] == 0
+    unfinished = client.post("/api/exchange-rates/revaluations", json={"periodYm": "2999-01"}, headers=headers)
+    assert unfinished.status_code == 400
+
+
+def test_crypto_holdings_keep_satoshi_precision_and_value_at_unit_price(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Cold wallet", purpose="Investment")
+    url = f"/api/accounts/{wallet['id']}/crypto"
+
+    held = client.post(url, json={"symbol": "btc", "quantity": "0.0153"}, headers=headers).json()
+    assert (held["holding"]["symbol"], held["holding"]["quantityUnits"], held["account"]["balanceCents"]) == (
+        "BTC",
+        1_530_000,
+        0,
+    )
+    priced = client.post(f"{url}/price", json={"unitPriceCents": 65_000_00}, headers=headers).json()
+    assert (priced["holding"]["valueCents"], priced["adjustmentTransaction"]["amountCents"]) == (994_50, 994_50)
+
+    more = client.post(url, json={"symbol": "BTC", "quantity": "0.02"}, headers=headers).json()
+    assert (more["holding"]["quantity"], more["account"]["balanceCents"]) == ("0.02", 1_300_00)
+    timeline = client.get(f"/api/accounts/{wallet['id']}/valuations", headers=headers).json()
+    assert [valuation["valueCents"] for valuation in timeline["valuations"]] == [994_50, 1_300_00]
+
+    too_precise = client.post(url, json={"symbol": "BTC", "quantity": "0.000000001"}, headers=headers)
+    assert too_precise.status_code == 400
+
+
+def test_security_trades_track_lots_and_book_the_cash_leg(client: TestClient) -> None:
+    headers = auth_headers(client)
+    broker = create_account(client, headers, "Broker", purpose="Investment")
+    checking = create_account(client, headers, "Checking", 10_000_00)
+    trade = {"accountId": broker["id"], "cashAccountId": checking["id"], "ticker": "acme"}
+    client.post(
+        "/api/holdings/buy",
+        json={**trade, "quantity": "10", "amountCents": 1_000_00, "occurredAt": "2025-01-10T10:00:00Z"},
+        headers=headers,
+    )
+    client.post(
+        "/api/holdings/buy",
+        json={**trade, "quantity": "5", "amountCents": 600_00, "occurredAt": "2025-02-10T10:00:00Z"},
+        headers=headers,
+    )
+
+    # The January lot goes first in full, then two fifths of the February one.
+    sold = client.post("/api/holdings/sell", json={**trade, "quantity": "12", "amountCents": 1_500_00}, headers=headers)
+    sold = sold.json()
+    assert (sold["realizedGainCents"], sold["holding"]["quantity"], sold["holding"]["costBasisCents"]) == (
+        260_00,
+        "3",
+        360_00,
+    )
+    assert [lot["originalQuantityUnits"] for lot in sold["holding"]["lots"]] == [500_000_000]
+    balances = {a["name"]: a["balanceCents"] for a in client.get("/api/accounts", headers=headers).json()}
+    assert balances == {"Broker": 360_00, "Checking": 9_900_00}
+
+    oversold = {**trade, "quantity": "4", "amountCents": 500_00}
+    assert client.post("/api/holdings/sell", json=oversold, headers=headers).status_code == 400
+    holdings = client.get("/api/holdings", params={"accountId": broker["id"]}, headers=headers).json()
+    assert [holding["ticker"] for holding in holdings] == ["ACME"]
+
+
+def test_recorded_prices_value_holdings_over_time(client: TestClient) -> None:
+    headers = auth_headers(client)
+    broker = create_account(client, headers, "Broker", purpose="Investment")
+    checking = create_account(client, headers, "Checking", 10_000_00)
+    trade = {"accountId": broker["id"], "cashAccountId": checking["id"], "quantity": "10"}
+    for ticker, amount in (("ACME", 1_000_00), ("BETA", 50_00)):
+        buy = {**trade, "ticker": ticker, "amountCents": amount, "occurredAt": "2025-01-10T10:00:00Z"}
+        client.post("/api/holdings/buy", json=buy, headers=headers)
+    sell = {**trade, "ticker": "ACME", "quantity": "4", "amountCents": 500_00, "occurredAt": "2025-02-15T10:00:00Z"}
+    client.post("/api/holdings/sell", json=sell, headers=headers)
+    for price_date, cents in (("2025-01-09", 95_00), ("2025-01-31", 110_00), ("2025-01-31", 120_00)):
+        price = {"instrument": "acme", "priceDate": price_date, "priceCents": cents}
+        client.post("/api/prices", json=price, headers=headers)
+
+    prices = client.get("/api/prices", params={"instrument": "ACME"}, headers=headers).json()
+    assert [(price["priceDate"], price["priceCents"]) for price in prices] == [
+        ("2025-01-31", 120_00),
+        ("2025-01-09", 95_00),
+    ]
+    params = {"fromDate": "2025-01-01", "toDate": "2025-02-28", "granularity": "Month", "accountId": broker["id"]}
+    history = client.get("/api/holdings/value-history", params=params, headers=headers).json()
+    assert [(p["date"], p["valueCents"], p["costBasisCents"]) for p in history["points"]] == [
+        ("2025-01-31", 1_200_00, 1_050_00),
+        ("2025-02-28", 720_00, 650_00),
+    ]
+    assert history["unpricedTickers"] == ["BETA"]
+    acme = client.get("/api/holdings", headers=headers).json()[0]
+    assert (acme["ticker"], acme["priceCents"], acme["marketValueCents"]) == ("ACME", 120_00, 720_00)
+
+
+def test_refresh_prices_stores_provider_closes_for_held_instruments(
+    client: TestClient, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    headers = auth_headers(client)
+    broker = create_account(client, headers, "Broker", purpose="Investment")
+    checking = create_account(client, headers, "Checking", 10_000_00)
+    buy = {"accountId": broker["id"], "cashAccountId": checking["id"], "ticker": "ACME", "quantity": "1"}
+    client.post("/api/holdings/buy", json={**buy, "amountCents": 100_00}, headers=headers)
+    wallet = create_account(client, headers, "Cold wallet", purpose="Investment")
+    client.post(f"/api/accounts/{wallet['id']}/crypto", json={"symbol": "BTC", "quantity": "0.5"}, headers=headers)
+    assert client.post("/api/prices/refresh", json={}, headers=headers).status_code == 400
+
+    requested = []
+
+    class FakeProvider:
+        def fetch_closes(self, instruments, on):
+            requested.append((instruments, on.isoformat()))
+            # Friday's close answers for the Saturday asked about.
+            return [FetchedPrice(instrument="ACME", price_date=on - timedelta(days=1), price_cents=123_46)]
+
+    monkeypatch.setattr(price_service, "get_price_provider", lambda: FakeProvider())
+    refreshed = client.post("/api/prices/refresh", json={"priceDate": "2025-03-15"}, headers=headers).json()
+    assert requested == [(["ACME", "BTC"], "2025-03-15")]
+    assert [(p["instrument"], p["priceDate"], p["priceCents"]) for p in refreshed["prices"]] == [
+        ("ACME", "2025-03-14", 123_46)
+    ]
+    assert refreshed["missingInstruments"] == ["BTC"]
+    assert len(client.get("/api/prices", params={"instrument": "ACME"}, headers=headers).json()) == 1
+
+
+def test_performance_report_chains_time_weighted_returns_across_contributions(client: TestClient) -> None:
+    headers = auth_headers(client)
+    broker = create_account(client, headers, "Broker", purpose="Investment")
+    checking = create_account(client, headers, "Checking", 10_000_00)
+    trade = {"accountId": broker["id"], "cashAccountId": checking["id"], "ticker": "ACME", "quantity": "10"}
+    for occurred_at, amount in (("2025-01-01T10:00:00Z", 1_000_00), ("2025-06-30T10:00:00Z", 1_100_00)):
+        buy = {**trade, "amountCents": amount, "occurredAt": occurred_at}
+        client.post("/api/holdings/buy", json=buy, headers=headers)
+    for price_date, cents in (("2025-06-30", 110_00), ("2025-12-31", 121_00)):
+        price = {"instrument": "ACME", "priceDate": price_date, "priceCents": cents}
+        client.post("/api/prices", json=price, headers=headers)
+
+    params = {"fromDate": "2025-01-01", "toDate": "2025-12-31"}
+    report = client.get("/api/reports/performance", params=params, headers=headers).json()
+    acme = report["holdings"][0]
+    # Two 10% half-years compound to 21% whatever was added between them.
+    assert (acme["label"], acme["startValueCents"], acme["endValueCents"], acme["netContributionsCents"]) == (
+        "ACME",
+        0,
+        2_420_00,
+        2_100_00,
+    )
+    assert acme["twr"] == pytest.approx(0.21)
+    assert 0.2 < acme["xirr"] < 0.22
+    group = report["investmentGroup"]
+    assert (group["label"], group["endValueCents"], group["twr"]) == ("Investment", 2_420_00, acme["twr"])
+    assert group["xirr"] == pytest.approx(acme["xirr"])
+    backwards = {**params, "toDate": "2024-12-31"}
+    assert client.get("/api/reports/performance", params=backwards, headers=headers).status_code == 400
+
+
+def test_realized_gains_report_sums_fifo_gains_per_year(client: TestClient) -> None:
+    headers = auth_headers(client)
+    broker = create_account(client, headers, "Broker", purpose="Investment")
+    checking = create_account(client, headers, "Checking", 10_000_00)
+    trade = {"accountId": broker["id"], "cashAccountId": checking["id"], "ticker": "ACME"}
+    for quantity, amount, occurred_at in (
+        ("10", 1_000_00, "2024-06-01T10:00:00Z"),
+        ("5", 600_00, "2025-02-10T10:00:00Z"),
+    ):
+        buy = {**trade, "quantity": quantity, "amountCents": amount, "occurredAt": occurred_at}
+        client.post("/api/holdings/buy", json=buy, headers=headers)
+    for quantity, amount, occurred_at in (
+        ("12", 1_500_00, "2025-03-01T10:00:00Z"),
+        ("3", 300_00, "2025-04-01T10:00:00Z"),
+    ):
+        sell = {**trade, "quantity": quantity, "amountCents": amount, "occurredAt": occurred_at}
+        client.post("/api/holdings/sell", json=sell, headers=headers)
+
+    report = client.get("/api/reports/realized-gains", params={"year": 2025}, headers=headers).json()
+    assert [(item["quantity"], item["costBasisCents"], item["gainCents"]) for item in report["items"]] == [
+        ("12", 1_240_00, 260_00),
+        ("3", 360_00, -60_00),
+    ]
+    assert (report["totalProceedsCents"], report["totalGainCents"]) == (1_800_00, 200_00)
+    assert client.get("/api/reports/realized-gains", params={"year": 2024}, headers=headers).json()["items"] == []
+
+    categories = {c["name"]: c["id"] for c in client.get("/api/categories", headers=headers).json()}
+    transactions = client.get("/api/transactions", headers=headers).json()["items"]
+    gain_postings = sorted(t["amountCents"] for t in transactions if t["categoryId"] == categories["Realized gains"])
+    assert gain_postings == [60_00, 260_00]
+
+
+def test_month_end_revaluation_marks_holdings_to_market(client: TestClient) -> None:
+    headers = auth_headers(client)
+    broker = create_account(client, headers, "Broker", purpose="Investment")
+    checking = create_account(client, headers, "Checking", 10_000_00)
+    buy = {"accountId": broker["id"], "cashAccountId": checking["id"], "ticker": "ACME", "quantity": "10"}
+    buy = {**buy, "amountCents": 1_000_00, "occurredAt": "2025-01-10T10:00:00Z"}
+    client.post("/api/holdings/buy", json=buy, headers=headers)
+    for price_date, cents in (("2025-01-31", 120_00), ("2025-02-28", 110_00)):
+        price = {"instrument": "ACME", "priceDate": price_date, "priceCents": cents}
+        client.post("/api/prices", json=price, headers=headers)
+
+    revalued = client.post("/api/holdings/revaluations", json={"periodYm": "2025-01"}, headers=headers).json()
+    rows = revalued["revaluations"]
+    assert [(r["marketValueCents"], r["costBasisCents"], r["adjustmentCents"]) for r in rows] == [
+        (1_200_00, 1_000_00, 200_00)
+    ]
+    again = client.post("/api/holdings/revaluations", json={"periodYm": "2025-01"}, headers=headers).json()
+    assert again["revaluations"] == []
+
+    # Reports leave the books alone; the background job marks February's month-end.
+    client.get("/api/reports/utility", params={"periodYm": "2025-02"}, headers=headers)
+    assert balances(client, headers)[broker["id"]] == 1_200_00
+    assert holdings_service.generate_holding_revaluations() == 1
+    assert balances(client, headers)[broker["id"]] == 1_100_00
+    cash = client.get("/api/reports/cash", params={"periodYm": "2025-02"}, headers=headers).json()
+    assert (cash["totalIncomeCents"], cash["totalExpenseCents"]) == (0, 0)
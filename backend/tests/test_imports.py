@@ -0,0 +1,158 @@
+from fastapi.testclient import TestClient
+
+from conftest import auth_headers, balances, create_account
+
+
+def test_statement_import_links_payees_by_fuzzy_descriptor(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 10_000)
+    coffee = client.post("/api/categories", json={"name": "Coffee"}, headers=headers).json()
+    starbucks = client.post(
+        "/api/payees", json={"name": "Starbucks", "defaultCategoryId": coffee["id"]}, headers=headers
+    ).json()
+    client.post("/api/payees", json={"name": "Stadtwerke"}, headers=headers)
+    lines = [
+        {"date": "2026-05-02", "amountCents": -450, "description": "STARBUCKS 0123"},
+        {"date": "2026-05-03", "amountCents": -300, "description": "Starbuck Coffee"},
+        {"date": "2026-05-04", "amountCents": 250_000, "description": "ACME PAYROLL"},
+    ]
+    result = client.post(
+        "/api/imports/statement", json={"accountId": checking["id"], "lines": lines}, headers=headers
+    ).json()["lines"]
+
+    linked, unsure, salary = result
+    assert (linked["transaction"]["payeeId"], linked["transaction"]["categoryId"]) == (starbucks["id"], coffee["id"])
+    assert linked["transaction"]["fromAccountId"] == checking["id"] and linked["candidates"] == []
+    assert unsure["transaction"]["payeeId"] is None
+    assert unsure["candidates"][0]["payeeId"] == starbucks["id"] and unsure["candidates"][0]["score"] < 0.8
+    assert (salary["transaction"]["toAccountId"], salary["candidates"]) == (checking["id"], [])
+    assert balances(client, headers)[checking["id"]] == 10_000 - 750 + 250_000
+
+    confirmed = client.post(
+        f"/api/transactions/{unsure['transaction']['id']}/payee", json={"payeeId": starbucks["id"]}, headers=headers
+    ).json()
+    assert (confirmed["payeeId"], confirmed["categoryId"]) == (starbucks["id"], coffee["id"])
+
+
+def test_statement_reimport_skips_lines_already_booked(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 10_000)
+    lines = [
+        {"date": "2026-05-02", "amountCents": -450, "description": "Bakery"},
+        {"date": "2026-05-02", "amountCents": -450, "description": "Bakery"},
+        {"date": "2026-05-03", "amountCents": -2_000, "description": "Transfer", "externalId": "TX-1"},
+    ]
+    first = client.post("/api/imports/statement", json={"accountId": checking["id"], "lines": lines}, headers=headers)
+    assert (len(first.json()["lines"]), first.json()["skippedLines"]) == (3, [])
+
+    # The bank re-sends the booked lines, renames the referenced one and adds a third bakery visit.
+    lines[2]["description"] = "Transfer to savings"
+    lines.append({"date": "2026-05-02", "amountCents": -450, "description": "BAKERY"})
+    again = client.post(
+        "/api/imports/statement", json={"accountId": checking["id"], "lines": lines}, headers=headers
+    ).json()
+    assert [item["index"] for item in again["skippedLines"]] == [0, 1, 2]
+    assert [item["index"] for item in again["lines"]] == [3]
+    assert balances(client, headers)[checking["id"]] == 10_000 - 3 * 450 - 2_000
+
+
+def test_import_preview_stages_lines_until_committed(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 10_000)
+    groceries = client.post("/api/categories", json={"name": "Groceries"}, headers=headers).json()
+    market = client.post(
+        "/api/payees", json={"name": "Farmers Market", "defaultCategoryId": groceries["id"]}, headers=headers
+    ).json()
+    client.post(
+        "/api/imports/statement",
+        json={"accountId": checking["id"], "lines": [{"date": "2026-06-01", "amountCents": -900, "externalId": "A1"}]},
+        headers=headers,
+    )
+    lines = [
+        {"date": "2026-06-01", "amountCents": -900, "externalId": "A1"},
+        {"date": "2026-06-02", "amountCents": -1_500, "description": "FARMERS MARKET 12"},
+    ]
+
+    preview = client.post("/api/imports/preview", json={"accountId": checking["id"], "lines": lines}, headers=headers)
+    assert preview.status_code == 200
+    staged = preview.json()
+    assert [line["isDuplicate"] for line in staged["lines"]] == [True, False]
+    assert (staged["lines"][1]["payeeId"], staged["lines"][1]["categoryId"]) == (market["id"], groceries["id"])
+    assert balances(client, headers)[checking["id"]] == 10_000 - 900
+    assert [item["id"] for item in client.get("/api/imports", headers=headers).json()] == [staged["id"]]
+
+    committed = client.post(f"/api/imports/{staged['id']}/commit", json={}, headers=headers).json()
+    assert [item["index"] for item in committed["skippedLines"]] == [0]
+    assert committed["lines"][0]["transaction"]["categoryId"] == groceries["id"]
+    assert balances(client, headers)[checking["id"]] == 10_000 - 900 - 1_500
+    assert client.get(f"/api/imports/{staged['id']}", headers=headers).status_code == 404
+
+    discarded = client.post("/api/imports/preview", json={"accountId": checking["id"], "lines": lines}, headers=headers)
+    assert all(line["isDuplicate"] for line in discarded.json()["lines"])
+    client.post(f"/api/imports/{discarded.json()['id']}/discard", json={}, headers=headers)
+    assert client.get("/api/imports", headers=headers).json() == []
+
+
+def test_import_profile_maps_a_bank_csv_onto_statement_lines(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 10_000)
+    created = client.post(
+        "/api/imports/profiles",
+        json={
+            "name": "Hausbank",
+            "accountId": checking["id"],
+            "delimiter": ";",
+            "skipRows": 1,
+            "dateColumn": "Buchungstag",
+            "dateFormat": "%d.%m.%Y",
+            "debitColumn": "Soll",
+            "creditColumn": "Haben",
+            "descriptionColumn": "Verwendungszweck",
+            "signConvention": "DebitCredit",
+            "decimalSeparator": ",",
+        },
+        headers=headers,
+    )
+    assert created.status_code == 200
+    profile = created.json()
+    duplicate = client.post(
+        "/api/imports/profiles",
+        json={"name": "Hausbank", "dateColumn": "Datum", "amountColumn": "Betrag"},
+        headers=headers,
+    )
+    assert duplicate.status_code == 409
+
+    content = (
+        "Kontoauszug 06/2026\n"
+        "Buchungstag;Verwendungszweck;Soll;Haben\n"
+        "01.06.2026;Miete Juni;1.200,00;\n"
+        "\n"
+        "15.06.2026;Gehalt;;2.500,50\n"
+    )
+    preview = client.post(
+        "/api/imports/preview-csv", json={"profileId": profile["id"], "content": content}, headers=headers
+    ).json()
+    assert [(line["date"], line["amountCents"], line["description"]) for line in preview["lines"]] == [
+        ("2026-06-01", -120_000, "Miete Juni"),
+        ("2026-06-15", 250_050, "Gehalt"),
+    ]
+
+    bad_date = client.post(
+        "/api/imports/preview-csv",
+        json={"profileId": profile["id"], "content": content.replace("15.06.2026", "2026-06-15")},
+        headers=headers,
+    )
+    assert bad_date.status_code == 400 and "line 5" in bad_date.json()["message"]
+
+    updated = client.patch(
+        f"/api/imports/profiles/{profile['id']}",
+        json={"signConvention": "Inverted", "amountColumn": "Betrag"},
+        headers=headers,
+    ).json()
+    assert (updated["signConvention"], updated["amountColumn"], updated["dateFormat"]) == (
+        "Inverted",
+        "Betrag",
+        "%d.%m.%Y",
+    )
+    client.post(f"/api/imports/profiles/{profile['id']}/delete", json={}, headers=headers)
+    assert client.get("/api/imports/profiles", headers=headers).json() == []
@@ -0,0 +1,101 @@
+from fastapi.testclient import TestClient
+
+from app.services import interest as interest_service
+from app.services import loans as loans_service
+from conftest import auth_headers, balances, create_account, record_transaction
+
+
+def test_loan_schedule_splits_installments_into_principal_and_interest(client: TestClient) -> None:
+    headers = auth_headers(client)
+    mortgage = create_account(client, headers, "Mortgage", account_type="Liability")
+    checking = create_account(client, headers, "Checking", 1_000_00)
+    loan = {
+        "liabilityAccountId": mortgage["id"],
+        "paymentAccountId": checking["id"],
+        "principalCents": 1_200_00,
+        "aprPercent": 12,
+        "termMonths": 12,
+        "paymentDay": 5,
+        "startPeriodYm": "2025-01",
+        "disbursedToAccountId": checking["id"],
+        "occurredAt": "2024-12-20T10:00:00Z",
+    }
+    created = client.post("/api/loans", json=loan, headers=headers).json()
+    assert created["loan"]["paymentCents"] == 106_62
+
+    posted = client.post("/api/loans/payments", json={"periodYm": "2025-01"}, headers=headers).json()
+    assert [tx["amountCents"] for tx in posted["transactions"]] == [94_62, 12_00]
+    again = client.post("/api/loans/payments", json={"periodYm": "2025-01"}, headers=headers).json()
+    assert again["transactions"] == []
+    balances = {a["name"]: a["balanceCents"] for a in client.get("/api/accounts", headers=headers).json()}
+    assert balances == {"Mortgage": -1_105_38, "Checking": 2_093_38}
+
+    # Reports only read; the background job posts the installments that have fallen due.
+    params = {"periodYm": "2025-02"}
+    before = client.get("/api/reports/utility", params=params, headers=headers).json()
+    assert "Loan interest" not in {item["label"] for item in before["items"]}
+    assert loans_service.generate_loan_payments() == 2 * 11
+    assert loans_service.generate_loan_payments() == 0
+    utility = client.get("/api/reports/utility", params=params, headers=headers).json()
+    assert {item["label"]: item["amountCents"] for item in utility["items"]}["Loan interest"] == 11_05
+    schedule = client.get(f"/api/loans/{created['loan']['id']}/schedule", headers=headers).json()
+    assert all(entry["posted"] for entry in schedule["entries"])
+    assert schedule["entries"][-1]["remainingPrincipalCents"] == 0
+
+
+def test_interest_accrues_monthly_on_liabilities_with_a_rate(client: TestClient) -> None:
+    headers = auth_headers(client)
+    card = create_account(client, headers, "Card", -1_000_00, account_type="Liability", interestRatePercent=12)
+    assert card["interestRatePercent"] == 12
+    savings = {"name": "Savings", "accountType": "Asset", "purpose": "LifeSupport", "initialBalanceCents": 0}
+    assert client.post("/api/accounts", json={**savings, "interestRatePercent": 3}, headers=headers).status_code == 400
+    record_transaction(client, headers, 200_00, "2025-02-10T10:00:00Z", fromAccountId=card["id"])
+
+    # January charges 1% of what was owed at its end, before February's purchase.
+    accrued = client.post("/api/accounts/interest-accruals", json={"periodYm": "2025-01"}, headers=headers).json()
+    assert [(a["owedCents"], a["amountCents"]) for a in accrued["accruals"]] == [(1_000_00, 10_00)]
+    again = client.post("/api/accounts/interest-accruals", json={"periodYm": "2025-01"}, headers=headers).json()
+    assert again["accruals"] == []
+
+    params = {"periodYm": "2025-02"}
+    before = client.get("/api/reports/utility", params=params, headers=headers).json()
+    assert "Interest" not in {item["label"] for item in before["items"]}
+    # The background job carries on from January through the last month that has ended.
+    assert interest_service.generate_interest_accruals() > 0
+    assert interest_service.generate_interest_accruals() == 0
+    utility = client.get("/api/reports/utility", params=params, headers=headers).json()
+    assert {item["label"]: item["amountCents"] for item in utility["items"]}["Interest"] == 12_10
+
+
+def test_prepayment_simulation_shortens_the_loan_without_posting(client: TestClient) -> None:
+    headers = auth_headers(client)
+    mortgage = create_account(client, headers, "Mortgage", -1_200_00, account_type="Liability")
+    checking = create_account(client, headers, "Checking", 5_000_00)
+    loan = {
+        "liabilityAccountId": mortgage["id"],
+        "paymentAccountId": checking["id"],
+        "principalCents": 1_200_00,
+        "aprPercent": 12,
+        "termMonths": 12,
+        "paymentDay": 5,
+        "startPeriodYm": "2025-01",
+    }
+    loan_id = client.post("/api/loans", json=loan, headers=headers).json()["loan"]["id"]
+    client.post("/api/loans/payments", json={"periodYm": "2025-01"}, headers=headers)
+    path = f"/api/loans/{loan_id}/prepayment-simulation"
+
+    plain = client.get(path, headers=headers).json()
+    assert (plain["fromPeriodYm"], plain["remainingPrincipalCents"], plain["baselineMonths"]) == (
+        "2025-02",
+        1_105_38,
+        11,
+    )
+    assert (plain["monthsSaved"], plain["interestSavedCents"]) == (0, 0)
+
+    params = {"lumpSumCents": 500_00, "lumpSumPeriodYm": "2025-03", "monthlyExtraCents": 50_00}
+    simulated = client.get(path, params=params, headers=headers).json()
+    assert simulated["simulatedMonths"] < simulated["baselineMonths"]
+    assert simulated["interestSavedCents"] > 0
+    assert simulated["simulatedPayoffPeriodYm"] < simulated["baselinePayoffPeriodYm"] == "2025-12"
+    assert len(client.get("/api/transactions", headers=headers).json()["items"]) == 2
+    assert client.get(path, params={"lumpSumPeriodYm": "2025-01"}, headers=headers).status_code == 400
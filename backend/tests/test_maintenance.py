@@ -0,0 +1,315 @@
+import sqlite3
+import threading
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app import migrations
+from app.db import get_connection
+from conftest import auth_headers, balances, create_account, record_transaction
+
+
+def test_cleanup_suggestions_and_archive(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    empty = create_account(client, headers, "Old Card")
+    stale = client.post("/api/payees", json={"name": "Closed Shop"}, headers=headers).json()
+    record_transaction(
+        client, headers, 500, "2020-01-01T00:00:00Z", fromAccountId=wallet["id"], payeeId=stale["id"]
+    )
+
+    suggestions = client.get("/api/maintenance/cleanup-suggestions", params={"months": 6}, headers=headers).json()
+    assert [item["id"] for item in suggestions["payees"]] == [stale["id"]]
+    assert suggestions["payees"][0]["lastUsedAt"] == "2020-01-01T00:00:00Z"
+    assert [item["id"] for item in suggestions["accounts"]] == [empty["id"]]
+
+    unused = client.post("/api/categories", json={"name": "Unused"}, headers=headers).json()
+    parent = client.post("/api/categories", json={"name": "Parent"}, headers=headers).json()
+    child = client.post("/api/categories", json={"name": "Child", "parentId": parent["id"]}, headers=headers).json()
+    budgeted = client.post("/api/categories", json={"name": "Budgeted"}, headers=headers).json()
+    scheduled = client.post("/api/categories", json={"name": "Scheduled"}, headers=headers).json()
+    defaulted = client.post("/api/categories", json={"name": "Defaulted"}, headers=headers).json()
+    budget = {"categoryId": budgeted["id"], "periodYm": "2020-01", "amountCents": 1_000}
+    assert client.post("/api/budgets", json=budget, headers=headers).status_code == 200
+    rule = {
+        "name": "Gym",
+        "amountCents": 3_000,
+        "fromAccountId": wallet["id"],
+        "categoryId": scheduled["id"],
+        "frequency": "Monthly",
+        "startDate": "2099-01-01",
+    }
+    assert client.post("/api/recurring-rules", json=rule, headers=headers).status_code == 200
+    payee = {"name": "Corner Shop", "defaultCategoryId": defaulted["id"]}
+    corner_shop = client.post("/api/payees", json=payee, headers=headers).json()
+
+    suggestions = client.get("/api/maintenance/cleanup-suggestions", params={"months": 6}, headers=headers).json()
+    assert [item["id"] for item in suggestions["categories"]] == [child["id"], unused["id"]]
+
+    archived = client.post(
+        "/api/maintenance/archive",
+        json={"payeeIds": [stale["id"]], "accountIds": [empty["id"]]},
+        headers=headers,
+    )
+    assert archived.status_code == 200
+    assert archived.json()["archivedCount"] == 2
+    assert [p["id"] for p in client.get("/api/payees", headers=headers).json()] == [corner_shop["id"]]
+    assert [a["id"] for a in client.get("/api/accounts", headers=headers).json()] == [wallet["id"]]
+
+    missing = client.post("/api/maintenance/archive", json={"tagIds": ["nope"]}, headers=headers)
+    assert missing.status_code == 404
+
+
+def test_upgrade_assistant_reports_and_converts_legacy_rows(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 50_000, recordOpeningBalance=True)
+    laptop = create_account(client, headers, "Laptop", purpose="Productivity")
+    purchase = client.post(
+        "/api/asset-purchases",
+        json={
+            "fromAccountId": wallet["id"],
+            "assetAccountId": laptop["id"],
+            "amountCents": 24_000,
+            "strategy": "Linear",
+            "totalPeriods": 24,
+            "residualCents": 0,
+            "startDate": "2026-01-01",
+        },
+        headers=headers,
+    ).json()
+    with get_connection() as conn:
+        conn.execute("UPDATE transactions SET is_asset_purchase = 0 WHERE id = ?", (purchase["transaction"]["id"],))
+    manual = record_transaction(client, headers, 100, "2026-01-15T00:00:00Z", accrualType="Depreciation")
+
+    scanned = client.get("/api/maintenance/upgrade-findings", headers=headers).json()
+    findings = {item["checkId"]: item for item in scanned}
+    assert findings["unflagged_asset_purchase"]["rowIds"] == [purchase["transaction"]["id"]]
+    assert findings["unlinked_depreciation"]["rowIds"] == [manual["id"]]
+    assert findings["unlinked_depreciation"]["convertible"] is False
+
+    report_only = client.post(
+        "/api/maintenance/upgrade-conversions", json={"checkIds": ["unlinked_depreciation"]}, headers=headers
+    )
+    assert report_only.status_code == 400
+
+    converted = client.post(
+        "/api/maintenance/upgrade-conversions", json={"checkIds": ["unflagged_asset_purchase"]}, headers=headers
+    ).json()
+    assert converted == [{"checkId": "unflagged_asset_purchase", "convertedCount": 1}]
+    remaining = client.get("/api/maintenance/upgrade-findings", headers=headers).json()
+    assert [item["checkId"] for item in remaining] == ["unlinked_depreciation"]
+
+
+def test_upgrade_assistant_converts_rows_from_before_later_semantics(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 50_000)
+    savings = create_account(client, headers, "Savings")
+    laptop = create_account(client, headers, "Laptop", purpose="Productivity")
+    outflow = record_transaction(client, headers, 2_000, "2026-02-01T09:00:00Z", fromAccountId=checking["id"])
+    inflow = record_transaction(client, headers, 2_000, "2026-02-01T10:00:00Z", toAccountId=savings["id"])
+    coffee = record_transaction(client, headers, 500, "2026-02-02T09:00:00Z", fromAccountId=checking["id"])
+    written_down = record_transaction(
+        client, headers, 100, "2026-01-31T00:00:00Z", accrualType="Depreciation", fromAccountId=laptop["id"]
+    )
+    purchase = {
+        "fromAccountId": checking["id"],
+        "assetAccountId": laptop["id"],
+        "amountCents": 24_000,
+        "strategy": "Linear",
+        "totalPeriods": 24,
+        "residualCents": 0,
+        "startDate": "2026-01-01",
+    }
+    schedule = client.post("/api/asset-purchases", json=purchase, headers=headers).json()["schedule"]
+    disposal = {"disposedPercent": 50, "occurredAt": "2026-03-01T00:00:00Z"}
+    disposed = client.post(
+        f"/api/amortization-schedules/{schedule['id']}/dispose", json=disposal, headers=headers
+    ).json()["disposedSchedule"]
+    accounts = client.get("/api/accounts", headers=headers).json()
+    book_value = {account["id"]: account["bookValueCents"] for account in accounts}
+    with get_connection() as conn:
+        conn.execute("UPDATE amortization_schedules SET disposed_at = NULL WHERE id = ?", (disposed["id"],))
+        conn.execute("DELETE FROM transaction_postings WHERE transaction_id = ?", (coffee["id"],))
+    before = balances(client, headers)
+
+    scanned = client.get("/api/maintenance/upgrade-findings", headers=headers).json()
+    assert {item["checkId"]: item["rowIds"] for item in scanned} == {
+        "unlinked_depreciation": [written_down["id"]],
+        "split_transfer": [outflow["id"], inflow["id"]],
+        "depreciation_on_account": [written_down["id"]],
+        "undated_disposal": [disposed["id"]],
+        "unbooked_opening_balance": [checking["id"]],
+        "missing_postings": [coffee["id"]],
+    }
+    check_ids = [item["checkId"] for item in scanned if item["convertible"]]
+    converted = client.post("/api/maintenance/upgrade-conversions", json={"checkIds": check_ids}, headers=headers)
+    assert [item["convertedCount"] for item in converted.json()] == [2, 1, 1, 1, 1]
+
+    remaining = client.get("/api/maintenance/upgrade-findings", headers=headers).json()
+    assert [item["checkId"] for item in remaining] == ["unlinked_depreciation"]
+    # Every conversion keeps what the accounts hold; only how it is explained changes.
+    assert balances(client, headers) == before
+    accounts = client.get("/api/accounts", headers=headers).json()
+    assert {account["id"]: account["bookValueCents"] for account in accounts} == book_value
+    postings = client.get(f"/api/transactions/{outflow['id']}/postings", headers=headers).json()
+    assert [(posting["accountId"], posting["amountCents"]) for posting in postings] == [
+        (checking["id"], -2_000),
+        (savings["id"], 2_000),
+    ]
+    assert client.get(f"/api/transactions/{coffee['id']}/postings", headers=headers).json() != []
+    opening = client.post(f"/api/accounts/{checking['id']}/opening-balance", json={}, headers=headers)
+    assert opening.status_code == 400
+
+
+def test_backfill_resumes_after_interrupted_batch(client: TestClient, monkeypatch: pytest.MonkeyPatch) -> None:
+    headers = auth_headers(client)
+    for index in range(5):
+        create_account(client, headers, f"Account {index}")
+
+    calls = {"count": 0}
+
+    def count_pending(conn: sqlite3.Connection) -> int:
+        return conn.execute("SELECT COUNT(*) FROM accounts WHERE exclude_from_adjustment_kpi = 0").fetchone()[0]
+
+    def run_batch(conn: sqlite3.Connection, limit: int) -> int:
+        calls["count"] += 1
+        if calls["count"] == 2:
+            raise RuntimeError("killed mid-migration")
+        return conn.execute(
+            """
+            UPDATE accounts SET exclude_from_adjustment_kpi = 1
+            WHERE id IN (SELECT id FROM accounts WHERE exclude_from_adjustment_kpi = 0 LIMIT ?)
+            """,
+            (limit,),
+        ).rowcount
+
+    monkeypatch.setattr(migrations, "BACKFILL_BATCH_SIZE", 2)
+    monkeypatch.setattr(migrations, "BACKFILLS", [migrations.Backfill("exclude-all", count_pending, run_batch)])
+    with pytest.raises(RuntimeError):
+        migrations.run_backfills()
+    interrupted = client.get("/api/system/migrations", headers=headers).json()
+    interrupted = [state for state in interrupted if state["id"] == "exclude-all"]
+    assert [(state["status"], state["processedCount"], state["totalCount"]) for state in interrupted] == [
+        ("Running", 2, 5)
+    ]
+
+    cursor = max([event["seq"] for event in client.get("/api/events", headers=headers).json()], default=0)
+    migrations.run_backfills()
+    resumed = client.get("/api/system/migrations", headers=headers).json()
+    resumed = [state for state in resumed if state["id"] == "exclude-all"]
+    assert [(state["status"], state["processedCount"], state["totalCount"]) for state in resumed] == [
+        ("Completed", 5, 5)
+    ]
+    assert count_pending(get_connection()) == 0
+    events = client.get("/api/events", params={"afterSeq": cursor}, headers=headers).json()
+    progress = [event["payload"]["processedCount"] for event in events if event["type"] == "migration:progress"]
+    assert progress == [2, 4, 5]
+
+
+def test_writes_wait_for_background_backfills(client: TestClient, monkeypatch: pytest.MonkeyPatch) -> None:
+    headers = auth_headers(client)
+    create_account(client, headers, "Wallet")
+    release = threading.Event()
+
+    def run_batch(conn: sqlite3.Connection, limit: int) -> int:
+        release.wait(timeout=10)
+        return 0
+
+    monkeypatch.setattr(migrations, "BACKFILLS", [migrations.Backfill("slow", lambda conn: 1, run_batch)])
+    cursor = max([event["seq"] for event in client.get("/api/events", headers=headers).json()], default=0)
+    migrations.start_backfills()
+    try:
+        assert client.get("/api/system/init", headers=headers).json()["readOnly"] is True
+        assert [account["name"] for account in client.get("/api/accounts", headers=headers).json()] == ["Wallet"]
+        blocked = client.post("/api/categories", json={"name": "Groceries"}, headers=headers)
+        assert blocked.status_code == 403
+        assert blocked.json()["details"] == {"reason": "migrating"}
+    finally:
+        release.set()
+        assert migrations.wait_for_backfills(timeout=10)
+
+    assert client.post("/api/categories", json={"name": "Groceries"}, headers=headers).status_code == 200
+    events = client.get("/api/events", params={"afterSeq": cursor}, headers=headers).json()
+    progress = [event["payload"]["status"] for event in events if event["type"] == "migration:progress"]
+    assert progress == ["Running", "Completed"]
+
+
+def test_rebuild_balances_reports_and_fixes_drift(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    card = create_account(client, headers, "Card", -1_000, account_type="Liability")
+    record_transaction(client, headers, 2_500, "2026-03-01T00:00:00Z", fromAccountId=wallet["id"])
+    record_transaction(client, headers, 400, "2026-03-02T00:00:00Z", fromAccountId=card["id"])
+
+    clean = client.post("/api/maintenance/rebuild-balances", json={"dryRun": True}, headers=headers).json()
+    assert (clean["checkedCount"], clean["discrepancies"], clean["applied"]) == (2, [], False)
+
+    with get_connection() as conn:
+        conn.execute("UPDATE accounts SET balance_cents = 7000 WHERE id = ?", (wallet["id"],))
+    dry_run = client.post("/api/maintenance/rebuild-balances", json={"dryRun": True}, headers=headers).json()
+    assert [(item["accountId"], item["storedCents"], item["rebuiltCents"], item["differenceCents"])
+            for item in dry_run["discrepancies"]] == [(wallet["id"], 7_000, 7_500, 500)]
+    assert balances(client, headers)[wallet["id"]] == 7_000
+
+    fixed = client.post("/api/maintenance/rebuild-balances", json={}, headers=headers).json()
+    assert fixed["applied"] is True
+    assert balances(client, headers) == {wallet["id"]: 7_500, card["id"]: -1_400}
+
+
+def test_audit_data_reports_broken_invariants(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 50_000)
+    card = create_account(client, headers, "Card", account_type="Liability")
+    laptop = create_account(client, headers, "Laptop", purpose="Productivity")
+    purchase = client.post(
+        "/api/asset-purchases",
+        json={
+            "fromAccountId": wallet["id"],
+            "assetAccountId": laptop["id"],
+            "amountCents": 24_000,
+            "strategy": "Linear",
+            "totalPeriods": 24,
+            "residualCents": 0,
+            "startDate": "2026-01-01",
+        },
+        headers=headers,
+    ).json()
+    client.post("/api/amortization-schedules/catch-up", json={"periodYm": "2026-01"}, headers=headers)
+    lunch = record_transaction(client, headers, 900, "2026-01-05T12:00:00Z", fromAccountId=wallet["id"])
+    assert client.get("/api/maintenance/audit", headers=headers).json() == []
+
+    with get_connection() as conn:
+        conn.execute("PRAGMA foreign_keys = OFF")
+        conn.execute("PRAGMA ignore_check_constraints = ON")
+        conn.execute("UPDATE amortization_postings SET amount_cents = 30000")
+        conn.execute(
+            """
+            INSERT INTO amortization_postings (id, schedule_id, period_ym, amount_cents, transaction_id, generated_at)
+            VALUES ('orphan', 'gone', '2026-01', 100, 'gone', '2026-01-31T00:00:00Z')
+            """
+        )
+        conn.execute("UPDATE accounts SET balance_cents = 500 WHERE id = ?", (card["id"],))
+        conn.execute("UPDATE transactions SET category_id = 'gone' WHERE id = ?", (lunch["id"],))
+
+    findings = client.get("/api/maintenance/audit", headers=headers).json()
+    assert {item["checkId"]: item["rowIds"] for item in findings} == {
+        "orphaned_posting": ["orphan"],
+        "over_depreciated_schedule": [purchase["schedule"]["id"]],
+        "positive_liability": [card["id"]],
+        "missing_category": [lunch["id"]],
+    }
+
+
+def test_database_maintenance_checks_and_compacts(client: TestClient) -> None:
+    headers = auth_headers(client)
+    with get_connection() as conn:
+        conn.execute("CREATE TABLE scratch (payload BLOB)")
+        conn.executemany("INSERT INTO scratch VALUES (randomblob(4096))", [()] * 64)
+    with get_connection() as conn:
+        conn.execute("DROP TABLE scratch")
+
+    result = client.post("/api/maintenance/database", headers=headers).json()
+    assert (result["integrityOk"], result["integrityMessages"], result["compacted"]) == (True, ["ok"], True)
+    assert result["reclaimedBytes"] == result["sizeBeforeBytes"] - result["sizeAfterBytes"] > 64 * 4096
+    with get_connection() as conn:
+        assert conn.execute("PRAGMA freelist_count").fetchone()[0] == 0
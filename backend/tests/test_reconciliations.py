@@ -0,0 +1,116 @@
+from fastapi.testclient import TestClient
+
+from conftest import auth_headers, create_account, record_transaction
+
+
+def test_balance_snapshots_list_reconciliation_history(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    client.post("/api/reconciliations", json={"accountId": wallet["id"], "actualBalanceCents": 10_000}, headers=headers)
+    client.post("/api/reconciliations", json={"accountId": wallet["id"], "actualBalanceCents": 9_500}, headers=headers)
+
+    snapshots = client.get("/api/reconciliations", params={"accountId": wallet["id"]}, headers=headers).json()
+    assert [(s["systemBalanceCents"], s["actualBalanceCents"], s["deltaCents"]) for s in snapshots] == [
+        (10_000, 9_500, -500),
+        (10_000, 10_000, 0),
+    ]
+    assert snapshots[0]["adjustmentTransaction"]["amountCents"] == 500
+    assert snapshots[1]["adjustmentTransaction"] is None
+    missing = client.get("/api/reconciliations", params={"accountId": "nope"}, headers=headers)
+    assert missing.status_code == 404
+
+
+def test_statement_reconciliation_uses_the_cleared_balance(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    pending = record_transaction(
+        client, headers, 2_000, "2025-03-02T00:00:00Z", fromAccountId=wallet["id"], status="Pending"
+    )
+    cleared = record_transaction(client, headers, 1_000, "2025-03-01T00:00:00Z", fromAccountId=wallet["id"])
+    assert (pending["status"], cleared["status"]) == ("Pending", "Cleared")
+
+    statement = {"accountId": wallet["id"], "actualBalanceCents": 9_000, "clearedOnly": True}
+    first = client.post("/api/reconciliations", json=statement, headers=headers).json()
+    assert (first["clearedBalanceCents"], first["deltaCents"], first["reconciledCount"]) == (9_000, 0, 1)
+    assert first["account"]["balanceCents"] == 7_000
+
+    locked = client.post("/api/transactions/mark-cleared", json={"transactionIds": [cleared["id"]]}, headers=headers)
+    assert locked.status_code == 409
+    marked = client.post("/api/transactions/mark-cleared", json={"transactionIds": [pending["id"]]}, headers=headers)
+    assert [tx["status"] for tx in marked.json()["transactions"]] == ["Cleared"]
+
+    statement["actualBalanceCents"] = 7_500
+    second = client.post("/api/reconciliations", json=statement, headers=headers).json()
+    assert (second["clearedBalanceCents"], second["deltaCents"], second["reconciledCount"]) == (7_000, 500, 2)
+    assert second["account"]["balanceCents"] == 7_500
+
+
+def test_statement_lines_match_transactions_within_the_window(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    rent = record_transaction(
+        client, headers, 3_000, "2025-03-01T00:00:00Z", fromAccountId=wallet["id"], status="Pending"
+    )
+    pay = record_transaction(client, headers, 5_000, "2025-03-05T00:00:00Z", toAccountId=wallet["id"])
+    coffee = record_transaction(client, headers, 400, "2025-03-06T00:00:00Z", fromAccountId=wallet["id"])
+
+    result = client.post(
+        "/api/reconciliations/match",
+        json={
+            "accountId": wallet["id"],
+            "lines": [
+                {"date": "2025-03-03", "amountCents": -3_000, "description": "RENT"},
+                {"date": "2025-03-05", "amountCents": 5_000},
+                {"date": "2025-03-07", "amountCents": -1_200, "description": "FEE"},
+            ],
+        },
+        headers=headers,
+    ).json()
+    matched = [(m["line"]["index"], m["transaction"]["id"]) for m in result["matches"]]
+    assert matched == [(0, rent["id"]), (1, pay["id"])]
+    assert result["matches"][0]["transaction"]["status"] == "Cleared"
+    assert [line["description"] for line in result["unmatchedLines"]] == ["FEE"]
+    assert [tx["id"] for tx in result["unmatchedTransactions"]] == [coffee["id"]]
+
+
+def test_balance_drift_report_charts_reconciliation_deltas(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    savings = create_account(client, headers, "Savings", 50_000)
+    for actual in (9_800, 9_900, 9_900):
+        reconcile = {"accountId": wallet["id"], "actualBalanceCents": actual}
+        client.post("/api/reconciliations", json=reconcile, headers=headers)
+
+    report = client.get("/api/reports/balance-drift", headers=headers).json()
+    assert [(s["label"], [p["deltaCents"] for p in s["points"]]) for s in report["series"]] == [
+        ("Wallet", [-200, 100, 0]),
+    ]
+    assert report["series"][0]["totalAbsoluteDeltaCents"] == 300
+    only_savings = client.get("/api/reports/balance-drift", params={"accountId": savings["id"]}, headers=headers)
+    assert only_savings.json()["series"] == []
+
+
+def test_recategorize_reconciliation_adjustment(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    food = client.post("/api/categories", json={"name": "Food"}, headers=headers).json()
+    reconcile = {"accountId": wallet["id"], "actualBalanceCents": 9_200}
+    adjustment = client.post("/api/reconciliations", json=reconcile, headers=headers).json()["adjustmentTransaction"]
+    manual = client.post(f"/api/accounts/{wallet['id']}/balance", json={"newBalanceCents": 9_000}, headers=headers)
+
+    unreviewed = client.get("/api/transactions/unreviewed-adjustments", headers=headers).json()
+    assert [tx["id"] for tx in unreviewed] == [adjustment["id"]]
+    not_reconciled = client.post(
+        f"/api/transactions/{manual.json()['adjustmentTransaction']['id']}/recategorize",
+        json={"categoryId": food["id"]},
+        headers=headers,
+    )
+    assert not_reconciled.status_code == 400
+
+    updated = client.post(
+        f"/api/transactions/{adjustment['id']}/recategorize",
+        json={"categoryId": food["id"], "note": "Forgotten lunch"},
+        headers=headers,
+    ).json()
+    assert (updated["accrualType"], updated["categoryId"], updated["note"]) == ("Flow", food["id"], "Forgotten lunch")
+    assert client.get("/api/transactions/unreviewed-adjustments", headers=headers).json() == []
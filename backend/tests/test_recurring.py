@@ -0,0 +1,107 @@
+import pytest
+from fastapi.testclient import TestClient
+
+from app.db import get_connection
+from app.services import recurring as recurring_service
+from conftest import auth_headers, create_account, record_transaction
+
+
+def test_run_recurring_materializes_due_occurrences_once(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 500_000)
+    rule = client.post(
+        "/api/recurring-rules",
+        json={
+            "name": "Rent",
+            "amountCents": 120_000,
+            "fromAccountId": checking["id"],
+            "frequency": "Monthly",
+            "startDate": "2026-01-31",
+        },
+        headers=headers,
+    )
+    assert rule.status_code == 200
+    assert rule.json()["nextRunDate"] == "2026-01-31"
+
+    first = client.post("/api/recurring-rules/run", json={"throughDate": "2026-03-30"}, headers=headers).json()
+    assert [tx["occurredAt"] for tx in first["createdTransactions"]] == [
+        "2026-01-31T00:00:00Z",
+        "2026-02-28T00:00:00Z",
+    ]
+    again = client.post("/api/recurring-rules/run", json={"throughDate": "2026-03-30"}, headers=headers).json()
+    assert again["createdTransactions"] == []
+
+    rules = client.get("/api/recurring-rules", headers=headers).json()
+    assert rules[0]["nextRunDate"] == "2026-03-31"
+    balance = client.get("/api/accounts", headers=headers).json()[0]["balanceCents"]
+    assert balance == 260_000
+
+
+def test_failing_recurring_rule_does_not_block_the_others(
+    client: TestClient, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 500_000)
+    rule = {"amountCents": 1_000, "fromAccountId": checking["id"], "frequency": "Daily", "startDate": "2026-03-01"}
+    broken = client.post("/api/recurring-rules", json={**rule, "name": "Broken"}, headers=headers).json()
+    coffee = client.post("/api/recurring-rules", json={**rule, "name": "Coffee"}, headers=headers).json()
+    # Written behind the API's back, the way an older version or a shell could have left it.
+    with get_connection() as conn:
+        conn.execute("UPDATE recurring_rules SET amount_cents = ? WHERE id = ?", (2**62, broken["id"]))
+    monkeypatch.setattr(recurring_service, "MAX_OCCURRENCES_PER_RUN", 3)
+
+    run = {"throughDate": "2026-03-05"}
+    first = client.post("/api/recurring-rules/run", json=run, headers=headers).json()
+    assert [tx["occurredAt"][:10] for tx in first["createdTransactions"]] == ["2026-03-01", "2026-03-02", "2026-03-03"]
+    assert [(failed["ruleId"], failed["code"]) for failed in first["failedRules"]] == [
+        (broken["id"], "amount_out_of_range")
+    ]
+    assert first["hasMore"] is True
+
+    second = client.post("/api/recurring-rules/run", json=run, headers=headers).json()
+    assert [tx["occurredAt"][:10] for tx in second["createdTransactions"]] == ["2026-03-04", "2026-03-05"]
+    assert [failed["ruleId"] for failed in second["failedRules"]] == [broken["id"]]
+    assert second["hasMore"] is False
+    rules = {rule["id"]: rule for rule in client.get("/api/recurring-rules", headers=headers).json()}
+    assert (rules[broken["id"]]["nextRunDate"], rules[coffee["id"]]["nextRunDate"]) == ("2026-03-01", "2026-03-06")
+    assert client.get("/api/accounts", headers=headers).json()[0]["balanceCents"] == 495_000
+
+    far_ahead = client.post("/api/recurring-rules/run", json={"throughDate": "2999-01-01"}, headers=headers)
+    assert far_ahead.status_code == 400
+
+
+def test_upcoming_bills_match_payments(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 100_000)
+    card = create_account(client, headers, "Credit Card", -40_000, account_type="Liability")
+    utility = client.post("/api/payees", json={"name": "Power Co"}, headers=headers).json()
+    for name, extra in (("Card", {"liabilityAccountId": card["id"]}), ("Power", {"payeeId": utility["id"]})):
+        created = client.post(
+            "/api/bills",
+            json={"name": name, "expectedAmountCents": 10_000, "dueDay": 10, **extra},
+            headers=headers,
+        )
+        assert created.status_code == 200
+    record_transaction(
+        client, headers, 30_000, "2026-04-08T09:00:00Z", fromAccountId=checking["id"], toAccountId=card["id"]
+    )
+
+    upcoming = client.get(
+        "/api/bills/upcoming", params={"periodYm": "2026-04", "asOf": "2026-04-15"}, headers=headers
+    ).json()
+    statuses = {item["bill"]["name"]: item["status"] for item in upcoming}
+    assert statuses == {"Card": "Paid", "Power": "Overdue"}
+    assert upcoming[0]["dueDate"] == "2026-04-10"
+
+    # A split with one leg paying down the card pays the bill as well.
+    savings = create_account(client, headers, "Savings")
+    legs = [(checking["id"], -15_000), (card["id"], 10_000), (savings["id"], 5_000)]
+    split = {
+        "postings": [{"accountId": account_id, "amountCents": amount} for account_id, amount in legs],
+        "occurredAt": "2026-05-08T09:00:00Z",
+    }
+    assert client.post("/api/transactions/multi-leg", json=split, headers=headers).status_code == 200
+    upcoming = client.get(
+        "/api/bills/upcoming", params={"periodYm": "2026-05", "asOf": "2026-05-15"}, headers=headers
+    ).json()
+    assert {item["bill"]["name"]: item["status"] for item in upcoming} == {"Card": "Paid", "Power": "Overdue"}
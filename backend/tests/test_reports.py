@@ -0,0 +1,514 @@
+from datetime import datetime, timezone
+
+import pytest
+from fastapi.testclient import TestClient
+
+from conftest import auth_headers, create_account, record_transaction
+
+
+def test_adjustment_kpi_skips_excluded_accounts(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    broker = create_account(client, headers, "Broker", 50_000, purpose="Investment")
+
+    record_transaction(client, headers, 4_000, "2026-03-05T10:00:00Z", fromAccountId=wallet["id"])
+    for account_id, actual in ((wallet["id"], 5_000), (broker["id"], 53_000)):
+        reconciled = client.post(
+            "/api/reconciliations",
+            json={"accountId": account_id, "actualBalanceCents": actual, "occurredAt": "2026-03-31T10:00:00Z"},
+            headers=headers,
+        )
+        assert reconciled.status_code == 200
+
+    before = client.get("/api/kpis/adjustment", headers=headers).json()
+    assert before["adjustmentTotalCents"] == 4_000
+
+    updated = client.patch(
+        f"/api/accounts/{broker['id']}",
+        json={"excludeFromAdjustmentKpi": True},
+        headers=headers,
+    )
+    assert updated.status_code == 200
+    assert updated.json()["excludeFromAdjustmentKpi"] is True
+
+    # A split with any leg on the excluded account is left out too.
+    savings = create_account(client, headers, "Savings")
+    legs = [(wallet["id"], -1_500), (broker["id"], 1_000), (savings["id"], 500)]
+    split = {
+        "postings": [{"accountId": account_id, "amountCents": amount} for account_id, amount in legs],
+        "occurredAt": "2026-04-02T10:00:00Z",
+    }
+    assert client.post("/api/transactions/multi-leg", json=split, headers=headers).status_code == 200
+
+    after = client.get("/api/kpis/adjustment", headers=headers).json()
+    assert after["adjustmentTotalCents"] == 1_000
+    assert after["expenseTotalCents"] == 4_000
+    assert after["ratio"] == 0.25
+
+
+def test_budget_report_carries_over_rollover_balance(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    groceries = client.post("/api/categories", json={"name": "Groceries"}, headers=headers).json()
+
+    for period_ym, rollover in (("2026-01", True), ("2026-02", True), ("2026-03", False)):
+        response = client.post(
+            "/api/budgets",
+            json={"categoryId": groceries["id"], "periodYm": period_ym, "amountCents": 30_000, "rollover": rollover},
+            headers=headers,
+        )
+        assert response.status_code == 200
+    for occurred_at, amount in (("2026-01-10T10:00:00Z", 20_000), ("2026-02-10T10:00:00Z", 45_000)):
+        record_transaction(
+            client, headers, amount, occurred_at, fromAccountId=wallet["id"], categoryId=groceries["id"]
+        )
+
+    february = client.get("/api/reports/budget", params={"periodYm": "2026-02"}, headers=headers).json()
+    assert february["items"][0]["carriedOverCents"] == 10_000
+    assert february["items"][0]["remainingCents"] == -5_000
+
+    march = client.get("/api/reports/budget", params={"periodYm": "2026-03"}, headers=headers).json()
+    item = march["items"][0]
+    assert item["budgetedCents"] == 30_000
+    assert item["carriedOverCents"] == -5_000
+    assert item["effectiveBudgetCents"] == 25_000
+
+
+def test_crossing_current_budget_emits_event(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    dining = client.post("/api/categories", json={"name": "Dining"}, headers=headers).json()
+    period_ym = datetime.now(timezone.utc).strftime("%Y-%m")
+    client.post(
+        "/api/budgets",
+        json={"categoryId": dining["id"], "periodYm": period_ym, "amountCents": 10_000},
+        headers=headers,
+    )
+    cursor = max([event["seq"] for event in client.get("/api/events", headers=headers).json()], default=0)
+
+    spend = {"fromAccountId": wallet["id"], "categoryId": dining["id"]}
+    record_transaction(client, headers, 6_000, f"{period_ym}-01T08:00:00Z", **spend)
+    crossing = record_transaction(client, headers, 6_000, f"{period_ym}-01T09:00:00Z", **spend)
+    record_transaction(client, headers, 1_000, f"{period_ym}-01T10:00:00Z", **spend)
+
+    events = client.get("/api/events", params={"afterSeq": cursor}, headers=headers).json()
+    exceeded = [event for event in events if event["type"] == "budget:exceeded"]
+    assert len(exceeded) == 1
+    assert exceeded[0]["payload"]["transactionId"] == crossing["id"]
+    assert exceeded[0]["payload"]["overByCents"] == 2_000
+
+
+def test_dashboard_summarizes_balances_and_current_month(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=50_000)
+    card = create_account(client, headers, "Card", account_type="Liability")
+    create_account(client, headers, "Savings", balance_cents=20_000)
+    today = datetime.now(timezone.utc).strftime("%Y-%m-%d")
+    record_transaction(client, headers, 3_000, f"{today}T09:00:00Z", fromAccountId=wallet["id"])
+    record_transaction(client, headers, 4_000, f"{today}T10:00:00Z", fromAccountId=card["id"])
+    record_transaction(client, headers, 10_000, f"{today}T11:00:00Z", toAccountId=wallet["id"])
+    record_transaction(client, headers, 1_000, "2020-01-15T10:00:00Z", fromAccountId=wallet["id"])
+    client.post(
+        "/api/reconciliations",
+        json={"accountId": wallet["id"], "actualBalanceCents": 56_000},
+        headers=headers,
+    )
+
+    dashboard = client.get("/api/dashboard", headers=headers).json()
+    assert dashboard["totalAssetsCents"] == 76_000
+    assert dashboard["totalLiabilitiesCents"] == 4_000
+    assert dashboard["netWorthCents"] == 72_000
+    assert dashboard["monthToDateExpenseCents"] == 7_000
+    assert dashboard["monthToDateIncomeCents"] == 10_000
+    assert dashboard["pendingReconciliationCount"] == 2
+    assert dashboard["activeAmortizationCount"] == 0
+
+
+def test_cash_flow_report_separates_income_expense_and_transfers(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=10_000)
+    savings = create_account(client, headers, "Savings")
+    salary = client.post("/api/categories", json={"name": "Salary"}, headers=headers).json()
+    food = client.post("/api/categories", json={"name": "Food"}, headers=headers).json()
+    record_transaction(
+        client, headers, 300_000, "2025-03-01T09:00:00Z", toAccountId=wallet["id"], categoryId=salary["id"]
+    )
+    record_transaction(
+        client, headers, 4_000, "2025-03-02T09:00:00Z", fromAccountId=wallet["id"], categoryId=food["id"]
+    )
+    record_transaction(
+        client, headers, 50_000, "2025-03-03T09:00:00Z", fromAccountId=wallet["id"], toAccountId=savings["id"]
+    )
+    card = create_account(client, headers, "Card", account_type="Liability")
+    record_transaction(
+        client, headers, 2_000, "2025-03-04T09:00:00Z", fromAccountId=card["id"], categoryId=food["id"]
+    )
+    record_transaction(
+        client, headers, 2_000, "2025-03-05T09:00:00Z", fromAccountId=wallet["id"], toAccountId=card["id"]
+    )
+    # A balance correction is neither income nor expense.
+    client.post(
+        f"/api/accounts/{wallet['id']}/balance",
+        json={"newBalanceCents": 250_000, "occurredAt": "2025-03-20T00:00:00Z"},
+        headers=headers,
+    )
+
+    # The whole month reads the monthly totals; drilling into transactions scans the rows.
+    for params in ({"periodYm": "2025-03"}, {"periodYm": "2025-03", "topTransactions": 1}):
+        report = client.get("/api/reports/cash", params=params, headers=headers).json()
+        assert report["totalIncomeCents"] == 300_000
+        assert [(item["label"], item["amountCents"]) for item in report["incomeItems"]] == [("Salary", 300_000)]
+        assert report["totalExpenseCents"] == 6_000
+        assert [(item["label"], item["amountCents"]) for item in report["items"]] == [("Food", 6_000)]
+        assert report["totalTransferCents"] == 50_000
+        assert (report["totalDebtRepaymentCents"], report["totalBorrowingCents"]) == (2_000, 0)
+        assert report["netCents"] == 294_000
+        assert [(item["label"], item["amountCents"]) for item in report["financing"]["items"]] == [
+            ("Debt repayments", -2_000)
+        ]
+
+
+def test_report_items_include_top_contributing_transactions(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=100_000)
+    food = client.post("/api/categories", json={"name": "Food"}, headers=headers).json()
+    spend = {"fromAccountId": wallet["id"], "categoryId": food["id"]}
+    small = record_transaction(client, headers, 1_000, "2025-03-02T09:00:00Z", **spend)
+    large = record_transaction(client, headers, 9_000, "2025-03-03T09:00:00Z", **spend)
+    medium = record_transaction(client, headers, 5_000, "2025-03-04T09:00:00Z", **spend)
+
+    plain = client.get("/api/reports/cash", params={"periodYm": "2025-03"}, headers=headers).json()
+    assert plain["items"][0]["topTransactionIds"] is None
+
+    drilled = client.get(
+        "/api/reports/utility",
+        params={"periodYm": "2025-03", "topTransactions": 2},
+        headers=headers,
+    ).json()
+    assert drilled["items"][0]["amountCents"] == 15_000
+    assert drilled["items"][0]["topTransactionIds"] == [large["id"], medium["id"]]
+    assert small["id"] not in drilled["items"][0]["topTransactionIds"]
+
+
+def test_category_trend_returns_monthly_totals_over_window(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=100_000)
+    groceries = client.post("/api/categories", json={"name": "Groceries"}, headers=headers).json()
+    spend = {"fromAccountId": wallet["id"], "categoryId": groceries["id"]}
+    record_transaction(client, headers, 2_000, "2025-01-10T09:00:00Z", **spend)
+    record_transaction(client, headers, 3_000, "2025-03-05T09:00:00Z", **spend)
+    latest = record_transaction(client, headers, 4_000, "2025-03-25T09:00:00Z", **spend)
+    record_transaction(client, headers, 9_000, "2024-12-31T09:00:00Z", **spend)
+
+    trend = client.get(
+        "/api/reports/category-trend",
+        params={"categoryIds": [groceries["id"]], "months": 3, "endPeriodYm": "2025-03", "topTransactions": 1},
+        headers=headers,
+    ).json()
+    assert (trend["fromPeriodYm"], trend["toPeriodYm"]) == ("2025-01", "2025-03")
+    points = trend["series"][0]["points"]
+    assert [(point["periodYm"], point["amountCents"]) for point in points] == [
+        ("2025-01", 2_000),
+        ("2025-02", 0),
+        ("2025-03", 7_000),
+    ]
+    assert points[2]["topTransactionIds"] == [latest["id"]]
+
+
+def test_purpose_report_groups_outflows_by_receiving_asset_purpose(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=200_000)
+    broker = create_account(client, headers, "Broker", purpose="Investment")
+    laptop = create_account(client, headers, "Laptop", purpose="Productivity")
+    card = create_account(client, headers, "Card", balance_cents=-10_000, account_type="Liability")
+    for amount, occurred_at, target in [
+        (30_000, "2025-04-02T09:00:00Z", broker),
+        (20_000, "2025-04-09T09:00:00Z", broker),
+        (80_000, "2025-04-12T09:00:00Z", laptop),
+        (5_000, "2025-04-15T09:00:00Z", card),
+        (9_000, "2025-05-01T09:00:00Z", broker),
+    ]:
+        record_transaction(client, headers, amount, occurred_at, fromAccountId=wallet["id"], toAccountId=target["id"])
+
+    report = client.get("/api/reports/purpose", params={"periodYm": "2025-04"}, headers=headers).json()
+    assert report["totalExpenseCents"] == 130_000
+    assert [(item["label"], item["amountCents"]) for item in report["items"]] == [
+        ("Productivity", 80_000),
+        ("Investment", 50_000),
+    ]
+
+
+def test_reports_accept_a_custom_date_range(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=100_000)
+    record_transaction(client, headers, 1_000, "2025-06-27T23:59:59Z", fromAccountId=wallet["id"])
+    record_transaction(client, headers, 2_000, "2025-06-28T08:00:00Z", fromAccountId=wallet["id"])
+    record_transaction(client, headers, 3_000, "2025-07-04T21:00:00Z", fromAccountId=wallet["id"])
+    record_transaction(client, headers, 4_000, "2025-07-05T00:00:00Z", fromAccountId=wallet["id"])
+
+    week = {"fromDate": "2025-06-28", "toDate": "2025-07-04"}
+    cash = client.get("/api/reports/cash", params=week, headers=headers).json()
+    assert (cash["periodYm"], cash["fromDate"], cash["toDate"]) == (None, "2025-06-28", "2025-07-04")
+    assert cash["totalExpenseCents"] == 5_000
+    assert client.get("/api/reports/utility", params=week, headers=headers).json()["totalExpenseCents"] == 5_000
+
+    month = client.get("/api/reports/cash", params={"periodYm": "2025-07"}, headers=headers).json()
+    assert (month["fromDate"], month["toDate"], month["totalExpenseCents"]) == ("2025-07-01", "2025-07-31", 7_000)
+
+    both = client.get("/api/reports/cash", params={"periodYm": "2025-07", **week}, headers=headers)
+    assert both.status_code == 400
+    backwards = {"fromDate": "2025-07-04", "toDate": "2025-06-28"}
+    assert client.get("/api/reports/cash", params=backwards, headers=headers).status_code == 400
+
+
+def test_spending_pace_compares_month_to_date_with_history(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=500_000)
+    for occurred_at, amount in [
+        ("2025-01-05T09:00:00Z", 10_000),
+        ("2025-01-20T09:00:00Z", 50_000),
+        ("2025-02-03T09:00:00Z", 20_000),
+        ("2025-03-09T09:00:00Z", 18_000),
+        ("2025-03-11T09:00:00Z", 12_000),
+    ]:
+        record_transaction(client, headers, amount, occurred_at, fromAccountId=wallet["id"])
+
+    pace = client.get("/api/dashboard/spending-pace", params={"asOf": "2025-03-10"}, headers=headers).json()
+    assert (pace["periodYm"], pace["dayOfMonth"], pace["monthToDateCents"]) == ("2025-03", 10, 18_000)
+    assert (pace["historyMonthCount"], pace["baselineCents"]) == (2, 15_000)
+    assert (pace["overPacePercent"], pace["thresholdPercent"], pace["isOverPace"]) == (20, 20, True)
+
+    later = client.get("/api/dashboard/spending-pace", params={"periodYm": "2025-02"}, headers=headers).json()
+    assert (later["asOfDate"], later["historyMonthCount"], later["isOverPace"]) == ("2025-02-28", 1, False)
+    outside = {"periodYm": "2025-02", "asOf": "2025-03-01"}
+    assert client.get("/api/dashboard/spending-pace", params=outside, headers=headers).status_code == 400
+
+
+def test_reports_accept_quarter_and_year_periods(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=100_000)
+    for occurred_at, amount in [
+        ("2025-12-31T12:00:00Z", 100),
+        ("2026-01-15T12:00:00Z", 1_000),
+        ("2026-03-31T23:00:00Z", 2_000),
+        ("2026-04-01T00:00:00Z", 4_000),
+    ]:
+        record_transaction(client, headers, amount, occurred_at, fromAccountId=wallet["id"])
+
+    quarter = client.get("/api/reports/cash", params={"periodYm": "2026-Q1"}, headers=headers).json()
+    assert (quarter["fromDate"], quarter["toDate"], quarter["totalExpenseCents"]) == ("2026-01-01", "2026-03-31", 3_000)
+    year = client.get("/api/reports/utility", params={"periodYm": "2026"}, headers=headers).json()
+    assert (year["toDate"], year["totalExpenseCents"]) == ("2026-12-31", 7_000)
+
+    assert client.get("/api/reports/cash", params={"periodYm": "2026-Q5"}, headers=headers).status_code == 400
+    budget = {"categoryId": "missing", "periodYm": "2026-Q1", "amountCents": 1_000}
+    assert client.post("/api/budgets", json=budget, headers=headers).status_code == 400
+
+
+def test_period_start_day_shifts_report_and_budget_boundaries(client: TestClient) -> None:
+    headers = auth_headers(client)
+    account = {"name": "Wallet", "accountType": "Asset", "purpose": "LifeSupport", "initialBalanceCents": 100_000}
+    onboarding = {"baseCurrency": "EUR", "periodStartDay": 25, "accounts": [account], "categories": [{"name": "Food"}]}
+    result = client.post("/api/onboarding", json=onboarding, headers=headers).json()
+    wallet, food = result["accounts"][0], result["categories"][0]
+    spend = {"fromAccountId": wallet["id"], "categoryId": food["id"]}
+    record_transaction(client, headers, 1_000, "2026-03-24T12:00:00Z", **spend)
+    inside = record_transaction(client, headers, 2_000, "2026-03-25T00:00:00Z", **spend)
+    record_transaction(client, headers, 3_000, "2026-04-24T23:00:00Z", **spend)
+    record_transaction(client, headers, 4_000, "2026-04-25T00:00:00Z", **spend)
+
+    cash = client.get("/api/reports/cash", params={"periodYm": "2026-03"}, headers=headers).json()
+    assert (cash["fromDate"], cash["toDate"], cash["totalExpenseCents"]) == ("2026-03-25", "2026-04-24", 5_000)
+    listed = client.get("/api/transactions", params={"periodYm": "2026-03"}, headers=headers).json()["items"]
+    assert [tx["amountCents"] for tx in listed] == [3_000, 2_000]
+    assert listed[-1]["id"] == inside["id"]
+
+    march_budget = {"categoryId": food["id"], "periodYm": "2026-03", "amountCents": 6_000}
+    client.post("/api/budgets", json=march_budget, headers=headers)
+    budget = client.get("/api/reports/budget", params={"periodYm": "2026-03"}, headers=headers).json()
+    assert budget["items"][0]["spentCents"] == 5_000
+    trend = client.get(
+        "/api/reports/category-trend",
+        params={"categoryIds": [food["id"]], "months": 2, "endPeriodYm": "2026-04"},
+        headers=headers,
+    ).json()
+    assert [point["amountCents"] for point in trend["series"][0]["points"]] == [5_000, 4_000]
+
+
+def test_rolling_average_smooths_spend_over_trailing_months(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=100_000)
+    rent = client.post("/api/categories", json={"name": "Rent"}, headers=headers).json()
+    spend = {"fromAccountId": wallet["id"], "categoryId": rent["id"]}
+    record_transaction(client, headers, 6_000, "2025-01-10T09:00:00Z", fromAccountId=wallet["id"])
+    record_transaction(client, headers, 9_000, "2025-05-01T09:00:00Z", **spend)
+    record_transaction(client, headers, 5_000, "2025-07-01T09:00:00Z", **spend)
+
+    report = client.get("/api/reports/rolling-average", params={"endPeriodYm": "2025-06"}, headers=headers).json()
+    assert (report["fromPeriodYm"], report["toPeriodYm"]) == ("2025-01", "2025-06")
+    assert (report["threeMonthAverageCents"], report["sixMonthAverageCents"]) == (3_000, 2_500)
+    items = report["items"]
+    assert [(item["label"], item["threeMonthAverageCents"], item["sixMonthAverageCents"]) for item in items] == [
+        ("Rent", 3_000, 1_500),
+        ("Uncategorized", 0, 1_000),
+    ]
+
+
+def test_spending_forecast_projects_run_rate_plus_scheduled_rules(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 500_000)
+    food, rent, gym = (
+        client.post("/api/categories", json={"name": name}, headers=headers).json() for name in ("Food", "Rent", "Gym")
+    )
+    budget = {"categoryId": food["id"], "periodYm": "2026-04", "amountCents": 8_000}
+    assert client.post("/api/budgets", json=budget, headers=headers).status_code == 200
+    for category, amount, start in ((rent, 50_000, "2026-04-05"), (gym, 4_000, "2026-04-20")):
+        rule = {"name": category["name"], "amountCents": amount, "fromAccountId": wallet["id"], "frequency": "Monthly"}
+        rule.update(categoryId=category["id"], startDate=start)
+        assert client.post("/api/recurring-rules", json=rule, headers=headers).status_code == 200
+    client.post("/api/recurring-rules/run", json={"throughDate": "2026-04-10"}, headers=headers)
+    record_transaction(
+        client, headers, 3_000, "2026-04-08T12:00:00Z", fromAccountId=wallet["id"], categoryId=food["id"]
+    )
+
+    forecast = client.get("/api/dashboard/spending-forecast", params={"asOf": "2026-04-10"}, headers=headers).json()
+    assert (forecast["periodYm"], forecast["daysElapsed"], forecast["daysInPeriod"]) == ("2026-04", 10, 30)
+    assert (forecast["monthToDateCents"], forecast["projectedCents"]) == (53_000, 63_000)
+    assert [
+        (item["label"], item["monthToDateCents"], item["scheduledCents"], item["projectedCents"], item["isOverBudget"])
+        for item in forecast["items"]
+    ] == [
+        ("Rent", 50_000, 0, 50_000, False),
+        ("Food", 3_000, 0, 9_000, True),
+        ("Gym", 0, 4_000, 4_000, False),
+    ]
+
+
+def test_anomalies_flag_outlier_amounts_and_double_charges(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 500_000)
+    power = client.post("/api/payees", json={"name": "Power Co"}, headers=headers).json()
+    bill = {"fromAccountId": wallet["id"], "payeeId": power["id"]}
+    for month, amount in ((1, 4_000), (2, 4_500), (3, 5_000)):
+        record_transaction(client, headers, amount, f"2026-{month:02d}-15T09:00:00Z", **bill)
+    spike = record_transaction(client, headers, 45_000, "2026-04-15T09:00:00Z", **bill)
+    first = record_transaction(client, headers, 1_999, "2026-04-20T10:00:00Z", fromAccountId=wallet["id"])
+    again = record_transaction(client, headers, 1_999, "2026-04-20T18:00:00Z", fromAccountId=wallet["id"])
+
+    anomalies = client.get("/api/transactions/anomalies", params={"periodYm": "2026-04"}, headers=headers).json()
+    assert [(item["transaction"]["id"], item["reason"]) for item in anomalies] == [
+        (spike["id"], "UnusualAmount"),
+        (again["id"], "PossibleDuplicate"),
+    ]
+    assert anomalies[0]["typicalAmountCents"] == 4_500
+    assert anomalies[0]["message"] == "10.0x the usual 45.00 for this payee"
+    assert anomalies[1]["relatedTransactionId"] == first["id"]
+
+
+def test_debt_to_asset_kpi_tracks_leverage_by_month_end(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 500_000)
+    loan = create_account(client, headers, "Car Loan", -200_000, account_type="Liability")
+    now = datetime.now(timezone.utc).replace(microsecond=0).isoformat().replace("+00:00", "Z")
+    record_transaction(client, headers, 50_000, now, fromAccountId=wallet["id"], toAccountId=loan["id"])
+
+    kpi = client.get("/api/kpis/debt-to-asset", params={"months": 2}, headers=headers).json()
+    assert (kpi["assetsCents"], kpi["liabilitiesCents"]) == (450_000, 150_000)
+    assert kpi["ratio"] == pytest.approx(1 / 3)
+    assert [(point["assetsCents"], point["liabilitiesCents"], point["ratio"]) for point in kpi["series"]] == [
+        (500_000, 200_000, pytest.approx(0.4)),
+        (450_000, 150_000, pytest.approx(1 / 3)),
+    ]
+    assert kpi["series"][-1]["date"] == now[:10]
+
+
+def test_liquidity_report_splits_net_worth_by_account_liquidity(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 200_000)
+    assert checking["liquidity"] == "Liquid"
+    create_account(client, headers, "Laptop", 150_000, purpose="Productivity", liquidity="Illiquid")
+    create_account(client, headers, "Card", -30_000, account_type="Liability")
+    brokerage = create_account(client, headers, "Brokerage", 80_000, purpose="Investment")
+    updated = client.patch(f"/api/accounts/{brokerage['id']}", json={"liquidity": "Semi"}, headers=headers).json()
+    assert updated["liquidity"] == "Semi"
+
+    report = client.get("/api/reports/liquidity", headers=headers).json()
+    assert report["netWorthCents"] == 400_000
+    split = [(item["liquidity"], item["assetsCents"], item["liabilitiesCents"]) for item in report["items"]]
+    assert split == [
+        ("Liquid", 200_000, 30_000),
+        ("Semi", 80_000, 0),
+        ("Illiquid", 150_000, 0),
+    ]
+    assert report["items"][0]["netCents"] == 170_000
+
+
+def test_allocation_drift_reports_rebalancing_against_purpose_targets(client: TestClient) -> None:
+    headers = auth_headers(client)
+    create_account(client, headers, "Brokerage", 30_000, purpose="Investment")
+    create_account(client, headers, "Checking", 70_000)
+    targets = [{"purpose": "Investment", "targetPercent": 40}, {"purpose": "LifeSupport", "targetPercent": 50}]
+    assert len(client.post("/api/allocation-targets", json={"targets": targets}, headers=headers).json()) == 2
+    too_much = [{"purpose": "Investment", "targetPercent": 80}, {"purpose": "Spiritual", "targetPercent": 30}]
+    assert client.post("/api/allocation-targets", json={"targets": too_much}, headers=headers).status_code == 400
+
+    report = client.get("/api/reports/allocation-drift", headers=headers).json()
+    assert report["totalAssetsCents"] == 100_000
+    drift = {item["purpose"]: (item["actualPercent"], item["rebalanceCents"]) for item in report["items"]}
+    assert drift == {
+        "Investment": (30.0, 10_000),
+        "Productivity": (0.0, None),
+        "LifeSupport": (70.0, -20_000),
+        "Spiritual": (0.0, None),
+    }
+
+
+def test_cash_flow_report_splits_operating_investing_and_financing(client: TestClient) -> None:
+    headers = auth_headers(client)
+    mortgage = create_account(client, headers, "Mortgage", account_type="Liability")
+    checking = create_account(client, headers, "Checking", 1_000_00)
+    camera = create_account(client, headers, "Camera", purpose="Spiritual")
+    loan = {
+        "liabilityAccountId": mortgage["id"],
+        "paymentAccountId": checking["id"],
+        "principalCents": 1_200_00,
+        "aprPercent": 12,
+        "termMonths": 12,
+        "paymentDay": 5,
+        "startPeriodYm": "2025-01",
+        "disbursedToAccountId": checking["id"],
+        "occurredAt": "2025-01-02T10:00:00Z",
+    }
+    client.post("/api/loans", json=loan, headers=headers)
+    client.post("/api/loans/payments", json={"periodYm": "2025-01"}, headers=headers)
+    client.post(
+        "/api/asset-purchases",
+        json={
+            "fromAccountId": checking["id"],
+            "assetAccountId": camera["id"],
+            "amountCents": 300_00,
+            "occurredAt": "2025-01-10T00:00:00Z",
+            "strategy": "Linear",
+            "totalPeriods": 12,
+            "residualCents": 0,
+            "startDate": "2025-01-01",
+        },
+        headers=headers,
+    )
+    record_transaction(client, headers, 500_00, "2025-01-15T09:00:00Z", toAccountId=checking["id"])
+
+    cash = client.get("/api/reports/cash", params={"periodYm": "2025-01"}, headers=headers).json()
+    assert cash["operating"]["netCents"] == 500_00 - 12_00
+    assert {item["label"]: item["amountCents"] for item in cash["operating"]["items"]} == {
+        "Uncategorized": 500_00,
+        "Loan interest": -12_00,
+    }
+    assert [(item["label"], item["amountCents"]) for item in cash["investing"]["items"]] == [
+        ("Asset purchases", -300_00)
+    ]
+    assert cash["investing"]["netCents"] == -300_00
+    assert [(item["label"], item["amountCents"]) for item in cash["financing"]["items"]] == [
+        ("Loan draws", 1_200_00),
+        ("Loan repayments", -94_62),
+    ]
+    assert cash["financing"]["netCents"] == 1_200_00 - 94_62
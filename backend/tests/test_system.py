@@ -0,0 +1,367 @@
+import fcntl
+import json
+import os
+import sqlite3
+from datetime import datetime, timedelta, timezone
+from pathlib import Path
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app import instance
+from app.config import get_data_dir, get_db_path
+from app.db import get_connection
+from app.main import app
+from app.replay import replay_commands
+from app.services import finance
+from conftest import auth_headers, create_account, record_transaction
+
+
+def test_onboarding_is_atomic_and_runs_once(client: TestClient) -> None:
+    headers = auth_headers(client)
+    account = {"name": "Checking", "accountType": "Asset", "purpose": "LifeSupport", "initialBalanceCents": 5_000}
+
+    broken = client.post(
+        "/api/onboarding",
+        json={"baseCurrency": "cny", "accounts": [account], "categories": [{"name": "Food"}, {"name": "Food"}]},
+        headers=headers,
+    )
+    assert broken.status_code == 409
+    assert client.get("/api/accounts", headers=headers).json() == []
+    assert client.get("/api/system/init", headers=headers).json()["onboardingCompleted"] is False
+
+    done = client.post(
+        "/api/onboarding",
+        json={"baseCurrency": "cny", "periodStartDay": 25, "accounts": [account], "categories": [{"name": "Food"}]},
+        headers=headers,
+    )
+    assert done.status_code == 200
+    payload = done.json()
+    assert payload["baseCurrency"] == "CNY"
+    assert payload["accounts"][0]["balanceCents"] == 5_000
+    assert [c["name"] for c in payload["categories"]] == ["Food"]
+    assert client.get("/api/system/init", headers=headers).json()["onboardingCompleted"] is True
+
+    again = client.post("/api/onboarding", json={"baseCurrency": "CNY", "accounts": [account]}, headers=headers)
+    assert again.status_code == 409
+
+
+def test_config_file_sets_locale_and_read_only(client: TestClient, tmp_path: Path) -> None:
+    headers = auth_headers(client)
+    (tmp_path / "config.toml").write_text('locale = "zh-CN"\nread_only = true\n', encoding="utf-8")
+
+    init_state = client.get("/api/system/init", headers=headers).json()
+    assert init_state["locale"] == "zh-CN"
+    assert init_state["readOnly"] is True
+
+    blocked = client.post(
+        "/api/categories",
+        json={"name": "Groceries"},
+        headers=headers,
+    )
+    assert blocked.status_code == 403
+    assert blocked.json()["code"] == "read_only"
+
+
+def test_read_only_start_serves_reports_without_touching_the_database(client: TestClient, tmp_path: Path) -> None:
+    headers = auth_headers(client)
+    mortgage = create_account(client, headers, "Mortgage", account_type="Liability")
+    checking = create_account(client, headers, "Checking", 1_000_00)
+    loan = {
+        "liabilityAccountId": mortgage["id"],
+        "paymentAccountId": checking["id"],
+        "principalCents": 1_200_00,
+        "aprPercent": 12,
+        "termMonths": 12,
+        "paymentDay": 5,
+        "startPeriodYm": "2025-01",
+    }
+    created = client.post("/api/loans", json=loan, headers=headers).json()
+    (tmp_path / "config.toml").write_text('base_currency = "USD"\nread_only = true\n', encoding="utf-8")
+    before = get_db_path().read_bytes()
+
+    # Installments have fallen due, but a read-only start runs no migrations or jobs to post them.
+    with TestClient(app) as restarted:
+        for path in (
+            "/api/reports/utility?periodYm=2025-02",
+            "/api/reports/income-statement?periodYm=2025-02",
+            "/api/dashboard",
+            f"/api/loans/{created['loan']['id']}/schedule",
+        ):
+            assert restarted.get(path, headers=headers).status_code == 200, path
+    assert get_db_path().read_bytes() == before
+
+
+def test_get_requests_cannot_write(client: TestClient, monkeypatch: pytest.MonkeyPatch) -> None:
+    headers = auth_headers(client)
+    resolve_range = finance._resolve_report_range
+
+    def resolve_and_write(conn: sqlite3.Connection, *args: object) -> object:
+        conn.execute("INSERT INTO settings (key, value, updated_at) VALUES ('report_side_effect', '1', '')")
+        return resolve_range(conn, *args)
+
+    monkeypatch.setattr(finance, "_resolve_report_range", resolve_and_write)
+    refused = client.get("/api/reports/utility", params={"periodYm": "2025-02"}, headers=headers)
+    assert refused.status_code == 500
+    assert "readonly" in refused.json()["details"]["reason"]
+    with get_connection() as conn:
+        assert conn.execute("SELECT 1 FROM settings WHERE key = 'report_side_effect'").fetchone() is None
+
+
+def test_mutations_emit_transaction_and_account_events(client: TestClient) -> None:
+    headers = auth_headers(client)
+    cursor = max([event["seq"] for event in client.get("/api/events", headers=headers).json()], default=0)
+    wallet = create_account(client, headers, "Wallet", balance_cents=10_000)
+    created = record_transaction(client, headers, 2_500, "2025-03-05T10:00:00Z", fromAccountId=wallet["id"])
+    reconciled = client.post(
+        "/api/reconciliations",
+        json={"accountId": wallet["id"], "actualBalanceCents": 7_000, "occurredAt": "2025-03-31T00:00:00Z"},
+        headers=headers,
+    )
+    assert reconciled.status_code == 200
+
+    events = client.get("/api/events", params={"afterSeq": cursor}, headers=headers).json()
+    types = [event["type"] for event in events]
+    assert types == [
+        "account:created",
+        "transaction:created",
+        "account:updated",
+        "transaction:created",
+        "account:updated",
+        "account:reconciled",
+    ]
+    assert events[1]["payload"]["id"] == created["id"]
+    assert events[2]["payload"]["balanceCents"] == 7_500
+    assert events[4]["payload"]["balanceCents"] == 7_000
+
+
+def test_rows_are_stamped_with_the_device_id(client: TestClient) -> None:
+    headers = auth_headers(client)
+    device_id = client.get("/api/system/init", headers=headers).json()["deviceId"]
+    assert device_id
+
+    wallet = create_account(client, headers, "Wallet", balance_cents=10_000)
+    created = record_transaction(client, headers, 1_000, "2025-03-05T10:00:00Z", fromAccountId=wallet["id"])
+    assert wallet["deviceId"] == device_id
+    assert created["deviceId"] == device_id
+
+    # A write that sets the device itself keeps it, like a row merged from another install.
+    with sqlite3.connect(get_db_path()) as conn:
+        conn.execute("UPDATE accounts SET device_id = 'other-machine' WHERE id = ?", (wallet["id"],))
+    accounts = client.get("/api/accounts", headers=headers).json()
+    assert [account["deviceId"] for account in accounts] == ["other-machine"]
+    # The triggers are in the database file, so any other write is stamped, even from a plain connection.
+    with sqlite3.connect(get_db_path()) as conn:
+        conn.execute("UPDATE accounts SET name = 'Purse' WHERE id = ?", (wallet["id"],))
+    accounts = client.get("/api/accounts", headers=headers).json()
+    assert [account["deviceId"] for account in accounts] == [device_id]
+
+
+def test_recorded_commands_are_sanitized_and_replay_cleanly(
+    client: TestClient, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    log_path = tmp_path / "commands.jsonl"
+    monkeypatch.setenv("OIKONOMOS_COMMAND_LOG", str(log_path))
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Private Wallet", balance_cents=5_000)
+    record_transaction(client, headers, 1_000, "2025-03-05T10:00:00Z", fromAccountId=wallet["id"], note="secret")
+    client.post("/api/categories", json={"name": "Therapy"}, headers=headers)
+    duplicate = client.post("/api/categories", json={"name": "Therapy"}, headers=headers)
+    assert duplicate.status_code == 409
+    tag = {"name": "Dr", "color": "#aabbcc", "description": "Tuesday sessions"}
+    client.post("/api/tags", json=tag, headers=headers)
+
+    recorded = log_path.read_text(encoding="utf-8")
+    for private_text in ("Private Wallet", "secret", "Therapy", "Tuesday sessions"):
+        assert private_text not in recorded
+    for kept in (wallet["id"], "2025-03-05T10:00:00Z", "LifeSupport", "#aabbcc"):
+        assert kept in recorded
+    assert len(recorded.splitlines()) == 5
+
+    result = replay_commands(str(log_path))
+    assert result.replayedCount == 5
+    assert result.failures == []
+
+
+def test_second_instance_is_read_only_until_takeover(client: TestClient) -> None:
+    headers = auth_headers(client)
+    lock_path = get_data_dir() / instance.LOCK_FILE_NAME
+    other = {"instanceId": "other-window", "pid": 1, "heartbeatAt": datetime.now(timezone.utc).isoformat()}
+    lock_path.write_text(json.dumps(other), encoding="utf-8")
+    instance.heartbeat()
+
+    assert client.get("/api/system/init", headers=headers).json()["readOnly"] is True
+    blocked = client.post("/api/categories", json={"name": "Groceries"}, headers=headers)
+    assert blocked.status_code == 403
+
+    pending = client.post("/api/instance/takeover", headers=headers).json()
+    assert pending["isOwner"] is False and pending["takeoverPending"] is True
+
+    # The other window hands the lock over on its next heartbeat.
+    lock_path.write_text(json.dumps({**other, "instanceId": pending["instanceId"]}), encoding="utf-8")
+    instance.heartbeat()
+
+    types = [event["type"] for event in client.get("/api/events", headers=headers).json()]
+    assert types[-2:] == ["instance:ownership_lost", "instance:ownership_acquired"]
+    assert client.post("/api/categories", json={"name": "Groceries"}, headers=headers).status_code == 200
+
+
+def test_follower_takes_over_a_stale_lock_on_its_heartbeat(client: TestClient) -> None:
+    headers = auth_headers(client)
+    lock_path = get_data_dir() / instance.LOCK_FILE_NAME
+    other = {"instanceId": "other-window", "pid": 1, "heartbeatAt": datetime.now(timezone.utc).isoformat()}
+    lock_path.write_text(json.dumps(other), encoding="utf-8")
+    instance.heartbeat()
+    instance.heartbeat()
+    assert instance.is_owner() is False
+
+    # The other window stopped refreshing its heartbeat without clearing the file.
+    stale_at = (datetime.now(timezone.utc) - timedelta(seconds=instance.STALE_AFTER_SECONDS + 1)).isoformat()
+    lock_path.write_text(json.dumps({**other, "heartbeatAt": stale_at}), encoding="utf-8")
+    instance.heartbeat()
+
+    status = client.get("/api/instance", headers=headers).json()
+    assert status["isOwner"] is True and status["ownerInstanceId"] == status["instanceId"]
+
+
+def test_lock_claimed_by_another_starting_instance_is_not_taken(client: TestClient) -> None:
+    headers = auth_headers(client)
+    lock_path = get_data_dir() / instance.LOCK_FILE_NAME
+    other = {"instanceId": "other-window", "pid": 1, "heartbeatAt": datetime.now(timezone.utc).isoformat()}
+    lock_path.write_text(json.dumps(other), encoding="utf-8")
+    instance.heartbeat()
+
+    # The other window holds the OS lock but has not written its heartbeat yet, so the
+    # file alone looks free.
+    held = os.open(lock_path, os.O_RDWR)
+    try:
+        fcntl.flock(held, fcntl.LOCK_EX | fcntl.LOCK_NB)
+        lock_path.write_text("", encoding="utf-8")
+        instance.heartbeat()
+        pending = client.post("/api/instance/takeover", headers=headers).json()
+        assert pending["isOwner"] is False and pending["takeoverPending"] is True
+    finally:
+        os.close(held)
+
+    instance.heartbeat()
+    assert instance.is_owner() is True
+
+
+def test_usage_stats_rank_recent_use_above_old_use(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", balance_cents=100_000)
+    card = create_account(client, headers, "Card", balance_cents=100_000)
+    create_account(client, headers, "Unused")
+    coffee = client.post("/api/categories", json={"name": "Coffee"}, headers=headers).json()
+    travel = client.post("/api/categories", json={"name": "Travel"}, headers=headers).json()
+
+    now = datetime.now(timezone.utc)
+    recent = (now - timedelta(days=1)).strftime("%Y-%m-%dT%H:%M:%SZ")
+    old = (now - timedelta(days=200)).strftime("%Y-%m-%dT%H:%M:%SZ")
+    record_transaction(client, headers, 500, recent, fromAccountId=wallet["id"], categoryId=coffee["id"])
+    for _ in range(3):
+        record_transaction(client, headers, 90_00, old, fromAccountId=card["id"], categoryId=travel["id"])
+    client.post(
+        "/api/reconciliations",
+        json={"accountId": card["id"], "actualBalanceCents": 0, "occurredAt": recent},
+        headers=headers,
+    )
+
+    stats = client.get("/api/usage", headers=headers).json()
+    assert [(item["id"], item["useCount"]) for item in stats["accounts"]] == [(wallet["id"], 1), (card["id"], 3)]
+    assert [item["id"] for item in stats["categories"]] == [coffee["id"], travel["id"]]
+    assert stats["categories"][0]["lastUsedAt"] == recent
+
+
+def test_settings_are_typed_and_saved_per_field(client: TestClient) -> None:
+    headers = auth_headers(client)
+    defaults = client.get("/api/settings", headers=headers).json()
+    assert defaults["baseCurrency"] is None
+    assert (defaults["periodStartDay"], defaults["spendingPaceAlertPercent"]) == (1, 20)
+    assert defaults["backupRetention"] == 10
+
+    account = create_account(client, headers, "Wallet", 0)
+    update = {"baseCurrency": "eur", "locale": "de_DE", "periodStartDay": 25, "backupRetention": 30}
+    saved = client.patch("/api/settings", json=update, headers=headers).json()
+    assert (saved["baseCurrency"], saved["locale"], saved["periodStartDay"]) == ("EUR", "de-DE", 25)
+    assert (saved["spendingPaceAlertPercent"], saved["backupRetention"]) == (20, 30)
+    accounts = client.get("/api/accounts", headers=headers).json()
+    assert next(a for a in accounts if a["id"] == account["id"])["currency"] == "EUR"
+    assert client.get("/api/system/init", headers=headers).json()["locale"] == "de-DE"
+
+    # Untouched fields keep their value and an empty locale clears it again.
+    saved = client.patch("/api/settings", json={"locale": "", "spendingPaceAlertPercent": 35}, headers=headers).json()
+    assert (saved["locale"], saved["periodStartDay"], saved["spendingPaceAlertPercent"]) == (None, 25, 35)
+
+    for invalid in (
+        {"periodStartDay": 29},
+        {"spendingPaceAlertPercent": -1},
+        {"backupRetention": 0},
+        {"locale": "en US"},
+        {"baseCurrency": "XXZ"},
+    ):
+        response = client.patch("/api/settings", json=invalid, headers=headers)
+        assert response.status_code == 400, invalid
+    assert client.get("/api/settings", headers=headers).json() == saved
+
+
+def test_init_returns_currency_display_settings(client: TestClient) -> None:
+    headers = auth_headers(client)
+    init = client.get("/api/system/init", headers=headers).json()
+    assert (init["currencySymbol"], init["decimalPlaces"], init["numberLocale"]) == (None, 2, init["locale"])
+
+    # Decimal places follow the base currency until they are set explicitly.
+    client.patch("/api/settings", json={"baseCurrency": "JPY", "locale": "ja-JP"}, headers=headers)
+    init = client.get("/api/system/init", headers=headers).json()
+    assert (init["decimalPlaces"], init["numberLocale"]) == (0, "ja-JP")
+
+    update = {"currencySymbol": " ¥ ", "decimalPlaces": 1, "numberLocale": "en-US"}
+    saved = client.patch("/api/settings", json=update, headers=headers).json()
+    assert (saved["currencySymbol"], saved["decimalPlaces"], saved["numberLocale"]) == ("¥", 1, "en-US")
+    init = client.get("/api/system/init", headers=headers).json()
+    assert (init["currencySymbol"], init["decimalPlaces"], init["numberLocale"], init["locale"]) == (
+        "¥",
+        1,
+        "en-US",
+        "ja-JP",
+    )
+
+    assert client.patch("/api/settings", json={"decimalPlaces": 3}, headers=headers).status_code == 400
+    cleared = client.patch("/api/settings", json={"currencySymbol": "", "numberLocale": ""}, headers=headers).json()
+    assert (cleared["currencySymbol"], cleared["numberLocale"]) == (None, "ja-JP")
+
+
+def test_timezone_setting_buckets_periods_in_local_time(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    groceries = client.post("/api/categories", json={"name": "Groceries"}, headers=headers).json()
+    # 22:30 on March 31st in New York, already April in UTC.
+    late = record_transaction(
+        client, headers, 4_000, "2025-04-01T02:30:00Z", fromAccountId=wallet["id"], categoryId=groceries["id"]
+    )
+
+    def expenses(period_ym: str) -> int:
+        report = client.get("/api/reports/cash", params={"periodYm": period_ym}, headers=headers).json()
+        return report["totalExpenseCents"]
+
+    def listed(period_ym: str) -> list[str]:
+        page = client.get("/api/transactions", params={"periodYm": period_ym}, headers=headers).json()
+        return [tx["id"] for tx in page["items"]]
+
+    assert (expenses("2025-03"), expenses("2025-04")) == (0, 4_000)
+
+    saved = client.patch("/api/settings", json={"timezone": "America/New_York"}, headers=headers).json()
+    assert saved["timezone"] == "America/New_York"
+    assert (expenses("2025-03"), expenses("2025-04")) == (4_000, 0)
+    assert (listed("2025-03"), listed("2025-04")) == ([late["id"]], [])
+    trend = client.get(
+        "/api/reports/category-trend",
+        params={"categoryIds": groceries["id"], "months": 2, "endPeriodYm": "2025-04"},
+        headers=headers,
+    ).json()
+    assert [point["amountCents"] for point in trend["series"][0]["points"]] == [4_000, 0]
+
+    assert client.patch("/api/settings", json={"timezone": "Mars/Olympus"}, headers=headers).status_code == 400
+    cleared = client.patch("/api/settings", json={"timezone": ""}, headers=headers).json()
+    assert cleared["timezone"] == "UTC"
+    assert (expenses("2025-03"), expenses("2025-04")) == (0, 4_000)
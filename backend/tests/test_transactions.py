@@ -0,0 +1,336 @@
+import sqlite3
+import sys
+from pathlib import Path
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app.config import get_data_dir
+from app.db import get_connection, initialize_database
+from conftest import auth_headers, balances, create_account, record_transaction
+
+
+def test_amounts_outside_supported_range_are_rejected(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 999_999_999_999_000)
+
+    huge = client.post(
+        "/api/transactions",
+        json={"amountCents": 10**15, "fromAccountId": wallet["id"]},
+        headers=headers,
+    )
+    assert huge.status_code == 400
+    assert huge.json()["code"] == "amount_out_of_range"
+
+    overflow = client.post(
+        "/api/transactions",
+        json={"amountCents": 5_000, "toAccountId": wallet["id"]},
+        headers=headers,
+    )
+    assert overflow.status_code == 400
+    assert overflow.json()["details"]["field"] == "balanceCents"
+    assert client.get("/api/transactions", headers=headers).json()["total"] == 0
+
+
+def test_monthly_category_totals_follow_transaction_writes(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 100_000)
+    food = client.post("/api/categories", json={"name": "Food"}, headers=headers).json()
+    spend = {"fromAccountId": wallet["id"], "categoryId": food["id"]}
+    moved = record_transaction(client, headers, 700, "2026-03-04T00:00:00Z", **spend)
+    dropped = record_transaction(client, headers, 300, "2026-03-05T00:00:00Z", **spend)
+    record_transaction(client, headers, 1_100, "2026-03-06T00:00:00Z", **spend)
+    record_transaction(client, headers, 5_000, "2026-03-07T00:00:00Z", toAccountId=wallet["id"])
+    with get_connection() as conn:
+        conn.execute("UPDATE transactions SET occurred_at = '2026-04-01T00:00:00Z' WHERE id = ?", (moved["id"],))
+        conn.execute("DELETE FROM transactions WHERE id = ?", (dropped["id"],))
+
+    def totals() -> list[tuple]:
+        with get_connection() as conn:
+            return [tuple(row) for row in conn.execute("SELECT * FROM monthly_category_totals ORDER BY 1, 2, 3")]
+
+    assert totals() == [
+        ("2026-03", "", "Inflow", "Flow", 0, 5_000, 1),
+        ("2026-03", food["id"], "Outflow", "Flow", 0, 1_100, 1),
+        ("2026-04", food["id"], "Outflow", "Flow", 0, 700, 1),
+    ]
+    # The summed path and the per-transaction drill-down path must agree.
+    for top in (0, 1):
+        report = client.get(
+            "/api/reports/cash", params={"periodYm": "2026-03", "topTransactions": top}, headers=headers
+        ).json()
+        assert (report["totalIncomeCents"], report["totalExpenseCents"]) == (5_000, 1_100)
+
+    expected = totals()
+    with get_connection() as conn:
+        conn.execute("DELETE FROM monthly_category_totals")
+    initialize_database()
+    assert totals() == expected
+
+
+def test_transactions_carry_an_indexed_period_column(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    created = record_transaction(client, headers, 500, "2026-05-31T23:30:00Z", fromAccountId=wallet["id"])
+
+    with get_connection() as conn:
+        row = conn.execute("SELECT period_ym FROM transactions WHERE id = ?", (created["id"],)).fetchone()
+        plan = conn.execute(
+            "EXPLAIN QUERY PLAN SELECT id FROM transactions WHERE period_ym = ? AND category_id = ?", ("2026-05", "x")
+        ).fetchall()
+    assert row["period_ym"] == "2026-05"
+    assert any("idx_transactions_period_ym_category" in step["detail"] for step in plan)
+
+    may = {"fromPeriodYm": "2026-05", "toPeriodYm": "2026-05"}
+    assert client.get("/api/kpis/adjustment", params=may, headers=headers).json()["expenseTotalCents"] == 500
+
+
+def test_deleted_transactions_go_to_trash_and_can_be_restored(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    lunch = record_transaction(client, headers, 2_500, "2026-03-05T12:00:00Z", fromAccountId=wallet["id"])
+    march = {"periodYm": "2026-03"}
+    adjustment = client.post(
+        "/api/reconciliations", json={"accountId": wallet["id"], "actualBalanceCents": 7_000}, headers=headers
+    ).json()["adjustmentTransaction"]
+    refused = client.post(f"/api/transactions/{adjustment['id']}/delete", headers=headers)
+    assert refused.status_code == 409
+
+    trashed = client.post(f"/api/transactions/{lunch['id']}/delete", headers=headers).json()
+    assert trashed["transaction"]["id"] == lunch["id"] and trashed["deletedAt"] is not None
+    assert balances(client, headers)[wallet["id"]] == 9_500
+    assert client.get("/api/transactions", params=march, headers=headers).json()["items"] == []
+    assert client.get("/api/reports/utility", params=march, headers=headers).json()["totalExpenseCents"] == 0
+    assert [item["transaction"]["id"] for item in client.get("/api/trash", headers=headers).json()] == [lunch["id"]]
+    assert client.post(f"/api/transactions/{lunch['id']}/delete", headers=headers).status_code == 404
+
+    restored = client.post(f"/api/trash/{lunch['id']}/restore", headers=headers).json()
+    assert restored["deletedAt"] is None
+    assert balances(client, headers)[wallet["id"]] == 7_000
+    assert client.get("/api/reports/utility", params=march, headers=headers).json()["totalExpenseCents"] == 2_500
+
+    client.post(f"/api/transactions/{lunch['id']}/delete", headers=headers)
+    kept = client.post("/api/trash/purge", json={"olderThanDays": 30}, headers=headers).json()
+    assert kept["purgedCount"] == 0
+    assert client.post("/api/trash/purge", json={}, headers=headers).json()["purgedCount"] == 1
+    assert client.get("/api/trash", headers=headers).json() == []
+    assert client.post(f"/api/trash/{lunch['id']}/restore", headers=headers).status_code == 404
+
+
+def test_transactions_are_stored_as_balanced_postings(client: TestClient) -> None:
+    headers = auth_headers(client)
+    wallet = create_account(client, headers, "Wallet", 10_000)
+    savings = create_account(client, headers, "Savings")
+    salary = record_transaction(client, headers, 3_000, "2026-02-01T09:00:00Z", toAccountId=wallet["id"])
+    transfer = record_transaction(
+        client, headers, 1_000, "2026-02-02T09:00:00Z", fromAccountId=wallet["id"], toAccountId=savings["id"]
+    )
+
+    def legs(tx_id: str) -> list[tuple[str | None, int]]:
+        postings = client.get(f"/api/transactions/{tx_id}/postings", headers=headers).json()
+        return [(posting["accountId"], posting["amountCents"]) for posting in postings]
+
+    assert legs(salary["id"]) == [(None, -3_000), (wallet["id"], 3_000)]
+    assert legs(transfer["id"]) == [(wallet["id"], -1_000), (savings["id"], 1_000)]
+    assert client.get("/api/transactions/missing/postings", headers=headers).status_code == 404
+
+    history = client.get(
+        f"/api/accounts/{savings['id']}/balance-history",
+        params={"fromDate": "2026-01-01", "toDate": "2026-02-28", "granularity": "Month"},
+        headers=headers,
+    ).json()
+    assert [point["balanceCents"] for point in history["points"]] == [0, 1_000]
+
+    with get_connection() as conn:
+        # The postings are the record; the row's from/to and amount only mirror them.
+        conn.execute("UPDATE transactions SET amount_cents = 1200 WHERE id = ?", (transfer["id"],))
+        assert conn.execute(
+            "SELECT SUM(amount_cents) FROM transaction_postings WHERE account_id = ?", (savings["id"],)
+        ).fetchone()[0] == 1_000
+        with pytest.raises(sqlite3.IntegrityError):
+            conn.execute("DELETE FROM accounts WHERE id = ?", (savings["id"],))
+        conn.execute("DELETE FROM transaction_postings WHERE transaction_id = ? AND leg = 0", (salary["id"],))
+    findings = client.get("/api/maintenance/audit", headers=headers).json()
+    assert {item["checkId"]: item["rowIds"] for item in findings} == {"unbalanced_transaction": [salary["id"]]}
+
+
+def test_multi_leg_transactions_move_every_account_and_must_balance(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 10_000)
+    savings = create_account(client, headers, "Savings")
+    card = create_account(client, headers, "Card", -3_000, account_type="Liability")
+    split = {
+        "postings": [
+            {"accountId": checking["id"], "amountCents": -5_000},
+            {"accountId": savings["id"], "amountCents": 2_000},
+            {"accountId": card["id"], "amountCents": 3_000},
+        ],
+        "note": "Payday split",
+        "occurredAt": "2026-03-01T09:00:00Z",
+    }
+
+    unbalanced = {**split, "postings": [*split["postings"][:2], {"accountId": card["id"], "amountCents": 2_000}]}
+    rejected = client.post("/api/transactions/multi-leg", json=unbalanced, headers=headers)
+    assert rejected.status_code == 400
+    assert rejected.json()["message"] == "postings must sum to zero"
+    assert balances(client, headers) == {checking["id"]: 10_000, savings["id"]: 0, card["id"]: -3_000}
+
+    created = client.post("/api/transactions/multi-leg", json=split, headers=headers).json()
+    assert (created["amountCents"], created["fromAccountId"], created["toAccountId"]) == (5_000, None, None)
+    assert balances(client, headers) == {checking["id"]: 5_000, savings["id"]: 2_000, card["id"]: 0}
+    postings = client.get(f"/api/transactions/{created['id']}/postings", headers=headers).json()
+    assert [(posting["accountId"], posting["amountCents"]) for posting in postings] == [
+        (checking["id"], -5_000),
+        (savings["id"], 2_000),
+        (card["id"], 3_000),
+    ]
+    # Money only moved between the user's own accounts, so nothing was earned.
+    statement = client.get("/api/reports/income-statement", params={"periodYm": "2026-03"}, headers=headers).json()
+    assert statement["totalIncomeCents"] == 0 and statement["totalExpenseCents"] == 0
+
+    # Two legs are an ordinary transfer.
+    pair = [{"accountId": savings["id"], "amountCents": -500}, {"accountId": checking["id"], "amountCents": 500}]
+    transfer = client.post("/api/transactions/multi-leg", json={**split, "postings": pair}, headers=headers).json()
+    assert (transfer["fromAccountId"], transfer["toAccountId"]) == (savings["id"], checking["id"])
+
+    voided = client.post(f"/api/transactions/{created['id']}/void", json={}, headers=headers)
+    assert voided.status_code == 200
+    assert balances(client, headers) == {checking["id"]: 10_500, savings["id"]: -500, card["id"]: -3_000}
+    reversal_id = voided.json()["reversal"]["id"]
+    assert client.post(f"/api/transactions/{reversal_id}/delete", headers=headers).status_code == 200
+    assert balances(client, headers) == {checking["id"]: 5_500, savings["id"]: 1_500, card["id"]: 0}
+
+
+def test_transaction_batch_is_all_or_nothing(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 10_000)
+    card = create_account(client, headers, "Card", 0, account_type="Liability")
+    rows = [
+        {"amountCents": 2_000, "fromAccountId": checking["id"], "occurredAt": "2026-01-05T00:00:00Z"},
+        {"amountCents": 500, "toAccountId": card["id"], "occurredAt": "2026-01-06T00:00:00Z"},
+        {"amountCents": 0, "fromAccountId": checking["id"]},
+    ]
+
+    rejected = client.post("/api/transactions/batch", json={"transactions": rows}, headers=headers)
+    assert rejected.status_code == 400
+    assert [error["index"] for error in rejected.json()["details"]["errors"]] == [1, 2]
+    assert balances(client, headers) == {checking["id"]: 10_000, card["id"]: 0}
+
+    created = client.post("/api/transactions/batch", json={"transactions": rows[:1] * 3}, headers=headers)
+    assert created.status_code == 200 and len(created.json()) == 3
+    assert balances(client, headers)[checking["id"]] == 10_000 - 3 * 2_000
+
+
+def test_duplicate_transaction_copies_it_with_its_tags_to_a_new_date(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 10_000)
+    gym = client.post("/api/categories", json={"name": "Gym"}, headers=headers).json()
+    health = client.post("/api/tags", json={"name": "Health"}, headers=headers).json()
+    source = record_transaction(
+        client, headers, 3_000, "2026-02-01T00:00:00Z", fromAccountId=checking["id"], categoryId=gym["id"], note="Fee"
+    )
+    with get_connection() as conn:
+        conn.execute(
+            "INSERT INTO transaction_tags (transaction_id, tag_id) VALUES (?, ?)", (source["id"], health["id"])
+        )
+
+    copy = client.post(
+        f"/api/transactions/{source['id']}/duplicate", json={"occurredAt": "2026-03-01T00:00:00Z"}, headers=headers
+    ).json()
+    assert copy["id"] != source["id"] and copy["occurredAt"] == "2026-03-01T00:00:00Z"
+    assert (copy["amountCents"], copy["categoryId"], copy["note"]) == (3_000, gym["id"], "Fee")
+    assert balances(client, headers)[checking["id"]] == 10_000 - 2 * 3_000
+    with get_connection() as conn:
+        tags = conn.execute("SELECT tag_id FROM transaction_tags WHERE transaction_id = ?", (copy["id"],)).fetchall()
+    assert [row["tag_id"] for row in tags] == [health["id"]]
+
+    adjustment = record_transaction(
+        client, headers, 100, "2026-02-02T00:00:00Z", toAccountId=checking["id"], accrualType="Adjustment"
+    )
+    refused = client.post(f"/api/transactions/{adjustment['id']}/duplicate", json={}, headers=headers)
+    assert refused.status_code == 400
+
+
+def test_void_transaction_books_a_linked_offsetting_entry(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 10_000)
+    rent = client.post("/api/categories", json={"name": "Rent"}, headers=headers).json()
+    original = record_transaction(
+        client, headers, 4_000, "2026-04-01T00:00:00Z", fromAccountId=checking["id"], categoryId=rent["id"], note="Apr"
+    )
+
+    voided = client.post(f"/api/transactions/{original['id']}/void", json={}, headers=headers)
+    assert voided.status_code == 200
+    reversal = voided.json()["reversal"]
+    assert (reversal["toAccountId"], reversal["fromAccountId"]) == (checking["id"], None)
+    assert (reversal["categoryId"], reversal["note"]) == (rent["id"], "Void: Apr")
+    assert reversal["occurredAt"] == original["occurredAt"]
+    assert balances(client, headers)[checking["id"]] == 10_000
+    with get_connection() as conn:
+        link = conn.execute("SELECT reversal_of_id FROM transactions WHERE id = ?", (reversal["id"],)).fetchone()
+    assert link["reversal_of_id"] == original["id"]
+
+    assert client.post(f"/api/transactions/{original['id']}/void", json={}, headers=headers).status_code == 409
+    assert client.post(f"/api/transactions/{reversal['id']}/void", json={}, headers=headers).status_code == 409
+    assert client.post(f"/api/transactions/{original['id']}/delete", headers=headers).status_code == 409
+    client.post(f"/api/transactions/{reversal['id']}/delete", headers=headers)
+    assert balances(client, headers)[checking["id"]] == 10_000 - 4_000
+
+
+def test_attachments_share_one_stored_file_per_content(client: TestClient) -> None:
+    headers = auth_headers(client)
+    checking = create_account(client, headers, "Checking", 10_000)
+    lunch = record_transaction(client, headers, 1_200, "2026-05-01T00:00:00Z", fromAccountId=checking["id"])
+    dinner = record_transaction(client, headers, 2_400, "2026-05-02T00:00:00Z", fromAccountId=checking["id"])
+    receipt = {"fileName": "receipt.pdf", "contentBase64": "JVBERi0xLjQ="}
+
+    first = client.post(f"/api/transactions/{lunch['id']}/attachments", json=receipt, headers=headers).json()
+    second = client.post(f"/api/transactions/{dinner['id']}/attachments", json=receipt, headers=headers).json()
+    assert (first["contentType"], first["sizeBytes"]) == ("application/pdf", 8)
+    assert first["contentHash"] == second["contentHash"]
+    stored = get_data_dir() / "attachments" / f"{first['contentHash']}.pdf"
+    assert [path.name for path in stored.parent.iterdir()] == [stored.name]
+    listed = client.get(f"/api/transactions/{lunch['id']}/attachments", headers=headers).json()
+    assert [item["id"] for item in listed] == [first["id"]]
+    rejected = client.post(
+        f"/api/transactions/{lunch['id']}/attachments",
+        json={"fileName": "x.exe", "contentBase64": "aGk="},
+        headers=headers,
+    )
+    assert rejected.status_code == 400
+
+    client.post(f"/api/transactions/{lunch['id']}/attachments/{first['id']}/delete", headers=headers)
+    assert stored.is_file()
+    client.post(f"/api/transactions/{dinner['id']}/delete", headers=headers)
+    client.post("/api/trash/purge", json={}, headers=headers)
+    assert not stored.exists()
+
+
+def test_read_receipt_prefills_from_the_configured_command(
+    client: TestClient, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    headers = auth_headers(client)
+    receipt = {"fileName": "till.png", "contentBase64": "aGVsbG8="}
+    unconfigured = client.post("/api/transactions/read-receipt", json=receipt, headers=headers)
+    assert unconfigured.status_code == 400
+
+    bakery_category = client.post("/api/categories", json={"name": "Bread"}, headers=headers).json()
+    bakery = client.post(
+        "/api/payees", json={"name": "Bakery", "defaultCategoryId": bakery_category["id"]}, headers=headers
+    ).json()
+    reader = tmp_path / "reader.py"
+    reader.write_text(
+        "import json, sys\n"
+        "assert open(sys.argv[1], 'rb').read() == b'hello'\n"
+        "print(json.dumps({'amount': '-7.45', 'date': '2026-07-03', 'payee': 'BAKERY #12'}))\n"
+    )
+    monkeypatch.setenv("OIKONOMOS_RECEIPT_READER_COMMAND", f"{sys.executable} {reader}")
+
+    suggestion = client.post("/api/transactions/read-receipt", json=receipt, headers=headers).json()
+    assert suggestion == {
+        "amountCents": 745,
+        "occurredOn": "2026-07-03",
+        "payeeName": "BAKERY #12",
+        "payeeId": bakery["id"],
+        "categoryId": bakery_category["id"],
+    }
@@ -28,7 +28,7 @@ import {
 import LoginPage from "./pages/LoginPage";
 import { useAuthStore } from "./store/useAuthStore";
 import { useFinanceStore, type TabKey } from "./store/useFinanceStore";
-import type { AccountType, AssetPurpose } from "./types/finance";
+import type { AccountType, AssetPurpose, InitState } from "./types/finance";
 
 const PURPOSES: AssetPurpose[] = [
   "Investment",
@@ -39,8 +39,13 @@ const PURPOSES: AssetPurpose[] = [
 const ACCOUNT_TYPES: AccountType[] = ["Asset", "Liability"];
 const CURRENT_DAY = new Date().toISOString().slice(0, 10);
 
-function centsToDisplay(value: number): string {
-  return (value / 100).toFixed(2);
+function centsToDisplay(value: number, display: InitState | null): string {
+  const decimalPlaces = display?.decimalPlaces ?? 2;
+  const amount = (value / 100).toLocaleString(display?.numberLocale ?? undefined, {
+    minimumFractionDigits: decimalPlaces,
+    maximumFractionDigits: decimalPlaces,
+  });
+  return display?.currencySymbol ? `${display.currencySymbol} ${amount}` : amount;
 }
 
 function App() {
@@ -62,6 +67,7 @@ function App() {
     utilityReport,
     kpi,
     reconcileResult,
+    maintenanceResult,
     loading,
     message,
     error,
@@ -73,6 +79,7 @@ function App() {
     submitAssetPurchase,
     refreshReports,
     submitReconcile,
+    runDatabaseMaintenance,
     clearNotices,
     reset,
   } = useFinanceStore();
@@ -237,6 +244,7 @@ function App() {
             <Tab value="transactions" label="Transactions" />
             <Tab value="reports" label="Reports" />
             <Tab value="reconcile" label="Reconcile" />
+            <Tab value="settings" label="Settings" />
           </Tabs>
         </Toolbar>
       </AppBar>
@@ -318,7 +326,7 @@ function App() {
                         <TableCell>{account.accountType}</TableCell>
                         <TableCell>{account.purpose}</TableCell>
                         <TableCell>{account.balanceCents}</TableCell>
-                        <TableCell>{centsToDisplay(account.balanceCents)}</TableCell>
+                        <TableCell>{centsToDisplay(account.balanceCents, appInfo)}</TableCell>
                       </TableRow>
                     ))}
                   </TableBody>
@@ -567,6 +575,40 @@ function App() {
               </CardContent>
             </Card>
           )}
+
+          {activeTab === "settings" && (
+            <Card>
+              <CardContent>
+                <Typography variant="h6" sx={{ mb: 2 }}>
+                  Database Maintenance
+                </Typography>
+                <Typography variant="body2" sx={{ mb: 2 }}>
+                  Checks the database file for corruption, then compacts it and refreshes query statistics.
+                </Typography>
+                <Button
+                  variant="contained"
+                  disabled={loading}
+                  onClick={() => void runDatabaseMaintenance()}
+                  sx={{ mb: 3 }}
+                >
+                  Run Maintenance
+                </Button>
+
+                {maintenanceResult && (
+                  <Card variant="outlined">
+                    <CardContent>
+                      <Typography variant="subtitle1">Result</Typography>
+                      <Typography>Integrity: {maintenanceResult.integrityMessages.join("; ")}</Typography>
+                      <Typography>
+                        Size: {maintenanceResult.sizeBeforeBytes} to {maintenanceResult.sizeAfterBytes} bytes
+                      </Typography>
+                      <Typography>Reclaimed: {maintenanceResult.reclaimedBytes} bytes</Typography>
+                    </CardContent>
+                  </Card>
+                )}
+              </CardContent>
+            </Card>
+          )}
         </Stack>
       </Container>
     </Box>
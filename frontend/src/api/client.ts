@@ -8,6 +8,8 @@ interface RequestOptions {
   skipAuthRetry?: boolean;
 }
 
+type QueryParams = Record<string, string | string[] | undefined | null>;
+
 const API_BASE = import.meta.env.VITE_API_BASE ?? "/api";
 
 let accessTokenProvider: (() => string | null) | null = null;
@@ -24,11 +26,13 @@ export function configureAuthClient(options: {
   unauthorizedHandler = options.onUnauthorized;
 }
 
-function buildUrl(path: string, query?: Record<string, string | undefined | null>): string {
+function buildUrl(path: string, query?: QueryParams): string {
   const url = new URL(`${API_BASE}${path}`, window.location.origin);
   if (query) {
     Object.entries(query).forEach(([key, value]) => {
-      if (value !== undefined && value !== null && value !== "") {
+      if (Array.isArray(value)) {
+        value.forEach((item) => url.searchParams.append(key, item));
+      } else if (value !== undefined && value !== null && value !== "") {
         url.searchParams.set(key, value);
       }
     });
@@ -50,9 +54,9 @@ async function parseError(response: Response): Promise<never> {
 }
 
 async function request<T>(
-  method: "GET" | "POST",
+  method: "GET" | "POST" | "PATCH" | "DELETE",
   path: string,
-  query?: Record<string, string | undefined | null>,
+  query?: QueryParams,
   body?: unknown,
   options?: RequestOptions,
 ): Promise<T> {
@@ -108,7 +112,7 @@ async function request<T>(
 
 export function apiGet<T>(
   path: string,
-  query?: Record<string, string | undefined | null>,
+  query?: QueryParams,
   options?: RequestOptions,
 ): Promise<T> {
   return request<T>("GET", path, query, undefined, options);
@@ -117,3 +121,7 @@ export function apiGet<T>(
 export function apiPost<T>(path: string, body: unknown, options?: RequestOptions): Promise<T> {
   return request<T>("POST", path, undefined, body, options);
 }
+
+export function apiPatch<T>(path: string, body: unknown, options?: RequestOptions): Promise<T> {
+  return request<T>("PATCH", path, undefined, body, options);
+}
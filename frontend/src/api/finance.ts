@@ -1,22 +1,168 @@
-import { apiGet, apiPost } from "./client";
+import { apiGet, apiPatch, apiPost } from "./client";
 import type {
   Account,
+  AccountPhoto,
+  AccountValuation,
+  AccountValuationTimeline,
+  AccrueInterestInput,
   AdjustmentKpi,
+  AllocationDriftReport,
+  AllocationTarget,
+  AmortizationPosting,
+  AmortizationScheduleProgress,
+  AmortizationStatus,
+  AnonymizedExportInput,
+  AppEvent,
+  ArchiveInput,
+  ArchiveResult,
   AssetPurchaseResult,
+  AssignPayeeInput,
+  AssetPurpose,
+  AssetRevaluationResult,
+  AuditFinding,
   AuthTokens,
+  BalanceDriftReport,
+  BalanceGranularity,
+  BalanceHistory,
+  BalanceSnapshot,
+  Bill,
+  Budget,
+  BudgetReport,
+  CancelAmortizationScheduleInput,
+  CancelAmortizationScheduleResult,
+  CashFlowReport,
+  CatchUpDepreciationInput,
+  CategorySuggestion,
+  CategoryTrend,
+  Category,
+  CleanupSuggestions,
+  CostPerUseReport,
   CreateAccountInput,
+  CreateAccountValuationInput,
   CreateAssetPurchaseInput,
+  CreateBillInput,
+  CreateCategoryInput,
+  CreateImportProfileInput,
+  CreateLoanInput,
+  CreateMaintenanceEventInput,
+  CreateMultiLegTransactionInput,
+  CreatePayeeInput,
+  CreateRecurringRuleInput,
+  CreateTagInput,
   CreateTransactionInput,
+  CreateTransactionsBatchInput,
+  CryptoHolding,
+  CryptoRevaluationResult,
   CurrentUser,
+  Dashboard,
+  DatabaseMaintenanceResult,
+  DebtToAssetKpi,
+  DepreciationCatchUpResult,
+  DepreciationForecast,
+  DisposeAssetInput,
+  DisposeAssetResult,
+  DuplicateTransactionInput,
+  ExchangeRate,
+  ExpiringWarranty,
+  ExportResult,
+  FxRevaluationResult,
+  Holding,
+  HoldingRevaluationResult,
+  ImportPreview,
+  ImportProfile,
+  ImportStatementInput,
+  ImportStatementResult,
+  IncomeStatement,
   InitState,
+  InstanceStatus,
+  InterestAccrualResult,
   KpiPeriodInput,
+  LiquidityReport,
+  Loan,
+  LoanCreated,
+  LoanPaymentsResult,
+  LoanSchedule,
+  LogUsageInput,
   LoginInput,
+  MaintenanceEvent,
+  MarkClearedInput,
+  MarkClearedResult,
+  MatchStatementInput,
+  MergeCategoriesInput,
+  MergeCategoriesResult,
+  MergePayeesInput,
+  MergePayeesResult,
+  MigrationState,
+  OnboardingInput,
+  OnboardingResult,
   PagedTransactions,
+  Payee,
+  PayeeSuggestion,
+  PerformanceReport,
+  PortfolioValueHistory,
+  PostLoanPaymentsInput,
+  PrepaymentSimulation,
+  PreviewCsvImportInput,
+  Price,
+  PurgeTrashInput,
+  PurgeTrashResult,
+  RealizedGainsReport,
+  RebuildBalancesInput,
+  RebuildBalancesResult,
+  ReceiptSuggestion,
+  RecategorizeAdjustmentInput,
   ReconcileInput,
   ReconcileResult,
+  RecordOpeningBalanceInput,
+  RecurringRule,
+  RefreshPricesInput,
+  RefreshPricesResult,
+  RevalueAssetInput,
+  RevalueForeignAccountsInput,
+  RevalueHoldingsInput,
+  RollingAverageReport,
   Report,
+  ReportCsvExportInput,
+  ReportRangeInput,
+  RunRecurringInput,
+  SecurityTradeInput,
+  SecurityTradeResult,
+  RunRecurringResult,
+  SetAccountBalanceInput,
+  SetAllocationTargetsInput,
+  SpendingForecast,
+  SpendingPace,
+  SetBudgetInput,
+  SetCryptoHoldingInput,
+  SetCryptoPriceInput,
+  SetExchangeRateInput,
+  SetPriceInput,
+  Settings,
+  StatementMatchResult,
+  Tag,
+  TotalCostOfOwnershipReport,
   Transaction,
+  TransactionAnomaly,
+  TransactionAttachment,
   TransactionFilter,
+  TransactionPosting,
+  TrashedTransaction,
+  UpcomingBill,
+  UpdateAccountInput,
+  UpdateAssetDetailsInput,
+  UpdateCategoryInput,
+  UpdateImportProfileInput,
+  UpdateSettingsInput,
+  UpdateTagInput,
+  UploadAccountPhotoInput,
+  UploadAttachmentInput,
+  UpgradeConversionResult,
+  UpgradeFinding,
+  UsageLog,
+  UsageStats,
+  UtilityMatrixExportInput,
+  VoidTransactionInput,
+  VoidTransactionResult,
 } from "../types/finance";
 
 export function login(input: LoginInput): Promise<AuthTokens> {
@@ -47,18 +193,298 @@ export function initApp(): Promise<InitState> {
   return apiGet<InitState>("/system/init");
 }
 
-export function listAccounts(): Promise<Account[]> {
-  return apiGet<Account[]>("/accounts");
+export function listMigrationStates(): Promise<MigrationState[]> {
+  return apiGet<MigrationState[]>("/system/migrations");
+}
+
+export function completeOnboarding(input: OnboardingInput): Promise<OnboardingResult> {
+  return apiPost<OnboardingResult>("/onboarding", input);
+}
+
+export function getSettings(): Promise<Settings> {
+  return apiGet<Settings>("/settings");
+}
+
+export function updateSettings(input: UpdateSettingsInput): Promise<Settings> {
+  return apiPatch<Settings>("/settings", input);
+}
+
+export function listEvents(afterSeq = 0): Promise<AppEvent[]> {
+  return apiGet<AppEvent[]>("/events", { afterSeq: String(afterSeq) });
+}
+
+export function getInstanceStatus(): Promise<InstanceStatus> {
+  return apiGet<InstanceStatus>("/instance");
+}
+
+export function requestTakeover(): Promise<InstanceStatus> {
+  return apiPost<InstanceStatus>("/instance/takeover", {});
+}
+
+export function listAccounts(includeArchived = false): Promise<Account[]> {
+  return apiGet<Account[]>("/accounts", { includeArchived: includeArchived ? "true" : undefined });
 }
 
 export function createAccount(input: CreateAccountInput): Promise<Account> {
   return apiPost<Account>("/accounts", input);
 }
 
+export function updateAccount(accountId: string, input: UpdateAccountInput): Promise<Account> {
+  return apiPatch<Account>(`/accounts/${accountId}`, input);
+}
+
+export function accrueInterest(input: AccrueInterestInput): Promise<InterestAccrualResult> {
+  return apiPost<InterestAccrualResult>("/accounts/interest-accruals", input);
+}
+
+export function setAccountBalance(accountId: string, input: SetAccountBalanceInput): Promise<ReconcileResult> {
+  return apiPost<ReconcileResult>(`/accounts/${accountId}/balance`, input);
+}
+
+export function recordOpeningBalance(
+  accountId: string,
+  input: RecordOpeningBalanceInput = {},
+): Promise<Transaction> {
+  return apiPost<Transaction>(`/accounts/${accountId}/opening-balance`, input);
+}
+
+export function getAccountBalanceHistory(
+  accountId: string,
+  fromDate: string,
+  toDate: string,
+  granularity: BalanceGranularity = "Day",
+): Promise<BalanceHistory> {
+  return apiGet<BalanceHistory>(`/accounts/${accountId}/balance-history`, { fromDate, toDate, granularity });
+}
+
+export function listAccountValuations(accountId: string): Promise<AccountValuationTimeline> {
+  return apiGet<AccountValuationTimeline>(`/accounts/${accountId}/valuations`);
+}
+
+export function createAccountValuation(
+  accountId: string,
+  input: CreateAccountValuationInput,
+): Promise<AccountValuation> {
+  return apiPost<AccountValuation>(`/accounts/${accountId}/valuations`, input);
+}
+
+export function listMaintenanceEvents(accountId: string): Promise<MaintenanceEvent[]> {
+  return apiGet<MaintenanceEvent[]>(`/accounts/${accountId}/maintenance`);
+}
+
+export function createMaintenanceEvent(
+  accountId: string,
+  input: CreateMaintenanceEventInput,
+): Promise<MaintenanceEvent> {
+  return apiPost<MaintenanceEvent>(`/accounts/${accountId}/maintenance`, input);
+}
+
+export function getCryptoHolding(accountId: string): Promise<CryptoHolding> {
+  return apiGet<CryptoHolding>(`/accounts/${accountId}/crypto`);
+}
+
+export function setCryptoHolding(accountId: string, input: SetCryptoHoldingInput): Promise<CryptoRevaluationResult> {
+  return apiPost<CryptoRevaluationResult>(`/accounts/${accountId}/crypto`, input);
+}
+
+export function setCryptoPrice(accountId: string, input: SetCryptoPriceInput): Promise<CryptoRevaluationResult> {
+  return apiPost<CryptoRevaluationResult>(`/accounts/${accountId}/crypto/price`, input);
+}
+
+export function uploadAccountPhoto(accountId: string, input: UploadAccountPhotoInput): Promise<AccountPhoto> {
+  return apiPost<AccountPhoto>(`/accounts/${accountId}/photos`, input);
+}
+
+export function listAllocationTargets(): Promise<AllocationTarget[]> {
+  return apiGet<AllocationTarget[]>("/allocation-targets");
+}
+
+export function setAllocationTargets(input: SetAllocationTargetsInput): Promise<AllocationTarget[]> {
+  return apiPost<AllocationTarget[]>("/allocation-targets", input);
+}
+
+export function listCategories(includeInactive = false): Promise<Category[]> {
+  return apiGet<Category[]>("/categories", { includeInactive: includeInactive ? "true" : undefined });
+}
+
+export function createCategory(input: CreateCategoryInput): Promise<Category> {
+  return apiPost<Category>("/categories", input);
+}
+
+export function updateCategory(categoryId: string, input: UpdateCategoryInput): Promise<Category> {
+  return apiPatch<Category>(`/categories/${categoryId}`, input);
+}
+
+export function suggestCategories(prefix: string): Promise<CategorySuggestion[]> {
+  return apiGet<CategorySuggestion[]>("/categories/suggestions", { prefix });
+}
+
+export function mergeCategories(input: MergeCategoriesInput): Promise<MergeCategoriesResult> {
+  return apiPost<MergeCategoriesResult>("/categories/merge", input);
+}
+
+export function listPayees(includeArchived = false): Promise<Payee[]> {
+  return apiGet<Payee[]>("/payees", { includeArchived: includeArchived ? "true" : undefined });
+}
+
+export function createPayee(input: CreatePayeeInput): Promise<Payee> {
+  return apiPost<Payee>("/payees", input);
+}
+
+export function suggestForPayee(payeeId: string): Promise<PayeeSuggestion> {
+  return apiGet<PayeeSuggestion>(`/payees/${payeeId}/suggestion`);
+}
+
+export function mergePayees(input: MergePayeesInput): Promise<MergePayeesResult> {
+  return apiPost<MergePayeesResult>("/payees/merge", input);
+}
+
+export function applyPayeeDefaultCategory(payeeId: string): Promise<Transaction[]> {
+  return apiPost<Transaction[]>(`/payees/${payeeId}/apply-default-category`, {});
+}
+
+export function listTags(includeArchived = false): Promise<Tag[]> {
+  return apiGet<Tag[]>("/tags", { includeArchived: includeArchived ? "true" : undefined });
+}
+
+export function createTag(input: CreateTagInput): Promise<Tag> {
+  return apiPost<Tag>("/tags", input);
+}
+
+export function updateTag(tagId: string, input: UpdateTagInput): Promise<Tag> {
+  return apiPatch<Tag>(`/tags/${tagId}`, input);
+}
+
+export function listBudgets(periodYm: string): Promise<Budget[]> {
+  return apiGet<Budget[]>("/budgets", { periodYm });
+}
+
+export function setBudget(input: SetBudgetInput): Promise<Budget> {
+  return apiPost<Budget>("/budgets", input);
+}
+
+export function listExchangeRates(fromCurrency?: string, toCurrency?: string): Promise<ExchangeRate[]> {
+  return apiGet<ExchangeRate[]>("/exchange-rates", { fromCurrency, toCurrency });
+}
+
+export function setExchangeRate(input: SetExchangeRateInput): Promise<ExchangeRate> {
+  return apiPost<ExchangeRate>("/exchange-rates", input);
+}
+
+export function listHoldings(accountId?: string): Promise<Holding[]> {
+  return apiGet<Holding[]>("/holdings", { accountId });
+}
+
+export function getPortfolioValueHistory(
+  fromDate: string,
+  toDate: string,
+  granularity: BalanceGranularity = "Day",
+  accountId?: string,
+): Promise<PortfolioValueHistory> {
+  return apiGet<PortfolioValueHistory>("/holdings/value-history", { fromDate, toDate, granularity, accountId });
+}
+
+export function listPrices(instrument?: string, fromDate?: string, toDate?: string): Promise<Price[]> {
+  return apiGet<Price[]>("/prices", { instrument, fromDate, toDate });
+}
+
+export function setPrice(input: SetPriceInput): Promise<Price> {
+  return apiPost<Price>("/prices", input);
+}
+
+export function refreshPrices(input: RefreshPricesInput = {}): Promise<RefreshPricesResult> {
+  return apiPost<RefreshPricesResult>("/prices/refresh", input);
+}
+
+export function buySecurity(input: SecurityTradeInput): Promise<SecurityTradeResult> {
+  return apiPost<SecurityTradeResult>("/holdings/buy", input);
+}
+
+export function sellSecurity(input: SecurityTradeInput): Promise<SecurityTradeResult> {
+  return apiPost<SecurityTradeResult>("/holdings/sell", input);
+}
+
+export function revalueHoldings(input: RevalueHoldingsInput): Promise<HoldingRevaluationResult> {
+  return apiPost<HoldingRevaluationResult>("/holdings/revaluations", input);
+}
+
+export function revalueForeignAccounts(input: RevalueForeignAccountsInput): Promise<FxRevaluationResult> {
+  return apiPost<FxRevaluationResult>("/exchange-rates/revaluations", input);
+}
+
 export function createTransaction(input: CreateTransactionInput): Promise<Transaction> {
   return apiPost<Transaction>("/transactions", input);
 }
 
+export function createMultiLegTransaction(input: CreateMultiLegTransactionInput): Promise<Transaction> {
+  return apiPost<Transaction>("/transactions/multi-leg", input);
+}
+
+export function createTransactionsBatch(input: CreateTransactionsBatchInput): Promise<Transaction[]> {
+  return apiPost<Transaction[]>("/transactions/batch", input);
+}
+
+export function duplicateTransaction(transactionId: string, input: DuplicateTransactionInput): Promise<Transaction> {
+  return apiPost<Transaction>(`/transactions/${transactionId}/duplicate`, input);
+}
+
+export function listUnreviewedAdjustments(accountId?: string): Promise<Transaction[]> {
+  return apiGet<Transaction[]>("/transactions/unreviewed-adjustments", { accountId });
+}
+
+export function recategorizeAdjustment(
+  transactionId: string,
+  input: RecategorizeAdjustmentInput,
+): Promise<Transaction> {
+  return apiPost<Transaction>(`/transactions/${transactionId}/recategorize`, input);
+}
+
+export function markCleared(input: MarkClearedInput): Promise<MarkClearedResult> {
+  return apiPost<MarkClearedResult>("/transactions/mark-cleared", input);
+}
+
+export function deleteTransaction(transactionId: string): Promise<TrashedTransaction> {
+  return apiPost<TrashedTransaction>(`/transactions/${transactionId}/delete`, {});
+}
+
+export function readReceipt(input: UploadAttachmentInput): Promise<ReceiptSuggestion> {
+  return apiPost<ReceiptSuggestion>("/transactions/read-receipt", input);
+}
+
+export function listTransactionAttachments(transactionId: string): Promise<TransactionAttachment[]> {
+  return apiGet<TransactionAttachment[]>(`/transactions/${transactionId}/attachments`);
+}
+
+export function uploadTransactionAttachment(
+  transactionId: string,
+  input: UploadAttachmentInput,
+): Promise<TransactionAttachment> {
+  return apiPost<TransactionAttachment>(`/transactions/${transactionId}/attachments`, input);
+}
+
+export function deleteTransactionAttachment(
+  transactionId: string,
+  attachmentId: string,
+): Promise<TransactionAttachment> {
+  return apiPost<TransactionAttachment>(`/transactions/${transactionId}/attachments/${attachmentId}/delete`, {});
+}
+
+export function voidTransaction(transactionId: string, input: VoidTransactionInput): Promise<VoidTransactionResult> {
+  return apiPost<VoidTransactionResult>(`/transactions/${transactionId}/void`, input);
+}
+
+export function listTrash(): Promise<TrashedTransaction[]> {
+  return apiGet<TrashedTransaction[]>("/trash");
+}
+
+export function restoreTransaction(transactionId: string): Promise<TrashedTransaction> {
+  return apiPost<TrashedTransaction>(`/trash/${transactionId}/restore`, {});
+}
+
+export function purgeTrash(input: PurgeTrashInput = {}): Promise<PurgeTrashResult> {
+  return apiPost<PurgeTrashResult>("/trash/purge", input);
+}
+
 export function listTransactions(filter?: TransactionFilter): Promise<PagedTransactions> {
   return apiGet<PagedTransactions>("/transactions", {
     periodYm: filter?.periodYm ?? undefined,
@@ -66,20 +492,289 @@ export function listTransactions(filter?: TransactionFilter): Promise<PagedTrans
   });
 }
 
+export function listTransactionPostings(transactionId: string): Promise<TransactionPosting[]> {
+  return apiGet<TransactionPosting[]>(`/transactions/${transactionId}/postings`);
+}
+
+export function listTransactionAnomalies(periodYm?: string): Promise<TransactionAnomaly[]> {
+  return apiGet<TransactionAnomaly[]>("/transactions/anomalies", { periodYm });
+}
+
+export function getUsageStats(): Promise<UsageStats> {
+  return apiGet<UsageStats>("/usage");
+}
+
+export function listRecurringRules(): Promise<RecurringRule[]> {
+  return apiGet<RecurringRule[]>("/recurring-rules");
+}
+
+export function createRecurringRule(input: CreateRecurringRuleInput): Promise<RecurringRule> {
+  return apiPost<RecurringRule>("/recurring-rules", input);
+}
+
+export function deactivateRecurringRule(ruleId: string): Promise<RecurringRule> {
+  return apiPost<RecurringRule>(`/recurring-rules/${ruleId}/deactivate`, {});
+}
+
+export function runRecurring(input?: RunRecurringInput): Promise<RunRecurringResult> {
+  return apiPost<RunRecurringResult>("/recurring-rules/run", input ?? {});
+}
+
+export function listBills(): Promise<Bill[]> {
+  return apiGet<Bill[]>("/bills");
+}
+
+export function createBill(input: CreateBillInput): Promise<Bill> {
+  return apiPost<Bill>("/bills", input);
+}
+
+export function listUpcomingBills(periodYm?: string, asOf?: string): Promise<UpcomingBill[]> {
+  return apiGet<UpcomingBill[]>("/bills/upcoming", { periodYm, asOf });
+}
+
 export function createAssetPurchase(input: CreateAssetPurchaseInput): Promise<AssetPurchaseResult> {
   return apiPost<AssetPurchaseResult>("/asset-purchases", input);
 }
 
+export function listAmortizationSchedules(
+  status?: AmortizationStatus,
+  assetAccountId?: string,
+): Promise<AmortizationScheduleProgress[]> {
+  return apiGet<AmortizationScheduleProgress[]>("/amortization-schedules", { status, assetAccountId });
+}
+
+export function listAmortizationPostings(scheduleId: string): Promise<AmortizationPosting[]> {
+  return apiGet<AmortizationPosting[]>(`/amortization-schedules/${scheduleId}/postings`);
+}
+
+export function catchUpDepreciation(input: CatchUpDepreciationInput): Promise<DepreciationCatchUpResult> {
+  return apiPost<DepreciationCatchUpResult>("/amortization-schedules/catch-up", input);
+}
+
+export function cancelAmortizationSchedule(
+  scheduleId: string,
+  input: CancelAmortizationScheduleInput = {},
+): Promise<CancelAmortizationScheduleResult> {
+  return apiPost<CancelAmortizationScheduleResult>(`/amortization-schedules/${scheduleId}/cancel`, input);
+}
+
+export function disposeAsset(scheduleId: string, input: DisposeAssetInput): Promise<DisposeAssetResult> {
+  return apiPost<DisposeAssetResult>(`/amortization-schedules/${scheduleId}/dispose`, input);
+}
+
+export function revalueAsset(scheduleId: string, input: RevalueAssetInput): Promise<AssetRevaluationResult> {
+  return apiPost<AssetRevaluationResult>(`/amortization-schedules/${scheduleId}/revaluations`, input);
+}
+
+export function updateAssetDetails(
+  scheduleId: string,
+  input: UpdateAssetDetailsInput,
+): Promise<AmortizationScheduleProgress> {
+  return apiPatch<AmortizationScheduleProgress>(`/amortization-schedules/${scheduleId}`, input);
+}
+
+export function listExpiringWarranties(withinDays?: number, asOf?: string): Promise<ExpiringWarranty[]> {
+  return apiGet<ExpiringWarranty[]>("/amortization-schedules/expiring-warranties", {
+    withinDays: withinDays === undefined ? undefined : String(withinDays),
+    asOf,
+  });
+}
+
+export function listUsageLogs(scheduleId: string): Promise<UsageLog[]> {
+  return apiGet<UsageLog[]>(`/amortization-schedules/${scheduleId}/usage`);
+}
+
+export function logUsage(scheduleId: string, input: LogUsageInput): Promise<UsageLog> {
+  return apiPost<UsageLog>(`/amortization-schedules/${scheduleId}/usage`, input);
+}
+
+export function listLoans(): Promise<Loan[]> {
+  return apiGet<Loan[]>("/loans");
+}
+
+export function createLoan(input: CreateLoanInput): Promise<LoanCreated> {
+  return apiPost<LoanCreated>("/loans", input);
+}
+
+export function getLoanSchedule(loanId: string): Promise<LoanSchedule> {
+  return apiGet<LoanSchedule>(`/loans/${loanId}/schedule`);
+}
+
+export function postLoanPayments(input: PostLoanPaymentsInput): Promise<LoanPaymentsResult> {
+  return apiPost<LoanPaymentsResult>("/loans/payments", input);
+}
+
+export function simulatePrepayment(
+  loanId: string,
+  lumpSumCents = 0,
+  lumpSumPeriodYm?: string,
+  monthlyExtraCents = 0,
+): Promise<PrepaymentSimulation> {
+  return apiGet<PrepaymentSimulation>(`/loans/${loanId}/prepayment-simulation`, {
+    lumpSumCents: String(lumpSumCents),
+    lumpSumPeriodYm,
+    monthlyExtraCents: String(monthlyExtraCents),
+  });
+}
+
 export function reconcileAccount(input: ReconcileInput): Promise<ReconcileResult> {
   return apiPost<ReconcileResult>("/reconciliations", input);
 }
 
-export function getCashFlowReport(periodYm: string): Promise<Report> {
-  return apiGet<Report>("/reports/cash", { periodYm });
+export function matchStatement(input: MatchStatementInput): Promise<StatementMatchResult> {
+  return apiPost<StatementMatchResult>("/reconciliations/match", input);
+}
+
+export function importStatement(input: ImportStatementInput): Promise<ImportStatementResult> {
+  return apiPost<ImportStatementResult>("/imports/statement", input);
+}
+
+export function previewImport(input: ImportStatementInput): Promise<ImportPreview> {
+  return apiPost<ImportPreview>("/imports/preview", input);
+}
+
+export function previewCsvImport(input: PreviewCsvImportInput): Promise<ImportPreview> {
+  return apiPost<ImportPreview>("/imports/preview-csv", input);
 }
 
-export function getUtilityReport(periodYm: string): Promise<Report> {
-  return apiGet<Report>("/reports/utility", { periodYm });
+export function listImports(): Promise<ImportPreview[]> {
+  return apiGet<ImportPreview[]>("/imports");
+}
+
+export function getImport(importId: string): Promise<ImportPreview> {
+  return apiGet<ImportPreview>(`/imports/${importId}`);
+}
+
+export function commitImport(importId: string): Promise<ImportStatementResult> {
+  return apiPost<ImportStatementResult>(`/imports/${importId}/commit`, {});
+}
+
+export function discardImport(importId: string): Promise<ImportPreview> {
+  return apiPost<ImportPreview>(`/imports/${importId}/discard`, {});
+}
+
+export function listImportProfiles(): Promise<ImportProfile[]> {
+  return apiGet<ImportProfile[]>("/imports/profiles");
+}
+
+export function createImportProfile(input: CreateImportProfileInput): Promise<ImportProfile> {
+  return apiPost<ImportProfile>("/imports/profiles", input);
+}
+
+export function updateImportProfile(profileId: string, input: UpdateImportProfileInput): Promise<ImportProfile> {
+  return apiPatch<ImportProfile>(`/imports/profiles/${profileId}`, input);
+}
+
+export function deleteImportProfile(profileId: string): Promise<ImportProfile> {
+  return apiPost<ImportProfile>(`/imports/profiles/${profileId}/delete`, {});
+}
+
+export function assignPayee(transactionId: string, input: AssignPayeeInput): Promise<Transaction> {
+  return apiPost<Transaction>(`/transactions/${transactionId}/payee`, input);
+}
+
+export function listBalanceSnapshots(accountId: string): Promise<BalanceSnapshot[]> {
+  return apiGet<BalanceSnapshot[]>("/reconciliations", { accountId });
+}
+
+function reportPeriodQuery(period: string | ReportRangeInput): Record<string, string> {
+  return typeof period === "string" ? { periodYm: period } : { fromDate: period.fromDate, toDate: period.toDate };
+}
+
+export function getCashFlowReport(
+  period: string | ReportRangeInput,
+  topTransactions = 0,
+  groupByParent = false,
+): Promise<CashFlowReport> {
+  return apiGet<CashFlowReport>("/reports/cash", {
+    ...reportPeriodQuery(period),
+    topTransactions: String(topTransactions),
+    groupByParent: groupByParent ? "true" : undefined,
+  });
+}
+
+export function getUtilityReport(period: string | ReportRangeInput, topTransactions = 0): Promise<Report> {
+  return apiGet<Report>("/reports/utility", { ...reportPeriodQuery(period), topTransactions: String(topTransactions) });
+}
+
+export function getIncomeStatement(period: string | ReportRangeInput, topTransactions = 0): Promise<IncomeStatement> {
+  return apiGet<IncomeStatement>("/reports/income-statement", {
+    ...reportPeriodQuery(period),
+    topTransactions: String(topTransactions),
+  });
+}
+
+export function getPurposeReport(periodYm: string, topTransactions = 0): Promise<Report> {
+  return apiGet<Report>("/reports/purpose", { periodYm, topTransactions: String(topTransactions) });
+}
+
+export function getLiquidityReport(): Promise<LiquidityReport> {
+  return apiGet<LiquidityReport>("/reports/liquidity");
+}
+
+export function getAllocationDriftReport(): Promise<AllocationDriftReport> {
+  return apiGet<AllocationDriftReport>("/reports/allocation-drift");
+}
+
+export function getBudgetReport(periodYm: string, groupByParent = false): Promise<BudgetReport> {
+  return apiGet<BudgetReport>("/reports/budget", { periodYm, groupByParent: groupByParent ? "true" : undefined });
+}
+
+export function getCategoryTrend(
+  categoryIds: string[],
+  months = 12,
+  endPeriodYm?: string,
+  topTransactions = 0,
+): Promise<CategoryTrend> {
+  return apiGet<CategoryTrend>("/reports/category-trend", {
+    categoryIds,
+    months: String(months),
+    endPeriodYm,
+    topTransactions: String(topTransactions),
+  });
+}
+
+export function getRollingAverageReport(endPeriodYm?: string): Promise<RollingAverageReport> {
+  return apiGet<RollingAverageReport>("/reports/rolling-average", { endPeriodYm });
+}
+
+export function getPerformanceReport(fromDate: string, toDate: string): Promise<PerformanceReport> {
+  return apiGet<PerformanceReport>("/reports/performance", { fromDate, toDate });
+}
+
+export function getCostPerUseReport(purpose?: AssetPurpose): Promise<CostPerUseReport> {
+  return apiGet<CostPerUseReport>("/reports/cost-per-use", { purpose });
+}
+
+export function getTotalCostOfOwnershipReport(purpose?: AssetPurpose): Promise<TotalCostOfOwnershipReport> {
+  return apiGet<TotalCostOfOwnershipReport>("/reports/total-cost-of-ownership", { purpose });
+}
+
+export function getDepreciationForecast(months?: number, fromPeriodYm?: string): Promise<DepreciationForecast> {
+  return apiGet<DepreciationForecast>("/reports/depreciation-forecast", {
+    months: months === undefined ? undefined : String(months),
+    fromPeriodYm,
+  });
+}
+
+export function getBalanceDriftReport(accountId?: string): Promise<BalanceDriftReport> {
+  return apiGet<BalanceDriftReport>("/reports/balance-drift", { accountId });
+}
+
+export function getRealizedGainsReport(year: number): Promise<RealizedGainsReport> {
+  return apiGet<RealizedGainsReport>("/reports/realized-gains", { year: String(year) });
+}
+
+export function getDashboard(): Promise<Dashboard> {
+  return apiGet<Dashboard>("/dashboard");
+}
+
+export function getSpendingPace(periodYm?: string, asOf?: string): Promise<SpendingPace> {
+  return apiGet<SpendingPace>("/dashboard/spending-pace", { periodYm, asOf });
+}
+
+export function getSpendingForecast(asOf?: string): Promise<SpendingForecast> {
+  return apiGet<SpendingForecast>("/dashboard/spending-forecast", { asOf });
 }
 
 export function listAdjustmentKpi(input?: KpiPeriodInput): Promise<AdjustmentKpi> {
@@ -88,3 +783,49 @@ export function listAdjustmentKpi(input?: KpiPeriodInput): Promise<AdjustmentKpi
     toPeriodYm: input?.toPeriodYm ?? undefined,
   });
 }
+
+export function getDebtToAssetKpi(months = 12): Promise<DebtToAssetKpi> {
+  return apiGet<DebtToAssetKpi>("/kpis/debt-to-asset", { months: String(months) });
+}
+
+export function suggestCleanup(months?: number): Promise<CleanupSuggestions> {
+  return apiGet<CleanupSuggestions>("/maintenance/cleanup-suggestions", {
+    months: months === undefined ? undefined : String(months),
+  });
+}
+
+export function archiveItems(input: ArchiveInput): Promise<ArchiveResult> {
+  return apiPost<ArchiveResult>("/maintenance/archive", input);
+}
+
+export function rebuildBalances(input: RebuildBalancesInput = {}): Promise<RebuildBalancesResult> {
+  return apiPost<RebuildBalancesResult>("/maintenance/rebuild-balances", input);
+}
+
+export function auditData(): Promise<AuditFinding[]> {
+  return apiGet<AuditFinding[]>("/maintenance/audit");
+}
+
+export function maintainDatabase(): Promise<DatabaseMaintenanceResult> {
+  return apiPost<DatabaseMaintenanceResult>("/maintenance/database", {});
+}
+
+export function exportUtilityMatrix(input: UtilityMatrixExportInput): Promise<ExportResult> {
+  return apiPost<ExportResult>("/exports/utility-matrix", input);
+}
+
+export function exportReportCsv(input: ReportCsvExportInput): Promise<ExportResult> {
+  return apiPost<ExportResult>("/exports/report-csv", input);
+}
+
+export function exportAnonymized(input: AnonymizedExportInput): Promise<ExportResult> {
+  return apiPost<ExportResult>("/exports/anonymized", input);
+}
+
+export function scanLegacyData(): Promise<UpgradeFinding[]> {
+  return apiGet<UpgradeFinding[]>("/maintenance/upgrade-findings");
+}
+
+export function convertLegacyData(checkIds: string[]): Promise<UpgradeConversionResult[]> {
+  return apiPost<UpgradeConversionResult[]>("/maintenance/upgrade-conversions", { checkIds });
+}
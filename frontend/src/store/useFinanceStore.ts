@@ -9,15 +9,18 @@ import {
   listAccounts,
   listAdjustmentKpi,
   listTransactions,
+  maintainDatabase,
   reconcileAccount,
 } from "../api/finance";
 import type {
   Account,
   AdjustmentKpi,
   AssetPurchaseResult,
+  CashFlowReport,
   CreateAccountInput,
   CreateAssetPurchaseInput,
   CreateTransactionInput,
+  DatabaseMaintenanceResult,
   InitState,
   ReconcileInput,
   ReconcileResult,
@@ -25,7 +28,7 @@ import type {
   Transaction,
 } from "../types/finance";
 
-export type TabKey = "accounts" | "transactions" | "reports" | "reconcile";
+export type TabKey = "accounts" | "transactions" | "reports" | "reconcile" | "settings";
 
 interface FinanceState {
   activeTab: TabKey;
@@ -33,10 +36,11 @@ interface FinanceState {
   periodYm: string;
   accounts: Account[];
   transactions: Transaction[];
-  cashReport: Report | null;
+  cashReport: CashFlowReport | null;
   utilityReport: Report | null;
   kpi: AdjustmentKpi | null;
   reconcileResult: ReconcileResult | null;
+  maintenanceResult: DatabaseMaintenanceResult | null;
   loading: boolean;
   message: string | null;
   error: string | null;
@@ -49,6 +53,7 @@ interface FinanceState {
   submitAssetPurchase: (input: CreateAssetPurchaseInput) => Promise<AssetPurchaseResult>;
   refreshReports: () => Promise<void>;
   submitReconcile: (input: ReconcileInput) => Promise<void>;
+  runDatabaseMaintenance: () => Promise<void>;
   clearNotices: () => void;
   reset: () => void;
 }
@@ -61,10 +66,11 @@ const initialState = {
   periodYm: currentMonth,
   accounts: [] as Account[],
   transactions: [] as Transaction[],
-  cashReport: null as Report | null,
+  cashReport: null as CashFlowReport | null,
   utilityReport: null as Report | null,
   kpi: null as AdjustmentKpi | null,
   reconcileResult: null as ReconcileResult | null,
+  maintenanceResult: null as DatabaseMaintenanceResult | null,
   loading: false,
   message: null as string | null,
   error: null as string | null,
@@ -195,4 +201,20 @@ export const useFinanceStore = create<FinanceState>((set, get) => ({
       set({ loading: false });
     }
   },
+  runDatabaseMaintenance: async () => {
+    set({ loading: true, message: null, error: null });
+    try {
+      const maintenanceResult = await maintainDatabase();
+      set({
+        maintenanceResult,
+        message: maintenanceResult.integrityOk ? "Database maintenance completed" : null,
+        error: maintenanceResult.integrityOk ? null : "Database integrity check failed",
+      });
+    } catch (error) {
+      set({ error: toErrorMessage(error) });
+      throw error;
+    } finally {
+      set({ loading: false });
+    }
+  },
 }));
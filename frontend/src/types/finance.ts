@@ -1,15 +1,48 @@
 export type AccountType = "Asset" | "Liability";
+export type AccountLiquidity = "Liquid" | "Semi" | "Illiquid";
 export type AssetPurpose =
   | "Investment"
   | "Productivity"
   | "LifeSupport"
   | "Spiritual";
-export type AccrualType = "Flow" | "Depreciation" | "Adjustment";
-export type AmortizationStrategy = "Linear" | "Accelerated";
+export type AccrualType = "Flow" | "Depreciation" | "Adjustment" | "OpeningBalance";
+export type TransactionStatus = "Pending" | "Cleared" | "Reconciled";
+export type AmortizationStrategy = "Linear" | "Accelerated" | "DecliningBalance" | "Usage";
+export type AmortizationStatus = "Active" | "Completed" | "Cancelled";
+export type RecurrenceFrequency = "Daily" | "Weekly" | "Monthly" | "Yearly";
+export type BillStatus = "Paid" | "Upcoming" | "Overdue";
+export type BalanceGranularity = "Day" | "Month";
+export type ImportSignConvention = "Signed" | "Inverted" | "DebitCredit";
 
 export interface InitState {
   dataDir: string;
   databasePath: string;
+  onboardingCompleted: boolean;
+  readOnly: boolean;
+  locale: string | null;
+  deviceId: string | null;
+  currencySymbol: string | null;
+  decimalPlaces: number;
+  numberLocale: string | null;
+}
+
+export type MigrationStatus = "Running" | "Completed";
+
+export interface MigrationState {
+  id: string;
+  status: MigrationStatus;
+  processedCount: number;
+  totalCount: number;
+  startedAt: string;
+  completedAt: string | null;
+}
+
+export interface InstanceStatus {
+  instanceId: string;
+  isOwner: boolean;
+  ownerInstanceId: string | null;
+  ownerHeartbeatAt: string | null;
+  takeoverPending: boolean;
 }
 
 export interface AuthTokens {
@@ -35,8 +68,75 @@ export interface Account {
   accountType: AccountType;
   purpose: AssetPurpose;
   balanceCents: number;
+  excludeFromAdjustmentKpi: boolean;
+  isArchived: boolean;
+  currency: string | null;
+  convertedBalanceCents?: number | null;
+  liquidity: AccountLiquidity;
+  interestRatePercent: number | null;
+  bookValueCents: number;
   createdAt: string;
   updatedAt: string;
+  deviceId: string | null;
+}
+
+export interface BalancePoint {
+  date: string;
+  balanceCents: number;
+}
+
+export interface BalanceHistory {
+  accountId: string;
+  granularity: BalanceGranularity;
+  points: BalancePoint[];
+}
+
+export interface AccountValuation {
+  id: string;
+  accountId: string;
+  valueCents: number;
+  note: string | null;
+  valuedAt: string;
+  photoIds: string[];
+}
+
+export interface CreateAccountValuationInput {
+  valueCents: number;
+  valuedAt?: string | null;
+  note?: string | null;
+}
+
+export interface MaintenanceEvent {
+  id: string;
+  accountId: string;
+  occurredOn: string;
+  costCents: number;
+  note: string | null;
+  transactionId: string | null;
+  createdAt: string;
+}
+
+export interface CreateMaintenanceEventInput {
+  occurredOn: string;
+  costCents: number;
+  note?: string | null;
+  transactionId?: string | null;
+}
+
+export interface AccountPhoto {
+  id: string;
+  accountId: string;
+  valuationId: string | null;
+  fileName: string;
+  contentType: string;
+  sizeBytes: number;
+  createdAt: string;
+}
+
+export interface UploadAccountPhotoInput {
+  fileName: string;
+  contentBase64: string;
+  valuationId?: string | null;
 }
 
 export interface Transaction {
@@ -51,6 +151,21 @@ export interface Transaction {
   note: string | null;
   occurredAt: string;
   createdAt: string;
+  status: TransactionStatus;
+  deviceId: string | null;
+}
+
+export interface TransactionPosting {
+  leg: number;
+  accountId: string | null;
+  amountCents: number;
+}
+
+export interface AccountValuationTimeline {
+  accountId: string;
+  valuations: AccountValuation[];
+  photos: AccountPhoto[];
+  adjustments: Transaction[];
 }
 
 export interface PagedTransactions {
@@ -58,6 +173,54 @@ export interface PagedTransactions {
   total: number;
 }
 
+export interface RecurringRule {
+  id: string;
+  name: string;
+  amountCents: number;
+  fromAccountId: string | null;
+  toAccountId: string | null;
+  payeeId: string | null;
+  categoryId: string | null;
+  note: string | null;
+  frequency: RecurrenceFrequency;
+  interval: number;
+  startDate: string;
+  endDate: string | null;
+  nextRunDate: string | null;
+  isActive: boolean;
+}
+
+export interface RecurringRuleFailure {
+  ruleId: string;
+  name: string;
+  code: string;
+  message: string;
+}
+
+export interface RunRecurringResult {
+  createdTransactions: Transaction[];
+  failedRules: RecurringRuleFailure[];
+  hasMore: boolean;
+}
+
+export interface Bill {
+  id: string;
+  name: string;
+  payeeId: string | null;
+  liabilityAccountId: string | null;
+  expectedAmountCents: number;
+  dueDay: number;
+  isActive: boolean;
+}
+
+export interface UpcomingBill {
+  bill: Bill;
+  dueDate: string;
+  status: BillStatus;
+  paidTransactionId: string | null;
+  paidAmountCents: number | null;
+}
+
 export interface AmortizationSchedule {
   id: string;
   assetAccountId: string;
@@ -67,6 +230,113 @@ export interface AmortizationSchedule {
   startDate: string;
   sourceTransactionId: string;
   status: string;
+  decliningFactorPercent: number | null;
+  expectedUnits: number | null;
+  prorateDaily: boolean;
+  serialNumber: string | null;
+  receiptReference: string | null;
+  warrantyExpiresOn: string | null;
+}
+
+export interface AmortizationScheduleProgress extends AmortizationSchedule {
+  purchaseAmountCents: number;
+  periodsPosted: number;
+  depreciatedCents: number;
+  revaluationCents: number;
+  remainingBookValueCents: number;
+  disposedAt: string | null;
+}
+
+export interface AmortizationPosting {
+  id: string;
+  scheduleId: string;
+  periodYm: string;
+  amountCents: number;
+  transactionId: string;
+  generatedAt: string;
+}
+
+export interface UpdateAssetDetailsInput {
+  serialNumber?: string | null;
+  receiptReference?: string | null;
+  warrantyExpiresOn?: string | null;
+}
+
+export interface ExpiringWarranty {
+  scheduleId: string;
+  assetAccountId: string;
+  label: string;
+  serialNumber: string | null;
+  warrantyExpiresOn: string;
+  daysLeft: number;
+}
+
+export interface LogUsageInput {
+  periodYm: string;
+  units: number;
+  note?: string | null;
+}
+
+export interface UsageLog {
+  id: string;
+  scheduleId: string;
+  periodYm: string;
+  units: number;
+  note: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface CatchUpDepreciationInput {
+  periodYm: string;
+}
+
+export interface DepreciationCatchUpResult {
+  periodYm: string;
+  postings: AmortizationPosting[];
+}
+
+export interface CancelAmortizationScheduleInput {
+  reversePostings?: boolean;
+}
+
+export interface CancelAmortizationScheduleResult {
+  schedule: AmortizationScheduleProgress;
+  reversedTransactionIds: string[];
+}
+
+export interface RevalueAssetInput {
+  appraisedCents: number;
+  note?: string | null;
+  occurredAt?: string | null;
+}
+
+export interface AssetRevaluation {
+  id: string;
+  scheduleId: string;
+  appraisedCents: number;
+  carryingCents: number;
+  transactionId: string | null;
+  occurredAt: string;
+}
+
+export interface AssetRevaluationResult {
+  revaluation: AssetRevaluation;
+  schedule: AmortizationScheduleProgress;
+  transaction: Transaction | null;
+}
+
+export interface DisposeAssetInput {
+  disposedPercent: number;
+  proceedsCents?: number;
+  toAccountId?: string | null;
+  occurredAt?: string | null;
+}
+
+export interface DisposeAssetResult {
+  schedule: AmortizationScheduleProgress;
+  disposedSchedule: AmortizationScheduleProgress;
+  transactions: Transaction[];
 }
 
 export interface AssetPurchaseResult {
@@ -74,76 +344,1141 @@ export interface AssetPurchaseResult {
   schedule: AmortizationSchedule;
 }
 
+export interface Loan {
+  id: string;
+  liabilityAccountId: string;
+  paymentAccountId: string;
+  principalCents: number;
+  aprPercent: number;
+  termMonths: number;
+  paymentDay: number;
+  startPeriodYm: string;
+  paymentCents: number;
+  status: string;
+}
+
+export interface CreateLoanInput {
+  liabilityAccountId: string;
+  paymentAccountId: string;
+  principalCents: number;
+  aprPercent: number;
+  termMonths: number;
+  paymentDay: number;
+  startPeriodYm: string;
+  disbursedToAccountId?: string | null;
+  occurredAt?: string | null;
+}
+
+export interface LoanScheduleEntry {
+  periodYm: string;
+  paymentDate: string;
+  paymentCents: number;
+  principalCents: number;
+  interestCents: number;
+  remainingPrincipalCents: number;
+  posted: boolean;
+}
+
+export interface LoanSchedule {
+  loan: Loan;
+  entries: LoanScheduleEntry[];
+}
+
+export interface LoanCreated {
+  loan: Loan;
+  disbursement: Transaction | null;
+}
+
+export interface PrepaymentSimulation {
+  loanId: string;
+  fromPeriodYm: string;
+  remainingPrincipalCents: number;
+  lumpSumCents: number;
+  lumpSumPeriodYm: string;
+  monthlyExtraCents: number;
+  baselineMonths: number;
+  baselineInterestCents: number;
+  baselinePayoffPeriodYm: string;
+  simulatedMonths: number;
+  simulatedInterestCents: number;
+  simulatedPayoffPeriodYm: string;
+  monthsSaved: number;
+  interestSavedCents: number;
+}
+
+export interface AccrueInterestInput {
+  periodYm: string;
+}
+
+export interface InterestAccrual {
+  id: string;
+  accountId: string;
+  periodYm: string;
+  owedCents: number;
+  ratePercent: number;
+  amountCents: number;
+  transactionId: string;
+}
+
+export interface InterestAccrualResult {
+  periodYm: string;
+  accruals: InterestAccrual[];
+}
+
+export interface PostLoanPaymentsInput {
+  periodYm: string;
+}
+
+export interface LoanPaymentsResult {
+  periodYm: string;
+  transactions: Transaction[];
+}
+
 export interface ReconcileResult {
   account: Account;
   deltaCents: number;
   adjustmentTransaction: Transaction | null;
+  clearedBalanceCents: number | null;
+  reconciledCount: number;
 }
 
-export interface ReportItem {
+export interface BalanceDriftPoint {
+  capturedAt: string;
+  deltaCents: number;
+}
+
+export interface BalanceDriftSeries {
+  accountId: string;
   label: string;
+  points: BalanceDriftPoint[];
+  totalAbsoluteDeltaCents: number;
+}
+
+export interface BalanceDriftReport {
+  series: BalanceDriftSeries[];
+}
+
+export interface StatementLineInput {
+  date: string;
   amountCents: number;
+  description?: string | null;
+  externalId?: string | null;
 }
 
-export interface Report {
-  periodYm: string;
-  totalExpenseCents: number;
-  items: ReportItem[];
+export interface MatchStatementInput {
+  accountId: string;
+  lines: StatementLineInput[];
+  dateWindowDays?: number;
 }
 
-export interface AdjustmentKpi {
-  adjustmentTotalCents: number;
-  expenseTotalCents: number;
-  ratio: number;
+export interface StatementLine {
+  index: number;
+  date: string;
+  amountCents: number;
+  description: string | null;
+  externalId: string | null;
 }
 
-export interface CreateAccountInput {
+export interface StatementMatch {
+  line: StatementLine;
+  transaction: Transaction;
+}
+
+export interface StatementMatchResult {
+  matches: StatementMatch[];
+  unmatchedLines: StatementLine[];
+  unmatchedTransactions: Transaction[];
+}
+
+export interface ImportStatementInput {
+  accountId: string;
+  lines: StatementLineInput[];
+  autoLinkThreshold?: number;
+}
+
+export interface PayeeCandidate {
+  payeeId: string;
   name: string;
-  accountType: AccountType;
-  purpose: AssetPurpose;
-  initialBalanceCents: number;
+  score: number;
 }
 
-export interface CreateTransactionInput {
-  amountCents: number;
-  fromAccountId?: string | null;
-  toAccountId?: string | null;
-  payeeId?: string | null;
-  categoryId?: string | null;
-  accrualType?: AccrualType;
-  isAssetPurchase?: boolean;
-  note?: string | null;
-  occurredAt?: string | null;
+export interface ImportedLine {
+  index: number;
+  transaction: Transaction;
+  candidates: PayeeCandidate[];
 }
 
-export interface TransactionFilter {
-  periodYm?: string | null;
-  accrualType?: AccrualType | null;
+export interface StagedLine extends StatementLine {
+  payeeId: string | null;
+  categoryId: string | null;
+  candidates: PayeeCandidate[];
+  isDuplicate: boolean;
 }
 
-export interface CreateAssetPurchaseInput {
-  fromAccountId: string;
-  assetAccountId: string;
-  amountCents: number;
-  categoryId?: string | null;
-  payeeId?: string | null;
-  note?: string | null;
-  occurredAt?: string | null;
-  strategy: AmortizationStrategy;
-  totalPeriods: number;
-  residualCents: number;
-  startDate: string;
+export interface ImportPreview {
+  id: string;
+  accountId: string;
+  createdAt: string;
+  lines: StagedLine[];
 }
 
-export interface ReconcileInput {
+export interface ImportStatementResult {
+  lines: ImportedLine[];
+  skippedLines: StatementLine[];
+}
+
+export interface ImportProfile {
+  id: string;
+  name: string;
+  accountId: string | null;
+  delimiter: string;
+  skipRows: number;
+  dateColumn: string;
+  dateFormat: string;
+  amountColumn: string | null;
+  debitColumn: string | null;
+  creditColumn: string | null;
+  descriptionColumn: string | null;
+  externalIdColumn: string | null;
+  signConvention: ImportSignConvention;
+  decimalSeparator: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface CreateImportProfileInput {
+  name: string;
+  accountId?: string | null;
+  delimiter?: string;
+  skipRows?: number;
+  dateColumn: string;
+  dateFormat?: string;
+  amountColumn?: string | null;
+  debitColumn?: string | null;
+  creditColumn?: string | null;
+  descriptionColumn?: string | null;
+  externalIdColumn?: string | null;
+  signConvention?: ImportSignConvention;
+  decimalSeparator?: string;
+}
+
+export type UpdateImportProfileInput = Partial<CreateImportProfileInput>;
+
+export interface PreviewCsvImportInput {
+  profileId: string;
+  content: string;
+  accountId?: string | null;
+  autoLinkThreshold?: number;
+}
+
+export interface AssignPayeeInput {
+  payeeId: string;
+}
+
+export interface BalanceSnapshot {
+  id: string;
   accountId: string;
   actualBalanceCents: number;
-  occurredAt?: string | null;
-  note?: string | null;
+  systemBalanceCents: number;
+  deltaCents: number;
+  capturedAt: string;
+  adjustmentTransaction: Transaction | null;
 }
 
-export interface ReportPeriodInput {
-  periodYm: string;
+export interface CryptoHolding {
+  accountId: string;
+  symbol: string;
+  quantity: string;
+  quantityUnits: number;
+  unitPriceCents: number | null;
+  pricedAt: string | null;
+  valueCents: number | null;
+}
+
+export interface SetCryptoHoldingInput {
+  symbol: string;
+  quantity: string;
+}
+
+export interface SetCryptoPriceInput {
+  unitPriceCents: number;
+  pricedAt?: string | null;
+}
+
+export interface Lot {
+  id: string;
+  quantity: string;
+  quantityUnits: number;
+  originalQuantityUnits: number;
+  costBasisCents: number;
+  acquiredAt: string;
+}
+
+export interface Holding {
+  id: string;
+  accountId: string;
+  ticker: string;
+  quantity: string;
+  quantityUnits: number;
+  costBasisCents: number;
+  lots: Lot[];
+  priceCents?: number | null;
+  priceDate?: string | null;
+  marketValueCents?: number | null;
+}
+
+export interface Price {
+  id: string;
+  instrument: string;
+  priceDate: string;
+  priceCents: number;
+}
+
+export interface SetPriceInput {
+  instrument: string;
+  priceDate: string;
+  priceCents: number;
+}
+
+export interface RefreshPricesInput {
+  priceDate?: string | null;
+}
+
+export interface RefreshPricesResult {
+  prices: Price[];
+  missingInstruments: string[];
+}
+
+export interface PortfolioValuePoint {
+  date: string;
+  valueCents: number;
+  costBasisCents: number;
+}
+
+export interface PortfolioValueHistory {
+  accountId: string | null;
+  granularity: BalanceGranularity;
+  points: PortfolioValuePoint[];
+  unpricedTickers: string[];
+}
+
+export interface PerformanceItem {
+  label: string;
+  holdingId: string | null;
+  accountId: string | null;
+  startValueCents: number;
+  endValueCents: number;
+  netContributionsCents: number;
+  xirr: number | null;
+  twr: number | null;
+}
+
+export interface PerformanceReport {
+  fromDate: string;
+  toDate: string;
+  holdings: PerformanceItem[];
+  investmentGroup: PerformanceItem;
+}
+
+export interface SecurityTradeInput {
+  accountId: string;
+  cashAccountId: string;
+  ticker: string;
+  quantity: string;
+  amountCents: number;
+  occurredAt?: string | null;
+}
+
+export interface SecurityTradeResult {
+  holding: Holding;
+  transaction: Transaction;
+  realizedGainCents: number | null;
+}
+
+export interface RealizedGain {
+  transactionId: string;
+  accountId: string;
+  ticker: string;
+  soldAt: string;
+  quantity: string;
+  quantityUnits: number;
+  proceedsCents: number;
+  costBasisCents: number;
+  gainCents: number;
+}
+
+export interface CostPerUseItem {
+  scheduleId: string;
+  assetAccountId: string;
+  label: string;
+  purpose: AssetPurpose;
+  costCents: number;
+  unitsToDate: number;
+  costPerUseCents: number | null;
+}
+
+export interface CostPerUseReport {
+  items: CostPerUseItem[];
+}
+
+export interface TotalCostOfOwnershipItem {
+  accountId: string;
+  label: string;
+  purpose: AssetPurpose;
+  purchaseCents: number;
+  depreciatedCents: number;
+  maintenanceCents: number;
+  totalCostCents: number;
+  consumedCents: number;
+}
+
+export interface TotalCostOfOwnershipReport {
+  items: TotalCostOfOwnershipItem[];
+}
+
+export interface DepreciationForecastMonth {
+  periodYm: string;
+  amountCents: number;
+}
+
+export interface DepreciationForecast {
+  months: DepreciationForecastMonth[];
+  totalCents: number;
+}
+
+export interface RealizedGainsReport {
+  year: number;
+  items: RealizedGain[];
+  totalProceedsCents: number;
+  totalCostBasisCents: number;
+  totalGainCents: number;
+}
+
+export interface RevalueHoldingsInput {
+  periodYm: string;
+}
+
+export interface HoldingRevaluation {
+  id: string;
+  accountId: string;
+  periodYm: string;
+  marketValueCents: number;
+  costBasisCents: number;
+  adjustmentCents: number;
+  transactionId: string;
+}
+
+export interface HoldingRevaluationResult {
+  periodYm: string;
+  revaluations: HoldingRevaluation[];
+}
+
+export interface CryptoRevaluationResult {
+  holding: CryptoHolding;
+  account: Account;
+  adjustmentTransaction: Transaction | null;
+}
+
+export interface ReportItem {
+  label: string;
+  amountCents: number;
+  topTransactionIds: string[] | null;
+  children?: ReportItem[] | null;
+}
+
+export interface Report {
+  periodYm: string | null;
+  fromDate: string;
+  toDate: string;
+  totalExpenseCents: number;
+  items: ReportItem[];
+}
+
+export interface TrendPoint {
+  periodYm: string;
+  amountCents: number;
+  topTransactionIds: string[] | null;
+}
+
+export interface CategoryTrendSeries {
+  categoryId: string;
+  label: string;
+  points: TrendPoint[];
+}
+
+export interface CategoryTrend {
+  fromPeriodYm: string;
+  toPeriodYm: string;
+  series: CategoryTrendSeries[];
+}
+
+export interface RollingAverageItem {
+  categoryId?: string | null;
+  label: string;
+  threeMonthAverageCents: number;
+  sixMonthAverageCents: number;
+}
+
+export interface RollingAverageReport {
+  fromPeriodYm: string;
+  toPeriodYm: string;
+  threeMonthAverageCents: number;
+  sixMonthAverageCents: number;
+  items: RollingAverageItem[];
+}
+
+export interface CashFlowSection {
+  items: ReportItem[];
+  netCents: number;
+}
+
+export interface CashFlowReport extends Report {
+  totalIncomeCents: number;
+  incomeItems: ReportItem[];
+  totalTransferCents: number;
+  totalDebtRepaymentCents: number;
+  totalBorrowingCents: number;
+  netCents: number;
+  operating: CashFlowSection;
+  investing: CashFlowSection;
+  financing: CashFlowSection;
+  baseCurrency?: string | null;
+  convertedTotalIncomeCents?: number | null;
+  convertedTotalExpenseCents?: number | null;
+  convertedNetCents?: number | null;
+}
+
+export interface IncomeStatement {
+  periodYm: string | null;
+  fromDate: string;
+  toDate: string;
+  incomeItems: ReportItem[];
+  totalIncomeCents: number;
+  expenseItems: ReportItem[];
+  totalExpenseCents: number;
+  depreciationCents: number;
+  netResultCents: number;
+}
+
+export interface Category {
+  id: string;
+  name: string;
+  parentId: string | null;
+  color: string | null;
+  icon: string | null;
+  sortOrder: number;
+  isActive: boolean;
+}
+
+export interface Payee {
+  id: string;
+  name: string;
+  defaultCategoryId: string | null;
+  isArchived: boolean;
+}
+
+export interface Tag {
+  id: string;
+  name: string;
+  color: string | null;
+  description: string | null;
+  isArchived: boolean;
+}
+
+export interface CleanupItem {
+  id: string;
+  name: string;
+  lastUsedAt: string | null;
+}
+
+export interface CleanupSuggestions {
+  cutoffDate: string;
+  payees: CleanupItem[];
+  tags: CleanupItem[];
+  categories: CleanupItem[];
+  accounts: CleanupItem[];
+}
+
+export interface ArchiveResult {
+  archivedCount: number;
+}
+
+export interface BalanceDiscrepancy {
+  accountId: string;
+  name: string;
+  storedCents: number;
+  rebuiltCents: number;
+  differenceCents: number;
+}
+
+export interface RebuildBalancesResult {
+  checkedCount: number;
+  discrepancies: BalanceDiscrepancy[];
+  applied: boolean;
+}
+
+export interface AuditFinding {
+  checkId: string;
+  description: string;
+  rowIds: string[];
+}
+
+export interface DatabaseMaintenanceResult {
+  integrityOk: boolean;
+  integrityMessages: string[];
+  compacted: boolean;
+  sizeBeforeBytes: number;
+  sizeAfterBytes: number;
+  reclaimedBytes: number;
+}
+
+export type AnomalyReason = "UnusualAmount" | "PossibleDuplicate";
+
+export interface TransactionAnomaly {
+  transaction: Transaction;
+  reason: AnomalyReason;
+  message: string;
+  typicalAmountCents?: number | null;
+  relatedTransactionId?: string | null;
+}
+
+export interface UpgradeFinding {
+  checkId: string;
+  description: string;
+  convertible: boolean;
+  rowIds: string[];
+}
+
+export interface UpgradeConversionResult {
+  checkId: string;
+  convertedCount: number;
+}
+
+export interface Budget {
+  id: string;
+  categoryId: string;
+  periodYm: string;
+  amountCents: number;
+  rollover: boolean;
+}
+
+export interface ExchangeRate {
+  id: string;
+  fromCurrency: string;
+  toCurrency: string;
+  rate: number;
+  rateDate: string;
+}
+
+export interface SetExchangeRateInput {
+  fromCurrency: string;
+  toCurrency: string;
+  rate: number;
+  rateDate: string;
+}
+
+export interface RevalueForeignAccountsInput {
+  periodYm: string;
+}
+
+export interface FxRevaluation {
+  id: string;
+  accountId: string;
+  periodYm: string;
+  baseCurrency: string;
+  balanceCents: number;
+  valueCents: number;
+  gainCents: number;
+  transactionId: string;
+}
+
+export interface FxRevaluationResult {
+  periodYm: string;
+  revaluations: FxRevaluation[];
+  skippedAccountIds: string[];
+}
+
+export interface BudgetReportItem {
+  categoryId: string;
+  label: string;
+  budgetedCents: number;
+  carriedOverCents: number;
+  effectiveBudgetCents: number;
+  spentCents: number;
+  remainingCents: number;
+  rollover: boolean;
+  children?: BudgetReportItem[] | null;
+}
+
+export interface BudgetReport {
+  periodYm: string;
+  totalEffectiveBudgetCents: number;
+  totalSpentCents: number;
+  items: BudgetReportItem[];
+}
+
+export interface ExportResult {
+  path: string;
+  rowCount: number;
+}
+
+export interface AppEvent {
+  seq: number;
+  type: string;
+  payload: Record<string, unknown>;
+  emittedAt: string;
+}
+
+export interface Dashboard {
+  periodYm: string;
+  netWorthCents: number;
+  totalAssetsCents: number;
+  totalLiabilitiesCents: number;
+  monthToDateExpenseCents: number;
+  monthToDateIncomeCents: number;
+  pendingReconciliationCount: number;
+  activeAmortizationCount: number;
+  baseCurrency?: string | null;
+  convertedNetWorthCents?: number | null;
+  convertedTotalAssetsCents?: number | null;
+  convertedTotalLiabilitiesCents?: number | null;
+}
+
+export interface UsageScore {
+  id: string;
+  score: number;
+  useCount: number;
+  lastUsedAt: string;
+}
+
+export interface UsageStats {
+  halfLifeDays: number;
+  accounts: UsageScore[];
+  categories: UsageScore[];
+}
+
+export interface AllocationTarget {
+  purpose: AssetPurpose;
+  targetPercent: number;
+}
+
+export interface SetAllocationTargetsInput {
+  targets: AllocationTarget[];
+}
+
+export interface AllocationDriftItem {
+  purpose: AssetPurpose;
+  actualCents: number;
+  actualPercent: number;
+  targetPercent?: number | null;
+  targetCents?: number | null;
+  rebalanceCents?: number | null;
+}
+
+export interface AllocationDriftReport {
+  totalAssetsCents: number;
+  items: AllocationDriftItem[];
+}
+
+export interface LiquidityReportItem {
+  liquidity: AccountLiquidity;
+  assetsCents: number;
+  liabilitiesCents: number;
+  netCents: number;
+}
+
+export interface LiquidityReport {
+  netWorthCents: number;
+  items: LiquidityReportItem[];
+}
+
+export interface SpendingForecastItem {
+  categoryId?: string | null;
+  label: string;
+  monthToDateCents: number;
+  scheduledCents: number;
+  projectedCents: number;
+  budgetCents?: number | null;
+  isOverBudget: boolean;
+}
+
+export interface SpendingForecast {
+  periodYm: string;
+  asOfDate: string;
+  daysElapsed: number;
+  daysInPeriod: number;
+  monthToDateCents: number;
+  projectedCents: number;
+  items: SpendingForecastItem[];
+}
+
+export interface SpendingPace {
+  periodYm: string;
+  asOfDate: string;
+  dayOfMonth: number;
+  monthToDateCents: number;
+  baselineCents: number | null;
+  historyMonthCount: number;
+  thresholdPercent: number;
+  overPacePercent: number | null;
+  isOverPace: boolean;
+}
+
+export interface AdjustmentKpi {
+  adjustmentTotalCents: number;
+  expenseTotalCents: number;
+  ratio: number;
+}
+
+export interface DebtToAssetPoint {
+  date: string;
+  assetsCents: number;
+  liabilitiesCents: number;
+  ratio?: number | null;
+}
+
+export interface DebtToAssetKpi {
+  assetsCents: number;
+  liabilitiesCents: number;
+  ratio?: number | null;
+  series: DebtToAssetPoint[];
+}
+
+export interface CreateAccountInput {
+  name: string;
+  accountType: AccountType;
+  purpose: AssetPurpose;
+  initialBalanceCents: number;
+  excludeFromAdjustmentKpi?: boolean;
+  currency?: string | null;
+  liquidity?: AccountLiquidity | null;
+  interestRatePercent?: number | null;
+  recordOpeningBalance?: boolean;
+  openedAt?: string | null;
+}
+
+export interface RecordOpeningBalanceInput {
+  occurredAt?: string | null;
+}
+
+export interface UpdateAccountInput {
+  name?: string;
+  purpose?: AssetPurpose;
+  excludeFromAdjustmentKpi?: boolean;
+  isArchived?: boolean;
+  liquidity?: AccountLiquidity;
+  interestRatePercent?: number;
+}
+
+export interface CreateCategoryInput {
+  name: string;
+  parentId?: string | null;
+  color?: string | null;
+  icon?: string | null;
+  sortOrder?: number;
+}
+
+export interface UpdateCategoryInput {
+  name?: string;
+  color?: string;
+  icon?: string;
+  sortOrder?: number;
+}
+
+export interface CategorySuggestion {
+  category: Category;
+  useCount: number;
+}
+
+export interface MergeCategoriesInput {
+  sourceId: string;
+  targetId: string;
+}
+
+export interface MergeCategoriesResult {
+  category: Category;
+  movedTransactionCount: number;
+  movedBudgetCount: number;
+}
+
+export interface CreatePayeeInput {
+  name: string;
+  defaultCategoryId?: string | null;
+}
+
+export interface PayeeSuggestion {
+  payeeId: string;
+  categoryId: string | null;
+  recentAmountsCents: number[];
+}
+
+export interface MergePayeesInput {
+  sourceIds: string[];
+  targetId: string;
+}
+
+export interface MergePayeesResult {
+  payee: Payee;
+  movedTransactionCount: number;
+}
+
+export interface CreateTagInput {
+  name: string;
+  color?: string | null;
+  description?: string | null;
+}
+
+export interface UpdateTagInput {
+  name?: string;
+  color?: string;
+  description?: string;
+  isArchived?: boolean;
+}
+
+export interface ArchiveInput {
+  payeeIds?: string[];
+  tagIds?: string[];
+  categoryIds?: string[];
+  accountIds?: string[];
+}
+
+export interface RebuildBalancesInput {
+  dryRun?: boolean;
+}
+
+export interface SetBudgetInput {
+  categoryId: string;
+  periodYm: string;
+  amountCents: number;
+  rollover?: boolean;
+}
+
+export interface CreateTransactionInput {
+  amountCents: number;
+  fromAccountId?: string | null;
+  toAccountId?: string | null;
+  payeeId?: string | null;
+  categoryId?: string | null;
+  accrualType?: AccrualType;
+  isAssetPurchase?: boolean;
+  note?: string | null;
+  occurredAt?: string | null;
+  status?: TransactionStatus;
+}
+
+export interface PostingInput {
+  accountId: string;
+  amountCents: number;
+}
+
+export interface CreateMultiLegTransactionInput {
+  postings: PostingInput[];
+  payeeId?: string | null;
+  categoryId?: string | null;
+  note?: string | null;
+  occurredAt?: string | null;
+  status?: TransactionStatus;
+}
+
+export interface CreateTransactionsBatchInput {
+  transactions: CreateTransactionInput[];
+}
+
+export interface DuplicateTransactionInput {
+  occurredAt?: string | null;
+}
+
+export interface RecategorizeAdjustmentInput {
+  categoryId: string;
+  payeeId?: string | null;
+  note?: string | null;
+}
+
+export interface MarkClearedInput {
+  transactionIds: string[];
+  cleared?: boolean;
+}
+
+export interface MarkClearedResult {
+  transactions: Transaction[];
+}
+
+export interface TrashedTransaction {
+  transaction: Transaction;
+  deletedAt: string | null;
+}
+
+export interface TransactionAttachment {
+  id: string;
+  transactionId: string;
+  fileName: string;
+  contentType: string;
+  sizeBytes: number;
+  contentHash: string;
+  createdAt: string;
+}
+
+export interface UploadAttachmentInput {
+  fileName: string;
+  contentBase64: string;
+}
+
+export interface ReceiptSuggestion {
+  amountCents: number | null;
+  occurredOn: string | null;
+  payeeName: string | null;
+  payeeId: string | null;
+  categoryId: string | null;
+}
+
+export interface VoidTransactionInput {
+  occurredAt?: string | null;
+  note?: string | null;
+}
+
+export interface VoidTransactionResult {
+  original: Transaction;
+  reversal: Transaction;
+}
+
+export interface PurgeTrashInput {
+  olderThanDays?: number | null;
+}
+
+export interface PurgeTrashResult {
+  purgedCount: number;
+}
+
+export interface TransactionFilter {
+  periodYm?: string | null;
+  accrualType?: AccrualType | null;
+}
+
+export interface CreateRecurringRuleInput {
+  name: string;
+  amountCents: number;
+  fromAccountId?: string | null;
+  toAccountId?: string | null;
+  payeeId?: string | null;
+  categoryId?: string | null;
+  note?: string | null;
+  frequency: RecurrenceFrequency;
+  interval?: number;
+  startDate: string;
+  endDate?: string | null;
+}
+
+export interface RunRecurringInput {
+  throughDate?: string | null;
+}
+
+export interface CreateBillInput {
+  name: string;
+  payeeId?: string | null;
+  liabilityAccountId?: string | null;
+  expectedAmountCents: number;
+  dueDay: number;
+}
+
+export interface CreateAssetPurchaseInput {
+  fromAccountId: string;
+  assetAccountId: string;
+  amountCents: number;
+  categoryId?: string | null;
+  payeeId?: string | null;
+  note?: string | null;
+  occurredAt?: string | null;
+  strategy: AmortizationStrategy;
+  totalPeriods: number;
+  residualCents: number;
+  startDate: string;
+  decliningFactorPercent?: number | null;
+  expectedUnits?: number | null;
+  prorateDaily?: boolean;
+  serialNumber?: string | null;
+  receiptReference?: string | null;
+  warrantyExpiresOn?: string | null;
+}
+
+export interface ReconcileInput {
+  accountId: string;
+  actualBalanceCents: number;
+  occurredAt?: string | null;
+  note?: string | null;
+  clearedOnly?: boolean;
+}
+
+export interface SetAccountBalanceInput {
+  newBalanceCents: number;
+  note?: string | null;
+  occurredAt?: string | null;
+}
+
+export interface ReportPeriodInput {
+  periodYm: string;
+}
+
+export interface ReportRangeInput {
+  fromDate: string;
+  toDate: string;
+}
+
+export interface OnboardingInput {
+  baseCurrency: string;
+  periodStartDay?: number;
+  accounts: CreateAccountInput[];
+  categories?: CreateCategoryInput[];
+}
+
+export interface OnboardingResult {
+  baseCurrency: string;
+  periodStartDay: number;
+  accounts: Account[];
+  categories: Category[];
+}
+
+export interface Settings {
+  baseCurrency: string | null;
+  locale: string | null;
+  periodStartDay: number;
+  spendingPaceAlertPercent: number;
+  backupRetention: number;
+  currencySymbol: string | null;
+  decimalPlaces: number;
+  numberLocale: string | null;
+  timezone: string;
+}
+
+export interface UpdateSettingsInput {
+  baseCurrency?: string | null;
+  locale?: string | null;
+  periodStartDay?: number | null;
+  spendingPaceAlertPercent?: number | null;
+  backupRetention?: number | null;
+  currencySymbol?: string | null;
+  decimalPlaces?: number | null;
+  numberLocale?: string | null;
+  timezone?: string | null;
+}
+
+export type ReportKind = "Cash" | "Utility" | "Purpose" | "Budget";
+
+export interface ReportCsvExportInput {
+  report: ReportKind;
+  path: string;
+  periodYm?: string | null;
+  fromDate?: string | null;
+  toDate?: string | null;
+}
+
+export interface AnonymizedExportInput {
+  path: string;
+  perturbAmounts?: boolean;
+}
+
+export interface UtilityMatrixExportInput {
+  year: number;
+  path: string;
 }
 
 export interface KpiPeriodInput {